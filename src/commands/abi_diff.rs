@@ -0,0 +1,145 @@
+use super::common;
+use super::common::options::*;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::abi::*;
+use crate::result::Error;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+use std::path::PathBuf;
+
+static SUBCOMM_NAME: &str = "abi-diff";
+
+const BREAKING_ONLY_FLAG: &str = "breaking";
+
+pub(super) struct AbiDiffHandler {
+    printer: Printer,
+}
+
+impl AbiDiffHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+struct Config {
+    /// Path of new version, the old one is parsed by caller
+    new_path: PathBuf,
+    breaking_only: bool,
+}
+
+impl Config {
+    fn build(opts: &mut Options, args: &[String]) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        // Without `--path` the first free argument is the old version
+        let new_path = match matches.opt_str(common::PATH_OPT_SHORT) {
+            Some(_) => matches.free.first(),
+            None => matches.free.get(1),
+        };
+        let new_path = match new_path {
+            Some(path) => PathBuf::from(path),
+            None => return Err(Error::Text("No new version to compare with".to_string())),
+        };
+
+        Ok(Config {
+            new_path,
+            breaking_only: matches.opt_present(BREAKING_ONLY_FLAG),
+        })
+    }
+}
+
+impl Handler for AbiDiffHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Compares exported symbols, ObjC classes and methods, versions and architectures of two versions of dylib"
+            .to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let config = Config::build(&mut opts, &other_args)?;
+        let new = Parser::build(&config.new_path)?.parse()?;
+
+        let diff = AbiDiff::compare(&filter.get_objects(object), &filter.get_objects(new))?;
+        let changes: Vec<&AbiChange> = diff
+            .changes
+            .iter()
+            .filter(|change| !config.breaking_only || change.is_removal())
+            .collect();
+        self.handle_changes(&changes);
+
+        match diff.has_removals() {
+            false => Ok(()),
+            true => {
+                let removals = diff.changes.iter().filter(|c| c.is_removal()).count();
+                Err(Error::Text(format!("{removals} removals break ABI")))
+            }
+        }
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        result.push(OptionItem {
+            option_type: OptionType::Flag(IsRequired(false)),
+            name: OptionName::Long(BREAKING_ONLY_FLAG.to_string()),
+            description: "Print only removals".to_string(),
+            hint: "".to_string(),
+        });
+        result
+    }
+}
+
+impl AbiDiffHandler {
+    fn handle_changes(&self, changes: &[&AbiChange]) {
+        if changes.is_empty() {
+            self.printer.print_line("No changes".dimmed());
+            return;
+        }
+
+        for change in changes {
+            let arch = match &change.arch {
+                Some(arch) => format!("[{}] ", arch.green()),
+                None => String::new(),
+            };
+            let item = change.item.to_string().blue();
+            let line = match &change.kind {
+                AbiChangeKind::Removed => {
+                    format!("{} {arch}{item} {}", "-".red(), change.name.red())
+                }
+                AbiChangeKind::Added => {
+                    format!("{} {arch}{item} {}", "+".green(), change.name.green())
+                }
+                AbiChangeKind::Changed(old, new) => format!(
+                    "{} {arch}{item} {}{}{} -> {}",
+                    "~".yellow(),
+                    change.name.yellow(),
+                    match change.name.is_empty() {
+                        true => "",
+                        false => ": ",
+                    },
+                    old,
+                    new
+                ),
+            };
+            self.printer.print_line(line);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use super::common;
+use super::common::helpers;
+use super::common::options::*;
+use super::common::Format;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::result::Error;
+use crate::symbolicator::*;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+static SUBCOMM_NAME: &str = "atos";
+
+const LOAD_ADDRESS_ARG_SHORT: &str = "l";
+const LOAD_ADDRESS_ARG_LONG: &str = "load-address";
+const DSYM_ARG_LONG: &str = "dsym";
+
+pub(super) struct AtosHandler {
+    printer: Printer,
+}
+
+impl AtosHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+struct Config {
+    load_address: Option<u64>,
+    dsym: Option<String>,
+    addresses: Vec<u64>,
+}
+
+impl Config {
+    fn build(opts: &mut Options, args: &[String]) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        let load_address = match matches.opt_str(LOAD_ADDRESS_ARG_SHORT) {
+            Some(address) => Some(helpers::parse_address(&address)?),
+            None => None,
+        };
+
+        // Without `--path` the first free argument is the path
+        let skip = match matches.opt_present(common::PATH_OPT_SHORT) {
+            true => 0,
+            false => 1,
+        };
+        let addresses = matches
+            .free
+            .iter()
+            .skip(skip)
+            .map(|address| helpers::parse_address(address))
+            .collect::<Result<Vec<u64>>>()?;
+        if addresses.is_empty() {
+            return Err(Error::Text("No addresses to symbolicate".to_string()));
+        }
+
+        Ok(Config {
+            load_address,
+            dsym: matches.opt_str(DSYM_ARG_LONG),
+            addresses,
+        })
+    }
+}
+
+impl Handler for AtosHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Converts addresses to symbols and source lines".to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let format = &Format::build(&mut opts, &other_args)?;
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let config = Config::build(&mut opts, &other_args)?;
+
+        let objects = filter.get_objects(object);
+        let object = match objects.len() {
+            0 => return Err(Error::Text("No matching architecture".to_string())),
+            1 => &objects[0],
+            _ => {
+                return Err(Error::Text(
+                    "Several architectures found, choose one with --arch".to_string(),
+                ))
+            }
+        };
+
+        let dsym = match &config.dsym {
+            Some(path) => Some(helpers::load_dsym_object(path, object)?),
+            None => None,
+        };
+        let symbolicator = Symbolicator::build(object, dsym.as_ref())?;
+
+        for (index, address) in config.addresses.iter().enumerate() {
+            let symbolication = symbolicator.symbolicate(*address, config.load_address)?;
+            self.handle_symbolication(&symbolication, index, format);
+        }
+
+        Ok(())
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        result.append(&mut Format::option_items());
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::ShortLong(
+                LOAD_ADDRESS_ARG_SHORT.to_string(),
+                LOAD_ADDRESS_ARG_LONG.to_string(),
+            ),
+            description: "Runtime address of __TEXT segment, to account for ASLR slide".to_string(),
+            hint: "ADDR".to_string(),
+        });
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::Long(DSYM_ARG_LONG.to_string()),
+            description: "dSYM bundle or DWARF file to look up source lines".to_string(),
+            hint: "FILE".to_string(),
+        });
+        result
+    }
+}
+
+impl AtosHandler {
+    fn handle_symbolication(&self, symbolication: &Symbolication, index: usize, format: &Format) {
+        if format.show_indices {
+            self.printer.out_list_item_dash(0, index);
+        }
+
+        let mut line = match &symbolication.symbol {
+            Some(symbol) => {
+                let name = demangle::demangle(symbol).unwrap_or(symbol.clone());
+                name.yellow().to_string()
+            }
+            None => match symbolication.symbol_address {
+                Some(address) => format!("{address:#x}").yellow().to_string(),
+                None => format!("{:#x}", symbolication.address).dimmed().to_string(),
+            },
+        };
+        if let Some(offset) = symbolication.offset() {
+            line += &format!(" + {offset}");
+        }
+        if let Some(location) = &symbolication.location {
+            if let (Some(file), Some(line_number)) = (&location.file, location.line) {
+                let file = file.rsplit('/').next().unwrap_or(file);
+                line += &format!(" ({})", format!("{file}:{line_number}").green());
+            }
+        }
+
+        if format.short {
+            self.printer.print_line(line);
+            return;
+        }
+
+        self.printer.print_line(format!(
+            "{} {line}",
+            format!("{:#018x}", symbolication.address).red()
+        ));
+    }
+}
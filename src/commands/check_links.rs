@@ -0,0 +1,154 @@
+use super::common;
+use super::common::options::*;
+use super::common::Format;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::link_check::*;
+use crate::result::Error;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+use std::path::PathBuf;
+
+static SUBCOMM_NAME: &str = "check-links";
+
+const SYSROOT_ARG_LONG: &str = "sysroot";
+
+pub(super) struct CheckLinksHandler {
+    printer: Printer,
+}
+
+impl CheckLinksHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+struct Config {
+    path: PathBuf,
+    sysroot: PathBuf,
+}
+
+impl Config {
+    fn build(opts: &mut Options, args: &[String]) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        let path = match matches.opt_str(common::PATH_OPT_SHORT) {
+            Some(path) => path,
+            None => matches.free.first().cloned().unwrap_or_default(),
+        };
+        let sysroot = match matches.opt_str(SYSROOT_ARG_LONG) {
+            Some(sysroot) => PathBuf::from(sysroot),
+            None => return Err(Error::Text(format!("--{SYSROOT_ARG_LONG} is required"))),
+        };
+        if !sysroot.is_dir() {
+            return Err(Error::Text(format!(
+                "Sysroot {} is not a directory",
+                sysroot.display()
+            )));
+        }
+
+        Ok(Config {
+            path: PathBuf::from(path),
+            sysroot,
+        })
+    }
+}
+
+impl Handler for CheckLinksHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Checks, that every imported symbol is exported by its library inside of sysroot"
+            .to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let format = &Format::build(&mut opts, &other_args)?;
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let config = Config::build(&mut opts, &other_args)?;
+        let checker = LinkChecker::new(&config.sysroot);
+
+        let mut errors = 0;
+        let objects = &filter.get_objects(object);
+        let out_arch = objects.len() > 1;
+        for (idx, obj) in objects.iter().enumerate() {
+            if out_arch {
+                common::out_single_arch_title(&self.printer, obj.header(), idx, format.short);
+            }
+            let issues = checker.check(obj, &config.path)?;
+            self.handle_issues(&issues, format);
+            errors += issues
+                .iter()
+                .filter(|issue| issue.severity == LinkSeverity::Error)
+                .count();
+        }
+
+        match errors {
+            0 => Ok(()),
+            _ => Err(Error::Text(format!("{errors} imported symbols not found"))),
+        }
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        result.append(&mut Format::option_items());
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(true)),
+            name: OptionName::Long(SYSROOT_ARG_LONG.to_string()),
+            description: "Directory, that absolute install names and rpaths are relative to"
+                .to_string(),
+            hint: "DIR".to_string(),
+        });
+        result
+    }
+}
+
+impl CheckLinksHandler {
+    fn handle_issues(&self, issues: &[LinkIssue], format: &Format) {
+        if issues.is_empty() {
+            self.printer
+                .print_line("All imported symbols are found".green());
+            return;
+        }
+
+        for (index, issue) in issues.iter().enumerate() {
+            if format.show_indices {
+                self.printer.out_list_item_dash(0, index);
+            }
+            let severity = match issue.severity {
+                LinkSeverity::Warning => "warning:".yellow(),
+                LinkSeverity::Error => "error:".red(),
+            };
+            let problem = match issue.problem {
+                LinkProblem::SymbolNotFound => "symbol not found in",
+                LinkProblem::LibraryNotFound => "library not found:",
+                LinkProblem::InvalidOrdinal => "symbol has",
+            };
+            let mut line = format!(
+                "{severity} {} {problem} {}",
+                issue.import.name.yellow(),
+                common::colored_path_string(&issue.import.library)
+            );
+            if issue.import.is_weak && !format.short {
+                line += &" (weak import)".dimmed().to_string();
+            }
+            self.printer.print_line(line);
+        }
+    }
+}
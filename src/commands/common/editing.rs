@@ -0,0 +1,166 @@
+use getopts::*;
+
+use colored::Colorize;
+use std::path::PathBuf;
+
+use super::options::*;
+use super::{out_single_arch_title, ObjectFilter, PATH_OPT_SHORT};
+use crate::output::Printer;
+use crate::result::{Error, Result};
+use crate::writer::{FileImage, MachImage};
+use crate::ObjectType;
+
+const OUTPUT_ARG_SHORT: &str = "o";
+const OUTPUT_ARG_LONG: &str = "output";
+const REMOVE_SIGNATURE_FLAG: &str = "remove-signature";
+
+/// Output of commands, that write edited files
+pub(crate) struct Editing {
+    pub(crate) output: PathBuf,
+    /// `None` for commands, that handle code signature themselves
+    remove_signature: Option<bool>,
+}
+
+impl Editing {
+    /// Without output path the input file is edited in place if `in_place`, otherwise it's an error
+    pub(crate) fn build(opts: &mut Options, args: &[String], in_place: bool) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        let input = match (in_place, matches.opt_str(PATH_OPT_SHORT)) {
+            (false, _) => None,
+            (true, Some(path)) => Some(path),
+            (true, None) => matches.free.first().cloned(),
+        };
+        let output = match (matches.opt_str(OUTPUT_ARG_SHORT), input) {
+            (Some(path), _) | (None, Some(path)) => PathBuf::from(path),
+            (None, None) => return Err(Error::Text("No output file".to_string())),
+        };
+
+        Ok(Self {
+            output,
+            remove_signature: match matches.opt_defined(REMOVE_SIGNATURE_FLAG) {
+                true => Some(matches.opt_present(REMOVE_SIGNATURE_FLAG)),
+                false => None,
+            },
+        })
+    }
+
+    /// Applies `edit` to images passing `filter` and writes the file.
+    /// Signed images are refused unless signature removal is asked
+    pub(crate) fn edit_images(
+        &self,
+        printer: &Printer,
+        object: &ObjectType,
+        filter: &ObjectFilter,
+        mut edit: impl FnMut(&mut MachImage) -> Result<()>,
+    ) -> Result<()> {
+        let mut file = FileImage::read(object)?;
+        handle_images(printer, &mut file, filter, |image| {
+            self.handle_signature(printer, image)?;
+            edit(image)
+        })?;
+        self.write(printer, &file)
+    }
+
+    pub(crate) fn write(&self, printer: &Printer, file: &FileImage) -> Result<()> {
+        file.write(&self.output)?;
+        printer.print_line(format!(
+            "{} {}",
+            "Written to".bright_white(),
+            self.output.display()
+        ));
+        Ok(())
+    }
+
+    fn handle_signature(&self, printer: &Printer, image: &mut MachImage) -> Result<()> {
+        match self.remove_signature {
+            Some(_) if !image.has_code_signature() => Ok(()),
+            Some(true) => {
+                image.remove_code_signature()?;
+                printer.print_line(format!("{} code signature", "-".red()));
+                Ok(())
+            }
+            Some(false) => Err(Error::Text(format!(
+                "Image is code signed, edits would invalidate its signature. Use --{REMOVE_SIGNATURE_FLAG} and sign it again afterwards"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Output path and, if `with_signature`, signature removal.
+    /// `description` tells what is written to output path
+    pub(crate) fn option_items(description: &str, with_signature: bool) -> Vec<OptionItem> {
+        let mut result = vec![OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::ShortLong(OUTPUT_ARG_SHORT.to_string(), OUTPUT_ARG_LONG.to_string()),
+            description: description.to_string(),
+            hint: "FILE".to_string(),
+        }];
+        if with_signature {
+            result.push(OptionItem {
+                option_type: OptionType::Flag(IsRequired(false)),
+                name: OptionName::Long(REMOVE_SIGNATURE_FLAG.to_string()),
+                description: "Remove code signature of signed files, that edits would invalidate"
+                    .to_string(),
+                hint: "".to_string(),
+            });
+        }
+        result
+    }
+}
+
+/// Calls `handle` for images of `file` passing `filter`, titled by architecture if there are several
+pub(crate) fn handle_images(
+    printer: &Printer,
+    file: &mut FileImage,
+    filter: &ObjectFilter,
+    mut handle: impl FnMut(&mut MachImage) -> Result<()>,
+) -> Result<()> {
+    let mut images: Vec<_> = file
+        .images_mut()
+        .into_iter()
+        .filter(|image| filter.matches(&image.header))
+        .collect();
+    if images.is_empty() {
+        return Err(Error::Text("No architectures to edit".to_string()));
+    }
+
+    let out_arch = images.len() > 1;
+    for (idx, image) in images.iter_mut().enumerate() {
+        if out_arch {
+            out_single_arch_title(printer, &image.header, idx, false);
+        }
+        handle(image)?;
+    }
+    Ok(())
+}
+
+/// Options with values in order of arguments, for options of `arities`, names and counts of values.
+/// They are collected by hand, as `getopts` doesn't keep order of different options
+/// and takes only one value of option
+pub(crate) fn ordered_options(
+    args: &[String],
+    arities: &[(&str, usize)],
+) -> Result<Vec<(String, Vec<String>)>> {
+    let mut result = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let name = match arg.strip_prefix("--").or(arg.strip_prefix('-')) {
+            Some(name) => name,
+            None => continue,
+        };
+        let count = match arities.iter().find(|(option, _)| *option == name) {
+            Some((_, count)) => *count,
+            None => continue,
+        };
+        let values: Vec<String> = args.by_ref().take(count).cloned().collect();
+        if values.len() < count {
+            return Err(Error::Text(format!("-{name} expects {count} arguments")));
+        }
+        result.push((name.to_string(), values));
+    }
+    Ok(result)
+}
@@ -1,6 +1,8 @@
+use crate::dsym;
+use crate::MachObject;
 use crate::ObjectType;
 use crate::Parser;
-use crate::Result;
+use crate::result::{Error, Result};
 use std::{path::Path};
 
 pub(crate) fn load_object_type_with(path: &str) -> Result<ObjectType> {
@@ -10,3 +12,63 @@ pub(crate) fn load_object_type_with(path: &str) -> Result<ObjectType> {
 
     Ok(object)
 }
+
+/// Parses hexadecimal (with `0x` prefix) or decimal address
+pub(crate) fn parse_address(string: &str) -> Result<u64> {
+    let string = string.trim();
+    let result = match string.strip_prefix("0x").or(string.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => string.parse::<u64>(),
+    };
+
+    result.map_err(|_| Error::Text(format!("Invalid address: {string}")))
+}
+
+/// Parses list like `_printf=0x1000,_malloc=0x2000`
+pub(crate) fn parse_symbol_addresses(string: &str) -> Result<Vec<(String, u64)>> {
+    let mut result = Vec::new();
+    for item in string.split(',').filter(|s| !s.trim().is_empty()) {
+        match item.split_once('=') {
+            Some((name, address)) => result.push((name.trim().to_string(), parse_address(address)?)),
+            None => return Err(Error::Text(format!("Expected NAME=ADDR, got {item}"))),
+        }
+    }
+    Ok(result)
+}
+
+/// Loads dSYM companion of `object`. `path` is either `.dSYM` bundle or DWARF file inside of it.
+/// Slice of fat dSYM is chosen by UUID, or by CPU type if there are no UUIDs.
+pub(crate) fn load_dsym_object(path: &str, object: &MachObject) -> Result<MachObject> {
+    let mut path = Path::new(path).to_path_buf();
+    if dsym::is_dsym_bundle(&path) {
+        let mut files = dsym::dwarf_files(&path)?;
+        path = match files.len() {
+            1 => files.remove(0),
+            _ => {
+                return Err(Error::Text(format!(
+                    "Expected single DWARF file in {}",
+                    path.join(dsym::DSYM_DWARF_DIR).display()
+                )))
+            }
+        };
+    }
+
+    let dsym = Parser::build(&path)?.parse()?;
+    let candidates = dsym.mach_objects();
+    let uuid = object.uuid();
+    let matched = match uuid {
+        Some(uuid) => candidates.iter().find(|o| o.uuid() == Some(uuid)),
+        None => candidates.iter().find(|o| {
+            o.header().cputype == object.header().cputype
+                && o.header().cpusubtype.masked() == object.header().cpusubtype.masked()
+        }),
+    };
+
+    match matched {
+        Some(matched) => Ok(matched.clone()),
+        None => Err(Error::Text(format!(
+            "No matching architecture in {}",
+            path.display()
+        ))),
+    }
+}
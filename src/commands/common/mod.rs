@@ -7,6 +7,9 @@ pub(super) use format::*;
 pub(super) mod object_filter;
 pub(super) use object_filter::*;
 
+pub(super) mod editing;
+pub(super) use editing::*;
+
 pub(super) mod help_string_builder;
 pub(super) mod options;
 
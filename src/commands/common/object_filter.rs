@@ -2,7 +2,7 @@ use getopts::*;
 
 use super::options::*;
 use crate::result::{Error, Result};
-use crate::{MachObject, ObjectType, FatArch};
+use crate::{MachHeader, MachObject, ObjectType, FatArch};
 
 const ARCH_ARG_SHORT: &str = "a";
 const ARCH_ARG_LONG: &str = "arch";
@@ -45,6 +45,17 @@ impl ObjectFilter {
         }
     }
 
+    /// Whether object with `header` passes the filter
+    pub(crate) fn matches(&self, header: &MachHeader) -> bool {
+        match &self.arch {
+            Some(arch) => match header.printable_cpu() {
+                Some(cpu) => cpu.to_string() == *arch,
+                None => false,
+            },
+            None => true,
+        }
+    }
+
     pub(crate) fn option_items() -> Vec<OptionItem> {
         vec![OptionItem {
             option_type: OptionType::Arg(IsRequired(false)),
@@ -41,6 +41,12 @@ impl AddToOptions for OptionItem {
                     Occur::Optional,
                 ),
             },
+            OptionType::MultiArg => opts.optmulti(
+                &self.name.short_or_empty(),
+                &self.name.long_or_empty(),
+                &self.description,
+                &self.hint,
+            ),
             OptionType::Flag(req) => match req.0 {
                 true => opts.optflag(
                     &self.name.short_or_empty(),
@@ -89,6 +95,8 @@ impl OptionItem {
 
 pub(crate) enum OptionType {
     Arg(IsRequired),
+    /// Optional argument, that may be given several times
+    MultiArg,
     Flag(IsRequired),
 }
 
@@ -96,6 +104,7 @@ impl OptionType {
     fn is_required(&self) -> bool {
         match self {
             OptionType::Arg(r) | OptionType::Flag(r) => r.0,
+            OptionType::MultiArg => false,
         }
     }
 }
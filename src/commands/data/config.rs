@@ -1,15 +1,26 @@
 use getopts::Options;
 
-use crate::{commands::common::{options::*}, result::Error};
+use crate::{commands::common::{options::*, helpers}, result::Error};
 
 const COMMON_ERROR: &str = "Unable to obtain section";
 
 const SECT_FLAG_SHORT: &str = "s";
 const SECT_FLAG_LONG: &str = "sect";
 
+const RELOCATE_ARG_LONG: &str = "relocate";
+const SYMS_ARG_LONG: &str = "syms";
+const GOT_ARG_LONG: &str = "got";
+
 pub(super) struct Config {
     pub(super) seg: String,
     pub(super) sect: String,
+    pub(super) relocation: Option<RelocationConfig>,
+}
+
+pub(super) struct RelocationConfig {
+    pub(super) base_address: u64,
+    pub(super) symbols: Vec<(String, u64)>,
+    pub(super) got: Vec<(String, u64)>,
 }
 
 impl Config {
@@ -24,11 +35,27 @@ impl Config {
             None => return Err(Error::Text(COMMON_ERROR.to_string())),
         };
 
+        let relocation = match matches.opt_str(RELOCATE_ARG_LONG) {
+            Some(address) => Some(RelocationConfig {
+                base_address: helpers::parse_address(&address)?,
+                symbols: match matches.opt_str(SYMS_ARG_LONG) {
+                    Some(list) => helpers::parse_symbol_addresses(&list)?,
+                    None => Vec::new(),
+                },
+                got: match matches.opt_str(GOT_ARG_LONG) {
+                    Some(list) => helpers::parse_symbol_addresses(&list)?,
+                    None => Vec::new(),
+                },
+            }),
+            None => None,
+        };
+
         match Self::search_after_sect_opt(args) {
             Ok((seg, sect)) => {
                 Ok(Self {
                     seg: seg.clone(),
                     sect: sect.clone(),
+                    relocation,
                 })
             },
             Err(e) => Err(e),
@@ -78,7 +105,32 @@ impl Config {
         ]
     }
 
+    fn relocation_option_items() -> Vec<OptionItem> {
+        vec![
+            OptionItem {
+                option_type: OptionType::Arg(IsRequired(false)),
+                name: OptionName::Long(RELOCATE_ARG_LONG.to_string()),
+                description: "Apply relocations (x86_64 and arm64 only) as if object linked at address".to_string(),
+                hint: "ADDR".to_string(),
+            },
+            OptionItem {
+                option_type: OptionType::Arg(IsRequired(false)),
+                name: OptionName::Long(SYMS_ARG_LONG.to_string()),
+                description: "Addresses of undefined symbols for relocation".to_string(),
+                hint: "NAME=ADDR,...".to_string(),
+            },
+            OptionItem {
+                option_type: OptionType::Arg(IsRequired(false)),
+                name: OptionName::Long(GOT_ARG_LONG.to_string()),
+                description: "Addresses of GOT slots of symbols for relocation".to_string(),
+                hint: "NAME=ADDR,...".to_string(),
+            },
+        ]
+    }
+
     pub(super) fn option_items() -> Vec<OptionItem> {
-        Self::required_option_items()
+        let mut items = Self::required_option_items();
+        items.append(&mut Self::relocation_option_items());
+        items
     }
 }
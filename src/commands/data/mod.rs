@@ -54,7 +54,7 @@ impl Handler for DataHandler {
                     false,
                 );
             }
-            self.handle_load_commands(obj.load_commands_iterator(), &config);
+            self.handle_mach_object(obj, &config)?;
         }
 
         Ok(())
@@ -68,39 +68,70 @@ impl Handler for DataHandler {
 }
 
 impl DataHandler {
-    fn handle_load_commands(&self, commands: LoadCommandIterator, config: &Config) {
-        let segs = commands.filter_map(|cmd| match cmd.variant {
-            LcVariant::Segment32(s) | LcVariant::Segment64(s) => {
-                if s.segname.to_string() == config.seg {
-                    Some(s)
-                } else {
-                    None
-                }
-            }
-            _ => None,
-        });
+    fn handle_mach_object(&self, obj: &MachObject, config: &Config) -> Result<()> {
+        let relocator = match &config.relocation {
+            Some(relocation) => Some(Self::build_relocator(obj, relocation)?),
+            None => None,
+        };
+
+        self.handle_load_commands(obj.load_commands_iterator(), config, relocator.as_ref())
+    }
 
-        let sections: Vec<Section> = segs.filter_map(|seg| {
-            seg.sections_iterator()
-                .find(|s| s.sectname.to_string() == config.sect)
-        }).collect();
+    fn build_relocator(obj: &MachObject, config: &RelocationConfig) -> Result<reloc::Relocator> {
+        let mut relocator = reloc::Relocator::build(obj, config.base_address)?;
+        for (name, address) in config.symbols.iter() {
+            relocator.set_symbol_address(name, *address);
+        }
+        for (name, address) in config.got.iter() {
+            relocator.set_got_address(name, *address);
+        }
+        Ok(relocator)
+    }
+
+    fn handle_load_commands(
+        &self,
+        commands: LoadCommandIterator,
+        config: &Config,
+        relocator: Option<&reloc::Relocator>,
+    ) -> Result<()> {
+        // Segment commands of object files (`MH_OBJECT`) are unnamed,
+        // so sections are matched by their own `segname`
+        let sections: Vec<Section> = commands
+            .filter_map(|cmd| match cmd.variant {
+                LcVariant::Segment32(s) | LcVariant::Segment64(s) => Some(s),
+                _ => None,
+            })
+            .filter_map(|seg| {
+                seg.sections_iterator().find(|s| {
+                    s.segname.to_string() == config.seg && s.sectname.to_string() == config.sect
+                })
+            })
+            .collect();
 
         if sections.len() == 0 {
             println!("{}\n", "Section not found".dimmed());
         }
 
         for sect in sections {
-            self.handle_section(sect);
+            self.handle_section(sect, relocator)?;
             print!("\n\n")
         }
+
+        Ok(())
     }
 
-    fn handle_section(&self, sect: Section) {
+    fn handle_section(&self, sect: Section, relocator: Option<&reloc::Relocator>) -> Result<()> {
         use crate::output::hex::*;
         println!("{} {}", sect.segname.to_string().yellow(), sect.sectname.to_string().yellow());
         if sect.size.0 == 0 {
             println!("{}\n", "No data in section".dimmed());
         }
-        dump_section(&sect).unwrap();
+        match relocator {
+            Some(relocator) => {
+                let data = relocator.apply(&sect)?;
+                dump_data(&data, relocator.relocated_address(&sect), sect.endian.is_little())
+            }
+            None => dump_section(&sect),
+        }
     }
 }
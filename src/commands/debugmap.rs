@@ -0,0 +1,128 @@
+use super::common;
+use super::common::options::*;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::debug_map::*;
+use crate::result::Error;
+use crate::*;
+use getopts::*;
+
+static SUBCOMM_NAME: &str = "debugmap";
+
+/// Width of keys column, the same as in `dsymutil -dump-debug-map`
+const KEY_WIDTH: usize = 17;
+
+pub(super) struct DebugMapHandler {
+    printer: Printer,
+}
+
+impl DebugMapHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+impl Handler for DebugMapHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Prints object files and symbols of STABS debug map as YAML, like dsymutil -dump-debug-map"
+            .to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let path = match opts.parse(&other_args) {
+            Ok(m) => match m.opt_str(common::PATH_OPT_SHORT) {
+                Some(path) => path,
+                None => m.free.first().cloned().unwrap_or_default(),
+            },
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        // Colors and arch titles would break YAML, so every arch is separate YAML document
+        for obj in filter.get_objects(object) {
+            let map = DebugMap::parse(&obj)?;
+            self.handle_debug_map(&map, &obj, &path);
+        }
+
+        Ok(())
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        default_option_items()
+    }
+}
+
+impl DebugMapHandler {
+    fn handle_debug_map(&self, map: &DebugMap, object: &MachObject, path: &str) {
+        let arch = match object.header().printable_cpu() {
+            Some(cpu) => cpu.to_string(),
+            None => "unknown".to_string(),
+        };
+
+        self.printer.print_line("---");
+        self.print_key_value("", "triple", &format!("'{arch}-apple-darwin'"));
+        self.print_key_value("", "binary-path", &yaml_scalar(path));
+        if !map.is_empty() {
+            self.printer.print_line("objects:");
+        }
+        for map_object in &map.objects {
+            self.print_key_value("  - ", "filename", &yaml_scalar(&map_object.path));
+            self.print_key_value("    ", "timestamp", &map_object.timestamp.to_string());
+            self.print_key_value("    ", "type", &nlist::constants::stab::N_OSO.to_string());
+            if map_object.symbols.is_empty() {
+                continue;
+            }
+            self.print_key_value("    ", "symbols", "");
+            for symbol in &map_object.symbols {
+                let address = match symbol.address {
+                    Some(address) => format!(", binAddr: {}", yaml_hex(address)),
+                    None => String::new(),
+                };
+                self.printer.print_line(format!(
+                    "      - {{ sym: {}{address}, size: {} }}",
+                    yaml_scalar(&symbol.name),
+                    yaml_hex(symbol.size.unwrap_or(0))
+                ));
+            }
+        }
+        self.printer.print_line("...");
+    }
+
+    /// `indent` is either spaces or spaces with dash of list item
+    fn print_key_value(&self, indent: &str, key: &str, value: &str) {
+        let key = format!("{key}:");
+        let line = format!("{indent}{key:<KEY_WIDTH$}{value}");
+        self.printer.print_line(line.trim_end());
+    }
+}
+
+/// Quotes string if it isn't safe as YAML plain scalar, like `-[Foo bar:]`
+fn yaml_scalar(string: &str) -> String {
+    let is_plain = !string.is_empty()
+        && string
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_./$".contains(c))
+        && !string.starts_with(|c: char| c.is_ascii_digit());
+    match is_plain {
+        true => string.to_string(),
+        false => format!("'{}'", string.replace('\'', "''")),
+    }
+}
+
+/// Hex values are in upper case with lower case prefix in `dsymutil` output
+fn yaml_hex(value: u64) -> String {
+    format!("0x{value:X}")
+}
@@ -0,0 +1,169 @@
+use super::common;
+use super::common::options::*;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::dependencies::*;
+use crate::imports::DylibLoadKind;
+use crate::result::Error;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+use std::path::{Path, PathBuf};
+
+static SUBCOMM_NAME: &str = "deps";
+
+const SYSROOT_ARG_LONG: &str = "sysroot";
+
+pub(super) struct DepsHandler {
+    printer: Printer,
+}
+
+impl DepsHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+struct Config {
+    path: PathBuf,
+    sysroot: PathBuf,
+}
+
+impl Config {
+    fn build(opts: &mut Options, args: &[String]) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        let path = match matches.opt_str(common::PATH_OPT_SHORT) {
+            Some(path) => path,
+            None => matches.free.first().cloned().unwrap_or_default(),
+        };
+        let sysroot = match matches.opt_str(SYSROOT_ARG_LONG) {
+            Some(sysroot) => PathBuf::from(sysroot),
+            None => return Err(Error::Text(format!("--{SYSROOT_ARG_LONG} is required"))),
+        };
+        if !sysroot.is_dir() {
+            return Err(Error::Text(format!(
+                "Sysroot {} is not a directory",
+                sysroot.display()
+            )));
+        }
+
+        Ok(Config {
+            path: PathBuf::from(path),
+            sysroot,
+        })
+    }
+}
+
+impl Handler for DepsHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Resolves dependent libraries recursively inside of sysroot and prints them as tree"
+            .to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let config = Config::build(&mut opts, &other_args)?;
+        let resolver = DependencyResolver::new(&config.sysroot);
+
+        let mut unresolved = 0;
+        let objects = &filter.get_objects(object);
+        let out_arch = objects.len() > 1;
+        for (idx, obj) in objects.iter().enumerate() {
+            if out_arch {
+                common::out_single_arch_title(&self.printer, obj.header(), idx, false);
+            }
+            let dependencies = resolver.resolve(obj, &config.path);
+            self.printer
+                .print_line(config.path.display().to_string().bright_white());
+            self.handle_dependencies(&dependencies, "");
+
+            unresolved += dependencies
+                .iter()
+                .flat_map(|dependency| dependency.unresolved())
+                .filter(|dependency| dependency.kind != DylibLoadKind::Weak)
+                .count();
+        }
+
+        match unresolved {
+            0 => Ok(()),
+            _ => Err(Error::Text(format!("{unresolved} libraries not resolved"))),
+        }
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(true)),
+            name: OptionName::Long(SYSROOT_ARG_LONG.to_string()),
+            description: "Directory, that absolute install names and rpaths are relative to"
+                .to_string(),
+            hint: "DIR".to_string(),
+        });
+        result
+    }
+}
+
+impl DepsHandler {
+    fn handle_dependencies(&self, dependencies: &[Dependency], prefix: &str) {
+        for (index, dependency) in dependencies.iter().enumerate() {
+            let is_last = index + 1 == dependencies.len();
+            let (branch, indent) = match is_last {
+                true => ("└── ", "    "),
+                false => ("├── ", "│   "),
+            };
+            self.printer.print_line(format!(
+                "{}{}",
+                format!("{prefix}{branch}").dimmed(),
+                self.dependency_line(dependency)
+            ));
+            self.handle_dependencies(&dependency.dependencies, &format!("{prefix}{indent}"));
+        }
+    }
+
+    fn dependency_line(&self, dependency: &Dependency) -> String {
+        let mut line = common::colored_path_string(&dependency.install_name);
+        if dependency.kind != DylibLoadKind::Load {
+            line += &format!(" ({})", dependency.kind).dimmed().to_string();
+        }
+
+        let path = |path: &Path| format!(" -> {}", path.display()).dimmed().to_string();
+        match &dependency.status {
+            DependencyStatus::Resolved(resolved) => line += &path(resolved),
+            DependencyStatus::Stub(stub) => {
+                line += &path(stub);
+                line += &" [text stub]".cyan().to_string();
+            }
+            DependencyStatus::Duplicate(duplicate) => {
+                line += &path(duplicate);
+                line += &" [listed above]".dimmed().to_string();
+            }
+            DependencyStatus::Unusable(unusable, reason) => {
+                line += &path(unusable);
+                line += &format!(" [{reason}]").red().to_string();
+            }
+            DependencyStatus::Unresolved => match dependency.kind {
+                DylibLoadKind::Weak => line += &" [not found, weak]".yellow().to_string(),
+                _ => line += &" [not found]".red().to_string(),
+            },
+        }
+        line
+    }
+}
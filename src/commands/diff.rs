@@ -0,0 +1,157 @@
+use super::common;
+use super::common::options::*;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::object_diff::*;
+use crate::result::Error;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+use std::path::PathBuf;
+
+static SUBCOMM_NAME: &str = "diff";
+
+const IGNORE_TIMESTAMPS_FLAG: &str = "ignore-timestamps";
+const IGNORE_UUID_FLAG: &str = "ignore-uuid";
+const IGNORE_SIGNATURE_FLAG: &str = "ignore-signature";
+
+pub(super) struct DiffHandler {
+    printer: Printer,
+}
+
+impl DiffHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+struct Config {
+    /// Path of second file, the first one is parsed by caller
+    other_path: PathBuf,
+    options: DiffOptions,
+}
+
+impl Config {
+    fn build(opts: &mut Options, args: &[String]) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        // Without `--path` the first free argument is the first file
+        let other_path = match matches.opt_str(common::PATH_OPT_SHORT) {
+            Some(_) => matches.free.first(),
+            None => matches.free.get(1),
+        };
+        let other_path = match other_path {
+            Some(path) => PathBuf::from(path),
+            None => return Err(Error::Text("No file to compare with".to_string())),
+        };
+
+        Ok(Config {
+            other_path,
+            options: DiffOptions {
+                ignore_timestamps: matches.opt_present(IGNORE_TIMESTAMPS_FLAG),
+                ignore_uuid: matches.opt_present(IGNORE_UUID_FLAG),
+                ignore_code_signature: matches.opt_present(IGNORE_SIGNATURE_FLAG),
+            },
+        })
+    }
+}
+
+impl Handler for DiffHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Compares structure of two files: header, load commands, segments, sections, dylibs, rpaths, symbols and contents of sections"
+            .to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let config = Config::build(&mut opts, &other_args)?;
+        let other = Parser::build(&config.other_path)?.parse()?;
+
+        let diff = ObjectDiff::compare(
+            &filter.get_objects(object),
+            &filter.get_objects(other),
+            &config.options,
+        )?;
+        self.handle_differences(&diff.differences);
+
+        match diff.differences.len() {
+            0 => Ok(()),
+            count => Err(Error::Text(format!("{count} differences found"))),
+        }
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        let flags = [
+            (
+                IGNORE_TIMESTAMPS_FLAG,
+                "Ignore timestamps of dylib commands and N_OSO stabs",
+            ),
+            (IGNORE_UUID_FLAG, "Ignore LC_UUID"),
+            (
+                IGNORE_SIGNATURE_FLAG,
+                "Ignore LC_CODE_SIGNATURE and sizes of __LINKEDIT",
+            ),
+        ];
+        for (name, description) in flags {
+            result.push(OptionItem {
+                option_type: OptionType::Flag(IsRequired(false)),
+                name: OptionName::Long(name.to_string()),
+                description: description.to_string(),
+                hint: "".to_string(),
+            });
+        }
+        result
+    }
+}
+
+impl DiffHandler {
+    fn handle_differences(&self, differences: &[Difference]) {
+        if differences.is_empty() {
+            self.printer.print_line("No differences".green());
+            return;
+        }
+
+        for difference in differences {
+            let arch = match &difference.arch {
+                Some(arch) => format!("[{}] ", arch.green()),
+                None => String::new(),
+            };
+            let scope = difference.scope.to_string().blue();
+            let line = match (&difference.field, &difference.old, &difference.new) {
+                (Some(field), Some(old), Some(new)) => {
+                    let name = match difference.name.is_empty() {
+                        true => String::new(),
+                        false => format!("{} ", difference.name.yellow()),
+                    };
+                    format!(
+                        "{} {arch}{scope} {name}{field}: {old} -> {new}",
+                        "~".yellow()
+                    )
+                }
+                _ if difference.is_removed() => {
+                    format!("{} {arch}{scope} {}", "-".red(), difference.name.red())
+                }
+                _ => format!("{} {arch}{scope} {}", "+".green(), difference.name.green()),
+            };
+            self.printer.print_line(line);
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use super::common;
+use super::common::helpers;
+use super::common::options::*;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::constants::SEG_TEXT;
+use crate::data_in_code::kind_name;
+use crate::disasm::*;
+use crate::result::Error;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+use std::ops::Range;
+
+static SUBCOMM_NAME: &str = "disasm";
+
+const SECT_ARG_SHORT: &str = "s";
+const SECT_ARG_LONG: &str = "sect";
+const SYMBOL_ARG_LONG: &str = "symbol";
+const START_ARG_LONG: &str = "start";
+const END_ARG_LONG: &str = "end";
+const NO_BYTES_FLAG: &str = "no-bytes";
+
+const DEFAULT_SECTNAME: &str = "__text";
+
+/// Width of mnemonic column
+const MNEMONIC_WIDTH: usize = 8;
+/// Raw bytes of longer instructions don't fit into the column
+const MAX_BYTES_COLUMN: usize = 8;
+
+pub(super) struct DisasmHandler {
+    printer: Printer,
+}
+
+impl DisasmHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+/// What part of the object to disassemble
+enum Target {
+    Section(String, String),
+    Symbol(String),
+    Range(u64, Option<u64>),
+}
+
+struct Config {
+    target: Target,
+    show_bytes: bool,
+}
+
+impl Config {
+    fn build(opts: &mut Options, args: &[String]) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        let start = match matches.opt_str(START_ARG_LONG) {
+            Some(address) => Some(helpers::parse_address(&address)?),
+            None => None,
+        };
+        let end = match matches.opt_str(END_ARG_LONG) {
+            Some(address) => Some(helpers::parse_address(&address)?),
+            None => None,
+        };
+
+        let target = match (
+            matches.opt_present(SECT_ARG_SHORT),
+            matches.opt_str(SYMBOL_ARG_LONG),
+            start,
+        ) {
+            (true, None, None) => {
+                let (segname, sectname) = Self::section_names(args)?;
+                Target::Section(segname, sectname)
+            }
+            (false, Some(symbol), None) => Target::Symbol(symbol),
+            (false, None, Some(start)) => Target::Range(start, end),
+            (false, None, None) => Target::Section(
+                SEG_TEXT.to_string(),
+                DEFAULT_SECTNAME.to_string(),
+            ),
+            _ => {
+                return Err(Error::Text(format!(
+                    "Only one of --{SECT_ARG_LONG}, --{SYMBOL_ARG_LONG} and --{START_ARG_LONG} may be given"
+                )))
+            }
+        };
+        if end.is_some() && start.is_none() {
+            return Err(Error::Text(format!(
+                "--{END_ARG_LONG} requires --{START_ARG_LONG}"
+            )));
+        }
+
+        Ok(Config {
+            target,
+            show_bytes: !matches.opt_present(NO_BYTES_FLAG),
+        })
+    }
+
+    /// Section option takes two values, the second one is a free argument for `getopts`
+    fn section_names(args: &[String]) -> Result<(String, String)> {
+        let short = format!("-{SECT_ARG_SHORT}");
+        let long = format!("--{SECT_ARG_LONG}");
+        let names = args
+            .iter()
+            .position(|arg| *arg == short || *arg == long)
+            .and_then(|pos| args.get(pos + 1..=pos + 2));
+        match names {
+            Some([segname, sectname]) if !sectname.starts_with('-') => {
+                Ok((segname.clone(), sectname.clone()))
+            }
+            _ => Err(Error::Text(
+                "Incorrect name pattern. Provide \"segname sectname\"".to_string(),
+            )),
+        }
+    }
+}
+
+impl Handler for DisasmHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Disassembles arm64 and x86 code, annotating referenced symbols, stubs and strings"
+            .to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let config = Config::build(&mut opts, &other_args)?;
+
+        let objects = &filter.get_objects(object);
+        let out_arch = objects.len() > 1;
+        for (idx, obj) in objects.iter().enumerate() {
+            if out_arch {
+                common::out_single_arch_title(&self.printer, obj.header(), idx, false);
+            }
+
+            let disassembler = Disassembler::build(obj)?;
+            let range = Self::range(obj, &disassembler, &config.target)?;
+            let instructions = disassembler.disassemble(range)?;
+            self.handle_instructions(&instructions, &disassembler, &config);
+        }
+
+        Ok(())
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::ShortLong(SECT_ARG_SHORT.to_string(), SECT_ARG_LONG.to_string()),
+            description: format!(
+                "Section to disassemble (default {SEG_TEXT} {DEFAULT_SECTNAME})"
+            ),
+            hint: "segname sectname".to_string(),
+        });
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::Long(SYMBOL_ARG_LONG.to_string()),
+            description: "Symbol to disassemble, up to the next symbol or function start"
+                .to_string(),
+            hint: "NAME".to_string(),
+        });
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::Long(START_ARG_LONG.to_string()),
+            description: "Start address of range to disassemble".to_string(),
+            hint: "ADDR".to_string(),
+        });
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::Long(END_ARG_LONG.to_string()),
+            description: "End address of range (default end of section)".to_string(),
+            hint: "ADDR".to_string(),
+        });
+        result.push(OptionItem {
+            option_type: OptionType::Flag(IsRequired(false)),
+            name: OptionName::Long(NO_BYTES_FLAG.to_string()),
+            description: "Don't display raw bytes of instructions".to_string(),
+            hint: "".to_string(),
+        });
+        result
+    }
+}
+
+impl DisasmHandler {
+    fn range(
+        object: &MachObject,
+        disassembler: &Disassembler,
+        target: &Target,
+    ) -> Result<Range<u64>> {
+        match target {
+            Target::Section(segname, sectname) => object
+                .sections()
+                .iter()
+                .find(|s| s.segname.to_string() == *segname && s.sectname.to_string() == *sectname)
+                .map(|s| s.addr.0..s.addr.0 + s.size.0)
+                .ok_or(Error::Text(format!("No section {segname},{sectname}"))),
+            Target::Symbol(name) => disassembler
+                .symbol_range(name)
+                .ok_or(Error::Text(format!("No symbol {name}"))),
+            Target::Range(start, end) => {
+                let section = match disassembler.section_range(*start) {
+                    Some(section) => section,
+                    None => return Err(Error::Text(format!("No section at {start:#x}"))),
+                };
+                let end = end.unwrap_or(section.end);
+                match end > *start && end <= section.end {
+                    true => Ok(*start..end),
+                    false => Err(Error::Text(format!(
+                        "Range should end after {start:#x} and not after end of its section {:#x}",
+                        section.end
+                    ))),
+                }
+            }
+        }
+    }
+
+    fn handle_instructions(
+        &self,
+        instructions: &[DisasmInstruction],
+        disassembler: &Disassembler,
+        config: &Config,
+    ) {
+        let bytes_width = instructions
+            .iter()
+            .map(|i| i.bytes.len().min(MAX_BYTES_COLUMN))
+            .max()
+            .unwrap_or(0)
+            * 3;
+
+        for instruction in instructions {
+            if let Some(label) = disassembler.label(instruction.address) {
+                self.printer.print_line(format!("{}:", label.green()));
+            }
+
+            let mut line = format!("{:#018x}", instruction.address).red().to_string();
+            if config.show_bytes {
+                let bytes: Vec<String> = instruction
+                    .bytes
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect();
+                line += &format!("  {:<bytes_width$}", bytes.join(" "))
+                    .dimmed()
+                    .to_string();
+            } else {
+                line += " ";
+            }
+            line += &format!(" {:<MNEMONIC_WIDTH$}", instruction.mnemonic)
+                .yellow()
+                .to_string();
+            line += &format!(" {}", instruction.operands);
+
+            let comment = match (instruction.data_kind, &instruction.annotation) {
+                (Some(kind), _) => Some(kind_name(kind)),
+                (None, Some(annotation)) => Some(annotation.to_string()),
+                (None, None) => None,
+            };
+            if let Some(comment) = comment {
+                line += &format!(" ; {comment}").cyan().to_string();
+            }
+
+            self.printer.print_line(line.trim_end());
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use super::common;
+use super::common::options::*;
+use super::common::Format;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::dsym::*;
+use crate::fmt_ext::printable_uuid_string;
+use crate::result::Error;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+use std::path::{Path, PathBuf};
+
+static SUBCOMM_NAME: &str = "dsym-match";
+
+pub(super) struct DsymMatchHandler {
+    printer: Printer,
+}
+
+impl DsymMatchHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+struct Config {
+    /// File name of the binary
+    name: String,
+    /// Directories to search `.dSYM` bundles in
+    dirs: Vec<PathBuf>,
+}
+
+impl Config {
+    fn build(opts: &mut Options, args: &[String]) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        // Without `--path` the first free argument is the path
+        let (path, dirs) = match matches.opt_str(common::PATH_OPT_SHORT) {
+            Some(path) => (path, &matches.free[..]),
+            None => match matches.free.split_first() {
+                Some((path, dirs)) => (path.clone(), dirs),
+                None => return Err(Error::Text("No binary to match".to_string())),
+            },
+        };
+
+        let path = Path::new(&path);
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => return Err(Error::Text(format!("Invalid path {}", path.display()))),
+        };
+        // dSYM is usually next to the binary
+        let dirs = match dirs.is_empty() {
+            true => vec![match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => PathBuf::from("."),
+            }],
+            false => dirs.iter().map(PathBuf::from).collect(),
+        };
+
+        Ok(Config { name, dirs })
+    }
+}
+
+impl Handler for DsymMatchHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Finds dSYM bundles matching every architecture of the binary by UUID".to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let format = &Format::build(&mut opts, &other_args)?;
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let config = Config::build(&mut opts, &other_args)?;
+
+        let objects = &filter.get_objects(object);
+        let slices = find_dsym_slices(&config.dirs)?;
+        let matches = match_dsyms(objects, &config.name, &slices);
+        for (index, dsym_match) in matches.iter().enumerate() {
+            self.handle_match(dsym_match, index, format);
+        }
+
+        let unmatched = matches.iter().filter(|m| !m.is_matched()).count();
+        match unmatched {
+            0 => Ok(()),
+            _ => Err(Error::Text(format!(
+                "{unmatched} of {} architectures without matching dSYM",
+                matches.len()
+            ))),
+        }
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        result.append(&mut Format::option_items());
+        result
+    }
+}
+
+impl DsymMatchHandler {
+    fn handle_match(&self, dsym_match: &DsymMatch, index: usize, format: &Format) {
+        if format.show_indices {
+            self.printer.out_list_item_dash(0, index);
+        }
+
+        let arch = dsym_match.arch.clone().unwrap_or("unknown".to_string());
+        let uuid = match &dsym_match.uuid {
+            Some(uuid) => printable_uuid_string(uuid),
+            None => "-".to_string(),
+        };
+        let status = match &dsym_match.status {
+            DsymMatchStatus::Matched(path) => {
+                format!("{} {}", "matched".green(), path.display())
+            }
+            DsymMatchStatus::Mismatched(path, other) => format!(
+                "{} {} ({})",
+                "mismatched".red(),
+                path.display(),
+                match other {
+                    Some(other) => printable_uuid_string(other),
+                    None => "no UUID".to_string(),
+                }
+            ),
+            DsymMatchStatus::Missing => "missing".red().to_string(),
+            DsymMatchStatus::NoUuid => "no UUID".red().to_string(),
+        };
+
+        match format.short {
+            true => self
+                .printer
+                .print_line(format!("{} {status}", arch.green())),
+            false => {
+                self.printer
+                    .print_line(format!("{} {} {status}", arch.green(), uuid.yellow()))
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use super::common::options::*;
+use super::common::{ordered_options, Editing, ObjectFilter};
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::edit::Edit;
+use crate::result::Error;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+static SUBCOMM_NAME: &str = "edit";
+
+const ID_ARG_LONG: &str = "id";
+const CHANGE_ARG_LONG: &str = "change";
+const ADD_RPATH_ARG_LONG: &str = "add_rpath";
+const DELETE_RPATH_ARG_LONG: &str = "delete_rpath";
+const RPATH_ARG_LONG: &str = "rpath";
+
+pub(super) struct EditHandler {
+    printer: Printer,
+}
+
+impl EditHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+/// Edits in order of arguments
+fn parse_edits(args: &[String]) -> Result<Vec<Edit>> {
+    let arities = [
+        (ID_ARG_LONG, 1),
+        (CHANGE_ARG_LONG, 2),
+        (ADD_RPATH_ARG_LONG, 1),
+        (DELETE_RPATH_ARG_LONG, 1),
+        (RPATH_ARG_LONG, 2),
+    ];
+    let mut edits = Vec::new();
+    for (name, values) in ordered_options(args, &arities)? {
+        let mut values = values.into_iter();
+        let mut next = || values.next().unwrap_or_default();
+        edits.push(match name.as_str() {
+            ID_ARG_LONG => Edit::SetId(next()),
+            CHANGE_ARG_LONG => Edit::ChangeDylib {
+                old: next(),
+                new: next(),
+            },
+            ADD_RPATH_ARG_LONG => Edit::AddRpath(next()),
+            DELETE_RPATH_ARG_LONG => Edit::DeleteRpath(next()),
+            _ => Edit::ChangeRpath {
+                old: next(),
+                new: next(),
+            },
+        });
+    }
+    if edits.is_empty() {
+        return Err(Error::Text("No edits given".to_string()));
+    }
+    Ok(edits)
+}
+
+impl Handler for EditHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Changes install name, dependent library names and rpaths like install_name_tool"
+            .to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        opts.long_only(true);
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let editing = Editing::build(&mut opts, &other_args, true)?;
+        let edits = parse_edits(&other_args)?;
+
+        editing.edit_images(&self.printer, &object, &filter, |image| {
+            for edit in &edits {
+                image.apply_edit(edit)?;
+                self.printer.print_line(edit_string(edit));
+            }
+            Ok(())
+        })
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        let args = [
+            (ID_ARG_LONG, "Set install name of dylib", "NAME"),
+            (
+                CHANGE_ARG_LONG,
+                "Change install name of dependent library",
+                "OLD NEW",
+            ),
+            (ADD_RPATH_ARG_LONG, "Add LC_RPATH", "PATH"),
+            (DELETE_RPATH_ARG_LONG, "Delete LC_RPATH", "PATH"),
+            (RPATH_ARG_LONG, "Change LC_RPATH", "OLD NEW"),
+        ];
+        for (name, description, hint) in args {
+            result.push(OptionItem {
+                option_type: OptionType::MultiArg,
+                name: OptionName::Long(name.to_string()),
+                description: description.to_string(),
+                hint: hint.to_string(),
+            });
+        }
+        result.append(&mut Editing::option_items(
+            "Write edited file to path instead of the input file",
+            true,
+        ));
+        result
+    }
+
+    fn accepts_single_dash_long_options(&self) -> bool {
+        true
+    }
+}
+
+fn edit_string(edit: &Edit) -> String {
+    match edit {
+        Edit::SetId(name) => format!("{} id {}", "~".yellow(), name.yellow()),
+        Edit::ChangeDylib { old, new } => {
+            format!("{} dylib {old} -> {}", "~".yellow(), new.yellow())
+        }
+        Edit::AddRpath(path) => format!("{} rpath {}", "+".green(), path.green()),
+        Edit::DeleteRpath(path) => format!("{} rpath {}", "-".red(), path.red()),
+        Edit::ChangeRpath { old, new } => {
+            format!("{} rpath {old} -> {}", "~".yellow(), new.yellow())
+        }
+    }
+}
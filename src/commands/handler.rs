@@ -19,6 +19,11 @@ pub(super) trait Handler {
     fn accepted_option_items(&self) -> Vec<OptionItem> {
         default_option_items()
     }
+
+    /// Long options may be given with single dash, like `-change`
+    fn accepts_single_dash_long_options(&self) -> bool {
+        false
+    }
 }
 
 pub(crate) fn default_options() -> Options {
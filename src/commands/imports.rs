@@ -0,0 +1,141 @@
+use super::common;
+use super::common::options::*;
+use super::common::Format;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::imports::*;
+use crate::result::Error;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+static SUBCOMM_NAME: &str = "imports";
+
+const DEMANGLE_FLAG: &str = "demangle";
+
+pub(super) struct ImportsHandler {
+    printer: Printer,
+}
+
+impl ImportsHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+impl Handler for ImportsHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Prints imported symbols grouped by libraries providing them".to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let format = &Format::build(&mut opts, &other_args)?;
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let demangle = match opts.parse(&other_args) {
+            Ok(m) => m.opt_present(DEMANGLE_FLAG),
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        let objects = &filter.get_objects(object);
+        let out_arch = objects.len() > 1;
+        for (idx, obj) in objects.iter().enumerate() {
+            if out_arch {
+                common::out_single_arch_title(&self.printer, obj.header(), idx, format.short);
+            }
+            let imports = Imports::parse(obj)?;
+            self.handle_imports(&imports, format, demangle);
+        }
+
+        Ok(())
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        result.append(&mut Format::option_items());
+        result.push(OptionItem {
+            option_type: OptionType::Flag(IsRequired(false)),
+            name: OptionName::Long(DEMANGLE_FLAG.to_string()),
+            description: "Demangle C++, Swift and Rust symbol names".to_string(),
+            hint: "".to_string(),
+        });
+        result
+    }
+}
+
+impl ImportsHandler {
+    fn handle_imports(&self, imports: &Imports, format: &Format, demangle: bool) {
+        let groups = imports.by_library();
+        if groups.is_empty() {
+            self.printer.print_line("No imported symbols".dimmed());
+            return;
+        }
+
+        for (library, symbols) in groups {
+            self.handle_library(library, symbols.len(), format);
+            for (index, symbol) in symbols.iter().enumerate() {
+                self.printer.print_string("  ");
+                if format.show_indices {
+                    self.printer.out_list_item_dash(0, index);
+                }
+                let name = match demangle {
+                    true => crate::demangle::demangle(&symbol.name).unwrap_or(symbol.name.clone()),
+                    false => symbol.name.clone(),
+                };
+                self.printer.print_string(name.yellow());
+                if symbol.is_weak {
+                    self.printer
+                        .print_string(format!(" {}", "(weak)".magenta()));
+                }
+                self.printer.print_line("");
+            }
+        }
+    }
+
+    fn handle_library(&self, library: &ImportLibrary, count: usize, format: &Format) {
+        let title = match library {
+            ImportLibrary::Dylib(_, dylib) => common::colored_path_string(&dylib.name),
+            library => library.to_string().cyan().to_string(),
+        };
+        self.printer.print_string(title);
+
+        if !format.short {
+            self.printer.print_colored_string(" (".bright_white());
+            match library {
+                ImportLibrary::Dylib(ordinal, dylib) => {
+                    self.printer
+                        .out_default_colored_field("Ordinal", &ordinal.to_string(), ", ");
+                    if dylib.kind != DylibLoadKind::Load {
+                        self.printer.out_default_colored_field(
+                            "Load",
+                            &dylib.kind.to_string(),
+                            ", ",
+                        );
+                    }
+                }
+                ImportLibrary::Invalid(ordinal) => {
+                    self.printer
+                        .out_default_colored_field("Ordinal", &ordinal.to_string(), ", ");
+                }
+                _ => (),
+            }
+            self.printer
+                .out_default_colored_field("Symbols", &count.to_string(), "");
+            self.printer.print_colored_string(")".bright_white());
+        }
+
+        self.printer.print_line(":");
+    }
+}
@@ -0,0 +1,223 @@
+use super::common;
+use super::common::options::*;
+use super::common::Editing;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::result::Error;
+use crate::writer::{FileImage, MachImage};
+use crate::*;
+use colored::*;
+use getopts::*;
+
+use std::path::{Path, PathBuf};
+
+static SUBCOMM_NAME: &str = "lipo";
+
+const CREATE_FLAG: &str = "create";
+const THIN_ARG_LONG: &str = "thin";
+const EXTRACT_ARG_LONG: &str = "extract";
+const REMOVE_ARG_LONG: &str = "remove";
+const REPLACE_ARG_LONG: &str = "replace";
+
+pub(super) struct LipoHandler {
+    printer: Printer,
+}
+
+impl LipoHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+enum Operation {
+    /// Universal file of all input files
+    Create,
+    Thin(String),
+    Extract(Vec<String>),
+    Remove(Vec<String>),
+    /// Architecture and file with the slice to replace it with
+    Replace(String, PathBuf),
+}
+
+struct Config {
+    /// All input files, the first one is parsed by caller
+    inputs: Vec<PathBuf>,
+    operation: Operation,
+}
+
+impl Config {
+    fn build(opts: &mut Options, args: &[String]) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        let mut inputs: Vec<PathBuf> = Vec::new();
+        if let Some(path) = matches.opt_str(common::PATH_OPT_SHORT) {
+            inputs.push(PathBuf::from(path));
+        }
+        inputs.extend(matches.free.iter().map(PathBuf::from));
+
+        let mut operations = Vec::new();
+        if matches.opt_present(CREATE_FLAG) {
+            operations.push(Operation::Create);
+        }
+        if let Some(arch) = matches.opt_str(THIN_ARG_LONG) {
+            operations.push(Operation::Thin(arch));
+        }
+        let archs = matches.opt_strs(EXTRACT_ARG_LONG);
+        if !archs.is_empty() {
+            operations.push(Operation::Extract(archs));
+        }
+        let archs = matches.opt_strs(REMOVE_ARG_LONG);
+        if !archs.is_empty() {
+            operations.push(Operation::Remove(archs));
+        }
+        if let Some(arch) = matches.opt_str(REPLACE_ARG_LONG) {
+            // The file follows the architecture
+            match inputs.get(1) {
+                Some(path) => operations.push(Operation::Replace(arch, path.clone())),
+                None => return Err(Error::Text(format!("No file to replace {arch} with"))),
+            }
+        }
+        if operations.len() != 1 {
+            return Err(Error::Text(format!(
+                "Exactly one of -{CREATE_FLAG}, -{THIN_ARG_LONG}, -{EXTRACT_ARG_LONG}, -{REMOVE_ARG_LONG} and -{REPLACE_ARG_LONG} is expected"
+            )));
+        }
+
+        Ok(Config {
+            inputs,
+            operation: operations.remove(0),
+        })
+    }
+}
+
+impl Handler for LipoHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Creates universal files from thin ones, thins, extracts, removes and replaces their slices like lipo"
+            .to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        opts.long_only(true);
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let config = Config::build(&mut opts, &other_args)?;
+        let editing = Editing::build(&mut opts, &other_args, false)?;
+        let file = FileImage::read(&object)?;
+
+        let result = match config.operation {
+            Operation::Create => {
+                let mut images = file.into_images();
+                for path in config.inputs.iter().skip(1) {
+                    images.append(&mut read_file(path)?.into_images());
+                }
+                FileImage::create(images)?
+            }
+            Operation::Thin(arch) => FileImage::Thin(file.thin(&arch)?),
+            Operation::Extract(archs) => file.extract(&archs)?,
+            Operation::Remove(archs) => file.remove(&archs)?,
+            Operation::Replace(arch, path) => {
+                let image = match arch_image(read_file(&path)?, &arch) {
+                    Some(image) => image,
+                    None => return Err(Error::Text(format!("{} has no {arch}", path.display()))),
+                };
+                file.replace(image)?
+            }
+        };
+
+        self.handle_result(&result);
+        editing.write(&self.printer, &result)
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        result.push(OptionItem {
+            option_type: OptionType::Flag(IsRequired(false)),
+            name: OptionName::Long(CREATE_FLAG.to_string()),
+            description: "Create universal file of all input files".to_string(),
+            hint: "".to_string(),
+        });
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::Long(THIN_ARG_LONG.to_string()),
+            description: "Write single architecture of universal file to thin file".to_string(),
+            hint: "ARCH".to_string(),
+        });
+        let args = [
+            (
+                EXTRACT_ARG_LONG,
+                "Write universal file with only given architectures",
+            ),
+            (
+                REMOVE_ARG_LONG,
+                "Write universal file without given architectures",
+            ),
+        ];
+        for (name, description) in args {
+            result.push(OptionItem {
+                option_type: OptionType::MultiArg,
+                name: OptionName::Long(name.to_string()),
+                description: description.to_string(),
+                hint: "ARCH".to_string(),
+            });
+        }
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::Long(REPLACE_ARG_LONG.to_string()),
+            description: "Replace slice of universal file with the one of given file".to_string(),
+            hint: "ARCH FILE".to_string(),
+        });
+        result.append(&mut Editing::option_items(
+            "Path of the resulting file",
+            false,
+        ));
+        result
+    }
+
+    fn accepts_single_dash_long_options(&self) -> bool {
+        true
+    }
+}
+
+impl LipoHandler {
+    fn handle_result(&self, file: &FileImage) {
+        match file {
+            FileImage::Thin(_) => {
+                for arch in file.arch_names() {
+                    self.printer.print_line(arch.green());
+                }
+            }
+            FileImage::Fat(slices, _) => {
+                for (arch, slice) in file.arch_names().iter().zip(slices) {
+                    self.printer.print_line(format!(
+                        "{} {}",
+                        arch.green(),
+                        format!("(align 2^{})", slice.align).dimmed()
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<FileImage> {
+    FileImage::read(&Parser::build(path)?.parse()?)
+}
+
+/// Image of `arch` of either thin or universal file
+fn arch_image(file: FileImage, arch: &str) -> Option<MachImage> {
+    let index = file.arch_names().iter().position(|name| name == arch)?;
+    Some(file.into_images().swap_remove(index))
+}
@@ -9,6 +9,23 @@ mod syms;
 mod rel;
 mod lc;
 mod data;
+mod objc_headers;
+mod swift;
+mod strings;
+mod atos;
+mod dsym_match;
+mod debugmap;
+mod disasm;
+mod imports;
+mod deps;
+mod check_links;
+mod abi_diff;
+mod diff;
+mod size;
+mod edit;
+mod lipo;
+mod strip;
+mod vtool;
 
 mod common;
 
@@ -31,6 +48,23 @@ use syms::*;
 use rel::*;
 use lc::*;
 use data::*;
+use objc_headers::*;
+use swift::*;
+use strings::*;
+use atos::*;
+use dsym_match::*;
+use debugmap::*;
+use disasm::*;
+use imports::*;
+use deps::*;
+use check_links::*;
+use abi_diff::*;
+use diff::*;
+use size::*;
+use edit::*;
+use lipo::*;
+use strip::*;
+use vtool::*;
 
 use std::process::exit;
 
@@ -49,6 +83,9 @@ pub fn handle_with_args() -> Result<()> {
 
     let mut opts = Options::new();
     option_items.add_to_opts(&mut opts);
+    if let Some(handler) = &handler {
+        opts.long_only(handler.accepts_single_dash_long_options());
+    }
 
     let help_request = match command_name {
         Some(command_name) => Some(HelpStringRequest(command_name.clone(), description, &mut option_items)),
@@ -165,5 +202,22 @@ fn available_handlers() -> Vec<Box<dyn Handler>> {
         Box::new(RelHandler::new(printer.clone())),
         Box::new(LcHandler::new(printer.clone())),
         Box::new(DataHandler::new(printer.clone())),
+        Box::new(ObjcHeadersHandler::new(printer.clone())),
+        Box::new(SwiftHandler::new(printer.clone())),
+        Box::new(StringsHandler::new(printer.clone())),
+        Box::new(AtosHandler::new(printer.clone())),
+        Box::new(DsymMatchHandler::new(printer.clone())),
+        Box::new(DebugMapHandler::new(printer.clone())),
+        Box::new(DisasmHandler::new(printer.clone())),
+        Box::new(ImportsHandler::new(printer.clone())),
+        Box::new(DepsHandler::new(printer.clone())),
+        Box::new(CheckLinksHandler::new(printer.clone())),
+        Box::new(AbiDiffHandler::new(printer.clone())),
+        Box::new(DiffHandler::new(printer.clone())),
+        Box::new(SizeHandler::new(printer.clone())),
+        Box::new(EditHandler::new(printer.clone())),
+        Box::new(LipoHandler::new(printer.clone())),
+        Box::new(StripHandler::new(printer.clone())),
+        Box::new(VtoolHandler::new(printer.clone())),
     ]
 }
@@ -0,0 +1,39 @@
+use getopts::Options;
+use std::path::PathBuf;
+
+use crate::commands::common::options::*;
+use crate::result::{Error, Result};
+
+const OUTPUT_DIR_ARG_SHORT: &str = "o";
+const OUTPUT_DIR_ARG_LONG: &str = "output-dir";
+
+pub(super) struct Config {
+    /// Directory for headers, one per class, category and protocol.
+    /// Single combined header is printed if `None`.
+    pub(super) output_dir: Option<PathBuf>,
+}
+
+impl Config {
+    pub(super) fn build(opts: &mut Options, args: &[String]) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        Ok(Self {
+            output_dir: matches.opt_str(OUTPUT_DIR_ARG_SHORT).map(PathBuf::from),
+        })
+    }
+
+    pub(super) fn option_items() -> Vec<OptionItem> {
+        vec![OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::ShortLong(
+                OUTPUT_DIR_ARG_SHORT.to_string(),
+                OUTPUT_DIR_ARG_LONG.to_string(),
+            ),
+            description: "Write separate header for each class, category and protocol into directory".to_string(),
+            hint: "DIR".to_string(),
+        }]
+    }
+}
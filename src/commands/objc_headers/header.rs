@@ -0,0 +1,250 @@
+//! Reconstruction of `@interface` and `@protocol` declarations from ObjC metadata
+
+use crate::objc::encoding::ObjcType;
+use crate::objc::*;
+
+use std::collections::{BTreeSet, HashSet};
+
+const INDENT: &str = "    ";
+
+/// Name used for categories of classes which can't be resolved
+pub(super) const UNKNOWN_CLASS_NAME: &str = "UnknownClass";
+
+/// Accumulates declarations and names of classes and protocols they reference
+#[derive(Default)]
+pub(super) struct HeaderWriter {
+    pub(super) text: String,
+    pub(super) classes: BTreeSet<String>,
+    pub(super) protocols: BTreeSet<String>,
+}
+
+impl HeaderWriter {
+    pub(super) fn write_class(&mut self, class: &ObjcClass) {
+        let mut title = format!("@interface {}", class.name);
+        if let Some(superclass) = &class.superclass {
+            title += &format!(" : {superclass}");
+        }
+        let title = title + &self.adopted_protocols(&class.protocols);
+        self.write_line(&title);
+
+        if !class.ivars.is_empty() {
+            self.write_line("{");
+            for ivar in &class.ivars {
+                let line = match ObjcType::parse(&ivar.type_encoding) {
+                    Ok(ivar_type) => {
+                        self.add_references(&ivar_type);
+                        format!("{};", ivar_type.declaration(&ivar.name))
+                    }
+                    Err(_) => format!("void *{}; // {}", ivar.name, ivar.type_encoding),
+                };
+                self.write_line(&format!("{INDENT}{line}"));
+            }
+            self.write_line("}");
+        }
+
+        self.write_members(
+            &class.properties,
+            &class.class_properties,
+            &class.instance_methods,
+            &class.class_methods,
+        );
+        self.write_line("@end");
+    }
+
+    pub(super) fn write_category(&mut self, category: &ObjcCategory) {
+        let class_name = category.class_name.as_deref().unwrap_or(UNKNOWN_CLASS_NAME);
+        let title = format!("@interface {class_name} ({})", category.name);
+        let title = title + &self.adopted_protocols(&category.protocols);
+        self.write_line(&title);
+
+        self.write_members(
+            &category.properties,
+            &category.class_properties,
+            &category.instance_methods,
+            &category.class_methods,
+        );
+        self.write_line("@end");
+    }
+
+    pub(super) fn write_protocol(&mut self, protocol: &ObjcProtocol) {
+        let title = format!("@protocol {}", protocol.name);
+        let title = title + &self.adopted_protocols(&protocol.protocols);
+        self.write_line(&title);
+
+        self.write_members(
+            &protocol.properties,
+            &protocol.class_properties,
+            &protocol.instance_methods,
+            &protocol.class_methods,
+        );
+
+        if !protocol.optional_class_methods.is_empty()
+            || !protocol.optional_instance_methods.is_empty()
+        {
+            self.write_line("@optional");
+            self.write_methods(&protocol.optional_class_methods, '+', &HashSet::new());
+            self.write_methods(&protocol.optional_instance_methods, '-', &HashSet::new());
+            self.write_line("");
+        }
+        self.write_line("@end");
+    }
+
+    pub(super) fn write_line(&mut self, line: &str) {
+        self.text += line;
+        self.text += "\n";
+    }
+}
+
+impl HeaderWriter {
+    fn adopted_protocols(&mut self, protocols: &[String]) -> String {
+        if protocols.is_empty() {
+            return "".to_string();
+        }
+        self.protocols.extend(protocols.iter().cloned());
+        format!(" <{}>", protocols.join(", "))
+    }
+
+    fn add_references(&mut self, objc_type: &ObjcType) {
+        self.classes.extend(objc_type.class_names());
+        self.protocols.extend(objc_type.protocol_names());
+    }
+
+    /// Writes properties and methods, except accessors of the properties
+    fn write_members(
+        &mut self,
+        properties: &[ObjcProperty],
+        class_properties: &[ObjcProperty],
+        instance_methods: &[ObjcMethod],
+        class_methods: &[ObjcMethod],
+    ) {
+        let mut accessors = HashSet::new();
+        let mut class_accessors = HashSet::new();
+
+        if !properties.is_empty() || !class_properties.is_empty() {
+            self.write_line("");
+            for property in class_properties {
+                self.write_property(property, true, &mut class_accessors);
+            }
+            for property in properties {
+                self.write_property(property, false, &mut accessors);
+            }
+        }
+
+        let has_class_methods = class_methods.iter().any(|m| !class_accessors.contains(&m.name));
+        let has_instance_methods = instance_methods.iter().any(|m| !accessors.contains(&m.name));
+        if has_class_methods || has_instance_methods {
+            self.write_line("");
+            self.write_methods(class_methods, '+', &class_accessors);
+            self.write_methods(instance_methods, '-', &accessors);
+        }
+        self.write_line("");
+    }
+
+    fn write_methods(&mut self, methods: &[ObjcMethod], kind: char, excluded: &HashSet<String>) {
+        for method in methods.iter().filter(|m| !excluded.contains(&m.name)) {
+            let line = self.method_declaration(method, kind);
+            self.write_line(&line);
+        }
+    }
+
+    fn method_declaration(&mut self, method: &ObjcMethod, kind: char) -> String {
+        // Extended types are more precise, but fall back to plain ones if they can't be parsed
+        let types = method
+            .extended_types
+            .as_ref()
+            .and_then(|types| ObjcType::parse_method(types).ok())
+            .or_else(|| ObjcType::parse_method(&method.types).ok())
+            .unwrap_or_default();
+        types.iter().for_each(|t| self.add_references(t));
+
+        // Implicit `self` and `_cmd` arguments go right after return type
+        let type_name = |index: usize| match types.get(index) {
+            Some(t) => t.declaration(""),
+            None => "id".to_string(),
+        };
+
+        if !method.name.contains(':') {
+            return format!("{kind} ({}){};", type_name(0), method.name);
+        }
+
+        let parts: Vec<String> = method
+            .name
+            .split_terminator(':')
+            .enumerate()
+            .map(|(index, part)| format!("{part}:({})arg{}", type_name(index + 3), index + 1))
+            .collect();
+        format!("{kind} ({}){};", type_name(0), parts.join(" "))
+    }
+
+    fn write_property(
+        &mut self,
+        property: &ObjcProperty,
+        is_class: bool,
+        accessors: &mut HashSet<String>,
+    ) {
+        let mut attributes = Vec::new();
+        let mut property_type = None;
+        let mut is_readonly = false;
+        let mut getter = property.name.clone();
+        let mut setter = None;
+        let mut is_dynamic = false;
+
+        if is_class {
+            attributes.push("class".to_string());
+        }
+        for attribute in property.attributes.split(',') {
+            let (code, value) = attribute.split_at(attribute.len().min(1));
+            match code {
+                "T" => property_type = ObjcType::parse(value).ok(),
+                "R" => is_readonly = true,
+                "C" => attributes.push("copy".to_string()),
+                "&" => attributes.push("retain".to_string()),
+                "W" => attributes.push("weak".to_string()),
+                "N" => attributes.push("nonatomic".to_string()),
+                "G" => {
+                    getter = value.to_string();
+                    attributes.push(format!("getter={value}"));
+                }
+                "S" => {
+                    setter = Some(value.to_string());
+                    attributes.push(format!("setter={value}"));
+                }
+                "D" => is_dynamic = true,
+                _ => (),
+            }
+        }
+        if is_readonly {
+            attributes.insert(is_class as usize, "readonly".to_string());
+        }
+
+        accessors.insert(getter);
+        if !is_readonly {
+            accessors.insert(setter.unwrap_or_else(|| setter_name(&property.name)));
+        }
+
+        let declaration = match property_type {
+            Some(property_type) => {
+                self.add_references(&property_type);
+                property_type.declaration(&property.name)
+            }
+            None => format!("id {}", property.name),
+        };
+        let attributes = match attributes.is_empty() {
+            true => "".to_string(),
+            false => format!("({})", attributes.join(", ")),
+        };
+        let comment = match is_dynamic {
+            true => " // @dynamic",
+            false => "",
+        };
+        self.write_line(&format!("@property{attributes} {declaration};{comment}"));
+    }
+}
+
+fn setter_name(property_name: &str) -> String {
+    let mut chars = property_name.chars();
+    match chars.next() {
+        Some(first) => format!("set{}{}:", first.to_uppercase(), chars.as_str()),
+        None => "set:".to_string(),
+    }
+}
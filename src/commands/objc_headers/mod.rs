@@ -0,0 +1,239 @@
+use super::common;
+use super::common::options::AddToOptions;
+use super::common::ObjectFilter;
+use super::handler;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::objc::*;
+use crate::*;
+
+mod config;
+mod header;
+use config::*;
+use getopts::Options;
+use header::*;
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+static SUBCOMM_NAME: &str = "objc-headers";
+
+const GENERATED_COMMENT: &str = "// Generated by schnauzer";
+
+pub(super) struct ObjcHeadersHandler {
+    printer: Printer,
+}
+
+impl ObjcHeadersHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+impl Handler for ObjcHeadersHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Reconstructs Objective-C headers".to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+        let config = Config::build(&mut opts, &other_args)?;
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+
+        let objects = &filter.get_objects(object);
+        let out_arch = objects.len() > 1;
+        for (idx, obj) in objects.iter().enumerate() {
+            if out_arch {
+                common::out_single_arch_title(&self.printer, obj.header(), idx, false);
+            }
+
+            let metadata = ObjcMetadata::parse(obj)?;
+            match &config.output_dir {
+                // Headers of different archs may differ, so each gets own directory
+                Some(dir) if out_arch => {
+                    let arch = match obj.header().printable_cpu() {
+                        Some(cpu) => cpu.to_string(),
+                        None => idx.to_string(),
+                    };
+                    self.write_headers(&metadata, &dir.join(arch))?;
+                }
+                Some(dir) => self.write_headers(&metadata, dir)?,
+                None => self.printer.print_string(combined_header(&metadata)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn accepted_option_items(&self) -> Vec<common::options::OptionItem> {
+        let mut items = handler::default_option_items();
+        items.append(&mut Config::option_items());
+        items
+    }
+}
+
+impl ObjcHeadersHandler {
+    fn write_headers(&self, metadata: &ObjcMetadata, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir)?;
+
+        let mut files = Vec::new();
+        for protocol in &metadata.protocols {
+            let mut writer = HeaderWriter::default();
+            writer.write_protocol(protocol);
+            let imports = protocol.protocols.iter().map(|p| protocol_file_name(p)).collect();
+            files.push((protocol_file_name(&protocol.name), writer, imports));
+        }
+        for class in &metadata.classes {
+            let mut writer = HeaderWriter::default();
+            writer.write_class(class);
+            writer.classes.remove(&class.name);
+            let mut imports: Vec<String> =
+                class.superclass.iter().map(|c| class_file_name(c)).collect();
+            imports.extend(class.protocols.iter().map(|p| protocol_file_name(p)));
+            files.push((class_file_name(&class.name), writer, imports));
+        }
+        for category in &metadata.categories {
+            let mut writer = HeaderWriter::default();
+            writer.write_category(category);
+            let class_name = category.class_name.as_deref().unwrap_or(UNKNOWN_CLASS_NAME);
+            writer.classes.remove(class_name);
+            let mut imports = vec![class_file_name(class_name)];
+            imports.extend(category.protocols.iter().map(|p| protocol_file_name(p)));
+            let file_name = sanitized_file_name(&format!("{class_name}+{}.h", category.name));
+            files.push((file_name, writer, imports));
+        }
+
+        for (file_name, writer, imports) in files {
+            let mut text = format!("{GENERATED_COMMENT}\n\n");
+            for import in &imports {
+                text += &format!("#import \"{import}\"\n");
+            }
+            if !imports.is_empty() {
+                text += "\n";
+            }
+
+            // Adopted protocols are imported, the rest of references are forward declared
+            let classes = writer
+                .classes
+                .iter()
+                .filter(|c| !imports.contains(&class_file_name(c)));
+            let protocols = writer
+                .protocols
+                .iter()
+                .filter(|p| !imports.contains(&protocol_file_name(p)))
+                .collect();
+            text += &forward_declarations(classes, protocols);
+            text += &writer.text;
+
+            let path = dir.join(&file_name);
+            std::fs::write(&path, text)?;
+            self.printer.print_line(common::colored_path_string(path.display()));
+        }
+
+        Ok(())
+    }
+}
+
+/// All declarations of the image in single header
+fn combined_header(metadata: &ObjcMetadata) -> String {
+    let mut writer = HeaderWriter::default();
+
+    for protocol in ordered(&metadata.protocols, |p| &p.name, |p| p.protocols.clone()) {
+        writer.write_protocol(protocol);
+        writer.write_line("");
+    }
+    let classes = ordered(&metadata.classes, |c| &c.name, |c| c.superclass.iter().cloned().collect());
+    for class in classes {
+        writer.write_class(class);
+        writer.write_line("");
+    }
+    for category in &metadata.categories {
+        writer.write_category(category);
+        writer.write_line("");
+    }
+
+    let defined_classes: HashSet<&String> = metadata.classes.iter().map(|c| &c.name).collect();
+    let defined_protocols: HashSet<&String> = metadata.protocols.iter().map(|p| &p.name).collect();
+    let classes = writer.classes.iter().filter(|c| !defined_classes.contains(c));
+    let protocols = writer.protocols.iter().filter(|p| !defined_protocols.contains(p)).collect();
+
+    format!(
+        "{GENERATED_COMMENT}\n\n{}{}",
+        forward_declarations(classes, protocols),
+        writer.text
+    )
+}
+
+fn forward_declarations<'a>(
+    classes: impl Iterator<Item = &'a String>,
+    protocols: BTreeSet<&String>,
+) -> String {
+    let mut result = String::new();
+    let classes: Vec<&str> = classes.map(|c| c.as_str()).collect();
+    if !classes.is_empty() {
+        result += &format!("@class {};\n", classes.join(", "));
+    }
+    let protocols: Vec<&str> = protocols.into_iter().map(|p| p.as_str()).collect();
+    if !protocols.is_empty() {
+        result += &format!("@protocol {};\n", protocols.join(", "));
+    }
+    if !result.is_empty() {
+        result += "\n";
+    }
+    result
+}
+
+/// Orders items so that dependencies defined in the same image go first, like superclasses
+fn ordered<T>(
+    items: &[T],
+    name: impl Fn(&T) -> &String,
+    dependencies: impl Fn(&T) -> Vec<String>,
+) -> Vec<&T> {
+    fn visit<'a, T>(
+        index: usize,
+        items: &'a [T],
+        name: &impl Fn(&T) -> &String,
+        dependencies: &impl Fn(&T) -> Vec<String>,
+        visited: &mut HashSet<usize>,
+        result: &mut Vec<&'a T>,
+    ) {
+        if !visited.insert(index) {
+            return;
+        }
+        for dependency in dependencies(&items[index]) {
+            if let Some(dependency) = items.iter().position(|item| *name(item) == dependency) {
+                visit(dependency, items, name, dependencies, visited, result);
+            }
+        }
+        result.push(&items[index]);
+    }
+
+    let mut visited = HashSet::new();
+    let mut result = Vec::new();
+    for index in 0..items.len() {
+        visit(index, items, &name, &dependencies, &mut visited, &mut result);
+    }
+    result
+}
+
+fn class_file_name(class_name: &str) -> String {
+    sanitized_file_name(&format!("{class_name}.h"))
+}
+
+fn protocol_file_name(protocol_name: &str) -> String {
+    sanitized_file_name(&format!("{protocol_name}-Protocol.h"))
+}
+
+fn sanitized_file_name(name: &str) -> String {
+    name.replace(['/', '\\', ':'], "_")
+}
@@ -1,5 +1,5 @@
 use super::common;
-use super::common::options::AddToOptions;
+use super::common::options::*;
 use super::common::ObjectFilter;
 use super::handler::*;
 use super::Printer;
@@ -10,6 +10,8 @@ use getopts::*;
 
 static SUBCOMM_NAME: &str = "rel";
 
+const RAW_FLAG: &str = "raw";
+
 pub(super) struct RelHandler {
     pub(super) printer: Printer,
 }
@@ -38,6 +40,10 @@ impl Handler for RelHandler {
         self.accepted_option_items().add_to_opts(&mut opts);
 
         let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let raw = match opts.parse(&other_args) {
+            Ok(m) => m.opt_present(RAW_FLAG),
+            Err(f) => return Err(crate::result::Error::Text(f.to_string())),
+        };
 
         let objects = &filter.get_objects(object);
         let out_arch = objects.len() > 1;
@@ -45,15 +51,67 @@ impl Handler for RelHandler {
             if out_arch {
                 common::out_single_arch_title(&self.printer, &obj.header(), idx, false);
             }
-            self.handle_load_commands(obj.load_commands_iterator());
+            match raw {
+                true => self.handle_load_commands(obj.load_commands_iterator(), obj.header().cputype),
+                false => self.handle_resolved(obj)?,
+            }
         }
 
         Ok(())
     }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        result.push(OptionItem {
+            option_type: OptionType::Flag(IsRequired(false)),
+            name: OptionName::Long(RAW_FLAG.to_string()),
+            description: "Display raw entries instead of resolved targets".to_string(),
+            hint: "".to_string(),
+        });
+        result
+    }
 }
 
 impl RelHandler {
-    fn handle_load_commands(&self, commands: LoadCommandIterator) {
+    fn handle_resolved(&self, obj: &MachObject) -> Result<()> {
+        use output::table::FixedTabLine;
+
+        let resolver = reloc::RelocationResolver::build(obj)?;
+        let line: FixedTabLine<3> = FixedTabLine::new([11, 20, 0]);
+
+        for section in obj.sections().iter().filter(|s| s.nreloc > 0) {
+            self.print_section_title(section);
+
+            for reloc in resolver.resolve(section)? {
+                line.print_line(
+                    [
+                        format!("{:#010x}", reloc.address),
+                        reloc.r_type.to_string(),
+                        reloc.target_string(),
+                    ],
+                    vec![Color::Red, Color::Blue, Color::Yellow],
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn print_section_title(&self, section: &Section) {
+        self.printer.print_strings(
+            vec![
+                section.segname.to_string().green(),
+                section.sectname.to_string().green(),
+            ],
+            " ",
+        );
+
+        self.printer.print_line(
+            format!(" ({} entries)", section.nreloc.to_string().blue()).bright_white(),
+        );
+    }
+
+    fn handle_load_commands(&self, commands: LoadCommandIterator, cputype: CPUType) {
         let commands = commands.filter(|cmd| match cmd.variant {
             LcVariant::Segment32(_) | LcVariant::Segment64(_) => true,
             _ => false,
@@ -61,41 +119,40 @@ impl RelHandler {
         for cmd in commands {
             match cmd.variant {
                 LcVariant::Segment32(seg) | LcVariant::Segment64(seg) => {
-                    self.handle_segment_command(seg);
+                    self.handle_segment_command(seg, cputype);
                 }
                 _ => (),
             }
         }
     }
 
-    fn handle_segment_command(&self, seg: LcSegment) {
+    fn handle_segment_command(&self, seg: LcSegment, cputype: CPUType) {
         use output::table::FixedTabLine;
 
-        let line: FixedTabLine<7> = FixedTabLine::new([9, 6, 7, 7, 5, 10, 16]);
+        let line: FixedTabLine<7> = FixedTabLine::new([9, 6, 7, 7, 20, 10, 16]);
 
         for section in seg.sections_iterator().filter(|s| s.nreloc > 0) {
-            self.printer.print_strings(
-                vec![
-                    section.segname.to_string().green(),
-                    section.sectname.to_string().green(),
-                ],
-                " ",
-            );
-
-            self.printer.print_line(
-                format!(" ({} entries)", section.nreloc.to_string().blue()).bright_white(),
-            );
+            self.print_section_title(&section);
 
             line.print_line(["address", "pcrel", "length", "extern", "type", "scattered", "symbolnum/value"], vec![Color::White]);
             for reloc in section.relocations_iterator() {
+                let (r_extern, value) = match &reloc {
+                    reloc::Relocation::Plain(info) => {
+                        (info.r_extern().to_string(), info.r_symbolnum().to_string())
+                    }
+                    reloc::Relocation::Scattered(info) => {
+                        ("-".to_string(), format!("{:#x}", info.r_value))
+                    }
+                };
+
                 line.print_line([
-                    format!("{:08x}", reloc.r_address),
+                    format!("{:08x}", reloc.r_address()),
                     reloc.r_pcrel().to_string(),
                     reloc.r_length().to_string(),
-                    reloc.r_extern().to_string(),
-                    reloc.r_type().to_string(),
+                    r_extern,
+                    reloc.relocation_type(cputype).to_string(),
                     reloc.is_scattered().to_string(),
-                    reloc.r_symbolnum().to_string(),
+                    value,
                 ],
                 vec![Color::Red, Color::Yellow]
             );
@@ -0,0 +1,250 @@
+use super::common;
+use super::common::options::*;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::abi::arch_name;
+use crate::result::Error;
+use crate::size_report::*;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+use std::path::Path;
+
+static SUBCOMM_NAME: &str = "size";
+
+const TOP_ARG_LONG: &str = "top";
+const SORT_ARG_LONG: &str = "sort";
+const BASELINE_ARG_LONG: &str = "baseline";
+
+const SORT_FILE: &str = "file";
+const SORT_VM: &str = "vm";
+const SORT_NAME: &str = "name";
+
+pub(super) struct SizeHandler {
+    printer: Printer,
+}
+
+impl SizeHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+struct Config {
+    /// Maximum number of rows of each table
+    top: Option<usize>,
+    sort: SizeSort,
+    baseline: Option<ObjectType>,
+}
+
+impl Config {
+    fn build(opts: &mut Options, args: &[String]) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        let top = match matches.opt_str(TOP_ARG_LONG) {
+            Some(top) => match top.parse::<usize>() {
+                Ok(top) => Some(top),
+                Err(_) => return Err(Error::Text(format!("Invalid number of rows: {top}"))),
+            },
+            None => None,
+        };
+        let sort = match matches.opt_str(SORT_ARG_LONG).as_deref() {
+            None | Some(SORT_FILE) => SizeSort::FileSize,
+            Some(SORT_VM) => SizeSort::VmSize,
+            Some(SORT_NAME) => SizeSort::Name,
+            Some(other) => return Err(Error::Text(format!("Unknown sort order: {other}"))),
+        };
+        let baseline = match matches.opt_str(BASELINE_ARG_LONG) {
+            Some(path) => Some(Parser::build(Path::new(&path))?.parse()?),
+            None => None,
+        };
+
+        Ok(Config {
+            top,
+            sort,
+            baseline,
+        })
+    }
+}
+
+impl Handler for SizeHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Attributes file and VM bytes to segments, sections, symbols and object files of debug map"
+            .to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let config = Config::build(&mut opts, &other_args)?;
+
+        let objects = &filter.get_objects(object);
+        let out_arch = objects.len() > 1;
+        for (idx, obj) in objects.iter().enumerate() {
+            if out_arch {
+                common::out_single_arch_title(&self.printer, obj.header(), idx, false);
+            }
+            let report = SizeReport::parse(obj)?;
+            match &config.baseline {
+                Some(baseline) => {
+                    let baseline = baseline_object(baseline, obj)?;
+                    let baseline = SizeReport::parse(&baseline)?;
+                    self.handle_diff(&baseline, &report, &config);
+                }
+                None => self.handle_report(report, &config),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::Long(TOP_ARG_LONG.to_string()),
+            description: "Print only N largest rows of each table".to_string(),
+            hint: "N".to_string(),
+        });
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::Long(SORT_ARG_LONG.to_string()),
+            description: format!(
+                "Sort rows by {SORT_FILE} size (default), {SORT_VM} size or {SORT_NAME}"
+            ),
+            hint: "ORDER".to_string(),
+        });
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::Long(BASELINE_ARG_LONG.to_string()),
+            description: "Print changes of sizes relative to baseline file".to_string(),
+            hint: "FILE".to_string(),
+        });
+        result
+    }
+}
+
+impl SizeHandler {
+    fn handle_report(&self, mut report: SizeReport, config: &Config) {
+        self.printer.print_line(format!(
+            "{} {} {}",
+            "File size:".bright_white(),
+            report.file_size.to_string().yellow(),
+            format!("(VM size: {})", report.vm_size).dimmed()
+        ));
+
+        let total = report.file_size;
+        let tables = [
+            ("Segments", &mut report.segments),
+            ("Sections", &mut report.sections),
+            ("Symbols", &mut report.symbols),
+            ("Objects", &mut report.objects),
+        ];
+        for (title, items) in tables {
+            if items.is_empty() {
+                continue;
+            }
+            sort_items(items, config.sort);
+
+            self.printer
+                .print_line(format!("{}:", title.bright_white()));
+            self.printer.print_line(
+                format!("{:>12} {:>7} {:>12}  NAME", "FILE SIZE", "", "VM SIZE").dimmed(),
+            );
+            for item in items.iter().take(config.top.unwrap_or(usize::MAX)) {
+                let percent = match total {
+                    0 => 0.0,
+                    _ => item.file_size as f64 * 100.0 / total as f64,
+                };
+                self.printer.print_line(format!(
+                    "{:>12} {:>6.1}% {:>12}  {}",
+                    item.file_size.to_string().yellow(),
+                    percent,
+                    item.vm_size.to_string().yellow(),
+                    item.name
+                ));
+            }
+        }
+    }
+
+    fn handle_diff(&self, baseline: &SizeReport, report: &SizeReport, config: &Config) {
+        self.printer.print_line(format!(
+            "{} {} -> {} ({})",
+            "File size:".bright_white(),
+            baseline.file_size,
+            report.file_size,
+            colored_delta(report.file_size as i64 - baseline.file_size as i64)
+        ));
+
+        let tables = [
+            ("Segments", &baseline.segments, &report.segments),
+            ("Sections", &baseline.sections, &report.sections),
+            ("Symbols", &baseline.symbols, &report.symbols),
+            ("Objects", &baseline.objects, &report.objects),
+        ];
+        for (title, old, new) in tables {
+            let deltas = diff_items(old, new, config.sort);
+            if deltas.is_empty() {
+                continue;
+            }
+
+            self.printer
+                .print_line(format!("{}:", title.bright_white()));
+            self.printer
+                .print_line(format!("{:>12} {:>12}  NAME", "FILE DELTA", "VM DELTA").dimmed());
+            for delta in deltas.iter().take(config.top.unwrap_or(usize::MAX)) {
+                let status = match (&delta.old, &delta.new) {
+                    (None, _) => " (added)".dimmed().to_string(),
+                    (_, None) => " (removed)".dimmed().to_string(),
+                    _ => String::new(),
+                };
+                self.printer.print_line(format!(
+                    "{:>12} {:>12}  {}{status}",
+                    colored_delta(delta.file_delta()),
+                    colored_delta(delta.vm_delta()),
+                    delta.name
+                ));
+            }
+        }
+    }
+}
+
+/// Slice of baseline with the same architecture as `object`
+fn baseline_object(baseline: &ObjectType, object: &MachObject) -> Result<MachObject> {
+    let mut objects = baseline.mach_objects();
+    if let ObjectType::MachO(_) = baseline {
+        return Ok(objects.remove(0));
+    }
+
+    let arch = arch_name(object);
+    match objects.into_iter().find(|o| arch_name(o) == arch) {
+        Some(object) => Ok(object),
+        None => Err(Error::Text(format!("Baseline has no {arch} slice"))),
+    }
+}
+
+/// Growth is red, shrink is green
+fn colored_delta(delta: i64) -> ColoredString {
+    let string = format!("{delta:+}");
+    match delta {
+        0 => string.normal(),
+        d if d > 0 => string.red(),
+        _ => string.green(),
+    }
+}
@@ -0,0 +1,161 @@
+use super::common;
+use super::common::options::*;
+use super::common::Format;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::result::Error;
+use crate::strings::*;
+use crate::*;
+use colored::*;
+use getopts::*;
+use regex::Regex;
+
+static SUBCOMM_NAME: &str = "strings";
+
+const MIN_LEN_ARG_LONG: &str = "min-len";
+const REGEX_ARG_LONG: &str = "regex";
+
+/// The same default as of `strings(1)`
+const DEFAULT_MIN_LEN: usize = 4;
+
+pub(super) struct StringsHandler {
+    printer: Printer,
+}
+
+impl StringsHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+struct Config {
+    min_len: usize,
+    regex: Option<Regex>,
+}
+
+impl Config {
+    fn build(opts: &mut Options, args: &[String]) -> Result<Self> {
+        let matches = match opts.parse(args) {
+            Ok(m) => m,
+            Err(f) => return Err(Error::Text(f.to_string())),
+        };
+
+        let min_len = match matches.opt_str(MIN_LEN_ARG_LONG) {
+            Some(len) => match len.parse() {
+                Ok(len) => len,
+                Err(_) => return Err(Error::Text(format!("Invalid minimum length: {len}"))),
+            },
+            None => DEFAULT_MIN_LEN,
+        };
+        let regex = match matches.opt_str(REGEX_ARG_LONG) {
+            Some(pattern) => match Regex::new(&pattern) {
+                Ok(regex) => Some(regex),
+                Err(err) => return Err(Error::Text(err.to_string())),
+            },
+            None => None,
+        };
+
+        Ok(Config { min_len, regex })
+    }
+
+    fn matches(&self, string: &StringLiteral) -> bool {
+        if string.value.chars().count() < self.min_len {
+            return false;
+        }
+        match &self.regex {
+            Some(regex) => regex.is_match(&string.value),
+            None => true,
+        }
+    }
+}
+
+impl Handler for StringsHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Prints C strings, UTF-16 strings and CFString constants".to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let format = &Format::build(&mut opts, &other_args)?;
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let config = Config::build(&mut opts, &other_args)?;
+
+        let objects = &filter.get_objects(object);
+        let out_arch = objects.len() > 1;
+        for (idx, obj) in objects.iter().enumerate() {
+            if out_arch {
+                common::out_single_arch_title(&self.printer, obj.header(), idx, format.short);
+            }
+
+            let literals = StringLiterals::parse(obj)?;
+            let strings = literals.strings.iter().filter(|s| config.matches(s));
+            for (index, string) in strings.enumerate() {
+                self.handle_string(string, index, format);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        result.append(&mut Format::option_items());
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::Long(MIN_LEN_ARG_LONG.to_string()),
+            description: format!("Minimum length of strings to print (default {DEFAULT_MIN_LEN})"),
+            hint: "N".to_string(),
+        });
+        result.push(OptionItem {
+            option_type: OptionType::Arg(IsRequired(false)),
+            name: OptionName::Long(REGEX_ARG_LONG.to_string()),
+            description: "Print only strings matching the pattern".to_string(),
+            hint: "PATTERN".to_string(),
+        });
+        result
+    }
+}
+
+impl StringsHandler {
+    fn handle_string(&self, string: &StringLiteral, index: usize, format: &Format) {
+        if format.show_indices {
+            self.printer.out_list_item_dash(0, index);
+        }
+
+        let value = escape_control_chars(&string.value);
+        if format.short {
+            self.printer.print_line(value.yellow());
+            return;
+        }
+
+        self.printer.print_line(format!(
+            "{} {} {}",
+            format!("{:#018x}", string.addr).red(),
+            format!("{},{}", string.segname, string.sectname).green(),
+            value.yellow()
+        ));
+    }
+}
+
+/// Strings may contain line breaks and other control characters
+fn escape_control_chars(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c.is_control() {
+            true => c.escape_debug().to_string(),
+            false => c.to_string(),
+        })
+        .collect()
+}
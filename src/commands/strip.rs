@@ -0,0 +1,103 @@
+use super::common::options::*;
+use super::common::{Editing, ObjectFilter};
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::result::Error;
+use crate::strip::StripOptions;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+static SUBCOMM_NAME: &str = "strip";
+
+const STABS_FLAG: &str = "stabs";
+const LOCALS_FLAG: &str = "locals";
+
+pub(super) struct StripHandler {
+    printer: Printer,
+}
+
+impl StripHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+/// Without flags both kinds of symbols are removed
+fn strip_options(opts: &mut Options, args: &[String]) -> Result<StripOptions> {
+    let matches = match opts.parse(args) {
+        Ok(m) => m,
+        Err(f) => return Err(Error::Text(f.to_string())),
+    };
+    let stabs = matches.opt_present(STABS_FLAG);
+    let locals = matches.opt_present(LOCALS_FLAG);
+    Ok(StripOptions {
+        stabs: stabs || !locals,
+        locals: locals || !stabs,
+    })
+}
+
+impl Handler for StripHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Removes STABS debug entries and local symbols, shrinking __LINKEDIT".to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        // Code signature is always removed by strip
+        let editing = Editing::build(&mut opts, &other_args, true)?;
+        let options = strip_options(&mut opts, &other_args)?;
+
+        editing.edit_images(&self.printer, &object, &filter, |image| {
+            let summary = image.strip(&options)?;
+            self.printer.print_line(format!(
+                "{} {} symbols, __LINKEDIT {} -> {} bytes",
+                "Removed".bright_white(),
+                summary.removed_symbols.to_string().yellow(),
+                summary.old_linkedit_size,
+                summary.new_linkedit_size.to_string().yellow()
+            ));
+            if summary.removed_signature {
+                self.printer
+                    .print_line("Warning: code signature is removed, sign the file again".yellow());
+            }
+            Ok(())
+        })
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        let flags = [
+            (STABS_FLAG, "Remove only N_STAB debug entries"),
+            (
+                LOCALS_FLAG,
+                "Remove only local symbols, except ones of indirect symbols",
+            ),
+        ];
+        for (name, description) in flags {
+            result.push(OptionItem {
+                option_type: OptionType::Flag(IsRequired(false)),
+                name: OptionName::Long(name.to_string()),
+                description: description.to_string(),
+                hint: "".to_string(),
+            });
+        }
+        result.append(&mut Editing::option_items(
+            "Write stripped file to path instead of the input file",
+            false,
+        ));
+        result
+    }
+}
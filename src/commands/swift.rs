@@ -0,0 +1,262 @@
+use super::common;
+use super::common::options::AddToOptions;
+use super::common::Format;
+use super::common::ObjectFilter;
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::demangle::swift::{demangle_context_descriptor, generic_param_name};
+use crate::swift::*;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+static SUBCOMM_NAME: &str = "swift";
+
+const MEMBER_LEVEL: usize = 4;
+
+pub(super) struct SwiftHandler {
+    printer: Printer,
+}
+
+impl SwiftHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+impl Handler for SwiftHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Prints Swift types, protocols and conformances".to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let format = &Format::build(&mut opts, &other_args)?;
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+
+        let objects = &filter.get_objects(object);
+        let out_arch = objects.len() > 1;
+        for (idx, obj) in objects.iter().enumerate() {
+            if out_arch {
+                common::out_single_arch_title(&self.printer, obj.header(), idx, format.short);
+            }
+
+            let metadata = SwiftMetadata::parse(obj)?;
+            if metadata.is_empty() {
+                self.printer.print_line("No Swift metadata".dimmed());
+                continue;
+            }
+            self.handle_metadata(&metadata, format);
+        }
+
+        Ok(())
+    }
+
+    fn accepted_option_items(&self) -> Vec<common::options::OptionItem> {
+        let mut result = default_option_items();
+        result.append(&mut Format::option_items());
+        result
+    }
+}
+
+impl SwiftHandler {
+    fn handle_metadata(&self, metadata: &SwiftMetadata, format: &Format) {
+        if !metadata.types.is_empty() {
+            self.out_title("Types", metadata.types.len());
+            for (index, swift_type) in metadata.types.iter().enumerate() {
+                self.out_index(index, format);
+                self.handle_type(swift_type, format);
+            }
+        }
+
+        if !metadata.protocols.is_empty() {
+            self.out_title("Protocols", metadata.protocols.len());
+            for (index, protocol) in metadata.protocols.iter().enumerate() {
+                self.out_index(index, format);
+                self.handle_protocol(protocol, format);
+            }
+        }
+
+        if !metadata.conformances.is_empty() {
+            self.out_title("Conformances", metadata.conformances.len());
+            for (index, conformance) in metadata.conformances.iter().enumerate() {
+                self.out_index(index, format);
+                self.handle_conformance(conformance);
+            }
+        }
+
+        if !metadata.associated_types.is_empty() {
+            self.out_title("Associated types", metadata.associated_types.len());
+            for (index, associated_types) in metadata.associated_types.iter().enumerate() {
+                self.out_index(index, format);
+                self.handle_associated_types(associated_types, format);
+            }
+        }
+    }
+
+    fn out_title(&self, title: &str, count: usize) {
+        self.printer.print_line(format!(
+            "{} {}",
+            title.bold().bright_white(),
+            format!("({count})").dimmed()
+        ));
+    }
+
+    fn out_index(&self, index: usize, format: &Format) {
+        if format.show_indices {
+            self.printer.out_list_item_dash(0, index);
+        }
+    }
+
+    fn out_member(&self, member: String) {
+        self.printer.out_string(member, MEMBER_LEVEL);
+    }
+
+    fn handle_type(&self, swift_type: &SwiftType, format: &Format) {
+        let mut title = format!(
+            "{} {}",
+            swift_type.kind.to_string().blue(),
+            swift_type.full_name().yellow()
+        );
+        if !swift_type.generic_params.is_empty() {
+            let params: Vec<String> = (0..swift_type.generic_params.len())
+                .map(|index| generic_param_name(0, index as u64))
+                .collect();
+            title += &format!("<{}>", params.join(", "));
+        }
+        if let Some(superclass) = &swift_type.superclass {
+            title += &format!(" : {}", type_name(superclass).green());
+        }
+        self.printer.print_line(title);
+
+        if format.short {
+            return;
+        }
+
+        for requirement in &swift_type.generic_requirements {
+            let relation = match requirement.kind {
+                GENERIC_REQUIREMENT_SAME_TYPE => " ==",
+                _ => ":",
+            };
+            self.out_member(format!(
+                "{} {}{relation} {}",
+                "where".blue(),
+                type_name(&requirement.param),
+                context_name(&requirement.target).green()
+            ));
+        }
+
+        for field in &swift_type.fields {
+            let member = match swift_type.kind {
+                SwiftContextKind::Enum => {
+                    let keyword = match field.is_indirect_case() {
+                        true => "indirect case",
+                        false => "case",
+                    };
+                    let payload = match &field.mangled_type {
+                        Some(payload) => format!("({})", type_name(payload).green()),
+                        None => "".to_string(),
+                    };
+                    format!("{} {}{payload}", keyword.blue(), field.name)
+                }
+                _ => {
+                    let keyword = match field.is_var() {
+                        true => "var",
+                        false => "let",
+                    };
+                    let field_type = match &field.mangled_type {
+                        Some(field_type) => type_name(field_type),
+                        None => "?".to_string(),
+                    };
+                    format!("{} {}: {}", keyword.blue(), field.name, field_type.green())
+                }
+            };
+            self.out_member(member);
+        }
+    }
+
+    fn handle_protocol(&self, protocol: &SwiftProtocol, format: &Format) {
+        self.printer.print_line(format!(
+            "{} {}",
+            SwiftContextKind::Protocol.to_string().blue(),
+            protocol.full_name().yellow()
+        ));
+
+        if format.short {
+            return;
+        }
+
+        for name in &protocol.associated_type_names {
+            self.out_member(format!("{} {name}", "associatedtype".blue()));
+        }
+        self.out_member(format!(
+            "{} {}",
+            "Requirements:".white(),
+            protocol.num_requirements.to_string().green()
+        ));
+    }
+
+    fn handle_conformance(&self, conformance: &SwiftConformance) {
+        let mut line = format!(
+            "{}: {}",
+            context_name(&conformance.type_name).yellow(),
+            context_name(&conformance.protocol).green()
+        );
+        if conformance.is_retroactive() {
+            line += &format!(" {}", "(retroactive)".dimmed());
+        }
+        if conformance.num_conditional_requirements() > 0 {
+            line += &format!(
+                " {}",
+                format!(
+                    "({} conditional requirements)",
+                    conformance.num_conditional_requirements()
+                )
+                .dimmed()
+            );
+        }
+        self.printer.print_line(line);
+    }
+
+    fn handle_associated_types(&self, associated_types: &SwiftAssociatedTypes, format: &Format) {
+        self.printer.print_line(format!(
+            "{}: {}",
+            type_name(&associated_types.conforming_type).yellow(),
+            type_name(&associated_types.protocol).green()
+        ));
+
+        if format.short {
+            return;
+        }
+
+        for associated_type in &associated_types.types {
+            self.out_member(format!(
+                "{} {} = {}",
+                "typealias".blue(),
+                associated_type.name,
+                type_name(&associated_type.substituted_type).green()
+            ));
+        }
+    }
+}
+
+/// Demangled type, or mangled one with resolved references if it can't be demangled
+fn type_name(name: &SwiftMangledName) -> String {
+    name.demangled().unwrap_or_else(|| name.to_string())
+}
+
+/// Contexts outside of the object are referenced by descriptor symbols
+fn context_name(name: &str) -> String {
+    demangle_context_descriptor(name).unwrap_or_else(|| name.to_string())
+}
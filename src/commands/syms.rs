@@ -1,7 +1,7 @@
 use super::common;
 use super::common::Format;
 use super::common::ObjectFilter;
-use super::common::options::AddToOptions;
+use super::common::options::*;
 use super::handler::*;
 use super::Printer;
 use super::Result;
@@ -12,6 +12,8 @@ use getopts::*;
 
 static SUBCOMM_NAME: &str = "syms";
 
+const DEMANGLE_FLAG: &str = "demangle";
+
 pub(super) struct SymsHandler {
     pub(super) printer: Printer,
 }
@@ -41,6 +43,10 @@ impl Handler for SymsHandler {
 
         let format = &Format::build(&mut opts, &other_args)?;
         let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let demangle = match opts.parse(&other_args) {
+            Ok(m) => m.opt_present(DEMANGLE_FLAG),
+            Err(f) => return Err(crate::result::Error::Text(f.to_string())),
+        };
 
         let objects = &filter.get_objects(object);
         let out_arch = objects.len() > 1;
@@ -48,37 +54,43 @@ impl Handler for SymsHandler {
             if out_arch {
                 common::out_single_arch_title(&self.printer, &obj.header(), idx, format.short);
             }
-            self.handle_load_commands(obj.load_commands_iterator(), format);
+            self.handle_load_commands(obj.load_commands_iterator(), format, demangle);
         }
 
         Ok(())
     }
 
-    fn accepted_option_items(&self) -> Vec<common::options::OptionItem> {
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
         let mut result = default_option_items();
         result.append(&mut Format::option_items());
+        result.push(OptionItem {
+            option_type: OptionType::Flag(IsRequired(false)),
+            name: OptionName::Long(DEMANGLE_FLAG.to_string()),
+            description: "Demangle C++, Swift and Rust symbol names".to_string(),
+            hint: "".to_string(),
+        });
         result
     }
 }
 
 impl SymsHandler {
-    fn handle_load_commands(&self, commands: LoadCommandIterator, format: &Format) {
+    fn handle_load_commands(&self, commands: LoadCommandIterator, format: &Format, demangle: bool) {
         let commands = commands.flat_map(|cmd| match cmd.variant {
             LcVariant::Symtab(symtab) => Some(symtab),
             _ => None,
         });
         for cmd in commands {
-            self.handle_symtab_command(cmd, format);
+            self.handle_symtab_command(cmd, format, demangle);
         }
     }
 
-    fn handle_symtab_command(&self, symtab: LcSymtab, format: &Format) {
+    fn handle_symtab_command(&self, symtab: LcSymtab, format: &Format, demangle: bool) {
         for (index, nlist) in symtab.nlist_iterator().enumerate() {
-            self.handle_nlist(nlist, index, format);
+            self.handle_nlist(nlist, index, format, demangle);
         }
     }
 
-    fn handle_nlist(&self, nlist: Nlist, index: usize, format: &Format) {
+    fn handle_nlist(&self, nlist: Nlist, index: usize, format: &Format, demangle: bool) {
         if format.show_indices {
             self.printer.out_list_item_dash(0, index);
         }
@@ -94,6 +106,10 @@ impl SymsHandler {
             },
             None => "".to_string(),
         };
+        let name = match demangle {
+            true => crate::demangle::demangle(&name).unwrap_or(name),
+            false => name,
+        };
 
         let name = if name.len() > 0 {
             name.yellow()
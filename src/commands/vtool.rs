@@ -0,0 +1,210 @@
+use super::common::options::*;
+use super::common::{handle_images, ordered_options, Editing, ObjectFilter};
+use super::handler::*;
+use super::Printer;
+use super::Result;
+use crate::build_version::*;
+use crate::result::Error;
+use crate::writer::FileImage;
+use crate::*;
+use colored::*;
+use getopts::*;
+
+static SUBCOMM_NAME: &str = "vtool";
+
+const SET_BUILD_VERSION_ARG_LONG: &str = "set-build-version";
+const TOOL_ARG_LONG: &str = "tool";
+const SET_VERSION_MIN_ARG_LONG: &str = "set-version-min";
+const REMOVE_BUILD_VERSION_ARG_LONG: &str = "remove-build-version";
+
+pub(super) struct VtoolHandler {
+    printer: Printer,
+}
+
+impl VtoolHandler {
+    pub(super) fn new(printer: Printer) -> Self {
+        Self { printer }
+    }
+}
+
+/// Edits in order of arguments, versions are only printed without them
+fn parse_edits(args: &[String]) -> Result<Vec<VersionEdit>> {
+    let arities = [
+        (SET_BUILD_VERSION_ARG_LONG, 3),
+        (TOOL_ARG_LONG, 2),
+        (SET_VERSION_MIN_ARG_LONG, 3),
+        (REMOVE_BUILD_VERSION_ARG_LONG, 1),
+    ];
+    let mut edits = Vec::new();
+    for (name, values) in ordered_options(args, &arities)? {
+        match name.as_str() {
+            SET_BUILD_VERSION_ARG_LONG => edits.push(VersionEdit::SetBuildVersion {
+                platform: parse_platform(&values[0])?,
+                minos: parse_version(&values[1])?,
+                sdk: parse_version(&values[2])?,
+                tools: None,
+            }),
+            SET_VERSION_MIN_ARG_LONG => edits.push(VersionEdit::SetVersionMin {
+                platform: parse_platform(&values[0])?,
+                minos: parse_version(&values[1])?,
+                sdk: parse_version(&values[2])?,
+            }),
+            REMOVE_BUILD_VERSION_ARG_LONG => {
+                edits.push(VersionEdit::RemoveBuildVersion(parse_platform(&values[0])?))
+            }
+            _ => {
+                // Tool belongs to the preceding build version
+                let tool = BuildToolVersion {
+                    tool: parse_tool(&values[0])?,
+                    version: parse_version(&values[1])?.0,
+                };
+                match edits.last_mut() {
+                    Some(VersionEdit::SetBuildVersion { tools, .. }) => {
+                        tools.get_or_insert_with(Vec::new).push(tool)
+                    }
+                    _ => {
+                        return Err(Error::Text(format!(
+                            "-{TOOL_ARG_LONG} is expected after -{SET_BUILD_VERSION_ARG_LONG}"
+                        )))
+                    }
+                }
+            }
+        }
+    }
+    Ok(edits)
+}
+
+impl Handler for VtoolHandler {
+    fn command_name(&self) -> String {
+        SUBCOMM_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Prints and changes platform, minimum OS and SDK versions like vtool".to_string()
+    }
+
+    fn can_handle_with_name(&self, name: &str) -> bool {
+        SUBCOMM_NAME == name
+    }
+
+    fn handle_object(&self, object: ObjectType, other_args: Vec<String>) -> Result<()> {
+        let mut opts = Options::new();
+        opts.long_only(true);
+        self.accepted_option_items().add_to_opts(&mut opts);
+
+        let filter = ObjectFilter::build(&mut opts, &other_args)?;
+        let editing = Editing::build(&mut opts, &other_args, true)?;
+        let edits = parse_edits(&other_args)?;
+
+        if edits.is_empty() {
+            let mut file = FileImage::read(&object)?;
+            return handle_images(&self.printer, &mut file, &filter, |image| {
+                for version in image.build_versions()? {
+                    self.printer.print_line(version_string(&version));
+                }
+                Ok(())
+            });
+        }
+        editing.edit_images(&self.printer, &object, &filter, |image| {
+            for edit in &edits {
+                image.apply_version_edit(edit)?;
+                self.printer.print_line(edit_string(edit));
+            }
+            Ok(())
+        })
+    }
+
+    fn accepted_option_items(&self) -> Vec<OptionItem> {
+        let mut result = default_option_items();
+        let args = [
+            (
+                SET_BUILD_VERSION_ARG_LONG,
+                "Set LC_BUILD_VERSION of platform, replacing LC_VERSION_MIN of it",
+                "PLATFORM MINOS SDK",
+            ),
+            (
+                TOOL_ARG_LONG,
+                "Set tool of the preceding build version, tools of existing one are kept without it",
+                "TOOL VERSION",
+            ),
+            (
+                SET_VERSION_MIN_ARG_LONG,
+                "Set LC_VERSION_MIN of platform, replacing LC_BUILD_VERSION of it",
+                "PLATFORM MINOS SDK",
+            ),
+            (
+                REMOVE_BUILD_VERSION_ARG_LONG,
+                "Remove build version of platform",
+                "PLATFORM",
+            ),
+        ];
+        for (name, description, hint) in args {
+            result.push(OptionItem {
+                option_type: OptionType::MultiArg,
+                name: OptionName::Long(name.to_string()),
+                description: description.to_string(),
+                hint: hint.to_string(),
+            });
+        }
+        result.append(&mut Editing::option_items(
+            "Write edited file to path instead of the input file",
+            true,
+        ));
+        result
+    }
+
+    fn accepts_single_dash_long_options(&self) -> bool {
+        true
+    }
+}
+
+fn version_string(version: &BuildVersion) -> String {
+    let mut result = format!(
+        "{} {} minos {} sdk {}",
+        fmt_ext::load_command_to_string(version.cmd).yellow(),
+        platform_name(version.platform).green(),
+        version.minos,
+        version.sdk
+    );
+    for tool in &version.tools {
+        result += &format!(
+            " {} {}",
+            tool_name(tool.tool).bright_white(),
+            Version32(tool.version)
+        );
+    }
+    result
+}
+
+fn edit_string(edit: &VersionEdit) -> String {
+    match edit {
+        VersionEdit::SetBuildVersion {
+            platform,
+            minos,
+            sdk,
+            ..
+        } => format!(
+            "{} build version {} minos {} sdk {}",
+            "~".yellow(),
+            platform_name(*platform).yellow(),
+            minos,
+            sdk
+        ),
+        VersionEdit::SetVersionMin {
+            platform,
+            minos,
+            sdk,
+        } => format!(
+            "{} version min {} minos {} sdk {}",
+            "~".yellow(),
+            platform_name(*platform).yellow(),
+            minos,
+            sdk
+        ),
+        VersionEdit::RemoveBuildVersion(platform) => format!(
+            "{} build version {}",
+            "-".red(),
+            platform_name(*platform).red()
+        ),
+    }
+}
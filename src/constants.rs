@@ -4,3 +4,9 @@ pub const BYTES_PER_FAT_ARCH: usize = 20;
 pub const BYTES_PER_LOAD_COMMAND: usize = 8;
 pub const BYTES_PER_NLIST32: usize = 12;
 pub const BYTES_PER_NLIST64: usize = 16;
+pub const BYTES_PER_RELOCATION_INFO: usize = 8;
+pub const BYTES_PER_MACH_HEADER32: usize = 28;
+pub const BYTES_PER_MACH_HEADER64: usize = 32;
+
+pub const SEG_TEXT: &str = "__TEXT";
+pub const SEG_LINKEDIT: &str = "__LINKEDIT";
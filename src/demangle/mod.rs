@@ -0,0 +1,74 @@
+//! Demangling of symbol names: Itanium C++, Swift and Rust (legacy and v0).
+//! Names may be given as they are stored in Mach-O, with an extra leading underscore.
+
+pub mod swift;
+
+/// Demangles symbol name. Returns `None` if the name isn't mangled or can't be demangled.
+pub fn demangle(name: &str) -> Option<String> {
+    // Mach-O adds underscore to C names, so `_Z...` becomes `__Z...`
+    let name = match name.strip_prefix('_') {
+        Some(stripped) if is_mangled_without_underscore(stripped) => stripped,
+        _ => name,
+    };
+
+    // Legacy Rust symbols are valid Itanium symbols, so Rust goes first
+    if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+        return Some(format!("{demangled:#}"));
+    }
+    if let Some(demangled) = swift::demangle(name) {
+        return Some(demangled);
+    }
+    if name.starts_with("_Z") {
+        let symbol = cpp_demangle::Symbol::new(name).ok()?;
+        return symbol.demangle(&Default::default()).ok();
+    }
+    None
+}
+
+fn is_mangled_without_underscore(name: &str) -> bool {
+    ["_Z", "_R", "$s", "$S", "$e", "_T"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demangle_cpp() {
+        assert_eq!(demangle("__ZN3foo3barEi").as_deref(), Some("foo::bar(int)"));
+        assert_eq!(
+            demangle("__ZNSt3__16vectorIiNS_9allocatorIiEEE9push_backERKi").as_deref(),
+            Some("std::__1::vector<int, std::__1::allocator<int> >::push_back(int const&)")
+        );
+    }
+
+    #[test]
+    fn test_demangle_rust() {
+        assert_eq!(
+            demangle("__ZN4core3fmt5write17h0123456789abcdefE").as_deref(),
+            Some("core::fmt::write")
+        );
+        assert_eq!(
+            demangle("__RNvCs1234_7mycrate3foo").as_deref(),
+            Some("mycrate::foo")
+        );
+    }
+
+    #[test]
+    fn test_demangle_swift() {
+        assert_eq!(
+            demangle("_$s4main3FooVMn").as_deref(),
+            Some("nominal type descriptor for main.Foo")
+        );
+        assert_eq!(demangle("__TtC4main3Foo").as_deref(), Some("main.Foo"));
+    }
+
+    #[test]
+    fn test_not_mangled() {
+        assert_eq!(demangle("_main"), None);
+        assert_eq!(demangle("radr://5614542"), None);
+        assert_eq!(demangle("_objc_msgSend"), None);
+    }
+}
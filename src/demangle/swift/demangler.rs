@@ -0,0 +1,1435 @@
+//! Stack based demangler of current Swift mangling (`$s`, `$S`, `_T0`).
+//! Follows structure of `swift/lib/Demangling/Demangler.cpp`, but supports only
+//! entities which may appear in symbol tables and reflection metadata.
+
+use super::generic_param_name;
+use super::node::{Kind, Node};
+
+const MAX_WORDS: usize = 26;
+const MAX_REPEAT_COUNT: u64 = 2048;
+/// Substitutions are copied into the tree, so malformed symbols could make it grow exponentially
+const MAX_SUBSTITUTED_NODES: usize = 1 << 16;
+/// Limits of demangled tree, as nested types of malformed symbols make every following
+/// node and substitution as large as the whole tree
+const MAX_DEPTH: usize = 512;
+const MAX_NODES: usize = 1 << 20;
+
+/// Kind of symbolic reference to context descriptor
+const SYMBOLIC_REFERENCE_DIRECT_CONTEXT: u8 = 0x01;
+const SYMBOLIC_REFERENCE_INDIRECT_CONTEXT: u8 = 0x02;
+const SYMBOLIC_REFERENCE_SIZE: usize = 4;
+
+pub(super) struct Demangler<'a> {
+    text: &'a [u8],
+    pos: usize,
+    stack: Vec<Node>,
+    substitutions: Vec<Node>,
+    words: Vec<String>,
+    /// Names of contexts referenced with symbolic references, by index stored in the reference
+    references: &'a [String],
+    substituted_nodes: usize,
+    /// Nodes of all demangled operators and substitutions
+    nodes: usize,
+}
+
+impl<'a> Demangler<'a> {
+    pub(super) fn new(text: &'a [u8], references: &'a [String]) -> Self {
+        Demangler {
+            text,
+            pos: 0,
+            stack: Vec::new(),
+            substitutions: Vec::new(),
+            words: Vec::new(),
+            references,
+            substituted_nodes: 0,
+            nodes: 0,
+        }
+    }
+
+    /// Demangles symbol with prefix already removed
+    pub(super) fn demangle_symbol(mut self) -> Option<Node> {
+        self.demangle_all()?;
+
+        let mut attributes = Vec::new();
+        while let Some(attribute) = self.pop_if(Kind::is_function_attribute) {
+            attributes.push(attribute);
+        }
+
+        let mut children: Vec<Node> = self
+            .stack
+            .into_iter()
+            .map(|node| match node.kind {
+                Kind::Type => node
+                    .children
+                    .into_iter()
+                    .next()
+                    .unwrap_or(Node::new(Kind::Type)),
+                _ => node,
+            })
+            .collect();
+        // Everything after partial apply forwarder belongs to it
+        for mut attribute in attributes.into_iter().rev() {
+            match attribute.kind {
+                Kind::PartialApplyForwarder | Kind::PartialApplyObjCForwarder => {
+                    attribute.children = children;
+                    children = vec![attribute];
+                }
+                _ => children.insert(0, attribute),
+            }
+        }
+
+        match children.is_empty() {
+            true => None,
+            false => Some(Node::with_children(Kind::Global, children)),
+        }
+    }
+
+    /// Demangles mangled name of type, like the ones in reflection metadata
+    pub(super) fn demangle_type(mut self) -> Option<Node> {
+        self.demangle_all()?;
+        let node = self.pop_kind(Kind::Type)?;
+        match self.stack.is_empty() {
+            true => Some(node),
+            false => None,
+        }
+    }
+
+    fn demangle_all(&mut self) -> Option<()> {
+        while self.pos < self.text.len() {
+            let node = self.demangle_operator()?;
+            let (count, depth) = node_size(&node);
+            self.nodes += count;
+            if depth > MAX_DEPTH || self.nodes > MAX_NODES {
+                return None;
+            }
+            self.stack.push(node);
+        }
+        Some(())
+    }
+}
+
+impl<'a> Demangler<'a> {
+    fn peek(&self) -> u8 {
+        self.text.get(self.pos).copied().unwrap_or(0)
+    }
+
+    fn next(&mut self) -> u8 {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn next_if(&mut self, c: u8) -> bool {
+        match self.peek() == c {
+            true => {
+                self.pos += 1;
+                true
+            }
+            false => false,
+        }
+    }
+
+    fn push_back(&mut self) {
+        self.pos -= 1;
+    }
+
+    fn natural(&mut self) -> Option<u64> {
+        if !self.peek().is_ascii_digit() {
+            return None;
+        }
+        let mut value: u64 = 0;
+        while self.peek().is_ascii_digit() {
+            let digit = (self.next() - b'0') as u64;
+            value = value.checked_mul(10)?.checked_add(digit)?;
+        }
+        Some(value)
+    }
+
+    /// `_` is 0, `<n>_` is `n + 1`
+    fn index(&mut self) -> Option<u64> {
+        if self.next_if(b'_') {
+            return Some(0);
+        }
+        let value = self.natural()?;
+        match self.next_if(b'_') {
+            true => value.checked_add(1),
+            false => None,
+        }
+    }
+
+    fn index_node(&mut self) -> Option<Node> {
+        Some(Node::with_index(Kind::Index, self.index()?))
+    }
+
+    fn pop(&mut self) -> Option<Node> {
+        self.stack.pop()
+    }
+
+    fn pop_kind(&mut self, kind: Kind) -> Option<Node> {
+        self.pop_if(|k| k == kind)
+    }
+
+    fn pop_if(&mut self, predicate: impl Fn(Kind) -> bool) -> Option<Node> {
+        match self.stack.last() {
+            Some(node) if predicate(node.kind) => self.stack.pop(),
+            _ => None,
+        }
+    }
+
+    fn add_substitution(&mut self, node: &Node) {
+        // Checked along with the node itself after the operator
+        self.nodes += node_size(node).0;
+        self.substitutions.push(node.clone());
+    }
+
+    fn substitution(&mut self, index: u64) -> Option<Node> {
+        let node = self.substitutions.get(index as usize)?.clone();
+        self.substituted_nodes += node_size(&node).0;
+        match self.substituted_nodes > MAX_SUBSTITUTED_NODES {
+            true => None,
+            false => Some(node),
+        }
+    }
+}
+
+/// Count of nodes and depth of tree
+fn node_size(node: &Node) -> (usize, usize) {
+    node.children
+        .iter()
+        .map(node_size)
+        .fold((1, 1), |(count, depth), (child_count, child_depth)| {
+            (count + child_count, depth.max(child_depth + 1))
+        })
+}
+
+impl<'a> Demangler<'a> {
+    fn demangle_operator(&mut self) -> Option<Node> {
+        match self.next() {
+            SYMBOLIC_REFERENCE_DIRECT_CONTEXT | SYMBOLIC_REFERENCE_INDIRECT_CONTEXT => {
+                self.demangle_symbolic_reference()
+            }
+            b'A' => self.demangle_multi_substitutions(),
+            b'B' => self.demangle_builtin_type(),
+            b'C' => self.demangle_any_generic_type(Kind::Class),
+            b'D' => self.with_popped_type(Kind::TypeMangling),
+            b'E' => self.demangle_extension_context(),
+            b'F' => self.demangle_plain_function(),
+            b'G' => self.demangle_bound_generic_type(),
+            b'K' => Some(Node::new(Kind::ThrowsAnnotation)),
+            b'L' => self.demangle_local_identifier(),
+            b'M' => self.demangle_metatype(),
+            b'N' => self.with_popped_type(Kind::TypeMetadata),
+            b'O' => self.demangle_any_generic_type(Kind::Enum),
+            b'P' => self.demangle_any_generic_type(Kind::Protocol),
+            b'Q' => self.demangle_archetype(),
+            b'R' => self.demangle_generic_requirement(),
+            b'S' => self.demangle_standard_substitution(),
+            b'T' => self.demangle_thunk(),
+            b'V' => self.demangle_any_generic_type(Kind::Structure),
+            b'W' => self.demangle_witness(),
+            b'X' => self.demangle_special_type(),
+            b'Y' => self.demangle_type_annotation(),
+            b'Z' => {
+                let entity = self.pop_if(Kind::is_entity)?;
+                Some(Node::with_children(Kind::Static, vec![entity]))
+            }
+            b'a' => self.demangle_any_generic_type(Kind::TypeAlias),
+            b'c' => self.pop_function_type(Kind::FunctionType),
+            b'd' => Some(Node::new(Kind::VariadicMarker)),
+            b'f' => self.demangle_function_entity(),
+            b'h' => self.wrap_popped_type(Kind::Shared),
+            b'i' => self.demangle_subscript(),
+            b'l' => self.demangle_generic_signature(false),
+            b'm' => {
+                let node = self.with_popped_type(Kind::Metatype)?;
+                Some(Node::wrap_type(node))
+            }
+            b'n' => self.wrap_popped_type(Kind::Owned),
+            b'o' => self.demangle_operator_identifier(),
+            b'p' => Some(Node::wrap_type(self.demangle_protocol_list()?)),
+            b'q' => Some(Node::wrap_type(self.demangle_generic_param_index()?)),
+            b'r' => self.demangle_generic_signature(true),
+            b's' => Some(Node::with_text(Kind::Module, super::STDLIB_NAME)),
+            b't' => self.pop_tuple(),
+            b'u' => self.demangle_generic_type(),
+            b'v' => {
+                let variable = self.demangle_entity(Kind::Variable)?;
+                self.demangle_accessor(variable)
+            }
+            b'x' => Some(Node::wrap_type(generic_param(0, 0))),
+            b'y' => Some(Node::new(Kind::EmptyList)),
+            b'z' => self.wrap_popped_type(Kind::InOut),
+            b'_' => Some(Node::new(Kind::FirstElementMarker)),
+            b'.' => {
+                // Suffixes like `.cold` or `.<n>` added by LLVM
+                self.push_back();
+                let suffix = String::from_utf8_lossy(&self.text[self.pos..]).to_string();
+                self.pos = self.text.len();
+                Some(Node::with_text(Kind::Suffix, suffix))
+            }
+            _ => {
+                self.push_back();
+                self.demangle_identifier()
+            }
+        }
+    }
+
+    /// Node of `kind` with popped type as the only child
+    fn with_popped_type(&mut self, kind: Kind) -> Option<Node> {
+        let ty = self.pop_kind(Kind::Type)?;
+        Some(Node::with_children(kind, vec![ty]))
+    }
+
+    /// Type of `kind` wrapping contents of popped type, like `inout T`
+    fn wrap_popped_type(&mut self, kind: Kind) -> Option<Node> {
+        let ty = self.pop_kind(Kind::Type)?;
+        let child = ty.children.into_iter().next()?;
+        Some(Node::wrap_type(Node::with_children(kind, vec![child])))
+    }
+
+    fn demangle_symbolic_reference(&mut self) -> Option<Node> {
+        let bytes = self
+            .text
+            .get(self.pos..self.pos + SYMBOLIC_REFERENCE_SIZE)?;
+        let index = u32::from_le_bytes(bytes.try_into().ok()?) as usize;
+        self.pos += SYMBOLIC_REFERENCE_SIZE;
+
+        let name = self.references.get(index)?;
+        let node = Node::wrap_type(Node::with_text(Kind::Resolved, name.clone()));
+        self.add_substitution(&node);
+        Some(node)
+    }
+
+    fn demangle_identifier(&mut self) -> Option<Node> {
+        let mut has_word_substitutions = false;
+        if !self.peek().is_ascii_digit() {
+            return None;
+        }
+        if self.next_if(b'0') {
+            // Punycode encoded identifiers are not supported
+            if self.peek() == b'0' {
+                return None;
+            }
+            has_word_substitutions = true;
+        }
+
+        let mut identifier = String::new();
+        loop {
+            while has_word_substitutions && self.peek().is_ascii_alphabetic() {
+                let c = self.next();
+                let index = match c.is_ascii_lowercase() {
+                    true => c - b'a',
+                    false => {
+                        has_word_substitutions = false;
+                        c - b'A'
+                    }
+                };
+                identifier += self.words.get(index as usize)?;
+            }
+            if self.next_if(b'0') {
+                break;
+            }
+
+            let length = self.natural()? as usize;
+            if length == 0 || self.pos + length > self.text.len() {
+                return None;
+            }
+            let slice = std::str::from_utf8(&self.text[self.pos..self.pos + length]).ok()?;
+            identifier += slice;
+            self.add_words(slice);
+            self.pos += length;
+
+            if !has_word_substitutions {
+                break;
+            }
+        }
+
+        if identifier.is_empty() {
+            return None;
+        }
+        let node = Node::with_text(Kind::Identifier, identifier);
+        self.add_substitution(&node);
+        Some(node)
+    }
+
+    /// Remembers words of identifier to be referenced by later identifiers
+    fn add_words(&mut self, identifier: &str) {
+        let bytes = identifier.as_bytes();
+        let mut word_start: Option<usize> = None;
+        for index in 0..=bytes.len() {
+            let c = bytes.get(index).copied().unwrap_or(0);
+            if let Some(start) = word_start {
+                let is_word_end = c == b'_'
+                    || c == 0
+                    || (!bytes[index - 1].is_ascii_uppercase() && c.is_ascii_uppercase());
+                if is_word_end {
+                    if index - start >= 2 && self.words.len() < MAX_WORDS {
+                        self.words.push(identifier[start..index].to_string());
+                    }
+                    word_start = None;
+                }
+            }
+            let is_word_start = !c.is_ascii_digit() && c != b'_' && c != 0;
+            if word_start.is_none() && is_word_start {
+                word_start = Some(index);
+            }
+        }
+    }
+
+    fn demangle_operator_identifier(&mut self) -> Option<Node> {
+        const OPERATOR_CHARS: &[u8; 26] = b"& @/= >    <*!|+?%-~   ^ .";
+
+        let identifier = self.pop_kind(Kind::Identifier)?;
+        let mut operator = String::new();
+        for c in identifier.text.chars() {
+            if !c.is_ascii() {
+                operator.push(c);
+                continue;
+            }
+            if !c.is_ascii_lowercase() {
+                return None;
+            }
+            match OPERATOR_CHARS[(c as u8 - b'a') as usize] {
+                b' ' => return None,
+                o => operator.push(o as char),
+            }
+        }
+
+        let kind = match self.next() {
+            b'i' => Kind::InfixOperator,
+            b'p' => Kind::PrefixOperator,
+            b'P' => Kind::PostfixOperator,
+            _ => return None,
+        };
+        Some(Node::with_text(kind, operator))
+    }
+
+    fn demangle_local_identifier(&mut self) -> Option<Node> {
+        if self.next_if(b'L') {
+            let discriminator = self.pop_kind(Kind::Identifier)?;
+            let name = self.pop_if(Kind::is_decl_name)?;
+            return Some(Node::with_children(
+                Kind::PrivateDeclName,
+                vec![discriminator, name],
+            ));
+        }
+        if self.next_if(b'l') {
+            let discriminator = self.pop_kind(Kind::Identifier)?;
+            return Some(Node::with_children(
+                Kind::PrivateDeclName,
+                vec![discriminator],
+            ));
+        }
+        if matches!(self.peek(), b'a'..=b'j' | b'A'..=b'J') {
+            let related_kind = self.next() as char;
+            let name = self.pop()?;
+            let kind = Node::with_text(Kind::Identifier, related_kind.to_string());
+            return Some(Node::with_children(
+                Kind::RelatedEntityDeclName,
+                vec![kind, name],
+            ));
+        }
+        let discriminator = self.index_node()?;
+        let name = self.pop_if(Kind::is_decl_name)?;
+        Some(Node::with_children(
+            Kind::LocalDeclName,
+            vec![discriminator, name],
+        ))
+    }
+
+    fn demangle_multi_substitutions(&mut self) -> Option<Node> {
+        let mut repeat_count: Option<u64> = None;
+        loop {
+            let c = self.next();
+            match c {
+                b'a'..=b'z' => {
+                    let node = self.push_multi_substitutions(repeat_count, (c - b'a') as u64)?;
+                    self.stack.push(node);
+                    repeat_count = None;
+                }
+                b'A'..=b'Z' => {
+                    return self.push_multi_substitutions(repeat_count, (c - b'A') as u64);
+                }
+                b'_' => {
+                    let index = repeat_count? + 27;
+                    return self.substitution(index);
+                }
+                0 => return None,
+                _ => {
+                    self.push_back();
+                    repeat_count = Some(self.natural()?);
+                }
+            }
+        }
+    }
+
+    /// Pushes all repetitions of substitution but the last one, which is returned
+    fn push_multi_substitutions(&mut self, repeat_count: Option<u64>, index: u64) -> Option<Node> {
+        let repeat_count = repeat_count.unwrap_or(1);
+        if repeat_count > MAX_REPEAT_COUNT {
+            return None;
+        }
+        let node = self.substitution(index)?;
+        for _ in 1..repeat_count {
+            let node = self.substitution(index)?;
+            self.stack.push(node);
+        }
+        Some(node)
+    }
+
+    fn demangle_standard_substitution(&mut self) -> Option<Node> {
+        match self.next() {
+            b'o' => Some(Node::with_text(Kind::Module, super::OBJC_MODULE_NAME)),
+            b'C' => Some(Node::with_text(
+                Kind::Module,
+                super::CLANG_IMPORTER_MODULE_NAME,
+            )),
+            b'g' => {
+                let ty = self.pop_kind(Kind::Type)?;
+                let optional = Node::wrap_type(Node::with_children(
+                    Kind::BoundGenericEnum,
+                    vec![
+                        swift_type(Kind::Enum, "Optional"),
+                        Node::with_children(Kind::TypeList, vec![ty]),
+                    ],
+                ));
+                self.add_substitution(&optional);
+                Some(optional)
+            }
+            _ => {
+                self.push_back();
+                let repeat_count = self.natural().unwrap_or(1);
+                if repeat_count > MAX_REPEAT_COUNT {
+                    return None;
+                }
+                let second_level = self.next_if(b'c');
+                let (kind, name) = standard_type(self.next(), second_level)?;
+                let node = swift_type(kind, name);
+                for _ in 1..repeat_count {
+                    self.stack.push(node.clone());
+                }
+                Some(node)
+            }
+        }
+    }
+
+    fn demangle_builtin_type(&mut self) -> Option<Node> {
+        const MAX_TYPE_SIZE: u64 = 4096;
+
+        let name = match self.next() {
+            b'b' => "Builtin.BridgeObject".to_string(),
+            b'B' => "Builtin.UnsafeValueBuffer".to_string(),
+            b'c' => "Builtin.RawUnsafeContinuation".to_string(),
+            b'D' => "Builtin.DefaultActorStorage".to_string(),
+            b'e' => "Builtin.Executor".to_string(),
+            b'f' => match self.index()?.checked_sub(1)? {
+                size @ 1..=MAX_TYPE_SIZE => format!("Builtin.FPIEEE{size}"),
+                _ => return None,
+            },
+            b'i' => match self.index()?.checked_sub(1)? {
+                size @ 1..=MAX_TYPE_SIZE => format!("Builtin.Int{size}"),
+                _ => return None,
+            },
+            b'I' => "Builtin.IntLiteral".to_string(),
+            b'j' => "Builtin.Job".to_string(),
+            b'O' => "Builtin.UnknownObject".to_string(),
+            b'o' => "Builtin.NativeObject".to_string(),
+            b'p' => "Builtin.RawPointer".to_string(),
+            b't' => "Builtin.SILToken".to_string(),
+            b'w' => "Builtin.Word".to_string(),
+            _ => return None,
+        };
+        Some(Node::wrap_type(Node::with_text(
+            Kind::BuiltinTypeName,
+            name,
+        )))
+    }
+
+    fn demangle_any_generic_type(&mut self, kind: Kind) -> Option<Node> {
+        let name = self.pop_if(Kind::is_decl_name)?;
+        let context = self.pop_context()?;
+        let node = Node::wrap_type(Node::with_children(kind, vec![context, name]));
+        self.add_substitution(&node);
+        Some(node)
+    }
+
+    fn demangle_extension_context(&mut self) -> Option<Node> {
+        let signature = self.pop_kind(Kind::DependentGenericSignature);
+        let module = self.pop_module()?;
+        let extended = self.pop_type_and_get_any_generic()?;
+        let mut children = vec![module, extended];
+        children.extend(signature);
+        Some(Node::with_children(Kind::Extension, children))
+    }
+
+    fn demangle_bound_generic_type(&mut self) -> Option<Node> {
+        let mut type_lists = Vec::new();
+        loop {
+            let mut list = Vec::new();
+            while let Some(ty) = self.pop_kind(Kind::Type) {
+                list.push(ty);
+            }
+            list.reverse();
+            type_lists.push(Node::with_children(Kind::TypeList, list));
+
+            if self.pop_kind(Kind::EmptyList).is_some() {
+                break;
+            }
+            self.pop_kind(Kind::FirstElementMarker)?;
+        }
+
+        let nominal = self.pop_type_and_get_any_generic()?;
+        let node = Node::wrap_type(bound_generic_args(nominal, &type_lists, 0)?);
+        self.add_substitution(&node);
+        Some(node)
+    }
+
+    fn pop_type_and_get_any_generic(&mut self) -> Option<Node> {
+        let ty = self.pop_kind(Kind::Type)?;
+        let child = ty.children.into_iter().next()?;
+        match child.kind.is_nominal() {
+            true => Some(child),
+            false => None,
+        }
+    }
+
+    fn pop_module(&mut self) -> Option<Node> {
+        if let Some(mut identifier) = self.pop_kind(Kind::Identifier) {
+            identifier.kind = Kind::Module;
+            return Some(identifier);
+        }
+        self.pop_kind(Kind::Module)
+    }
+
+    fn pop_context(&mut self) -> Option<Node> {
+        if let Some(module) = self.pop_module() {
+            return Some(module);
+        }
+        if let Some(ty) = self.pop_kind(Kind::Type) {
+            if ty.children.len() != 1 {
+                return None;
+            }
+            let child = ty.children.into_iter().next()?;
+            return match child.kind.is_context() {
+                true => Some(child),
+                false => None,
+            };
+        }
+        self.pop_if(Kind::is_context)
+    }
+
+    fn pop_protocol(&mut self) -> Option<Node> {
+        if let Some(ty) = self.pop_kind(Kind::Type) {
+            return match ty.child(0)?.kind {
+                Kind::Protocol | Kind::Resolved => Some(ty),
+                _ => None,
+            };
+        }
+        let name = self.pop_if(Kind::is_decl_name)?;
+        let context = self.pop_context()?;
+        Some(Node::wrap_type(Node::with_children(
+            Kind::Protocol,
+            vec![context, name],
+        )))
+    }
+
+    fn pop_protocol_conformance(&mut self) -> Option<Node> {
+        let signature = self.pop_kind(Kind::DependentGenericSignature);
+        let module = self.pop_module()?;
+        let protocol = self.pop_protocol()?;
+        let mut ty = self.pop_kind(Kind::Type)?;
+        if let Some(signature) = signature {
+            ty = Node::wrap_type(Node::with_children(
+                Kind::DependentGenericType,
+                vec![signature, ty],
+            ));
+        }
+        Some(Node::with_children(
+            Kind::ProtocolConformance,
+            vec![ty, protocol, module],
+        ))
+    }
+
+    fn pop_tuple(&mut self) -> Option<Node> {
+        let mut elements = Vec::new();
+        if self.pop_kind(Kind::EmptyList).is_none() {
+            loop {
+                let is_first = self.pop_kind(Kind::FirstElementMarker).is_some();
+                let mut element = Node::new(Kind::TupleElement);
+                element.children.extend(self.pop_kind(Kind::VariadicMarker));
+                if let Some(label) = self.pop_kind(Kind::Identifier) {
+                    element
+                        .children
+                        .push(Node::with_text(Kind::TupleElementName, label.text));
+                }
+                element.children.push(self.pop_kind(Kind::Type)?);
+                elements.push(element);
+                if is_first {
+                    break;
+                }
+            }
+            elements.reverse();
+        }
+        Some(Node::wrap_type(Node::with_children(Kind::Tuple, elements)))
+    }
+
+    fn pop_function_type(&mut self, kind: Kind) -> Option<Node> {
+        let mut function = Node::new(kind);
+        function
+            .children
+            .extend(self.pop_kind(Kind::ThrowsAnnotation));
+        function
+            .children
+            .extend(self.pop_kind(Kind::ConcurrentFunctionType));
+        function
+            .children
+            .extend(self.pop_kind(Kind::AsyncAnnotation));
+        function
+            .children
+            .push(self.pop_function_params(Kind::ArgumentTuple)?);
+        function
+            .children
+            .push(self.pop_function_params(Kind::ReturnType)?);
+        Some(Node::wrap_type(function))
+    }
+
+    fn pop_function_params(&mut self, kind: Kind) -> Option<Node> {
+        let params = match self.pop_kind(Kind::EmptyList) {
+            Some(_) => Node::wrap_type(Node::new(Kind::Tuple)),
+            None => self.pop_kind(Kind::Type)?,
+        };
+        Some(Node::with_children(kind, vec![params]))
+    }
+
+    /// Argument labels go before the function type, `_` stands for unlabeled argument
+    fn pop_function_param_labels(&mut self, ty: &Node) -> Option<Node> {
+        if self.pop_kind(Kind::EmptyList).is_some() {
+            return Some(Node::new(Kind::LabelList));
+        }
+        if ty.kind != Kind::Type {
+            return None;
+        }
+
+        let mut function = ty.unwrapped();
+        if function.kind == Kind::DependentGenericType {
+            function = function.child(1)?.unwrapped();
+        }
+        if !function.kind.is_function_type() {
+            return None;
+        }
+        let params = function
+            .child_of_kind(Kind::ArgumentTuple)?
+            .child(0)?
+            .unwrapped();
+        let count = match params.kind {
+            Kind::Tuple => params.children.len(),
+            _ => 1,
+        };
+        if count == 0 {
+            return None;
+        }
+
+        let mut labels = Vec::new();
+        for _ in 0..count {
+            labels.push(self.pop_if(|k| k == Kind::Identifier || k == Kind::FirstElementMarker)?);
+        }
+        if labels.iter().all(|l| l.kind == Kind::FirstElementMarker) {
+            return Some(Node::new(Kind::LabelList));
+        }
+        labels.reverse();
+        Some(Node::with_children(Kind::LabelList, labels))
+    }
+
+    fn demangle_plain_function(&mut self) -> Option<Node> {
+        let signature = self.pop_kind(Kind::DependentGenericSignature);
+        let mut ty = self.pop_function_type(Kind::FunctionType)?;
+        let labels = self.pop_function_param_labels(&ty);
+        if let Some(signature) = signature {
+            ty = Node::wrap_type(Node::with_children(
+                Kind::DependentGenericType,
+                vec![signature, ty],
+            ));
+        }
+
+        let name = self.pop_if(Kind::is_decl_name)?;
+        let context = self.pop_context()?;
+        let mut children = vec![context, name];
+        children.extend(labels);
+        children.push(ty);
+        Some(Node::with_children(Kind::Function, children))
+    }
+
+    fn demangle_entity(&mut self, kind: Kind) -> Option<Node> {
+        let ty = self.pop_kind(Kind::Type)?;
+        let labels = self.pop_function_param_labels(&ty);
+        let name = self.pop_if(Kind::is_decl_name)?;
+        let context = self.pop_context()?;
+        let mut children = vec![context, name];
+        children.extend(labels);
+        children.push(ty);
+        Some(Node::with_children(kind, children))
+    }
+
+    fn demangle_subscript(&mut self) -> Option<Node> {
+        let private_name = self.pop_kind(Kind::PrivateDeclName);
+        let ty = self.pop_kind(Kind::Type)?;
+        let labels = self.pop_function_param_labels(&ty);
+        let context = self.pop_context()?;
+        let mut children = vec![context];
+        children.extend(labels);
+        children.push(ty);
+        children.extend(private_name);
+        let subscript = Node::with_children(Kind::Subscript, children);
+        self.demangle_accessor(subscript)
+    }
+
+    fn demangle_accessor(&mut self, storage: Node) -> Option<Node> {
+        let addressor = |c: u8| match c {
+            b'O' => Some("owning"),
+            b'o' => Some("nativeOwning"),
+            b'p' => Some("nativePinning"),
+            b'u' => Some("unsafe"),
+            _ => None,
+        };
+
+        let (kind, text) = match self.next() {
+            b'm' => (Kind::MaterializeForSet, None),
+            b's' => (Kind::Setter, None),
+            b'g' => (Kind::Getter, None),
+            b'G' => (Kind::GlobalGetter, None),
+            b'w' => (Kind::WillSet, None),
+            b'W' => (Kind::DidSet, None),
+            b'r' => (Kind::ReadAccessor, None),
+            b'M' => (Kind::ModifyAccessor, None),
+            b'a' => (Kind::MutableAddressor, Some(addressor(self.next())?)),
+            b'l' => (Kind::Addressor, Some(addressor(self.next())?)),
+            b'p' => return Some(storage),
+            _ => return None,
+        };
+        let mut accessor = Node::with_children(kind, vec![storage]);
+        accessor.text = text.unwrap_or_default().to_string();
+        Some(accessor)
+    }
+
+    fn demangle_function_entity(&mut self) -> Option<Node> {
+        enum Args {
+            None,
+            TypeAndMaybePrivateName,
+            TypeAndIndex,
+            Index,
+        }
+
+        let (kind, args) = match self.next() {
+            b'D' => (Kind::Deallocator, Args::None),
+            b'd' => (Kind::Destructor, Args::None),
+            b'E' => (Kind::IVarDestroyer, Args::None),
+            b'e' => (Kind::IVarInitializer, Args::None),
+            b'i' => (Kind::Initializer, Args::None),
+            b'C' => (Kind::Allocator, Args::TypeAndMaybePrivateName),
+            b'c' => (Kind::Constructor, Args::TypeAndMaybePrivateName),
+            b'U' => (Kind::ExplicitClosure, Args::TypeAndIndex),
+            b'u' => (Kind::ImplicitClosure, Args::TypeAndIndex),
+            b'A' => (Kind::DefaultArgumentInitializer, Args::Index),
+            _ => return None,
+        };
+
+        let mut children = Vec::new();
+        match args {
+            Args::None => children.push(self.pop_context()?),
+            Args::TypeAndMaybePrivateName => {
+                let private_name = self.pop_kind(Kind::PrivateDeclName);
+                let ty = self.pop_kind(Kind::Type)?;
+                let labels = self.pop_function_param_labels(&ty);
+                children.push(self.pop_context()?);
+                children.extend(labels);
+                children.push(ty);
+                children.extend(private_name);
+            }
+            Args::TypeAndIndex => {
+                let index = self.index_node()?;
+                let ty = self.pop_kind(Kind::Type)?;
+                children.push(self.pop_context()?);
+                children.push(index);
+                children.push(ty);
+            }
+            Args::Index => {
+                let index = self.index_node()?;
+                children.push(self.pop_context()?);
+                children.push(index);
+            }
+        }
+        Some(Node::with_children(kind, children))
+    }
+
+    fn demangle_generic_signature(&mut self, has_param_counts: bool) -> Option<Node> {
+        let mut counts = Vec::new();
+        match has_param_counts {
+            true => {
+                while !self.next_if(b'l') {
+                    let count = match self.next_if(b'z') {
+                        true => 0,
+                        false => self.index()? + 1,
+                    };
+                    counts.push(Node::with_index(Kind::DependentGenericParamCount, count));
+                }
+            }
+            false => counts.push(Node::with_index(Kind::DependentGenericParamCount, 1)),
+        }
+
+        let mut requirements = Vec::new();
+        while let Some(requirement) = self.pop_if(Kind::is_requirement) {
+            requirements.push(requirement);
+        }
+        requirements.reverse();
+
+        counts.extend(requirements);
+        Some(Node::with_children(Kind::DependentGenericSignature, counts))
+    }
+
+    fn demangle_generic_param_index(&mut self) -> Option<Node> {
+        if self.next_if(b'd') {
+            let depth = self.index()? + 1;
+            let index = self.index()?;
+            return Some(generic_param(depth, index));
+        }
+        if self.next_if(b'z') {
+            return Some(generic_param(0, 0));
+        }
+        Some(generic_param(0, self.index()? + 1))
+    }
+
+    fn demangle_generic_requirement(&mut self) -> Option<Node> {
+        enum TypeKind {
+            Generic,
+            Assoc,
+            Substitution,
+        }
+        enum ConstraintKind {
+            Protocol,
+            BaseClass,
+            SameType,
+            Layout,
+        }
+
+        let (constraint, type_kind) = match self.next() {
+            b'c' => (ConstraintKind::BaseClass, TypeKind::Assoc),
+            b'b' => (ConstraintKind::BaseClass, TypeKind::Generic),
+            b'B' => (ConstraintKind::BaseClass, TypeKind::Substitution),
+            b't' => (ConstraintKind::SameType, TypeKind::Assoc),
+            b's' => (ConstraintKind::SameType, TypeKind::Generic),
+            b'S' => (ConstraintKind::SameType, TypeKind::Substitution),
+            b'm' => (ConstraintKind::Layout, TypeKind::Assoc),
+            b'l' => (ConstraintKind::Layout, TypeKind::Generic),
+            b'L' => (ConstraintKind::Layout, TypeKind::Substitution),
+            b'p' => (ConstraintKind::Protocol, TypeKind::Assoc),
+            b'Q' => (ConstraintKind::Protocol, TypeKind::Substitution),
+            // Compound associated types, packs and inverse requirements
+            b'C' | b'T' | b'M' | b'P' | b'h' | b'i' | b'I' | b'v' | b'V' => return None,
+            _ => {
+                self.push_back();
+                (ConstraintKind::Protocol, TypeKind::Generic)
+            }
+        };
+
+        let constrained = match type_kind {
+            TypeKind::Generic => Node::wrap_type(self.demangle_generic_param_index()?),
+            TypeKind::Assoc => {
+                let param = self.demangle_generic_param_index()?;
+                let node = self.demangle_associated_type_simple(Some(param))?;
+                self.add_substitution(&node);
+                node
+            }
+            TypeKind::Substitution => self.pop_kind(Kind::Type)?,
+        };
+
+        let (kind, constraint) = match constraint {
+            ConstraintKind::Protocol => (
+                Kind::DependentGenericConformanceRequirement,
+                self.pop_protocol()?,
+            ),
+            ConstraintKind::BaseClass => (
+                Kind::DependentGenericConformanceRequirement,
+                self.pop_kind(Kind::Type)?,
+            ),
+            ConstraintKind::SameType => (
+                Kind::DependentGenericSameTypeRequirement,
+                self.pop_kind(Kind::Type)?,
+            ),
+            ConstraintKind::Layout => {
+                let layout = match self.next() {
+                    b'U' => "_UnknownLayout".to_string(),
+                    b'R' => "_RefCountedObject".to_string(),
+                    b'N' => "_NativeRefCountedObject".to_string(),
+                    b'C' => "AnyObject".to_string(),
+                    b'D' => "_NativeClass".to_string(),
+                    b'T' => "_Trivial".to_string(),
+                    b'E' => format!("_Trivial({}, {})", self.index()?, self.index()?),
+                    b'e' => format!("_Trivial({})", self.index()?),
+                    b'M' => format!("_TrivialAtMost({}, {})", self.index()?, self.index()?),
+                    b'm' => format!("_TrivialAtMost({})", self.index()?),
+                    _ => return None,
+                };
+                (
+                    Kind::DependentGenericLayoutRequirement,
+                    Node::with_text(Kind::Identifier, layout),
+                )
+            }
+        };
+        Some(Node::with_children(kind, vec![constrained, constraint]))
+    }
+
+    fn demangle_generic_type(&mut self) -> Option<Node> {
+        let signature = self.pop_kind(Kind::DependentGenericSignature)?;
+        let ty = self.pop_kind(Kind::Type)?;
+        Some(Node::wrap_type(Node::with_children(
+            Kind::DependentGenericType,
+            vec![signature, ty],
+        )))
+    }
+
+    fn demangle_archetype(&mut self) -> Option<Node> {
+        let node = match self.next() {
+            b'a' => {
+                let identifier = self.pop_kind(Kind::Identifier)?;
+                let base = self.pop_kind(Kind::Type)?.children.into_iter().next()?;
+                Node::wrap_type(Node::with_children(
+                    Kind::AssociatedTypeRef,
+                    vec![base, identifier],
+                ))
+            }
+            b'r' => return Some(Node::wrap_type(Node::new(Kind::OpaqueReturnType))),
+            b'x' => self.demangle_associated_type_simple(None)?,
+            b'y' => {
+                let param = self.demangle_generic_param_index()?;
+                self.demangle_associated_type_simple(Some(param))?
+            }
+            b'z' => self.demangle_associated_type_simple(Some(generic_param(0, 0)))?,
+            _ => return None,
+        };
+        self.add_substitution(&node);
+        Some(node)
+    }
+
+    fn demangle_associated_type_simple(&mut self, param: Option<Node>) -> Option<Node> {
+        let name = self.pop_assoc_type_name()?;
+        let base = match param {
+            Some(param) => Node::wrap_type(param),
+            None => self.pop_kind(Kind::Type)?,
+        };
+        Some(Node::wrap_type(Node::with_children(
+            Kind::DependentMemberType,
+            vec![base, name],
+        )))
+    }
+
+    fn pop_assoc_type_name(&mut self) -> Option<Node> {
+        let protocol = self.pop_kind(Kind::Type);
+        if let Some(protocol) = &protocol {
+            if protocol.child(0)?.kind != Kind::Protocol {
+                return None;
+            }
+        }
+        let mut name = self.pop_kind(Kind::Identifier)?;
+        name.kind = Kind::DependentAssociatedTypeRef;
+        name.children.extend(protocol);
+        Some(name)
+    }
+
+    fn demangle_protocol_list(&mut self) -> Option<Node> {
+        let mut protocols = Vec::new();
+        if self.pop_kind(Kind::EmptyList).is_none() {
+            loop {
+                let is_first = self.pop_kind(Kind::FirstElementMarker).is_some();
+                protocols.push(self.pop_protocol()?);
+                if is_first {
+                    break;
+                }
+            }
+            protocols.reverse();
+        }
+        let list = Node::with_children(Kind::TypeList, protocols);
+        Some(Node::with_children(Kind::ProtocolList, vec![list]))
+    }
+
+    fn demangle_special_type(&mut self) -> Option<Node> {
+        let wrapped = |demangler: &mut Self, kind: Kind| {
+            let ty = demangler.pop_kind(Kind::Type)?;
+            Some(Node::wrap_type(Node::with_children(kind, vec![ty])))
+        };
+
+        match self.next() {
+            b'E' => self.pop_function_type(Kind::NoEscapeFunctionType),
+            b'A' => self.pop_function_type(Kind::EscapingAutoClosureType),
+            b'f' => self.pop_function_type(Kind::ThinFunctionType),
+            b'K' => self.pop_function_type(Kind::AutoClosureType),
+            b'L' => self.pop_function_type(Kind::EscapingObjCBlock),
+            b'B' => self.pop_function_type(Kind::ObjCBlock),
+            b'C' => self.pop_function_type(Kind::CFunctionPointer),
+            b'o' => wrapped(self, Kind::Unowned),
+            b'u' => wrapped(self, Kind::Unmanaged),
+            b'w' => wrapped(self, Kind::Weak),
+            b'D' => wrapped(self, Kind::DynamicSelf),
+            b'p' => wrapped(self, Kind::ExistentialMetatype),
+            b'c' => {
+                let superclass = self.pop_kind(Kind::Type)?;
+                let protocols = self.demangle_protocol_list()?;
+                Some(Node::wrap_type(Node::with_children(
+                    Kind::ProtocolListWithClass,
+                    vec![protocols, superclass],
+                )))
+            }
+            b'l' => {
+                let protocols = self.demangle_protocol_list()?;
+                Some(Node::wrap_type(Node::with_children(
+                    Kind::ProtocolListWithAnyObject,
+                    vec![protocols],
+                )))
+            }
+            _ => None,
+        }
+    }
+
+    fn demangle_type_annotation(&mut self) -> Option<Node> {
+        match self.next() {
+            b'a' => Some(Node::new(Kind::AsyncAnnotation)),
+            b'b' => Some(Node::new(Kind::ConcurrentFunctionType)),
+            _ => None,
+        }
+    }
+
+    fn demangle_metatype(&mut self) -> Option<Node> {
+        let with_child = |kind: Kind, child: Node| Some(Node::with_children(kind, vec![child]));
+
+        match self.next() {
+            b'a' => self.with_popped_type(Kind::TypeMetadataAccessFunction),
+            b'A' => with_child(
+                Kind::ReflectionMetadataAssocTypeDescriptor,
+                self.pop_protocol_conformance()?,
+            ),
+            b'B' => self.with_popped_type(Kind::ReflectionMetadataBuiltinDescriptor),
+            b'C' => {
+                let ty = self.pop_kind(Kind::Type)?;
+                with_child(
+                    Kind::ReflectionMetadataSuperclassDescriptor,
+                    ty.children.into_iter().next()?,
+                )
+            }
+            b'c' => with_child(
+                Kind::ProtocolConformanceDescriptor,
+                self.pop_protocol_conformance()?,
+            ),
+            b'D' => self.with_popped_type(Kind::TypeMetadataDemanglingCache),
+            b'F' => self.with_popped_type(Kind::ReflectionMetadataFieldDescriptor),
+            b'f' => self.with_popped_type(Kind::FullTypeMetadata),
+            b'i' => self.with_popped_type(Kind::TypeMetadataInstantiationFunction),
+            b'I' => self.with_popped_type(Kind::TypeMetadataInstantiationCache),
+            b'l' => self.with_popped_type(Kind::TypeMetadataSingletonInitializationCache),
+            b'L' => self.with_popped_type(Kind::TypeMetadataLazyCache),
+            b'm' => self.with_popped_type(Kind::Metaclass),
+            b'n' => self.with_popped_type(Kind::NominalTypeDescriptor),
+            b'o' => self.with_popped_type(Kind::ClassMetadataBaseOffset),
+            b'P' => self.with_popped_type(Kind::GenericTypeMetadataPattern),
+            b'p' => with_child(Kind::ProtocolDescriptor, self.pop_protocol()?),
+            b'r' => self.with_popped_type(Kind::TypeMetadataCompletionFunction),
+            b's' => self.with_popped_type(Kind::ObjCResilientClassStub),
+            b't' => self.with_popped_type(Kind::FullObjCResilientClassStub),
+            b'u' => self.with_popped_type(Kind::MethodLookupFunction),
+            b'U' => self.with_popped_type(Kind::ObjCMetadataUpdateFunction),
+            b'V' => with_child(Kind::PropertyDescriptor, self.pop_if(Kind::is_entity)?),
+            b'X' => match self.next() {
+                b'E' => with_child(Kind::ExtensionDescriptor, self.pop_context()?),
+                b'M' => with_child(Kind::ModuleDescriptor, self.pop_module()?),
+                b'A' => with_child(Kind::AnonymousDescriptor, self.pop_context()?),
+                b'X' => with_child(Kind::AnonymousContext, self.pop_context()?),
+                b'Y' => {
+                    let discriminator = self.pop()?;
+                    let context = self.pop_context()?;
+                    Some(Node::with_children(
+                        Kind::AnonymousContext,
+                        vec![context, discriminator],
+                    ))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn demangle_witness(&mut self) -> Option<Node> {
+        let with_child = |kind: Kind, child: Node| Some(Node::with_children(kind, vec![child]));
+
+        match self.next() {
+            b'C' => with_child(Kind::EnumCase, self.pop_if(Kind::is_entity)?),
+            b'V' => self.with_popped_type(Kind::ValueWitnessTable),
+            b'v' => {
+                let directness = match self.next() {
+                    b'd' => "direct",
+                    b'i' => "indirect",
+                    _ => return None,
+                };
+                let entity = self.pop_if(Kind::is_entity)?;
+                Some(Node::with_children(
+                    Kind::FieldOffset,
+                    vec![Node::with_text(Kind::Directness, directness), entity],
+                ))
+            }
+            b'P' => with_child(Kind::ProtocolWitnessTable, self.pop_protocol_conformance()?),
+            b'p' => with_child(
+                Kind::ProtocolWitnessTablePattern,
+                self.pop_protocol_conformance()?,
+            ),
+            b'G' => with_child(
+                Kind::GenericProtocolWitnessTable,
+                self.pop_protocol_conformance()?,
+            ),
+            b'I' => with_child(
+                Kind::GenericProtocolWitnessTableInstantiationFunction,
+                self.pop_protocol_conformance()?,
+            ),
+            b'r' => with_child(
+                Kind::ResilientProtocolWitnessTable,
+                self.pop_protocol_conformance()?,
+            ),
+            b'a' => with_child(
+                Kind::ProtocolWitnessTableAccessor,
+                self.pop_protocol_conformance()?,
+            ),
+            b'l' | b'L' => {
+                let kind = match self.text[self.pos - 1] {
+                    b'l' => Kind::LazyProtocolWitnessTableAccessor,
+                    _ => Kind::LazyProtocolWitnessTableCacheVariable,
+                };
+                let conformance = self.pop_protocol_conformance()?;
+                let ty = self.pop_kind(Kind::Type)?;
+                Some(Node::with_children(kind, vec![ty, conformance]))
+            }
+            b't' => {
+                let name = self.pop_if(Kind::is_decl_name)?;
+                let conformance = self.pop_protocol_conformance()?;
+                Some(Node::with_children(
+                    Kind::AssociatedTypeMetadataAccessor,
+                    vec![conformance, name],
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn demangle_thunk(&mut self) -> Option<Node> {
+        let with_entity = |demangler: &mut Self, kind: Kind| {
+            let entity = demangler.pop_if(Kind::is_entity)?;
+            Some(Node::with_children(kind, vec![entity]))
+        };
+
+        match self.next() {
+            b'c' => with_entity(self, Kind::CurryThunk),
+            b'j' => with_entity(self, Kind::DispatchThunk),
+            b'q' => with_entity(self, Kind::MethodDescriptor),
+            b'o' => Some(Node::new(Kind::ObjCAttribute)),
+            b'O' => Some(Node::new(Kind::NonObjCAttribute)),
+            b'D' => Some(Node::new(Kind::DynamicAttribute)),
+            b'd' => Some(Node::new(Kind::DirectMethodReferenceAttribute)),
+            b'a' => Some(Node::new(Kind::PartialApplyObjCForwarder)),
+            b'A' => Some(Node::new(Kind::PartialApplyForwarder)),
+            b'm' => Some(Node::new(Kind::MergedFunction)),
+            b'X' => Some(Node::new(Kind::DynamicallyReplaceableFunctionVar)),
+            b'x' => Some(Node::new(Kind::DynamicallyReplaceableFunctionKey)),
+            b'I' => Some(Node::new(Kind::DynamicallyReplaceableFunctionImpl)),
+            b'V' => {
+                let base = self.pop_if(Kind::is_entity)?;
+                let derived = self.pop_if(Kind::is_entity)?;
+                Some(Node::with_children(Kind::VTableThunk, vec![derived, base]))
+            }
+            b'W' => {
+                let entity = self.pop_if(Kind::is_entity)?;
+                let conformance = self.pop_protocol_conformance()?;
+                Some(Node::with_children(
+                    Kind::ProtocolWitness,
+                    vec![conformance, entity],
+                ))
+            }
+            b'L' => {
+                let protocol = self.pop_protocol()?;
+                Some(Node::with_children(
+                    Kind::ProtocolRequirementsBaseDescriptor,
+                    vec![protocol],
+                ))
+            }
+            b'l' => {
+                let name = self.pop_assoc_type_name()?;
+                Some(Node::with_children(
+                    Kind::AssociatedTypeDescriptor,
+                    vec![name],
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Applies generic arguments to the nominal type and its generic parents, the innermost list goes first
+fn bound_generic_args(nominal: Node, type_lists: &[Node], mut list_index: usize) -> Option<Node> {
+    let mut args = type_lists.get(list_index)?.clone();
+
+    // Context of symbolic reference is unknown, so it takes all the arguments
+    if nominal.kind == Kind::Resolved {
+        let children = type_lists
+            .iter()
+            .rev()
+            .flat_map(|l| l.children.clone())
+            .collect();
+        args.children = children;
+        return Some(Node::with_children(
+            Kind::BoundGenericStructure,
+            vec![Node::wrap_type(nominal), args],
+        ));
+    }
+
+    let consumes_args = !matches!(
+        nominal.kind,
+        Kind::Variable
+            | Kind::Subscript
+            | Kind::ImplicitClosure
+            | Kind::ExplicitClosure
+            | Kind::DefaultArgumentInitializer
+            | Kind::Initializer
+    );
+    if consumes_args {
+        list_index += 1;
+    }
+
+    let mut nominal = nominal;
+    if list_index < type_lists.len() {
+        let context = nominal.child(0)?.clone();
+        let parent = match context.kind {
+            Kind::Extension => {
+                let mut extension = context.clone();
+                extension.children[1] =
+                    bound_generic_args(context.child(1)?.clone(), type_lists, list_index)?;
+                extension
+            }
+            _ => bound_generic_args(context, type_lists, list_index)?,
+        };
+        nominal.children[0] = parent;
+    }
+
+    if !consumes_args || args.children.is_empty() {
+        return Some(nominal);
+    }
+
+    let kind = match nominal.kind {
+        Kind::Class => Kind::BoundGenericClass,
+        Kind::Structure => Kind::BoundGenericStructure,
+        Kind::Enum => Kind::BoundGenericEnum,
+        Kind::Protocol => Kind::BoundGenericProtocol,
+        Kind::TypeAlias => Kind::BoundGenericTypeAlias,
+        _ => return None,
+    };
+    args.kind = Kind::TypeList;
+    Some(Node::with_children(
+        kind,
+        vec![Node::wrap_type(nominal), args],
+    ))
+}
+
+fn generic_param(depth: u64, index: u64) -> Node {
+    Node::with_text(
+        Kind::DependentGenericParamType,
+        generic_param_name(depth, index),
+    )
+}
+
+fn swift_type(kind: Kind, name: &str) -> Node {
+    Node::wrap_type(Node::with_children(
+        kind,
+        vec![
+            Node::with_text(Kind::Module, super::STDLIB_NAME),
+            Node::with_text(Kind::Identifier, name),
+        ],
+    ))
+}
+
+/// Known types of standard library with single letter substitutions, `S<c>` and `Sc<c>`
+fn standard_type(c: u8, second_level: bool) -> Option<(Kind, &'static str)> {
+    if second_level {
+        let name = match c {
+            b'A' => "Actor",
+            b'C' => "CheckedContinuation",
+            b'c' => "UnsafeContinuation",
+            b'E' => "CancellationError",
+            b'e' => "UnownedSerialExecutor",
+            b'F' => "Executor",
+            b'f' => "SerialExecutor",
+            b'G' => "TaskGroup",
+            b'g' => "ThrowingTaskGroup",
+            b'I' => "AsyncIteratorProtocol",
+            b'i' => "AsyncSequence",
+            b'J' => "UnownedJob",
+            b'M' => "MainActor",
+            b'P' => "TaskPriority",
+            b'S' => "AsyncStream",
+            b's' => "AsyncThrowingStream",
+            b'T' => "Task",
+            b't' => "UnsafeCurrentTask",
+            _ => return None,
+        };
+        let kind = match c {
+            b'A' | b'F' | b'f' | b'I' | b'i' => Kind::Protocol,
+            _ => Kind::Structure,
+        };
+        return Some((kind, name));
+    }
+
+    let (kind, name) = match c {
+        b'A' => (Kind::Structure, "AutoreleasingUnsafeMutablePointer"),
+        b'a' => (Kind::Structure, "Array"),
+        b'b' => (Kind::Structure, "Bool"),
+        b'D' => (Kind::Structure, "Dictionary"),
+        b'd' => (Kind::Structure, "Double"),
+        b'f' => (Kind::Structure, "Float"),
+        b'h' => (Kind::Structure, "Set"),
+        b'I' => (Kind::Structure, "DefaultIndices"),
+        b'i' => (Kind::Structure, "Int"),
+        b'J' => (Kind::Structure, "Character"),
+        b'N' => (Kind::Structure, "ClosedRange"),
+        b'n' => (Kind::Structure, "Range"),
+        b'O' => (Kind::Structure, "ObjectIdentifier"),
+        b'P' => (Kind::Structure, "UnsafePointer"),
+        b'p' => (Kind::Structure, "UnsafeMutablePointer"),
+        b'R' => (Kind::Structure, "UnsafeBufferPointer"),
+        b'r' => (Kind::Structure, "UnsafeMutableBufferPointer"),
+        b'S' => (Kind::Structure, "String"),
+        b's' => (Kind::Structure, "Substring"),
+        b'u' => (Kind::Structure, "UInt"),
+        b'V' => (Kind::Structure, "UnsafeRawPointer"),
+        b'v' => (Kind::Structure, "UnsafeMutableRawPointer"),
+        b'W' => (Kind::Structure, "UnsafeRawBufferPointer"),
+        b'w' => (Kind::Structure, "UnsafeMutableRawBufferPointer"),
+        b'q' => (Kind::Enum, "Optional"),
+        b'B' => (Kind::Protocol, "BinaryFloatingPoint"),
+        b'E' => (Kind::Protocol, "Encodable"),
+        b'e' => (Kind::Protocol, "Decodable"),
+        b'F' => (Kind::Protocol, "FloatingPoint"),
+        b'G' => (Kind::Protocol, "RandomNumberGenerator"),
+        b'H' => (Kind::Protocol, "Hashable"),
+        b'j' => (Kind::Protocol, "Numeric"),
+        b'K' => (Kind::Protocol, "BidirectionalCollection"),
+        b'k' => (Kind::Protocol, "RandomAccessCollection"),
+        b'L' => (Kind::Protocol, "Comparable"),
+        b'l' => (Kind::Protocol, "Collection"),
+        b'M' => (Kind::Protocol, "MutableCollection"),
+        b'm' => (Kind::Protocol, "RangeReplaceableCollection"),
+        b'Q' => (Kind::Protocol, "Equatable"),
+        b'T' => (Kind::Protocol, "Sequence"),
+        b't' => (Kind::Protocol, "IteratorProtocol"),
+        b'U' => (Kind::Protocol, "UnsignedInteger"),
+        b'X' => (Kind::Protocol, "RangeExpression"),
+        b'x' => (Kind::Protocol, "Strideable"),
+        b'Y' => (Kind::Protocol, "RawRepresentable"),
+        b'y' => (Kind::Protocol, "StringProtocol"),
+        b'Z' => (Kind::Protocol, "SignedInteger"),
+        b'z' => (Kind::Protocol, "BinaryInteger"),
+        _ => return None,
+    };
+    Some((kind, name))
+}
@@ -0,0 +1,194 @@
+//! Swift demangler. Prints names in the format of `swift demangle --simplified=false`.
+
+mod demangler;
+mod node;
+mod old;
+mod printer;
+
+use demangler::Demangler;
+use node::Kind;
+use old::OldDemangler;
+
+pub(crate) const STDLIB_NAME: &str = "Swift";
+pub(crate) const OBJC_MODULE_NAME: &str = "__C";
+pub(crate) const CLANG_IMPORTER_MODULE_NAME: &str = "__C_Synthesized";
+
+/// Prefixes of current mangling, with leading underscore added by Mach-O
+const PREFIXES: &[&str] = &["$s", "$S", "$e", "_$s", "_$S", "_$e", "_T0"];
+const OLD_PREFIX: &str = "_T";
+
+/// Demangles Swift symbol, either with current (`$s`, `_T0`) or old (`_T`) mangling.
+/// Returns `None` if `name` isn't a Swift symbol or can't be demangled.
+pub fn demangle(name: &str) -> Option<String> {
+    if let Some(text) = PREFIXES.iter().find_map(|prefix| name.strip_prefix(prefix)) {
+        let node = Demangler::new(text.as_bytes(), &[]).demangle_symbol()?;
+        return printer::print(&node);
+    }
+    if let Some(text) = name.strip_prefix(OLD_PREFIX) {
+        let node = OldDemangler::new(text.as_bytes()).demangle_symbol()?;
+        return printer::print(&node);
+    }
+    None
+}
+
+/// Demangles mangled name of type from reflection metadata, like `Si` or `SaySSG`.
+/// Symbolic reference `0x01` followed by 32-bit little endian index is printed as `references[index]`.
+pub fn demangle_type(mangled: &[u8], references: &[String]) -> Option<String> {
+    let node = Demangler::new(mangled, references).demangle_type()?;
+    printer::print(&node)
+}
+
+/// Name of the context described by descriptor symbol, like `Swift.Equatable` for `_$sSQMp`
+pub fn demangle_context_descriptor(symbol: &str) -> Option<String> {
+    let text = PREFIXES
+        .iter()
+        .find_map(|prefix| symbol.strip_prefix(prefix))?;
+    let global = Demangler::new(text.as_bytes(), &[]).demangle_symbol()?;
+    let descriptor = match global.children.as_slice() {
+        [descriptor] => descriptor,
+        _ => return None,
+    };
+    match descriptor.kind {
+        Kind::NominalTypeDescriptor
+        | Kind::ProtocolDescriptor
+        | Kind::ModuleDescriptor
+        | Kind::ExtensionDescriptor => printer::print(descriptor.child(0)?),
+        _ => None,
+    }
+}
+
+/// Name of generic parameter in the same way as Swift demangler does:
+/// `A`, `B`, ..., `Z`, `AB`, `BB`, ..., with depth appended for nested generic contexts
+pub fn generic_param_name(depth: u64, index: u64) -> String {
+    let mut name = String::new();
+    let mut index = index;
+    loop {
+        name.push((b'A' + (index % 26) as u8) as char);
+        index /= 26;
+        if index == 0 {
+            break;
+        }
+    }
+    if depth != 0 {
+        name += &depth.to_string();
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(mangled: &str, expected: &str) {
+        assert_eq!(demangle(mangled).as_deref(), Some(expected), "{mangled}");
+    }
+
+    #[test]
+    fn test_demangle_types_and_metadata() {
+        check("_$s4main3FooVMn", "nominal type descriptor for main.Foo");
+        check("_$s4main3FooCMa", "type metadata accessor for main.Foo");
+        check("$s4main3FooCN", "type metadata for main.Foo");
+        check("_$sSQMp", "protocol descriptor for Swift.Equatable");
+        check(
+            "_$s4main5PointVAA5ShapeAAMc",
+            "protocol conformance descriptor for main.Point : main.Shape in main",
+        );
+        check(
+            "_$sSaySiGMD",
+            "demangling cache variable for type metadata for [Swift.Int]",
+        );
+    }
+
+    #[test]
+    fn test_demangle_functions() {
+        check("_$s4main3FooV1xSivg", "main.Foo.x.getter : Swift.Int");
+        check(
+            "_$s4main3FooCACycfC",
+            "main.Foo.__allocating_init() -> main.Foo",
+        );
+        check("_$s4main3FooVACycfC", "main.Foo.init() -> main.Foo");
+        check(
+            "_$s4main3FooC3bar1xySi_tF",
+            "main.Foo.bar(x: Swift.Int) -> ()",
+        );
+        check(
+            "_$s4main3fooyyFyycfU_",
+            "closure #1 () -> () in main.foo() -> ()",
+        );
+        check("_$s4main3fooyyxlF", "main.foo<A>(A) -> ()");
+        check(
+            "_$s4main3FooV2eeoiySbAC_ACtFZ",
+            "static main.Foo.== infix(main.Foo, main.Foo) -> Swift.Bool",
+        );
+        check("_$s4main3fooSSSgyF", "main.foo() -> Swift.String?");
+    }
+
+    #[test]
+    fn test_demangle_old_mangling() {
+        check("_TtC4main3Foo", "main.Foo");
+        check("_TtGSaSS_", "[Swift.String]");
+        check("_TtGVs10DictionarySSSi_", "[Swift.String : Swift.Int]");
+        check("_TtT3fooSi3barSu_", "(foo: Swift.Int, bar: Swift.UInt)");
+        check("_TF3foog3barSi", "foo.bar.getter : Swift.Int");
+        check(
+            "_TFC3foo3bar3basfT3zimCS_3zim_T_",
+            "foo.bar.bas(zim: foo.zim) -> ()",
+        );
+        check("_TMnC4main3Foo", "nominal type descriptor for main.Foo");
+    }
+
+    #[test]
+    fn test_not_swift() {
+        assert_eq!(demangle("_main"), None);
+        assert_eq!(demangle("__ZN4core3fmt5write17h0123456789abcdefE"), None);
+        assert_eq!(demangle("_$s4main3FooQQ"), None);
+    }
+
+    #[test]
+    fn test_deep_nesting() {
+        let nested = |depth: usize| format!("$s{}Si{}", "Say".repeat(depth), "G".repeat(depth));
+        let expected = format!("{}Swift.Int{}", "[".repeat(8), "]".repeat(8));
+        assert_eq!(demangle(&nested(8)), Some(expected));
+        assert_eq!(demangle(&nested(5000)), None);
+        assert_eq!(demangle_type(&nested(5000).as_bytes()[2..], &[]), None);
+
+        let old = format!("_TtGSa{}Si_{}", "GSa".repeat(100000), "_".repeat(100000));
+        assert_eq!(demangle(&old), None);
+        assert_eq!(demangle("_TtGSaGSaSi__"), Some("[[Swift.Int]]".to_string()));
+    }
+
+    #[test]
+    fn test_demangle_type() {
+        assert_eq!(demangle_type(b"Si", &[]).as_deref(), Some("Swift.Int"));
+        assert_eq!(
+            demangle_type(b"SaySSG", &[]).as_deref(),
+            Some("[Swift.String]")
+        );
+        let references = ["main.Foo".to_string()];
+        assert_eq!(
+            demangle_type(b"\x01\x00\x00\x00\x00Sg", &references).as_deref(),
+            Some("main.Foo?")
+        );
+    }
+
+    #[test]
+    fn test_demangle_context_descriptor() {
+        assert_eq!(
+            demangle_context_descriptor("_$sSQMp").as_deref(),
+            Some("Swift.Equatable")
+        );
+        assert_eq!(
+            demangle_context_descriptor("_$s4main3FooVMn").as_deref(),
+            Some("main.Foo")
+        );
+        assert_eq!(demangle_context_descriptor("_$s4main3FooVMa"), None);
+    }
+
+    #[test]
+    fn test_generic_param_name() {
+        assert_eq!(generic_param_name(0, 0), "A");
+        assert_eq!(generic_param_name(0, 25), "Z");
+        assert_eq!(generic_param_name(0, 26), "AB");
+        assert_eq!(generic_param_name(1, 1), "B1");
+    }
+}
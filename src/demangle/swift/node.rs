@@ -0,0 +1,330 @@
+//! Tree of demangled Swift symbol, the subset of `swift::Demangle::Node` kinds we produce
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Kind {
+    Global,
+    Suffix,
+
+    Module,
+    Identifier,
+    LocalDeclName,
+    PrivateDeclName,
+    RelatedEntityDeclName,
+    PrefixOperator,
+    PostfixOperator,
+    InfixOperator,
+    /// Context resolved from symbolic reference
+    Resolved,
+
+    Type,
+    Structure,
+    Class,
+    Enum,
+    Protocol,
+    TypeAlias,
+    Extension,
+    AnonymousContext,
+    BoundGenericStructure,
+    BoundGenericClass,
+    BoundGenericEnum,
+    BoundGenericProtocol,
+    BoundGenericTypeAlias,
+    BuiltinTypeName,
+    Tuple,
+    TupleElement,
+    TupleElementName,
+    TypeList,
+    FunctionType,
+    NoEscapeFunctionType,
+    AutoClosureType,
+    EscapingAutoClosureType,
+    ThinFunctionType,
+    CFunctionPointer,
+    ObjCBlock,
+    EscapingObjCBlock,
+    ArgumentTuple,
+    ReturnType,
+    ThrowsAnnotation,
+    AsyncAnnotation,
+    ConcurrentFunctionType,
+    InOut,
+    Shared,
+    Owned,
+    Weak,
+    Unowned,
+    Unmanaged,
+    DynamicSelf,
+    Metatype,
+    ExistentialMetatype,
+    ProtocolList,
+    ProtocolListWithClass,
+    ProtocolListWithAnyObject,
+    DependentGenericType,
+    DependentGenericSignature,
+    DependentGenericParamCount,
+    DependentGenericParamType,
+    DependentGenericConformanceRequirement,
+    DependentGenericSameTypeRequirement,
+    DependentGenericLayoutRequirement,
+    DependentMemberType,
+    DependentAssociatedTypeRef,
+    AssociatedTypeRef,
+    OpaqueReturnType,
+    LabelList,
+    EmptyList,
+    FirstElementMarker,
+    VariadicMarker,
+    Index,
+
+    Function,
+    Variable,
+    Subscript,
+    Static,
+    Allocator,
+    Constructor,
+    Deallocator,
+    Destructor,
+    IVarInitializer,
+    IVarDestroyer,
+    Initializer,
+    ExplicitClosure,
+    ImplicitClosure,
+    DefaultArgumentInitializer,
+    Getter,
+    Setter,
+    GlobalGetter,
+    WillSet,
+    DidSet,
+    ReadAccessor,
+    ModifyAccessor,
+    MaterializeForSet,
+    Addressor,
+    MutableAddressor,
+
+    TypeMangling,
+    TypeMetadata,
+    TypeMetadataAccessFunction,
+    TypeMetadataLazyCache,
+    TypeMetadataInstantiationCache,
+    TypeMetadataInstantiationFunction,
+    TypeMetadataCompletionFunction,
+    TypeMetadataSingletonInitializationCache,
+    TypeMetadataDemanglingCache,
+    FullTypeMetadata,
+    GenericTypeMetadataPattern,
+    Metaclass,
+    ClassMetadataBaseOffset,
+    NominalTypeDescriptor,
+    ProtocolDescriptor,
+    ProtocolConformanceDescriptor,
+    ProtocolRequirementsBaseDescriptor,
+    AssociatedTypeDescriptor,
+    ModuleDescriptor,
+    ExtensionDescriptor,
+    AnonymousDescriptor,
+    PropertyDescriptor,
+    MethodDescriptor,
+    MethodLookupFunction,
+    ObjCMetadataUpdateFunction,
+    ObjCResilientClassStub,
+    FullObjCResilientClassStub,
+    ReflectionMetadataFieldDescriptor,
+    ReflectionMetadataAssocTypeDescriptor,
+    ReflectionMetadataBuiltinDescriptor,
+    ReflectionMetadataSuperclassDescriptor,
+    ProtocolConformance,
+    ProtocolWitnessTable,
+    ProtocolWitnessTablePattern,
+    GenericProtocolWitnessTable,
+    GenericProtocolWitnessTableInstantiationFunction,
+    ResilientProtocolWitnessTable,
+    ProtocolWitnessTableAccessor,
+    LazyProtocolWitnessTableAccessor,
+    LazyProtocolWitnessTableCacheVariable,
+    AssociatedTypeMetadataAccessor,
+    ValueWitnessTable,
+    EnumCase,
+    FieldOffset,
+    Directness,
+
+    CurryThunk,
+    DispatchThunk,
+    ProtocolWitness,
+    VTableThunk,
+    ObjCAttribute,
+    NonObjCAttribute,
+    DynamicAttribute,
+    DirectMethodReferenceAttribute,
+    PartialApplyForwarder,
+    PartialApplyObjCForwarder,
+    MergedFunction,
+    DynamicallyReplaceableFunctionVar,
+    DynamicallyReplaceableFunctionKey,
+    DynamicallyReplaceableFunctionImpl,
+}
+
+impl Kind {
+    pub(super) fn is_decl_name(self) -> bool {
+        matches!(
+            self,
+            Kind::Identifier
+                | Kind::LocalDeclName
+                | Kind::PrivateDeclName
+                | Kind::RelatedEntityDeclName
+                | Kind::PrefixOperator
+                | Kind::PostfixOperator
+                | Kind::InfixOperator
+        )
+    }
+
+    pub(super) fn is_nominal(self) -> bool {
+        matches!(
+            self,
+            Kind::Structure
+                | Kind::Class
+                | Kind::Enum
+                | Kind::Protocol
+                | Kind::TypeAlias
+                | Kind::Resolved
+        )
+    }
+
+    pub(super) fn is_entity(self) -> bool {
+        // Nominal types are entities too, the `Type` wrapper goes along with them
+        self == Kind::Type || self.is_context()
+    }
+
+    pub(super) fn is_context(self) -> bool {
+        self.is_nominal()
+            || matches!(
+                self,
+                Kind::Module
+                    | Kind::Extension
+                    | Kind::AnonymousContext
+                    | Kind::Function
+                    | Kind::Variable
+                    | Kind::Subscript
+                    | Kind::Static
+                    | Kind::Allocator
+                    | Kind::Constructor
+                    | Kind::Deallocator
+                    | Kind::Destructor
+                    | Kind::IVarInitializer
+                    | Kind::IVarDestroyer
+                    | Kind::Initializer
+                    | Kind::ExplicitClosure
+                    | Kind::ImplicitClosure
+                    | Kind::DefaultArgumentInitializer
+                    | Kind::Getter
+                    | Kind::Setter
+                    | Kind::GlobalGetter
+                    | Kind::WillSet
+                    | Kind::DidSet
+                    | Kind::ReadAccessor
+                    | Kind::ModifyAccessor
+                    | Kind::MaterializeForSet
+                    | Kind::Addressor
+                    | Kind::MutableAddressor
+            )
+    }
+
+    pub(super) fn is_function_attribute(self) -> bool {
+        matches!(
+            self,
+            Kind::ObjCAttribute
+                | Kind::NonObjCAttribute
+                | Kind::DynamicAttribute
+                | Kind::DirectMethodReferenceAttribute
+                | Kind::PartialApplyForwarder
+                | Kind::PartialApplyObjCForwarder
+                | Kind::MergedFunction
+                | Kind::DynamicallyReplaceableFunctionVar
+                | Kind::DynamicallyReplaceableFunctionKey
+                | Kind::DynamicallyReplaceableFunctionImpl
+        )
+    }
+
+    pub(super) fn is_requirement(self) -> bool {
+        matches!(
+            self,
+            Kind::DependentGenericConformanceRequirement
+                | Kind::DependentGenericSameTypeRequirement
+                | Kind::DependentGenericLayoutRequirement
+        )
+    }
+
+    pub(super) fn is_function_type(self) -> bool {
+        matches!(
+            self,
+            Kind::FunctionType
+                | Kind::NoEscapeFunctionType
+                | Kind::AutoClosureType
+                | Kind::EscapingAutoClosureType
+                | Kind::ThinFunctionType
+                | Kind::CFunctionPointer
+                | Kind::ObjCBlock
+                | Kind::EscapingObjCBlock
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Node {
+    pub(super) kind: Kind,
+    pub(super) text: String,
+    pub(super) index: u64,
+    pub(super) children: Vec<Node>,
+}
+
+impl Node {
+    pub(super) fn new(kind: Kind) -> Self {
+        Node {
+            kind,
+            text: String::new(),
+            index: 0,
+            children: Vec::new(),
+        }
+    }
+
+    pub(super) fn with_text(kind: Kind, text: impl Into<String>) -> Self {
+        Node {
+            text: text.into(),
+            ..Self::new(kind)
+        }
+    }
+
+    pub(super) fn with_index(kind: Kind, index: u64) -> Self {
+        Node {
+            index,
+            ..Self::new(kind)
+        }
+    }
+
+    pub(super) fn with_children(kind: Kind, children: Vec<Node>) -> Self {
+        Node {
+            children,
+            ..Self::new(kind)
+        }
+    }
+
+    /// `Type` node wrapping `node`
+    pub(super) fn wrap_type(node: Node) -> Self {
+        Self::with_children(Kind::Type, vec![node])
+    }
+
+    pub(super) fn child(&self, index: usize) -> Option<&Node> {
+        self.children.get(index)
+    }
+
+    pub(super) fn child_of_kind(&self, kind: Kind) -> Option<&Node> {
+        self.children.iter().find(|c| c.kind == kind)
+    }
+
+    /// Unwraps `Type` node
+    pub(super) fn unwrapped(&self) -> &Node {
+        match (self.kind, self.children.first()) {
+            (Kind::Type, Some(child)) => child,
+            _ => self,
+        }
+    }
+}
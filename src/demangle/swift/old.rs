@@ -0,0 +1,664 @@
+//! Recursive descent demangler of mangling used before Swift 4 (`_T` prefix).
+//! Produces the same tree as current mangling, so it's printed the same way.
+//! Covers names of types, functions, accessors and metadata.
+
+use super::node::{Kind, Node};
+
+/// Limit of recursion, so nested types of malformed symbols don't overflow the stack
+const MAX_DEPTH: usize = 256;
+
+pub(super) struct OldDemangler<'a> {
+    text: &'a [u8],
+    pos: usize,
+    substitutions: Vec<Node>,
+    depth: usize,
+}
+
+impl<'a> OldDemangler<'a> {
+    pub(super) fn new(text: &'a [u8]) -> Self {
+        OldDemangler {
+            text,
+            pos: 0,
+            substitutions: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// Demangles symbol without `_T` prefix
+    pub(super) fn demangle_symbol(mut self) -> Option<Node> {
+        let global = self.demangle_global()?;
+        match self.pos == self.text.len() {
+            true => Some(global),
+            false => None,
+        }
+    }
+
+    fn demangle_global(&mut self) -> Option<Node> {
+        self.nested(Self::demangle_global_node)
+    }
+
+    fn demangle_global_node(&mut self) -> Option<Node> {
+        if self.next_str("PA") {
+            let inner = self.demangle_global()?;
+            let forwarder = Node::with_children(Kind::PartialApplyForwarder, inner.children);
+            return Some(Node::with_children(Kind::Global, vec![forwarder]));
+        }
+
+        let node = match self.next() {
+            b'T' => {
+                let attribute = match self.next() {
+                    b'o' => Kind::ObjCAttribute,
+                    b'O' => Kind::NonObjCAttribute,
+                    b'D' => Kind::DynamicAttribute,
+                    b'd' => Kind::DirectMethodReferenceAttribute,
+                    b'W' => {
+                        let conformance = self.demangle_protocol_conformance()?;
+                        let entity = self.demangle_entity()?;
+                        let witness =
+                            Node::with_children(Kind::ProtocolWitness, vec![conformance, entity]);
+                        return Some(Node::with_children(Kind::Global, vec![witness]));
+                    }
+                    _ => return None,
+                };
+                let mut children = vec![Node::new(attribute)];
+                children.extend(self.demangle_global()?.children);
+                return Some(Node::with_children(Kind::Global, children));
+            }
+            b't' => self.demangle_type()?.children.into_iter().next()?,
+            b'M' => self.demangle_metadata()?,
+            b'W' => self.demangle_witness()?,
+            _ => {
+                self.pos -= 1;
+                self.demangle_entity()?
+            }
+        };
+        Some(Node::with_children(Kind::Global, vec![node]))
+    }
+
+    fn demangle_metadata(&mut self) -> Option<Node> {
+        let kind = match self.next() {
+            b'P' => Kind::GenericTypeMetadataPattern,
+            b'a' => Kind::TypeMetadataAccessFunction,
+            b'L' => Kind::TypeMetadataLazyCache,
+            b'm' => Kind::Metaclass,
+            b'n' => Kind::NominalTypeDescriptor,
+            b'f' => Kind::FullTypeMetadata,
+            b'o' => Kind::ClassMetadataBaseOffset,
+            b'p' => {
+                let protocol = self.demangle_protocol_name()?;
+                return Some(Node::with_children(
+                    Kind::ProtocolDescriptor,
+                    vec![protocol],
+                ));
+            }
+            _ => {
+                self.pos -= 1;
+                Kind::TypeMetadata
+            }
+        };
+        // Directness of metadata isn't printed
+        let _ = self.next_if(b'd') || self.next_if(b'i');
+        let ty = self.demangle_type()?;
+        Some(Node::with_children(kind, vec![ty]))
+    }
+
+    fn demangle_witness(&mut self) -> Option<Node> {
+        let kind = match self.next() {
+            b'V' => {
+                let ty = self.demangle_type()?;
+                return Some(Node::with_children(Kind::ValueWitnessTable, vec![ty]));
+            }
+            b'l' | b'L' => {
+                let kind = match self.text[self.pos - 1] {
+                    b'l' => Kind::LazyProtocolWitnessTableAccessor,
+                    _ => Kind::LazyProtocolWitnessTableCacheVariable,
+                };
+                let ty = self.demangle_type()?;
+                let conformance = self.demangle_protocol_conformance()?;
+                return Some(Node::with_children(kind, vec![ty, conformance]));
+            }
+            b'P' => Kind::ProtocolWitnessTable,
+            b'G' => Kind::GenericProtocolWitnessTable,
+            b'I' => Kind::GenericProtocolWitnessTableInstantiationFunction,
+            b'a' => Kind::ProtocolWitnessTableAccessor,
+            _ => return None,
+        };
+        let conformance = self.demangle_protocol_conformance()?;
+        Some(Node::with_children(kind, vec![conformance]))
+    }
+
+    fn demangle_protocol_conformance(&mut self) -> Option<Node> {
+        let ty = self.demangle_type()?;
+        let protocol = self.demangle_protocol_name()?;
+        let context = self.demangle_context()?;
+        Some(Node::with_children(
+            Kind::ProtocolConformance,
+            vec![ty, protocol, context],
+        ))
+    }
+
+    fn demangle_entity(&mut self) -> Option<Node> {
+        let is_static = self.next_if(b'Z');
+        let basic_kind = match self.next() {
+            b'F' => Kind::Function,
+            b'v' => Kind::Variable,
+            b'I' => Kind::Initializer,
+            b'i' => Kind::Subscript,
+            _ => {
+                self.pos -= 1;
+                return self.demangle_nominal_type()?.children.into_iter().next();
+            }
+        };
+
+        let context = self.demangle_context()?;
+        let accessor = |kind: Kind| (kind, true, true);
+        let (kind, has_name, has_type) = match self.next() {
+            b'D' => (Kind::Deallocator, false, false),
+            b'd' => (Kind::Destructor, false, false),
+            b'e' => (Kind::IVarInitializer, false, false),
+            b'E' => (Kind::IVarDestroyer, false, false),
+            b'C' => (Kind::Allocator, false, true),
+            b'c' => (Kind::Constructor, false, true),
+            b'g' => accessor(Kind::Getter),
+            b'G' => accessor(Kind::GlobalGetter),
+            b's' => accessor(Kind::Setter),
+            b'm' => accessor(Kind::MaterializeForSet),
+            b'w' => accessor(Kind::WillSet),
+            b'W' => accessor(Kind::DidSet),
+            b'U' | b'u' => {
+                let kind = match self.text[self.pos - 1] {
+                    b'U' => Kind::ExplicitClosure,
+                    _ => Kind::ImplicitClosure,
+                };
+                let index = self.index()?;
+                let ty = self.demangle_type()?;
+                let closure = Node::with_children(
+                    kind,
+                    vec![context, Node::with_index(Kind::Index, index), ty],
+                );
+                return Some(wrap_static(closure, is_static));
+            }
+            b'A' if basic_kind == Kind::Initializer => {
+                let index = self.index()?;
+                let initializer = Node::with_children(
+                    Kind::DefaultArgumentInitializer,
+                    vec![context, Node::with_index(Kind::Index, index)],
+                );
+                return Some(wrap_static(initializer, is_static));
+            }
+            b'i' if basic_kind == Kind::Initializer => {
+                let name = self.demangle_decl_name()?;
+                let ty = self.demangle_type()?;
+                let variable = Node::with_children(Kind::Variable, vec![context, name, ty]);
+                let initializer = Node::with_children(Kind::Initializer, vec![variable]);
+                return Some(wrap_static(initializer, is_static));
+            }
+            _ => {
+                self.pos -= 1;
+                (basic_kind, true, true)
+            }
+        };
+
+        let is_accessor = matches!(
+            kind,
+            Kind::Getter
+                | Kind::GlobalGetter
+                | Kind::Setter
+                | Kind::MaterializeForSet
+                | Kind::WillSet
+                | Kind::DidSet
+        );
+
+        let mut children = vec![context];
+        if has_name {
+            children.push(self.demangle_decl_name()?);
+        }
+        if has_type {
+            children.push(self.demangle_type()?);
+        }
+
+        let entity = match is_accessor {
+            true => {
+                let storage = match basic_kind {
+                    Kind::Subscript => {
+                        // Name of subscript is always `subscript`
+                        children.remove(1);
+                        Node::with_children(Kind::Subscript, children)
+                    }
+                    _ => Node::with_children(Kind::Variable, children),
+                };
+                Node::with_children(kind, vec![storage])
+            }
+            false => Node::with_children(kind, children),
+        };
+        Some(wrap_static(entity, is_static))
+    }
+
+    fn demangle_context(&mut self) -> Option<Node> {
+        self.nested(Self::demangle_context_node)
+    }
+
+    fn demangle_context_node(&mut self) -> Option<Node> {
+        match self.peek() {
+            b'E' => {
+                self.pos += 1;
+                let module = self.demangle_module()?;
+                let extended = self.demangle_context()?;
+                Some(Node::with_children(Kind::Extension, vec![module, extended]))
+            }
+            b'S' => {
+                self.pos += 1;
+                let node = self.demangle_substitution()?;
+                Some(node.unwrapped().clone())
+            }
+            b's' => {
+                self.pos += 1;
+                Some(Node::with_text(Kind::Module, super::STDLIB_NAME))
+            }
+            b'F' | b'I' | b'v' | b'i' | b'Z' | b'C' | b'V' | b'O' | b'P' => self.demangle_entity(),
+            _ => self.demangle_module(),
+        }
+    }
+
+    fn demangle_module(&mut self) -> Option<Node> {
+        if self.next_if(b's') {
+            return Some(Node::with_text(Kind::Module, super::STDLIB_NAME));
+        }
+        if self.next_if(b'S') {
+            let node = self.demangle_substitution()?;
+            return match node.kind {
+                Kind::Module => Some(node),
+                _ => None,
+            };
+        }
+        let mut module = self.demangle_identifier()?;
+        module.kind = Kind::Module;
+        self.substitutions.push(module.clone());
+        Some(module)
+    }
+
+    /// Substitution after `S`
+    fn demangle_substitution(&mut self) -> Option<Node> {
+        let c = self.next();
+        let standard = |kind: Kind, name: &str| Some(swift_type(kind, name));
+        match c {
+            b's' => Some(Node::with_text(Kind::Module, super::STDLIB_NAME)),
+            b'o' => Some(Node::with_text(Kind::Module, super::OBJC_MODULE_NAME)),
+            b'C' => Some(Node::with_text(
+                Kind::Module,
+                super::CLANG_IMPORTER_MODULE_NAME,
+            )),
+            b'a' => standard(Kind::Structure, "Array"),
+            b'b' => standard(Kind::Structure, "Bool"),
+            b'c' => standard(Kind::Structure, "UnicodeScalar"),
+            b'd' => standard(Kind::Structure, "Double"),
+            b'f' => standard(Kind::Structure, "Float"),
+            b'i' => standard(Kind::Structure, "Int"),
+            b'V' => standard(Kind::Structure, "UnsafeRawPointer"),
+            b'v' => standard(Kind::Structure, "UnsafeMutableRawPointer"),
+            b'P' => standard(Kind::Structure, "UnsafePointer"),
+            b'p' => standard(Kind::Structure, "UnsafeMutablePointer"),
+            b'Q' => standard(Kind::Enum, "ImplicitlyUnwrappedOptional"),
+            b'q' => standard(Kind::Enum, "Optional"),
+            b'R' => standard(Kind::Structure, "UnsafeBufferPointer"),
+            b'r' => standard(Kind::Structure, "UnsafeMutableBufferPointer"),
+            b'S' => standard(Kind::Structure, "String"),
+            b'u' => standard(Kind::Structure, "UInt"),
+            _ => {
+                self.pos -= 1;
+                let index = self.index()?;
+                self.substitutions.get(index as usize).cloned()
+            }
+        }
+    }
+
+    fn demangle_nominal_type(&mut self) -> Option<Node> {
+        let kind = match self.next() {
+            b'S' => return self.demangle_substitution(),
+            b'C' => Kind::Class,
+            b'V' => Kind::Structure,
+            b'O' => Kind::Enum,
+            b'P' => Kind::Protocol,
+            _ => return None,
+        };
+        self.demangle_declaration(kind)
+    }
+
+    fn demangle_declaration(&mut self, kind: Kind) -> Option<Node> {
+        let context = self.demangle_context()?;
+        let name = self.demangle_decl_name()?;
+        let node = Node::wrap_type(Node::with_children(kind, vec![context, name]));
+        self.substitutions.push(node.clone());
+        Some(node)
+    }
+
+    fn demangle_protocol_name(&mut self) -> Option<Node> {
+        if self.next_if(b'S') {
+            let node = self.demangle_substitution()?;
+            return match node.unwrapped().kind {
+                Kind::Protocol => Some(node),
+                // Protocol in the module referenced by substitution
+                Kind::Module => {
+                    let name = self.demangle_decl_name()?;
+                    let node =
+                        Node::wrap_type(Node::with_children(Kind::Protocol, vec![node, name]));
+                    self.substitutions.push(node.clone());
+                    Some(node)
+                }
+                _ => None,
+            };
+        }
+        self.demangle_declaration(Kind::Protocol)
+    }
+
+    fn demangle_decl_name(&mut self) -> Option<Node> {
+        if self.next_if(b'L') {
+            let index = self.index()?;
+            let name = self.demangle_identifier()?;
+            return Some(Node::with_children(
+                Kind::LocalDeclName,
+                vec![Node::with_index(Kind::Index, index), name],
+            ));
+        }
+        if self.next_if(b'P') {
+            let discriminator = self.demangle_identifier()?;
+            let name = self.demangle_identifier()?;
+            return Some(Node::with_children(
+                Kind::PrivateDeclName,
+                vec![discriminator, name],
+            ));
+        }
+        self.demangle_identifier()
+    }
+
+    fn demangle_identifier(&mut self) -> Option<Node> {
+        const OPERATOR_CHARS: &[u8; 26] = b"& @/= >    <*!|+?%-~   ^ .";
+
+        let operator_kind = match self.next_if(b'o') {
+            true => match self.next() {
+                b'p' => Some(Kind::PrefixOperator),
+                b'P' => Some(Kind::PostfixOperator),
+                b'i' => Some(Kind::InfixOperator),
+                _ => return None,
+            },
+            false => None,
+        };
+
+        let length = self.natural()? as usize;
+        let bytes = self.text.get(self.pos..self.pos + length)?;
+        let text = std::str::from_utf8(bytes).ok()?.to_string();
+        self.pos += length;
+        if text.is_empty() {
+            return None;
+        }
+
+        match operator_kind {
+            Some(kind) => {
+                let mut operator = String::new();
+                for c in text.bytes() {
+                    match c {
+                        b'a'..=b'z' if OPERATOR_CHARS[(c - b'a') as usize] != b' ' => {
+                            operator.push(OPERATOR_CHARS[(c - b'a') as usize] as char)
+                        }
+                        _ => return None,
+                    }
+                }
+                Some(Node::with_text(kind, operator))
+            }
+            None => Some(Node::with_text(Kind::Identifier, text)),
+        }
+    }
+
+    fn demangle_type(&mut self) -> Option<Node> {
+        self.nested(Self::demangle_type_node)
+    }
+
+    fn demangle_type_node(&mut self) -> Option<Node> {
+        let node = match self.next() {
+            b'B' => self.demangle_builtin_type()?,
+            b'a' => return self.demangle_declaration(Kind::TypeAlias),
+            b'b' => self.demangle_function_type(Kind::ObjCBlock)?,
+            b'c' => self.demangle_function_type(Kind::CFunctionPointer)?,
+            b'D' => Node::with_children(Kind::DynamicSelf, vec![self.demangle_type()?]),
+            b'F' | b'f' => self.demangle_function_type(Kind::FunctionType)?,
+            b'K' => self.demangle_function_type(Kind::AutoClosureType)?,
+            b'G' => {
+                let nominal = self.demangle_type()?;
+                let mut args = Vec::new();
+                while !self.next_if(b'_') {
+                    args.push(self.demangle_type()?);
+                }
+                let kind = match nominal.unwrapped().kind {
+                    Kind::Class => Kind::BoundGenericClass,
+                    Kind::Structure => Kind::BoundGenericStructure,
+                    Kind::Enum => Kind::BoundGenericEnum,
+                    _ => return None,
+                };
+                Node::with_children(
+                    kind,
+                    vec![nominal, Node::with_children(Kind::TypeList, args)],
+                )
+            }
+            b'M' => Node::with_children(Kind::Metatype, vec![self.demangle_type()?]),
+            b'P' => {
+                if self.next_if(b'M') {
+                    let ty = self.demangle_type()?;
+                    Node::with_children(Kind::ExistentialMetatype, vec![ty])
+                } else {
+                    let mut protocols = Vec::new();
+                    while !self.next_if(b'_') {
+                        protocols.push(self.demangle_protocol_name()?);
+                    }
+                    let list = Node::with_children(Kind::TypeList, protocols);
+                    Node::with_children(Kind::ProtocolList, vec![list])
+                }
+            }
+            b'Q' => {
+                let index = self.index()?;
+                Node::with_text(
+                    Kind::DependentGenericParamType,
+                    super::generic_param_name(0, index),
+                )
+            }
+            b'q' => {
+                let (depth, index) = match self.next_if(b'd') {
+                    true => (self.index()? + 1, self.index()?),
+                    false => (0, self.index()? + 1),
+                };
+                Node::with_text(
+                    Kind::DependentGenericParamType,
+                    super::generic_param_name(depth, index),
+                )
+            }
+            b'x' => Node::with_text(
+                Kind::DependentGenericParamType,
+                super::generic_param_name(0, 0),
+            ),
+            b'R' => Node::with_children(Kind::InOut, vec![self.demangle_type()?]),
+            b'T' | b't' => {
+                let is_variadic = self.text[self.pos - 1] == b't';
+                let mut elements = Vec::new();
+                while !self.next_if(b'_') {
+                    let mut element = Node::new(Kind::TupleElement);
+                    if self.peek().is_ascii_digit() {
+                        let label = self.demangle_identifier()?;
+                        element
+                            .children
+                            .push(Node::with_text(Kind::TupleElementName, label.text));
+                    }
+                    element.children.push(self.demangle_type()?);
+                    elements.push(element);
+                }
+                if let (true, Some(last)) = (is_variadic, elements.last_mut()) {
+                    last.children.push(Node::new(Kind::VariadicMarker));
+                }
+                Node::with_children(Kind::Tuple, elements)
+            }
+            b'u' => {
+                let signature = self.demangle_generic_signature()?;
+                let ty = self.demangle_type()?;
+                Node::with_children(Kind::DependentGenericType, vec![signature, ty])
+            }
+            b'X' => {
+                let kind = match self.next() {
+                    b'o' => Kind::Unowned,
+                    b'u' => Kind::Unmanaged,
+                    b'w' => Kind::Weak,
+                    _ => return None,
+                };
+                Node::with_children(kind, vec![self.demangle_type()?])
+            }
+            b'S' | b'C' | b'V' | b'O' => {
+                self.pos -= 1;
+                return self.demangle_nominal_type();
+            }
+            _ => return None,
+        };
+        Some(Node::wrap_type(node))
+    }
+
+    fn demangle_function_type(&mut self, kind: Kind) -> Option<Node> {
+        let mut children = Vec::new();
+        if self.next_if(b'z') {
+            children.push(Node::new(Kind::ThrowsAnnotation));
+        }
+        let arguments = self.demangle_type()?;
+        let result = self.demangle_type()?;
+        children.push(Node::with_children(Kind::ArgumentTuple, vec![arguments]));
+        children.push(Node::with_children(Kind::ReturnType, vec![result]));
+        Some(Node::with_children(kind, children))
+    }
+
+    fn demangle_generic_signature(&mut self) -> Option<Node> {
+        let mut children = Vec::new();
+        while !matches!(self.peek(), b'R' | b'r') {
+            let count = match self.next_if(b'z') {
+                true => 0,
+                false => self.index()? + 1,
+            };
+            children.push(Node::with_index(Kind::DependentGenericParamCount, count));
+        }
+        if children.is_empty() {
+            children.push(Node::with_index(Kind::DependentGenericParamCount, 1));
+        }
+
+        if self.next_if(b'R') {
+            while !self.next_if(b'r') {
+                let param = self.demangle_type()?;
+                let protocol = self.demangle_protocol_name()?;
+                children.push(Node::with_children(
+                    Kind::DependentGenericConformanceRequirement,
+                    vec![param, protocol],
+                ));
+            }
+        } else {
+            self.next_if(b'r');
+        }
+        Some(Node::with_children(
+            Kind::DependentGenericSignature,
+            children,
+        ))
+    }
+
+    fn demangle_builtin_type(&mut self) -> Option<Node> {
+        let name = match self.next() {
+            b'f' => format!("Builtin.FPIEEE{}", self.natural_with_terminator()?),
+            b'i' => format!("Builtin.Int{}", self.natural_with_terminator()?),
+            b'O' => "Builtin.UnknownObject".to_string(),
+            b'o' => "Builtin.NativeObject".to_string(),
+            b'b' => "Builtin.BridgeObject".to_string(),
+            b'p' => "Builtin.RawPointer".to_string(),
+            b'w' => "Builtin.Word".to_string(),
+            _ => return None,
+        };
+        Some(Node::with_text(Kind::BuiltinTypeName, name))
+    }
+}
+
+impl<'a> OldDemangler<'a> {
+    /// Calls `demangle` one level deeper, fails when it's too deep
+    fn nested(&mut self, demangle: impl FnOnce(&mut Self) -> Option<Node>) -> Option<Node> {
+        if self.depth >= MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let result = demangle(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn peek(&self) -> u8 {
+        self.text.get(self.pos).copied().unwrap_or(0)
+    }
+
+    fn next(&mut self) -> u8 {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn next_if(&mut self, c: u8) -> bool {
+        match self.peek() == c {
+            true => {
+                self.pos += 1;
+                true
+            }
+            false => false,
+        }
+    }
+
+    fn next_str(&mut self, s: &str) -> bool {
+        match self.text[self.pos.min(self.text.len())..].starts_with(s.as_bytes()) {
+            true => {
+                self.pos += s.len();
+                true
+            }
+            false => false,
+        }
+    }
+
+    fn natural(&mut self) -> Option<u64> {
+        if !self.peek().is_ascii_digit() {
+            return None;
+        }
+        let mut value: u64 = 0;
+        while self.peek().is_ascii_digit() {
+            let digit = (self.next() - b'0') as u64;
+            value = value.checked_mul(10)?.checked_add(digit)?;
+        }
+        Some(value)
+    }
+
+    fn natural_with_terminator(&mut self) -> Option<u64> {
+        let value = self.natural()?;
+        match self.next_if(b'_') {
+            true => Some(value),
+            false => None,
+        }
+    }
+
+    /// `_` is 0, `<n>_` is `n + 1`
+    fn index(&mut self) -> Option<u64> {
+        if self.next_if(b'_') {
+            return Some(0);
+        }
+        self.natural_with_terminator()?.checked_add(1)
+    }
+}
+
+fn wrap_static(entity: Node, is_static: bool) -> Node {
+    match is_static {
+        true => Node::with_children(Kind::Static, vec![entity]),
+        false => entity,
+    }
+}
+
+fn swift_type(kind: Kind, name: &str) -> Node {
+    Node::wrap_type(Node::with_children(
+        kind,
+        vec![
+            Node::with_text(Kind::Module, super::STDLIB_NAME),
+            Node::with_text(Kind::Identifier, name),
+        ],
+    ))
+}
@@ -0,0 +1,914 @@
+//! Textual representation of demangled tree, in the format of `swift demangle` with sugared types
+//! and without private discriminators
+
+use super::node::{Kind, Node};
+
+/// Guards against huge output of malformed symbols
+const MAX_OUTPUT_LENGTH: usize = 1 << 16;
+const MAX_DEPTH: usize = 256;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum TypePrinting {
+    NoType,
+    WithColon,
+    FunctionStyle,
+}
+
+pub(super) fn print(node: &Node) -> Option<String> {
+    let mut printer = Printer {
+        out: String::new(),
+        depth: 0,
+        is_valid: true,
+    };
+    printer.print(node, false);
+    match printer.is_valid && printer.out.len() <= MAX_OUTPUT_LENGTH {
+        true => Some(printer.out),
+        false => None,
+    }
+}
+
+struct Printer {
+    out: String,
+    depth: usize,
+    is_valid: bool,
+}
+
+impl Printer {
+    /// Prints `node` and returns context, which should be printed after it
+    fn print<'n>(&mut self, node: &'n Node, as_prefix_context: bool) -> Option<&'n Node> {
+        if self.depth > MAX_DEPTH || self.out.len() > MAX_OUTPUT_LENGTH {
+            self.is_valid = false;
+            return None;
+        }
+        self.depth += 1;
+        let result = self.print_node(node, as_prefix_context);
+        self.depth -= 1;
+        result
+    }
+
+    fn print_child(&mut self, node: &Node, index: usize) {
+        match node.child(index) {
+            Some(child) => {
+                self.print(child, false);
+            }
+            None => self.is_valid = false,
+        }
+    }
+
+    fn print_children(&mut self, node: &Node, separator: &str) {
+        for (index, child) in node.children.iter().enumerate() {
+            if index > 0 {
+                self.out += separator;
+            }
+            self.print(child, false);
+        }
+    }
+
+    fn print_prefixed(&mut self, prefix: &str, node: &Node) {
+        self.out += prefix;
+        self.print_child(node, 0);
+    }
+
+    fn print_node<'n>(&mut self, node: &'n Node, as_prefix_context: bool) -> Option<&'n Node> {
+        use TypePrinting::*;
+
+        match node.kind {
+            Kind::Global => self.print_children(node, ""),
+            Kind::Suffix => self.out += &format!(" with unmangled suffix {:?}", node.text),
+            Kind::Type | Kind::TypeMangling => self.print_child(node, 0),
+            Kind::Module | Kind::Identifier | Kind::Resolved | Kind::BuiltinTypeName => {
+                self.out += &node.text
+            }
+            Kind::DependentGenericParamType | Kind::TupleElementName => self.out += &node.text,
+            Kind::Index => self.out += &node.index.to_string(),
+            Kind::LocalDeclName => {
+                self.print_child(node, 1);
+                self.out += &format!(" #{}", node.child(0).map(|i| i.index).unwrap_or(0) + 1);
+            }
+            Kind::PrivateDeclName | Kind::RelatedEntityDeclName => {
+                if node.children.len() > 1 {
+                    self.print_child(node, 1);
+                }
+            }
+            Kind::PrefixOperator => self.out += &format!("{} prefix", node.text),
+            Kind::PostfixOperator => self.out += &format!("{} postfix", node.text),
+            Kind::InfixOperator => self.out += &format!("{} infix", node.text),
+
+            Kind::Structure | Kind::Class | Kind::Enum | Kind::Protocol | Kind::TypeAlias => {
+                return self.print_entity(node, as_prefix_context, NoType, true, "", None, "");
+            }
+            Kind::Function => {
+                return self.print_entity(
+                    node,
+                    as_prefix_context,
+                    FunctionStyle,
+                    true,
+                    "",
+                    None,
+                    "",
+                );
+            }
+            Kind::Variable => {
+                return self.print_entity(node, as_prefix_context, WithColon, true, "", None, "");
+            }
+            Kind::Subscript => {
+                return self.print_entity(
+                    node,
+                    as_prefix_context,
+                    FunctionStyle,
+                    false,
+                    "",
+                    None,
+                    "subscript",
+                );
+            }
+            Kind::Allocator => {
+                let name = match is_class_type(node.child(0)) {
+                    true => "__allocating_init",
+                    false => "init",
+                };
+                return self.print_entity(
+                    node,
+                    as_prefix_context,
+                    FunctionStyle,
+                    false,
+                    name,
+                    None,
+                    "",
+                );
+            }
+            Kind::Constructor => {
+                return self.print_entity(
+                    node,
+                    as_prefix_context,
+                    FunctionStyle,
+                    false,
+                    "init",
+                    None,
+                    "",
+                );
+            }
+            Kind::Deallocator => {
+                let name = match is_class_type(node.child(0)) {
+                    true => "__deallocating_deinit",
+                    false => "deinit",
+                };
+                return self.print_entity(node, as_prefix_context, NoType, false, name, None, "");
+            }
+            Kind::Destructor => {
+                return self.print_entity(
+                    node,
+                    as_prefix_context,
+                    NoType,
+                    false,
+                    "deinit",
+                    None,
+                    "",
+                );
+            }
+            Kind::IVarInitializer => {
+                return self.print_entity(
+                    node,
+                    as_prefix_context,
+                    NoType,
+                    false,
+                    "__ivar_initializer",
+                    None,
+                    "",
+                );
+            }
+            Kind::IVarDestroyer => {
+                return self.print_entity(
+                    node,
+                    as_prefix_context,
+                    NoType,
+                    false,
+                    "__ivar_destroyer",
+                    None,
+                    "",
+                );
+            }
+            Kind::Initializer => {
+                let name = "variable initialization expression";
+                return self.print_entity(node, as_prefix_context, NoType, false, name, None, "");
+            }
+            Kind::ExplicitClosure | Kind::ImplicitClosure => {
+                let name = match node.kind {
+                    Kind::ExplicitClosure => "closure #",
+                    _ => "implicit closure #",
+                };
+                let index = node.child(1).map(|i| i.index + 1);
+                return self.print_entity(
+                    node,
+                    as_prefix_context,
+                    FunctionStyle,
+                    false,
+                    name,
+                    index,
+                    "",
+                );
+            }
+            Kind::DefaultArgumentInitializer => {
+                let index = node.child(1).map(|i| i.index);
+                return self.print_entity(
+                    node,
+                    as_prefix_context,
+                    NoType,
+                    false,
+                    "default argument ",
+                    index,
+                    "",
+                );
+            }
+            Kind::Getter
+            | Kind::Setter
+            | Kind::GlobalGetter
+            | Kind::WillSet
+            | Kind::DidSet
+            | Kind::ReadAccessor
+            | Kind::ModifyAccessor
+            | Kind::MaterializeForSet
+            | Kind::Addressor
+            | Kind::MutableAddressor => {
+                let name = match node.kind {
+                    Kind::Getter | Kind::GlobalGetter => "getter".to_string(),
+                    Kind::Setter => "setter".to_string(),
+                    Kind::WillSet => "willset".to_string(),
+                    Kind::DidSet => "didset".to_string(),
+                    Kind::ReadAccessor => "read".to_string(),
+                    Kind::ModifyAccessor => "modify".to_string(),
+                    Kind::MaterializeForSet => "materializeForSet".to_string(),
+                    Kind::Addressor => format!("{}Addressor", node.text),
+                    _ => format!("{}MutableAddressor", node.text),
+                };
+                return self.print_abstract_storage(node.child(0)?, as_prefix_context, &name);
+            }
+            Kind::Static => self.print_prefixed("static ", node),
+            Kind::Extension => {
+                self.out += "(extension in ";
+                if let Some(module) = node.child(0) {
+                    self.print(module, true);
+                }
+                self.out += "):";
+                self.print_child(node, 1);
+                if let Some(signature) = node.child(2) {
+                    self.print(signature, false);
+                }
+            }
+            Kind::AnonymousContext => {
+                self.print_child(node, 0);
+                self.out += ".(unknown context)";
+            }
+
+            Kind::BoundGenericStructure
+            | Kind::BoundGenericClass
+            | Kind::BoundGenericEnum
+            | Kind::BoundGenericProtocol
+            | Kind::BoundGenericTypeAlias => self.print_bound_generic(node),
+            Kind::TypeList => self.print_children(node, ", "),
+            Kind::Tuple => {
+                self.out += "(";
+                self.print_children(node, ", ");
+                self.out += ")";
+            }
+            Kind::TupleElement => {
+                if let Some(label) = node.child_of_kind(Kind::TupleElementName) {
+                    self.out += &format!("{}: ", label.text);
+                }
+                if let Some(ty) = node.child_of_kind(Kind::Type) {
+                    self.print(ty, false);
+                }
+                if node.child_of_kind(Kind::VariadicMarker).is_some() {
+                    self.out += "...";
+                }
+            }
+            Kind::FunctionType | Kind::NoEscapeFunctionType => self.print_function_type(None, node),
+            Kind::AutoClosureType | Kind::EscapingAutoClosureType => {
+                self.out += "@autoclosure ";
+                self.print_function_type(None, node);
+            }
+            Kind::ThinFunctionType => {
+                self.out += "@convention(thin) ";
+                self.print_function_type(None, node);
+            }
+            Kind::CFunctionPointer => {
+                self.out += "@convention(c) ";
+                self.print_function_type(None, node);
+            }
+            Kind::ObjCBlock => {
+                self.out += "@convention(block) ";
+                self.print_function_type(None, node);
+            }
+            Kind::EscapingObjCBlock => {
+                self.out += "@escaping @convention(block) ";
+                self.print_function_type(None, node);
+            }
+            Kind::ArgumentTuple => self.print_function_parameters(None, node),
+            Kind::ReturnType => self.print_prefixed(" -> ", node),
+            Kind::ThrowsAnnotation => self.out += " throws",
+            Kind::AsyncAnnotation => self.out += " async",
+            Kind::ConcurrentFunctionType => self.out += "@Sendable ",
+            Kind::InOut => self.print_prefixed("inout ", node),
+            Kind::Shared => self.print_prefixed("__shared ", node),
+            Kind::Owned => self.print_prefixed("__owned ", node),
+            Kind::Weak => self.print_prefixed("weak ", node),
+            Kind::Unowned => self.print_prefixed("unowned ", node),
+            Kind::Unmanaged => self.print_prefixed("unowned(unsafe) ", node),
+            Kind::DynamicSelf => self.out += "Self",
+            Kind::Metatype | Kind::ExistentialMetatype => {
+                let ty = node.child(0)?.unwrapped();
+                self.print_with_parens(ty);
+                match node.kind == Kind::Metatype && is_existential_type(ty) {
+                    true => self.out += ".Protocol",
+                    false => self.out += ".Type",
+                }
+            }
+            Kind::ProtocolList => {
+                let list = node.child(0)?;
+                match list.children.is_empty() {
+                    true => self.out += "Any",
+                    false => self.print_children(list, " & "),
+                }
+            }
+            Kind::ProtocolListWithClass => {
+                self.print_child(node, 1);
+                self.out += " & ";
+                if let Some(list) = node.child(0).and_then(|p| p.child(0)) {
+                    self.print_children(list, " & ");
+                }
+            }
+            Kind::ProtocolListWithAnyObject => {
+                if let Some(list) = node.child(0).and_then(|p| p.child(0)) {
+                    if !list.children.is_empty() {
+                        self.print_children(list, " & ");
+                        self.out += " & ";
+                    }
+                }
+                self.out += "Swift.AnyObject";
+            }
+            Kind::DependentGenericType => {
+                self.print_child(node, 0);
+                let ty = node.child(1)?;
+                if need_space_before_type(ty) {
+                    self.out += " ";
+                }
+                self.print(ty, false);
+            }
+            Kind::DependentGenericSignature => self.print_generic_signature(node),
+            Kind::DependentGenericConformanceRequirement => self.print_requirement(node, ": "),
+            Kind::DependentGenericSameTypeRequirement => self.print_requirement(node, " == "),
+            Kind::DependentGenericLayoutRequirement => self.print_requirement(node, ": "),
+            Kind::DependentMemberType | Kind::AssociatedTypeRef => {
+                self.print_requirement(node, ".")
+            }
+            Kind::DependentAssociatedTypeRef => {
+                if let Some(protocol) = node.child(0) {
+                    self.print(protocol, false);
+                    self.out += ".";
+                }
+                self.out += &node.text;
+            }
+            Kind::OpaqueReturnType => self.out += "some",
+
+            Kind::TypeMetadata => self.print_prefixed("type metadata for ", node),
+            Kind::TypeMetadataAccessFunction => {
+                self.print_prefixed("type metadata accessor for ", node)
+            }
+            Kind::TypeMetadataLazyCache => {
+                self.print_prefixed("lazy cache variable for type metadata for ", node)
+            }
+            Kind::TypeMetadataInstantiationCache => {
+                self.print_prefixed("type metadata instantiation cache for ", node)
+            }
+            Kind::TypeMetadataInstantiationFunction => {
+                self.print_prefixed("type metadata instantiation function for ", node)
+            }
+            Kind::TypeMetadataCompletionFunction => {
+                self.print_prefixed("type metadata completion function for ", node)
+            }
+            Kind::TypeMetadataSingletonInitializationCache => {
+                self.print_prefixed("type metadata singleton initialization cache for ", node)
+            }
+            Kind::TypeMetadataDemanglingCache => {
+                self.print_prefixed("demangling cache variable for type metadata for ", node)
+            }
+            Kind::FullTypeMetadata => self.print_prefixed("full type metadata for ", node),
+            Kind::GenericTypeMetadataPattern => {
+                self.print_prefixed("generic type metadata pattern for ", node)
+            }
+            Kind::Metaclass => self.print_prefixed("metaclass for ", node),
+            Kind::ClassMetadataBaseOffset => {
+                self.print_prefixed("class metadata base offset for ", node)
+            }
+            Kind::NominalTypeDescriptor => {
+                self.print_prefixed("nominal type descriptor for ", node)
+            }
+            Kind::ProtocolDescriptor => self.print_prefixed("protocol descriptor for ", node),
+            Kind::ProtocolConformanceDescriptor => {
+                self.print_prefixed("protocol conformance descriptor for ", node)
+            }
+            Kind::ProtocolRequirementsBaseDescriptor => {
+                self.print_prefixed("protocol requirements base descriptor for ", node)
+            }
+            Kind::AssociatedTypeDescriptor => {
+                self.print_prefixed("associated type descriptor for ", node)
+            }
+            Kind::ModuleDescriptor => self.print_prefixed("module descriptor ", node),
+            Kind::ExtensionDescriptor => self.print_prefixed("extension descriptor ", node),
+            Kind::AnonymousDescriptor => self.print_prefixed("anonymous descriptor ", node),
+            Kind::PropertyDescriptor => self.print_prefixed("property descriptor for ", node),
+            Kind::MethodDescriptor => self.print_prefixed("method descriptor for ", node),
+            Kind::MethodLookupFunction => self.print_prefixed("method lookup function for ", node),
+            Kind::ObjCMetadataUpdateFunction => {
+                self.print_prefixed("ObjC metadata update function for ", node)
+            }
+            Kind::ObjCResilientClassStub => {
+                self.print_prefixed("ObjC resilient class stub for ", node)
+            }
+            Kind::FullObjCResilientClassStub => {
+                self.print_prefixed("full ObjC resilient class stub for ", node)
+            }
+            Kind::ReflectionMetadataFieldDescriptor => {
+                self.print_prefixed("reflection metadata field descriptor ", node)
+            }
+            Kind::ReflectionMetadataAssocTypeDescriptor => {
+                self.print_prefixed("reflection metadata associated type descriptor ", node)
+            }
+            Kind::ReflectionMetadataBuiltinDescriptor => {
+                self.print_prefixed("reflection metadata builtin descriptor ", node)
+            }
+            Kind::ReflectionMetadataSuperclassDescriptor => {
+                self.print_prefixed("reflection metadata superclass descriptor ", node)
+            }
+            Kind::ProtocolConformance => {
+                self.print_child(node, 0);
+                self.out += " : ";
+                self.print_child(node, 1);
+                self.out += " in ";
+                self.print_child(node, 2);
+            }
+            Kind::ProtocolWitnessTable => self.print_prefixed("protocol witness table for ", node),
+            Kind::ProtocolWitnessTablePattern => {
+                self.print_prefixed("protocol witness table pattern for ", node)
+            }
+            Kind::GenericProtocolWitnessTable => {
+                self.print_prefixed("generic protocol witness table for ", node)
+            }
+            Kind::GenericProtocolWitnessTableInstantiationFunction => self.print_prefixed(
+                "instantiation function for generic protocol witness table for ",
+                node,
+            ),
+            Kind::ResilientProtocolWitnessTable => {
+                self.print_prefixed("resilient protocol witness table for ", node)
+            }
+            Kind::ProtocolWitnessTableAccessor => {
+                self.print_prefixed("protocol witness table accessor for ", node)
+            }
+            Kind::LazyProtocolWitnessTableAccessor
+            | Kind::LazyProtocolWitnessTableCacheVariable => {
+                self.out += match node.kind {
+                    Kind::LazyProtocolWitnessTableAccessor => {
+                        "lazy protocol witness table accessor for type "
+                    }
+                    _ => "lazy protocol witness table cache variable for type ",
+                };
+                self.print_child(node, 0);
+                self.out += " and conformance ";
+                self.print_child(node, 1);
+            }
+            Kind::AssociatedTypeMetadataAccessor => {
+                self.out += "associated type metadata accessor for ";
+                self.print_child(node, 1);
+                self.out += " in ";
+                self.print_child(node, 0);
+            }
+            Kind::ValueWitnessTable => self.print_prefixed("value witness table for ", node),
+            Kind::EnumCase => self.print_prefixed("enum case for ", node),
+            Kind::FieldOffset => {
+                self.out += &format!("{} field offset for ", node.child(0)?.text);
+                self.print_child(node, 1);
+            }
+
+            Kind::CurryThunk => self.print_prefixed("curry thunk of ", node),
+            Kind::DispatchThunk => self.print_prefixed("dispatch thunk of ", node),
+            Kind::ProtocolWitness => {
+                self.out += "protocol witness for ";
+                self.print_child(node, 1);
+                self.out += " in conformance ";
+                self.print_child(node, 0);
+            }
+            Kind::VTableThunk => {
+                self.out += "vtable thunk for ";
+                self.print_child(node, 1);
+                self.out += " dispatching to ";
+                self.print_child(node, 0);
+            }
+            Kind::ObjCAttribute => self.out += "@objc ",
+            Kind::NonObjCAttribute => self.out += "@nonobjc ",
+            Kind::DynamicAttribute => self.out += "dynamic ",
+            Kind::DirectMethodReferenceAttribute => self.out += "super ",
+            Kind::PartialApplyForwarder | Kind::PartialApplyObjCForwarder => {
+                self.out += match node.kind {
+                    Kind::PartialApplyForwarder => "partial apply forwarder",
+                    _ => "partial apply ObjC forwarder",
+                };
+                if !node.children.is_empty() {
+                    self.out += " for ";
+                    self.print_children(node, "");
+                }
+            }
+            Kind::MergedFunction => self.out += "merged ",
+            Kind::DynamicallyReplaceableFunctionVar => {
+                self.out += "dynamically replaceable variable for "
+            }
+            Kind::DynamicallyReplaceableFunctionKey => {
+                self.out += "dynamically replaceable key for "
+            }
+            Kind::DynamicallyReplaceableFunctionImpl => {
+                self.out += "dynamically replaceable thunk for "
+            }
+
+            // Consumed by parents
+            Kind::LabelList
+            | Kind::EmptyList
+            | Kind::FirstElementMarker
+            | Kind::VariadicMarker
+            | Kind::DependentGenericParamCount
+            | Kind::Directness => (),
+        }
+        None
+    }
+
+    /// Prints `<context>.<name><type>`, or `<name><type> in <context>` if the name has several words
+    #[allow(clippy::too_many_arguments)]
+    fn print_entity<'n>(
+        &mut self,
+        entity: &'n Node,
+        as_prefix_context: bool,
+        mut type_printing: TypePrinting,
+        has_name: bool,
+        mut extra_name: &str,
+        mut extra_index: Option<u64>,
+        overwrite_name: &str,
+    ) -> Option<&'n Node> {
+        let mut multi_word_name = extra_name.contains(' ');
+        let local_name = has_name && entity.child(1).map(|n| n.kind) == Some(Kind::LocalDeclName);
+        if local_name {
+            multi_word_name = true;
+        }
+        if as_prefix_context && (type_printing != TypePrinting::NoType || multi_word_name) {
+            // Context with type can't be printed as prefix
+            return Some(entity);
+        }
+
+        let mut postfix_context = None;
+        let context = entity.child(0)?;
+        if print_context(context) {
+            match multi_word_name {
+                true => postfix_context = Some(context),
+                false => {
+                    let position = self.out.len();
+                    postfix_context = self.print(context, true);
+                    if self.out.len() != position {
+                        self.out += ".";
+                    }
+                }
+            }
+        }
+
+        if has_name || !overwrite_name.is_empty() {
+            if !extra_name.is_empty() && multi_word_name {
+                self.out += extra_name;
+                if let Some(index) = extra_index {
+                    self.out += &index.to_string();
+                }
+                self.out += " of ";
+                extra_name = "";
+                extra_index = None;
+            }
+            let position = self.out.len();
+            match overwrite_name.is_empty() {
+                true => {
+                    let name = entity.child(1)?;
+                    self.print(name, false);
+                }
+                false => self.out += overwrite_name,
+            }
+            if self.out.len() != position && !extra_name.is_empty() {
+                self.out += ".";
+            }
+        }
+        if !extra_name.is_empty() {
+            self.out += extra_name;
+            if let Some(index) = extra_index {
+                self.out += &index.to_string();
+            }
+        }
+
+        if type_printing != TypePrinting::NoType {
+            let ty = entity
+                .children
+                .iter()
+                .rev()
+                .find(|c| c.kind == Kind::Type)?
+                .unwrapped();
+            if type_printing == TypePrinting::FunctionStyle {
+                let mut function = ty;
+                while function.kind == Kind::DependentGenericType {
+                    function = function.child(1)?.unwrapped();
+                }
+                if !function.kind.is_function_type() {
+                    type_printing = TypePrinting::WithColon;
+                }
+            }
+            match type_printing {
+                TypePrinting::WithColon => {
+                    self.out += " : ";
+                    self.print_entity_type(entity, ty);
+                }
+                _ => {
+                    if multi_word_name || need_space_before_type(ty) {
+                        self.out += " ";
+                    }
+                    self.print_entity_type(entity, ty);
+                }
+            }
+        }
+
+        if !as_prefix_context {
+            if let Some(context) = postfix_context {
+                match entity.kind {
+                    Kind::DefaultArgumentInitializer | Kind::Initializer => self.out += " of ",
+                    _ => self.out += " in ",
+                }
+                self.print(context, false);
+                return None;
+            }
+        }
+        postfix_context
+    }
+
+    fn print_entity_type(&mut self, entity: &Node, ty: &Node) {
+        let labels = match entity.child_of_kind(Kind::LabelList) {
+            Some(labels) => labels,
+            None => {
+                self.print(ty, false);
+                return;
+            }
+        };
+
+        let mut ty = ty;
+        if ty.kind == Kind::DependentGenericType {
+            self.print_child(ty, 0);
+            let dependent = match ty.child(1) {
+                Some(dependent) => dependent,
+                None => return,
+            };
+            if need_space_before_type(dependent) {
+                self.out += " ";
+            }
+            ty = dependent.unwrapped();
+        }
+        self.print_function_type(Some(labels), ty);
+    }
+
+    fn print_abstract_storage<'n>(
+        &mut self,
+        storage: &'n Node,
+        as_prefix_context: bool,
+        extra_name: &str,
+    ) -> Option<&'n Node> {
+        use TypePrinting::*;
+
+        match storage.kind {
+            Kind::Variable => self.print_entity(
+                storage,
+                as_prefix_context,
+                WithColon,
+                true,
+                extra_name,
+                None,
+                "",
+            ),
+            Kind::Subscript => self.print_entity(
+                storage,
+                as_prefix_context,
+                WithColon,
+                false,
+                extra_name,
+                None,
+                "subscript",
+            ),
+            _ => {
+                self.is_valid = false;
+                None
+            }
+        }
+    }
+
+    fn print_function_type(&mut self, labels: Option<&Node>, function: &Node) {
+        let is_throwing = function.child_of_kind(Kind::ThrowsAnnotation).is_some();
+        let is_async = function.child_of_kind(Kind::AsyncAnnotation).is_some();
+        let is_sendable = function
+            .child_of_kind(Kind::ConcurrentFunctionType)
+            .is_some();
+        let (arguments, result) = match (
+            function.child_of_kind(Kind::ArgumentTuple),
+            function.child_of_kind(Kind::ReturnType),
+        ) {
+            (Some(arguments), Some(result)) => (arguments, result),
+            _ => {
+                self.is_valid = false;
+                return;
+            }
+        };
+
+        if is_sendable {
+            self.out += "@Sendable ";
+        }
+        self.print_function_parameters(labels, arguments);
+        if is_async {
+            self.out += " async";
+        }
+        if is_throwing {
+            self.out += " throws";
+        }
+        self.print(result, false);
+    }
+
+    fn print_function_parameters(&mut self, labels: Option<&Node>, arguments: &Node) {
+        let params = match arguments.child(0) {
+            Some(params) => params.unwrapped(),
+            None => {
+                self.is_valid = false;
+                return;
+            }
+        };
+        if params.kind != Kind::Tuple {
+            // Single unlabeled parameter
+            self.out += "(";
+            self.print(params, false);
+            self.out += ")";
+            return;
+        }
+
+        let labels = labels.filter(|l| !l.children.is_empty());
+        self.out += "(";
+        for (index, param) in params.children.iter().enumerate() {
+            if index > 0 {
+                self.out += ", ";
+            }
+            if let Some(labels) = labels {
+                match labels.child(index) {
+                    Some(label) if label.kind == Kind::Identifier => self.out += &label.text,
+                    _ => self.out += "_",
+                }
+                self.out += ": ";
+            }
+            self.print(param, false);
+        }
+        self.out += ")";
+    }
+
+    fn print_generic_signature(&mut self, signature: &Node) {
+        self.out += "<";
+        let counts: Vec<u64> = signature
+            .children
+            .iter()
+            .take_while(|c| c.kind == Kind::DependentGenericParamCount)
+            .map(|c| c.index)
+            .collect();
+        for (depth, count) in counts.iter().enumerate() {
+            if depth > 0 {
+                self.out += "><";
+            }
+            let names: Vec<String> = (0..(*count).min(128))
+                .map(|index| super::generic_param_name(depth as u64, index))
+                .collect();
+            self.out += &names.join(", ");
+        }
+
+        let requirements = &signature.children[counts.len()..];
+        if !requirements.is_empty() {
+            self.out += " where ";
+            for (index, requirement) in requirements.iter().enumerate() {
+                if index > 0 {
+                    self.out += ", ";
+                }
+                self.print(requirement, false);
+            }
+        }
+        self.out += ">";
+    }
+
+    fn print_requirement(&mut self, node: &Node, separator: &str) {
+        self.print_child(node, 0);
+        self.out += separator;
+        self.print_child(node, 1);
+    }
+
+    fn print_bound_generic(&mut self, node: &Node) {
+        let (nominal, args) = match (node.child(0), node.child(1)) {
+            (Some(nominal), Some(args)) => (nominal, args),
+            _ => {
+                self.is_valid = false;
+                return;
+            }
+        };
+
+        match (sugar_name(node), args.children.as_slice()) {
+            (Some("Optional"), [wrapped]) => {
+                let wrapped = wrapped.unwrapped();
+                self.print_with_parens(wrapped);
+                self.out += "?";
+            }
+            (Some("Array"), [element]) => {
+                self.out += "[";
+                self.print(element, false);
+                self.out += "]";
+            }
+            (Some("Dictionary"), [key, value]) => {
+                self.out += "[";
+                self.print(key, false);
+                self.out += " : ";
+                self.print(value, false);
+                self.out += "]";
+            }
+            _ => {
+                self.print(nominal, false);
+                self.out += "<";
+                self.print_children(args, ", ");
+                self.out += ">";
+            }
+        }
+    }
+
+    fn print_with_parens(&mut self, ty: &Node) {
+        let needs_parens = !is_simple_type(ty);
+        if needs_parens {
+            self.out += "(";
+        }
+        self.print(ty, false);
+        if needs_parens {
+            self.out += ")";
+        }
+    }
+}
+
+/// Whether `context` is printed in qualified names. Imported ObjC types are printed without module.
+fn print_context(context: &Node) -> bool {
+    match context.kind {
+        Kind::Module => {
+            context.text != super::OBJC_MODULE_NAME
+                && context.text != super::CLANG_IMPORTER_MODULE_NAME
+        }
+        _ => true,
+    }
+}
+
+fn is_class_type(node: Option<&Node>) -> bool {
+    matches!(node.map(|n| n.kind), Some(Kind::Class))
+}
+
+fn need_space_before_type(ty: &Node) -> bool {
+    match ty.kind {
+        Kind::Type => ty.child(0).map(need_space_before_type).unwrap_or(true),
+        Kind::FunctionType | Kind::NoEscapeFunctionType | Kind::DependentGenericType => false,
+        _ => true,
+    }
+}
+
+fn is_existential_type(ty: &Node) -> bool {
+    matches!(
+        ty.kind,
+        Kind::ExistentialMetatype
+            | Kind::ProtocolList
+            | Kind::ProtocolListWithClass
+            | Kind::ProtocolListWithAnyObject
+    )
+}
+
+fn is_simple_type(ty: &Node) -> bool {
+    match ty.kind {
+        Kind::Type => ty.child(0).map(is_simple_type).unwrap_or(true),
+        Kind::ProtocolList => ty.child(0).map(|l| l.children.len() <= 1).unwrap_or(true),
+        Kind::ProtocolListWithClass | Kind::ProtocolListWithAnyObject => false,
+        kind => !kind.is_function_type() && kind != Kind::DependentGenericType,
+    }
+}
+
+/// Name of standard library type which has sugared syntax, like `[Element]`
+fn sugar_name(node: &Node) -> Option<&'static str> {
+    let nominal = node.child(0)?.unwrapped();
+    let module = nominal.child(0)?;
+    let name = nominal.child(1)?;
+    if module.kind != Kind::Module || module.text != super::STDLIB_NAME {
+        return None;
+    }
+    match (node.kind, name.text.as_str()) {
+        (Kind::BoundGenericEnum, "Optional") => Some("Optional"),
+        (Kind::BoundGenericStructure, "Array") => Some("Array"),
+        (Kind::BoundGenericStructure, "Dictionary") => Some("Dictionary"),
+        _ => None,
+    }
+}
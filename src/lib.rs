@@ -10,6 +10,7 @@ pub mod fmt_ext;
 pub mod auto_enum_fields;
 pub mod output;
 pub mod commands;
+pub mod demangle;
 
 mod reader;
 
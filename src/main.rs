@@ -1,8 +1,8 @@
 use schnauzer::commands;
 
 fn main() {
-    match commands::handle_with_args() {
-        Ok(_) => (),
-        Err(err) => eprintln!("{:#?}", err),
+    if let Err(err) = commands::handle_with_args() {
+        eprintln!("{err}");
+        std::process::exit(1);
     }
 }
@@ -6,6 +6,26 @@ use std::io::stdout;
 use crate::Section;
 
 pub(crate) fn dump_section(sect: &Section) -> Result<()> {
+    let mut printer = hex_printer(sect.addr.0, sect.endian.is_little());
+
+    let result = sect.read_data_to(&mut printer);
+    printer.finish();
+
+    result
+}
+
+pub(crate) fn dump_data(data: &[u8], address: u64, is_little_endian: bool) -> Result<()> {
+    use std::io::Write;
+
+    let mut printer = hex_printer(address, is_little_endian);
+
+    let result = printer.write_all(data);
+    printer.finish();
+
+    Ok(result?)
+}
+
+fn hex_printer(address: u64, is_little_endian: bool) -> Printer<std::io::Stdout, AddressFormatter, ByteFormatter, CharFormatter> {
     let config = Config::new(
         Some(AddressFormatter::new(
             AddressStyle::Hex(16),
@@ -15,17 +35,12 @@ pub(crate) fn dump_section(sect: &Section) -> Result<()> {
             Default::default(),
             Groupping::RepeatingGroup(Group::new(4, " "), 4),
             Default::default(),
-            sect.endian.is_little(),
+            is_little_endian,
             Default::default(),
         ),
         Some(CharFormatter::new(".".dimmed().to_string(), Default::default())),
         false
     );
     
-    let mut printer = Printer::new(stdout(), sect.addr.0 as usize, config);
-
-    let result = sect.read_data_to(&mut printer);
-    printer.finish();
-
-    result
+    Printer::new(stdout(), address as usize, config)
 }
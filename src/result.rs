@@ -45,6 +45,12 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<gimli::Error> for Error {
+    fn from(value: gimli::Error) -> Self {
+        Error::Other(Box::new(value))
+    }
+}
+
 unsafe impl Send for Error {}
 unsafe impl Sync for Error {}
 
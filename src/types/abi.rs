@@ -0,0 +1,365 @@
+//! Public interface of a dylib and comparison of two versions of it: exported symbols,
+//! ObjC classes and methods, `LC_ID_DYLIB` versions and architectures.
+
+use super::exports::*;
+use super::objc::ObjcMetadata;
+use super::LcVariant;
+use super::MachHeader;
+use super::MachObject;
+use super::Version32;
+use crate::result::Result;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+
+/// Interface of single architecture
+#[derive(Debug, Clone, Default)]
+pub struct AbiSurface {
+    pub arch: String,
+    pub install_name: Option<String>,
+    pub current_version: Option<u32>,
+    pub compatibility_version: Option<u32>,
+    /// Exported symbols and their export flags
+    pub symbols: BTreeMap<String, u64>,
+    pub objc_classes: BTreeSet<String>,
+    /// Methods as `-[Class selector]` and `+[Class selector]`, including ones of categories
+    pub objc_methods: BTreeSet<String>,
+}
+
+impl AbiSurface {
+    pub fn parse(object: &MachObject) -> Result<Self> {
+        let mut surface = AbiSurface {
+            arch: arch_name(object),
+            ..Default::default()
+        };
+
+        let id = object
+            .load_commands_iterator()
+            .find_map(|cmd| match cmd.variant {
+                LcVariant::IdDylib(c) => Some(c),
+                _ => None,
+            });
+        if let Some(id) = id {
+            surface.install_name = Some(id.name.to_string());
+            surface.current_version = Some(id.current_version.0);
+            surface.compatibility_version = Some(id.compatibility_version.0);
+        }
+
+        for entry in Exports::parse_or_symtab(object)?.entries {
+            surface.symbols.insert(entry.name, entry.flags);
+        }
+
+        let objc = ObjcMetadata::parse(object)?;
+        for class in objc.classes {
+            let methods = class
+                .instance_methods
+                .iter()
+                .map(|m| format!("-[{} {}]", class.name, m.name))
+                .chain(
+                    class
+                        .class_methods
+                        .iter()
+                        .map(|m| format!("+[{} {}]", class.name, m.name)),
+                );
+            surface.objc_methods.extend(methods);
+            surface.objc_classes.insert(class.name);
+        }
+        for category in objc.categories {
+            let class_name = match category.class_name {
+                Some(name) => name,
+                None => continue,
+            };
+            let methods = category
+                .instance_methods
+                .iter()
+                .map(|m| format!("-[{class_name} {}]", m.name))
+                .chain(
+                    category
+                        .class_methods
+                        .iter()
+                        .map(|m| format!("+[{class_name} {}]", m.name)),
+                );
+            surface.objc_methods.extend(methods);
+        }
+
+        Ok(surface)
+    }
+}
+
+pub(crate) fn arch_name(object: &MachObject) -> String {
+    header_arch_name(object.header())
+}
+
+pub(crate) fn header_arch_name(header: &MachHeader) -> String {
+    match header.printable_cpu() {
+        Some(cpu) => cpu.to_string(),
+        None => format!("{} {}", header.cputype.0, header.cpusubtype.masked().0),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AbiItem {
+    Arch,
+    InstallName,
+    CurrentVersion,
+    CompatibilityVersion,
+    Symbol,
+    ObjcClass,
+    ObjcMethod,
+}
+
+impl Display for AbiItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            AbiItem::Arch => "arch",
+            AbiItem::InstallName => "install name",
+            AbiItem::CurrentVersion => "current version",
+            AbiItem::CompatibilityVersion => "compatibility version",
+            AbiItem::Symbol => "symbol",
+            AbiItem::ObjcClass => "objc class",
+            AbiItem::ObjcMethod => "objc method",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiChangeKind {
+    Removed,
+    Added,
+    /// Old and new values
+    Changed(String, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiChange {
+    /// `None` for changes of all architectures
+    pub arch: Option<String>,
+    pub item: AbiItem,
+    pub name: String,
+    pub kind: AbiChangeKind,
+}
+
+impl AbiChange {
+    pub fn is_removal(&self) -> bool {
+        self.kind == AbiChangeKind::Removed
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AbiDiff {
+    pub changes: Vec<AbiChange>,
+}
+
+impl AbiDiff {
+    /// Compares slices with the same architectures. Changes, that are the same for all
+    /// common architectures, are reported once with `arch` being `None`.
+    pub fn compare(old: &[MachObject], new: &[MachObject]) -> Result<Self> {
+        let old = Self::surfaces(old)?;
+        let new = Self::surfaces(new)?;
+
+        let mut diff = AbiDiff::default();
+        for surface in &old {
+            if !new.iter().any(|s| s.arch == surface.arch) {
+                diff.push(None, AbiItem::Arch, &surface.arch, AbiChangeKind::Removed);
+            }
+        }
+        for surface in &new {
+            if !old.iter().any(|s| s.arch == surface.arch) {
+                diff.push(None, AbiItem::Arch, &surface.arch, AbiChangeKind::Added);
+            }
+        }
+
+        let pairs: Vec<(&AbiSurface, &AbiSurface)> = old
+            .iter()
+            .filter_map(|o| new.iter().find(|n| n.arch == o.arch).map(|n| (o, n)))
+            .collect();
+        let per_arch: Vec<Vec<AbiChange>> = pairs
+            .iter()
+            .map(|(old, new)| Self::compare_surfaces(old, new))
+            .collect();
+
+        // Changes of every architecture go first, then ones specific to some of them
+        let mut common = Vec::new();
+        if let Some(first) = per_arch.first() {
+            for change in first {
+                if per_arch[1..].iter().all(|changes| changes.contains(change)) {
+                    common.push(change.clone());
+                }
+            }
+        }
+        diff.changes.extend(common.iter().cloned());
+        for ((old, _), changes) in pairs.iter().zip(per_arch) {
+            for change in changes {
+                if !common.contains(&change) {
+                    diff.changes.push(AbiChange {
+                        arch: Some(old.arch.clone()),
+                        ..change
+                    });
+                }
+            }
+        }
+
+        Ok(diff)
+    }
+
+    pub fn has_removals(&self) -> bool {
+        self.changes.iter().any(|change| change.is_removal())
+    }
+
+    fn surfaces(objects: &[MachObject]) -> Result<Vec<AbiSurface>> {
+        objects.iter().map(AbiSurface::parse).collect()
+    }
+
+    fn push(&mut self, arch: Option<String>, item: AbiItem, name: &str, kind: AbiChangeKind) {
+        self.changes.push(AbiChange {
+            arch,
+            item,
+            name: name.to_string(),
+            kind,
+        });
+    }
+
+    /// Changes without architecture
+    fn compare_surfaces(old: &AbiSurface, new: &AbiSurface) -> Vec<AbiChange> {
+        let mut diff = AbiDiff::default();
+
+        if old.install_name != new.install_name {
+            diff.push_value_change(AbiItem::InstallName, &old.install_name, &new.install_name);
+        }
+        let version = |version: &Option<u32>| version.map(|v| Version32(v).to_string());
+        if old.current_version != new.current_version {
+            diff.push_value_change(
+                AbiItem::CurrentVersion,
+                &version(&old.current_version),
+                &version(&new.current_version),
+            );
+        }
+        if old.compatibility_version != new.compatibility_version {
+            diff.push_value_change(
+                AbiItem::CompatibilityVersion,
+                &version(&old.compatibility_version),
+                &version(&new.compatibility_version),
+            );
+        }
+
+        for (name, flags) in &old.symbols {
+            match new.symbols.get(name) {
+                None => diff.push(None, AbiItem::Symbol, name, AbiChangeKind::Removed),
+                Some(new_flags) if new_flags != flags => diff.push(
+                    None,
+                    AbiItem::Symbol,
+                    name,
+                    AbiChangeKind::Changed(
+                        flags_description(*flags),
+                        flags_description(*new_flags),
+                    ),
+                ),
+                Some(_) => (),
+            }
+        }
+        for name in new.symbols.keys() {
+            if !old.symbols.contains_key(name) {
+                diff.push(None, AbiItem::Symbol, name, AbiChangeKind::Added);
+            }
+        }
+
+        diff.compare_sets(AbiItem::ObjcClass, &old.objc_classes, &new.objc_classes);
+        diff.compare_sets(AbiItem::ObjcMethod, &old.objc_methods, &new.objc_methods);
+
+        diff.changes
+    }
+
+    fn push_value_change(&mut self, item: AbiItem, old: &Option<String>, new: &Option<String>) {
+        let value = |value: &Option<String>| value.clone().unwrap_or("none".to_string());
+        self.push(
+            None,
+            item,
+            "",
+            AbiChangeKind::Changed(value(old), value(new)),
+        );
+    }
+
+    fn compare_sets(&mut self, item: AbiItem, old: &BTreeSet<String>, new: &BTreeSet<String>) {
+        for name in old.difference(new) {
+            self.push(None, item, name, AbiChangeKind::Removed);
+        }
+        for name in new.difference(old) {
+            self.push(None, item, name, AbiChangeKind::Added);
+        }
+    }
+}
+
+/// Kind and attributes of exported symbol, like `regular, weak`
+pub fn flags_description(flags: u64) -> String {
+    let mut parts = vec![match flags & EXPORT_SYMBOL_FLAGS_KIND_MASK {
+        EXPORT_SYMBOL_FLAGS_KIND_REGULAR => "regular",
+        EXPORT_SYMBOL_FLAGS_KIND_THREAD_LOCAL => "thread local",
+        EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE => "absolute",
+        _ => "unknown kind",
+    }];
+    if flags & EXPORT_SYMBOL_FLAGS_WEAK_DEFINITION != 0 {
+        parts.push("weak");
+    }
+    if flags & EXPORT_SYMBOL_FLAGS_REEXPORT != 0 {
+        parts.push("re-export");
+    }
+    if flags & EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER != 0 {
+        parts.push("resolver");
+    }
+    if flags & EXPORT_SYMBOL_FLAGS_STATIC_RESOLVER != 0 {
+        parts.push("static resolver");
+    }
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse;
+
+    #[test]
+    fn test_compare() {
+        let old = parse("testable/abi/libabi_old.dylib").mach_objects();
+        let new = parse("testable/abi/libabi_new.dylib").mach_objects();
+        let diff = AbiDiff::compare(&old, &new).unwrap();
+        assert!(diff.has_removals());
+
+        let change = |item, name: &str, kind| AbiChange {
+            arch: None,
+            item,
+            name: name.to_string(),
+            kind,
+        };
+        assert_eq!(
+            diff.changes,
+            vec![
+                change(AbiItem::Arch, "x86_64", AbiChangeKind::Removed),
+                change(
+                    AbiItem::CurrentVersion,
+                    "",
+                    AbiChangeKind::Changed("1.2.0".to_string(), "1.3.0".to_string())
+                ),
+                change(
+                    AbiItem::Symbol,
+                    "_b",
+                    AbiChangeKind::Changed("regular, weak".to_string(), "regular".to_string())
+                ),
+                change(AbiItem::Symbol, "_c", AbiChangeKind::Removed),
+                change(AbiItem::Symbol, "_d", AbiChangeKind::Added),
+            ]
+        );
+
+        let diff = AbiDiff::compare(&new, &new).unwrap();
+        assert!(diff.changes.is_empty());
+    }
+
+    #[test]
+    fn test_objc() {
+        let object = parse("testable/objc_arm64e").mach_objects().remove(0);
+        let surface = AbiSurface::parse(&object).unwrap();
+        assert!(surface.objc_classes.contains("Foo"));
+        assert!(surface.objc_methods.contains("-[Foo req]"));
+        assert!(surface.objc_methods.contains("+[Foo opt]"));
+    }
+}
@@ -0,0 +1,207 @@
+//! Bind opcodes of `LC_DYLD_INFO` and `LC_DYLD_INFO_ONLY`, as described in `mach-o/loader.h`
+
+use super::LcVariant;
+use super::MachObject;
+use crate::result::{Error, Result};
+
+use scroll::{Pread, Sleb128, Uleb128};
+
+pub const BIND_OPCODE_MASK: u8 = 0xF0;
+pub const BIND_IMMEDIATE_MASK: u8 = 0x0F;
+pub const BIND_OPCODE_DONE: u8 = 0x00;
+pub const BIND_OPCODE_SET_DYLIB_ORDINAL_IMM: u8 = 0x10;
+pub const BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB: u8 = 0x20;
+pub const BIND_OPCODE_SET_DYLIB_SPECIAL_IMM: u8 = 0x30;
+pub const BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM: u8 = 0x40;
+pub const BIND_OPCODE_SET_TYPE_IMM: u8 = 0x50;
+pub const BIND_OPCODE_SET_ADDEND_SLEB: u8 = 0x60;
+pub const BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB: u8 = 0x70;
+pub const BIND_OPCODE_ADD_ADDR_ULEB: u8 = 0x80;
+pub const BIND_OPCODE_DO_BIND: u8 = 0x90;
+pub const BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB: u8 = 0xA0;
+pub const BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED: u8 = 0xB0;
+pub const BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB: u8 = 0xC0;
+pub const BIND_OPCODE_THREADED: u8 = 0xD0;
+pub const BIND_SUBOPCODE_THREADED_SET_BIND_ORDINAL_TABLE_SIZE_ULEB: u8 = 0x00;
+pub const BIND_SUBOPCODE_THREADED_APPLY: u8 = 0x01;
+
+pub const BIND_SYMBOL_FLAGS_WEAK_IMPORT: u8 = 0x1;
+pub const BIND_SYMBOL_FLAGS_NON_WEAK_DEFINITION: u8 = 0x8;
+
+pub const BIND_TYPE_POINTER: u8 = 1;
+pub const BIND_TYPE_TEXT_ABSOLUTE32: u8 = 2;
+pub const BIND_TYPE_TEXT_PCREL32: u8 = 3;
+
+/// Single bind performed by dyld
+#[derive(Debug, Clone)]
+pub struct BindRecord {
+    pub segment_index: usize,
+    pub segment_offset: u64,
+    pub bind_type: u8,
+    pub lib_ordinal: i32,
+    pub symbol_name: String,
+    pub symbol_flags: u8,
+    pub addend: i64,
+}
+
+impl BindRecord {
+    pub fn is_weak_import(&self) -> bool {
+        self.symbol_flags & BIND_SYMBOL_FLAGS_WEAK_IMPORT != 0
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BindInfo {
+    /// Binds to specific locations
+    pub records: Vec<BindRecord>,
+    /// Ordinal table of threaded binds (used by old arm64e binaries).
+    /// Locations of such binds are encoded in place, in the same way as `DYLD_CHAINED_PTR_ARM64E`.
+    /// `segment_index` and `segment_offset` are not meaningful there.
+    pub threaded: Vec<BindRecord>,
+}
+
+impl BindInfo {
+    /// Parses non-lazy bind opcodes of `LC_DYLD_INFO(_ONLY)`, if the command exists
+    pub fn parse(object: &MachObject) -> Result<Option<Self>> {
+        let command = object.load_commands_iterator().find_map(|cmd| match cmd.variant {
+            LcVariant::DyldInfo(c) | LcVariant::DyldInfoOnly(c) => Some(c),
+            _ => None,
+        });
+        let command = match command {
+            Some(command) => command,
+            None => return Ok(None),
+        };
+
+        let data = object.read_bytes(command.bind_off as u64, command.bind_size as usize)?;
+        let pointer_size = match object.header().magic.is_64() {
+            true => 8,
+            false => 4,
+        };
+        Self::from_bytes(&data, pointer_size).map(Some)
+    }
+
+    pub fn from_bytes(data: &[u8], pointer_size: u64) -> Result<Self> {
+        let mut info = BindInfo::default();
+        let mut record = BindRecord {
+            segment_index: 0,
+            segment_offset: 0,
+            bind_type: BIND_TYPE_POINTER,
+            lib_ordinal: 0,
+            symbol_name: String::new(),
+            symbol_flags: 0,
+            addend: 0,
+        };
+        let mut is_threaded = false;
+
+        let offset = &mut 0usize;
+        while *offset < data.len() {
+            let byte: u8 = data.gread(offset)?;
+            let immediate = byte & BIND_IMMEDIATE_MASK;
+
+            match byte & BIND_OPCODE_MASK {
+                // Lazy binds use `DONE` as separator, for non-lazy it's the end
+                BIND_OPCODE_DONE => break,
+                BIND_OPCODE_SET_DYLIB_ORDINAL_IMM => record.lib_ordinal = immediate as i32,
+                BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB => {
+                    record.lib_ordinal = Uleb128::read(data, offset)? as i32
+                }
+                BIND_OPCODE_SET_DYLIB_SPECIAL_IMM => {
+                    record.lib_ordinal = match immediate {
+                        0 => 0,
+                        _ => (immediate | BIND_OPCODE_MASK) as i8 as i32,
+                    }
+                }
+                BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM => {
+                    let end = data[*offset..]
+                        .iter()
+                        .position(|b| *b == 0)
+                        .ok_or(Error::BadBufferLength)?;
+                    record.symbol_name =
+                        String::from_utf8_lossy(&data[*offset..*offset + end]).to_string();
+                    record.symbol_flags = immediate;
+                    *offset += end + 1;
+                }
+                BIND_OPCODE_SET_TYPE_IMM => record.bind_type = immediate,
+                BIND_OPCODE_SET_ADDEND_SLEB => record.addend = Sleb128::read(data, offset)?,
+                BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                    record.segment_index = immediate as usize;
+                    record.segment_offset = Uleb128::read(data, offset)?;
+                }
+                BIND_OPCODE_ADD_ADDR_ULEB => {
+                    let delta = Uleb128::read(data, offset)?;
+                    record.segment_offset = record.segment_offset.wrapping_add(delta);
+                }
+                BIND_OPCODE_DO_BIND => match is_threaded {
+                    true => info.threaded.push(record.clone()),
+                    false => {
+                        info.records.push(record.clone());
+                        record.segment_offset += pointer_size;
+                    }
+                },
+                BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB => {
+                    info.records.push(record.clone());
+                    let delta = Uleb128::read(data, offset)?;
+                    record.segment_offset =
+                        record.segment_offset.wrapping_add(delta).wrapping_add(pointer_size);
+                }
+                BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED => {
+                    info.records.push(record.clone());
+                    record.segment_offset += immediate as u64 * pointer_size + pointer_size;
+                }
+                BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB => {
+                    let count = Uleb128::read(data, offset)?;
+                    let skip = Uleb128::read(data, offset)?;
+                    for _ in 0..count {
+                        info.records.push(record.clone());
+                        record.segment_offset =
+                            record.segment_offset.wrapping_add(skip).wrapping_add(pointer_size);
+                    }
+                }
+                BIND_OPCODE_THREADED => match immediate {
+                    BIND_SUBOPCODE_THREADED_SET_BIND_ORDINAL_TABLE_SIZE_ULEB => {
+                        let size = Uleb128::read(data, offset)?;
+                        info.threaded = Vec::with_capacity(size.min(0x10000) as usize);
+                        is_threaded = true;
+                    }
+                    // Chain starting at current location is decoded in place
+                    BIND_SUBOPCODE_THREADED_APPLY => (),
+                    other => {
+                        return Err(Error::Text(format!("Unknown threaded bind subopcode {other:#x}")))
+                    }
+                },
+                other => return Err(Error::Text(format!("Unknown bind opcode {other:#x}"))),
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bind_opcodes() {
+        let data = [
+            BIND_OPCODE_SET_DYLIB_ORDINAL_IMM | 2,
+            BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM | BIND_SYMBOL_FLAGS_WEAK_IMPORT,
+            b'_', b'f', b'o', b'o', 0,
+            BIND_OPCODE_SET_TYPE_IMM | BIND_TYPE_POINTER,
+            BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 3, 0x90, 0x01,
+            BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB, 2, 8,
+            BIND_OPCODE_SET_DYLIB_SPECIAL_IMM | 0x0E,
+            BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED | 1,
+            BIND_OPCODE_DO_BIND,
+            BIND_OPCODE_DONE,
+        ];
+
+        let info = BindInfo::from_bytes(&data, 8).unwrap();
+        let offsets: Vec<u64> = info.records.iter().map(|r| r.segment_offset).collect();
+        assert_eq!(offsets, vec![0x90, 0xA0, 0xB0, 0xC0]);
+        assert!(info.records.iter().all(|r| r.segment_index == 3 && r.symbol_name == "_foo"));
+        assert!(info.records[0].is_weak_import());
+        assert_eq!(info.records[0].lib_ordinal, 2);
+        assert_eq!(info.records[2].lib_ordinal, -2);
+    }
+}
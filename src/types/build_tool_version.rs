@@ -7,7 +7,7 @@ use schnauzer_derive::AutoEnumFields;
 
 /// `build_tool_version`
 #[repr(C)]
-#[derive(Debug, IOread, SizeWith, AutoEnumFields)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IOread, SizeWith, AutoEnumFields)]
 pub struct BuildToolVersion {
     pub tool: u32,
     pub version: u32,
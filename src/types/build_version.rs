@@ -0,0 +1,460 @@
+//! Editing of platform, deployment target and SDK version, like `vtool` does.
+//!
+//! Older linkers describe platform with one of `LC_VERSION_MIN_*` commands, that exist only
+//! for macOS, iOS, tvOS and watchOS, newer ones with `LC_BUILD_VERSION`. Setting version
+//! of platform replaces any of these commands of it, so they can be converted to each other.
+//! Zippered binaries, that run both on macOS and Mac Catalyst, have `LC_BUILD_VERSION`
+//! for each of the platforms.
+
+use super::writer::{MachImage, RawLoadCommand};
+use super::{BuildToolVersion, Version32};
+use super::{
+    LC_BUILD_VERSION, LC_VERSION_MIN_IPHONEOS, LC_VERSION_MIN_MACOSX, LC_VERSION_MIN_TVOS,
+    LC_VERSION_MIN_WATCHOS,
+};
+use crate::result::{Error, Result};
+
+use scroll::Pwrite;
+
+pub const PLATFORM_MACOS: u32 = 1;
+pub const PLATFORM_IOS: u32 = 2;
+pub const PLATFORM_TVOS: u32 = 3;
+pub const PLATFORM_WATCHOS: u32 = 4;
+pub const PLATFORM_BRIDGEOS: u32 = 5;
+pub const PLATFORM_MACCATALYST: u32 = 6;
+pub const PLATFORM_IOSSIMULATOR: u32 = 7;
+pub const PLATFORM_TVOSSIMULATOR: u32 = 8;
+pub const PLATFORM_WATCHOSSIMULATOR: u32 = 9;
+pub const PLATFORM_DRIVERKIT: u32 = 10;
+pub const PLATFORM_VISIONOS: u32 = 11;
+pub const PLATFORM_VISIONOSSIMULATOR: u32 = 12;
+
+pub const TOOL_CLANG: u32 = 1;
+pub const TOOL_SWIFT: u32 = 2;
+pub const TOOL_LD: u32 = 3;
+pub const TOOL_LLD: u32 = 4;
+
+/// Names `vtool` accepts
+const PLATFORM_NAMES: [(u32, &str); 12] = [
+    (PLATFORM_MACOS, "macos"),
+    (PLATFORM_IOS, "ios"),
+    (PLATFORM_TVOS, "tvos"),
+    (PLATFORM_WATCHOS, "watchos"),
+    (PLATFORM_BRIDGEOS, "bridgeos"),
+    (PLATFORM_MACCATALYST, "maccatalyst"),
+    (PLATFORM_IOSSIMULATOR, "iossim"),
+    (PLATFORM_TVOSSIMULATOR, "tvossim"),
+    (PLATFORM_WATCHOSSIMULATOR, "watchossim"),
+    (PLATFORM_DRIVERKIT, "driverkit"),
+    (PLATFORM_VISIONOS, "visionos"),
+    (PLATFORM_VISIONOSSIMULATOR, "visionossim"),
+];
+
+const TOOL_NAMES: [(u32, &str); 4] = [
+    (TOOL_CLANG, "clang"),
+    (TOOL_SWIFT, "swift"),
+    (TOOL_LD, "ld"),
+    (TOOL_LLD, "lld"),
+];
+
+const VERSION_MIN_COMMANDS: [(u32, u32); 4] = [
+    (PLATFORM_MACOS, LC_VERSION_MIN_MACOSX),
+    (PLATFORM_IOS, LC_VERSION_MIN_IPHONEOS),
+    (PLATFORM_TVOS, LC_VERSION_MIN_TVOS),
+    (PLATFORM_WATCHOS, LC_VERSION_MIN_WATCHOS),
+];
+
+/// `cmd`, `cmdsize`, platform, minos, sdk and ntools
+const BUILD_VERSION_COMMAND_SIZE: usize = 24;
+/// tool and version
+const BUILD_TOOL_VERSION_SIZE: usize = 8;
+/// `cmd`, `cmdsize`, version and sdk
+const VERSION_MIN_COMMAND_SIZE: usize = 16;
+
+/// Platform, deployment target and SDK of either `LC_BUILD_VERSION` or `LC_VERSION_MIN_*`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildVersion {
+    /// `cmd` the version is stored in
+    pub cmd: u32,
+    pub platform: u32,
+    pub minos: Version32,
+    pub sdk: Version32,
+    /// Always empty for `LC_VERSION_MIN_*`
+    pub tools: Vec<BuildToolVersion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionEdit {
+    /// Replaces `LC_BUILD_VERSION` or `LC_VERSION_MIN_*` of `platform` with `LC_BUILD_VERSION`,
+    /// or adds one. Tools of replaced `LC_BUILD_VERSION` are kept without `tools`
+    SetBuildVersion {
+        platform: u32,
+        minos: Version32,
+        sdk: Version32,
+        tools: Option<Vec<BuildToolVersion>>,
+    },
+    /// Replaces `LC_BUILD_VERSION` or `LC_VERSION_MIN_*` of `platform` with `LC_VERSION_MIN_*`,
+    /// or adds one
+    SetVersionMin {
+        platform: u32,
+        minos: Version32,
+        sdk: Version32,
+    },
+    /// Removes any version command of platform
+    RemoveBuildVersion(u32),
+}
+
+/// Name like `macos`, or number of unknown platform
+pub fn platform_name(platform: u32) -> String {
+    match PLATFORM_NAMES.iter().find(|(value, _)| *value == platform) {
+        Some((_, name)) => name.to_string(),
+        None => platform.to_string(),
+    }
+}
+
+/// Platform of name like `macos` or of number
+pub fn parse_platform(name: &str) -> Result<u32> {
+    match PLATFORM_NAMES.iter().find(|(_, n)| *n == name) {
+        Some((value, _)) => Ok(*value),
+        None => name
+            .parse()
+            .map_err(|_| Error::Text(format!("Unknown platform {name}"))),
+    }
+}
+
+/// Name like `ld`, or number of unknown tool
+pub fn tool_name(tool: u32) -> String {
+    match TOOL_NAMES.iter().find(|(value, _)| *value == tool) {
+        Some((_, name)) => name.to_string(),
+        None => tool.to_string(),
+    }
+}
+
+/// Tool of name like `ld` or of number
+pub fn parse_tool(name: &str) -> Result<u32> {
+    match TOOL_NAMES.iter().find(|(_, n)| *n == name) {
+        Some((value, _)) => Ok(*value),
+        None => name
+            .parse()
+            .map_err(|_| Error::Text(format!("Unknown tool {name}"))),
+    }
+}
+
+/// Version of `X[.Y[.Z]]` form, encoded as `xxxx.yy.zz` nibbles
+pub fn parse_version(string: &str) -> Result<Version32> {
+    let error = || Error::Text(format!("Invalid version {string}"));
+    let parts: Vec<&str> = string.split('.').collect();
+    if parts.len() > 3 {
+        return Err(error());
+    }
+    let limits = [0xFFFF, 0xFF, 0xFF];
+    let mut result = 0;
+    for (index, limit) in limits.into_iter().enumerate() {
+        let part = match parts.get(index) {
+            Some(part) => part.parse::<u32>().map_err(|_| error())?,
+            None => 0,
+        };
+        if part > limit {
+            return Err(error());
+        }
+        result = (result << limit.count_ones()) | part;
+    }
+    Ok(Version32(result))
+}
+
+impl MachImage {
+    /// Versions of all `LC_BUILD_VERSION` and `LC_VERSION_MIN_*` commands in order
+    pub fn build_versions(&self) -> Result<Vec<BuildVersion>> {
+        let endian = self.endian();
+        let mut result = Vec::new();
+        for command in &self.load_commands {
+            if command.cmd == LC_BUILD_VERSION {
+                let ntools = command.field(20, endian)? as usize;
+                let mut tools = Vec::new();
+                for index in 0..ntools {
+                    let offset = BUILD_VERSION_COMMAND_SIZE + index * BUILD_TOOL_VERSION_SIZE;
+                    tools.push(BuildToolVersion {
+                        tool: command.field(offset, endian)?,
+                        version: command.field(offset + 4, endian)?,
+                    });
+                }
+                result.push(BuildVersion {
+                    cmd: command.cmd,
+                    platform: command.field(8, endian)?,
+                    minos: Version32(command.field(12, endian)?),
+                    sdk: Version32(command.field(16, endian)?),
+                    tools,
+                });
+            } else if let Some(platform) = version_min_platform(command.cmd) {
+                result.push(BuildVersion {
+                    cmd: command.cmd,
+                    platform,
+                    minos: Version32(command.field(8, endian)?),
+                    sdk: Version32(command.field(12, endian)?),
+                    tools: Vec::new(),
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    /// Applies `edit` to load commands. Image is left unchanged on error
+    pub fn apply_version_edit(&mut self, edit: &VersionEdit) -> Result<()> {
+        match edit {
+            VersionEdit::SetBuildVersion {
+                platform,
+                minos,
+                sdk,
+                tools,
+            } => {
+                let tools = match tools {
+                    Some(tools) => tools.clone(),
+                    None => self
+                        .build_versions()?
+                        .into_iter()
+                        .find(|v| v.platform == *platform && v.cmd == LC_BUILD_VERSION)
+                        .map(|v| v.tools)
+                        .unwrap_or_default(),
+                };
+                let size = BUILD_VERSION_COMMAND_SIZE + tools.len() * BUILD_TOOL_VERSION_SIZE;
+                let mut bytes = vec![0u8; size];
+                let endian = self.endian();
+                let fields = [
+                    LC_BUILD_VERSION,
+                    size as u32,
+                    *platform,
+                    minos.0,
+                    sdk.0,
+                    tools.len() as u32,
+                ];
+                for (index, field) in fields.into_iter().enumerate() {
+                    bytes.pwrite_with(field, index * 4, endian)?;
+                }
+                for (index, tool) in tools.iter().enumerate() {
+                    let offset = BUILD_VERSION_COMMAND_SIZE + index * BUILD_TOOL_VERSION_SIZE;
+                    bytes.pwrite_with(tool.tool, offset, endian)?;
+                    bytes.pwrite_with(tool.version, offset + 4, endian)?;
+                }
+                self.replace_version_commands(
+                    *platform,
+                    RawLoadCommand {
+                        cmd: LC_BUILD_VERSION,
+                        bytes,
+                    },
+                )
+            }
+            VersionEdit::SetVersionMin {
+                platform,
+                minos,
+                sdk,
+            } => {
+                let cmd = match VERSION_MIN_COMMANDS.iter().find(|(p, _)| p == platform) {
+                    Some((_, cmd)) => *cmd,
+                    None => {
+                        return Err(Error::Text(format!(
+                            "Platform {} has no LC_VERSION_MIN command",
+                            platform_name(*platform)
+                        )))
+                    }
+                };
+                let mut bytes = vec![0u8; VERSION_MIN_COMMAND_SIZE];
+                let endian = self.endian();
+                let fields = [cmd, VERSION_MIN_COMMAND_SIZE as u32, minos.0, sdk.0];
+                for (index, field) in fields.into_iter().enumerate() {
+                    bytes.pwrite_with(field, index * 4, endian)?;
+                }
+                self.replace_version_commands(*platform, RawLoadCommand { cmd, bytes })
+            }
+            VersionEdit::RemoveBuildVersion(platform) => {
+                let indices = self.version_command_indices(*platform)?;
+                if indices.is_empty() {
+                    return Err(Error::Text(format!(
+                        "No build version of platform {}",
+                        platform_name(*platform)
+                    )));
+                }
+                for index in indices.into_iter().rev() {
+                    self.load_commands.remove(index);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Indices of `LC_BUILD_VERSION` and `LC_VERSION_MIN_*` commands of `platform`
+    fn version_command_indices(&self, platform: u32) -> Result<Vec<usize>> {
+        let mut indices = Vec::new();
+        for (index, command) in self.load_commands.iter().enumerate() {
+            let command_platform = match command.cmd {
+                LC_BUILD_VERSION => Some(command.field(8, self.endian())?),
+                cmd => version_min_platform(cmd),
+            };
+            if command_platform == Some(platform) {
+                indices.push(index);
+            }
+        }
+        Ok(indices)
+    }
+
+    /// Puts `command` in place of the first version command of `platform`,
+    /// or after all commands if there are none
+    fn replace_version_commands(&mut self, platform: u32, command: RawLoadCommand) -> Result<()> {
+        let indices = self.version_command_indices(platform)?;
+        let old_size: usize = indices
+            .iter()
+            .map(|index| self.load_commands[*index].bytes.len())
+            .sum();
+        self.check_growth(command.bytes.len().saturating_sub(old_size))?;
+
+        let position = indices.first().copied().unwrap_or(self.load_commands.len());
+        for index in indices.into_iter().rev() {
+            self.load_commands.remove(index);
+        }
+        self.load_commands.insert(position, command);
+        Ok(())
+    }
+}
+
+fn version_min_platform(cmd: u32) -> Option<u32> {
+    VERSION_MIN_COMMANDS
+        .iter()
+        .find(|(_, c)| *c == cmd)
+        .map(|(platform, _)| *platform)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{read, reread};
+    use crate::writer::FileImage;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse_version("12.1").unwrap(), Version32(0x000C0100));
+        assert_eq!(parse_version("10.15.7").unwrap(), Version32(0x000A0F07));
+        assert_eq!(parse_version("14").unwrap().to_string(), "14.0.0");
+        for invalid in ["", "1.256", "65536", "1.2.3.4", "a.b"] {
+            assert!(parse_version(invalid).is_err(), "{invalid}");
+        }
+
+        assert_eq!(parse_platform("maccatalyst").unwrap(), PLATFORM_MACCATALYST);
+        assert_eq!(parse_platform("7").unwrap(), PLATFORM_IOSSIMULATOR);
+        assert!(parse_platform("amigaos").is_err());
+        assert_eq!(platform_name(42), "42");
+        assert_eq!(parse_tool("ld").unwrap(), TOOL_LD);
+        assert_eq!(tool_name(TOOL_SWIFT), "swift");
+    }
+
+    #[test]
+    fn test_version_edits() {
+        let mut image = read("testable/cat").into_images().remove(1);
+        let versions = image.build_versions().unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].platform, PLATFORM_MACOS);
+        assert_eq!(versions[0].minos.to_string(), "12.1.0");
+        let tools = versions[0].tools.clone();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].tool, TOOL_LD);
+
+        let commands = image.load_commands.len();
+        let position = image
+            .load_commands
+            .iter()
+            .position(|command| command.cmd == LC_BUILD_VERSION);
+        let set_macos = VersionEdit::SetBuildVersion {
+            platform: PLATFORM_MACOS,
+            minos: parse_version("11.0").unwrap(),
+            sdk: parse_version("13.0").unwrap(),
+            tools: None,
+        };
+        image.apply_version_edit(&set_macos).unwrap();
+        assert_eq!(image.build_versions().unwrap()[0].tools, tools);
+
+        // Version min and build version replace each other in place
+        image
+            .apply_version_edit(&VersionEdit::SetVersionMin {
+                platform: PLATFORM_MACOS,
+                minos: parse_version("10.15").unwrap(),
+                sdk: parse_version("13.0").unwrap(),
+            })
+            .unwrap();
+        assert_eq!(image.load_commands.len(), commands);
+        assert_eq!(
+            image.load_commands[position.unwrap()].cmd,
+            LC_VERSION_MIN_MACOSX
+        );
+        image.apply_version_edit(&set_macos).unwrap();
+        assert!(image.build_versions().unwrap()[0].tools.is_empty());
+        assert_eq!(image.load_commands[position.unwrap()].cmdsize(), 24);
+
+        // Zippered
+        image
+            .apply_version_edit(&VersionEdit::SetBuildVersion {
+                platform: PLATFORM_MACCATALYST,
+                minos: parse_version("14.0").unwrap(),
+                sdk: parse_version("16.1").unwrap(),
+                tools: Some(tools),
+            })
+            .unwrap();
+        let mut image = reread(&FileImage::Thin(image), "schnauzer_build_version_test").remove(0);
+
+        let versions = image.build_versions().unwrap();
+        assert_eq!(
+            versions
+                .iter()
+                .map(|v| (
+                    platform_name(v.platform),
+                    v.minos.to_string(),
+                    v.sdk.to_string()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "macos".to_string(),
+                    "11.0.0".to_string(),
+                    "13.0.0".to_string()
+                ),
+                (
+                    "maccatalyst".to_string(),
+                    "14.0.0".to_string(),
+                    "16.1.0".to_string()
+                ),
+            ]
+        );
+        assert_eq!(versions[1].tools[0].tool, TOOL_LD);
+
+        let remove = VersionEdit::RemoveBuildVersion(PLATFORM_MACOS);
+        image.apply_version_edit(&remove).unwrap();
+        assert_eq!(image.build_versions().unwrap().len(), 1);
+        assert!(image.apply_version_edit(&remove).is_err());
+        assert!(image
+            .apply_version_edit(&VersionEdit::SetVersionMin {
+                platform: PLATFORM_MACCATALYST,
+                minos: parse_version("14.0").unwrap(),
+                sdk: parse_version("16.1").unwrap(),
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_header_padding() {
+        let mut image = read("testable/cat").into_images().remove(0);
+        let tools = vec![
+            BuildToolVersion {
+                tool: TOOL_LD,
+                version: 0
+            };
+            image.header_padding() / BUILD_TOOL_VERSION_SIZE
+        ];
+        let commands = image.load_commands.clone();
+        assert!(image
+            .apply_version_edit(&VersionEdit::SetBuildVersion {
+                platform: PLATFORM_MACCATALYST,
+                minos: Version32(0),
+                sdk: Version32(0),
+                tools: Some(tools),
+            })
+            .is_err());
+        assert_eq!(image.load_commands, commands);
+    }
+}
@@ -0,0 +1,361 @@
+//! Chained fixups (`LC_DYLD_CHAINED_FIXUPS`), as described in `mach-o/fixup-chains.h`.
+//! Locations to rebase or bind are not listed separately,
+//! each of them stores encoded pointer with offset to next location in the chain.
+
+use super::LcVariant;
+use super::MachObject;
+use crate::result::{Error, Result};
+
+use scroll::{Pread, SizeWith};
+
+use std::fmt::Debug;
+
+use crate::auto_enum_fields::*;
+use schnauzer_derive::AutoEnumFields;
+
+pub const DYLD_CHAINED_PTR_ARM64E: u16 = 1;
+pub const DYLD_CHAINED_PTR_64: u16 = 2;
+pub const DYLD_CHAINED_PTR_32: u16 = 3;
+pub const DYLD_CHAINED_PTR_32_CACHE: u16 = 4;
+pub const DYLD_CHAINED_PTR_32_FIRMWARE: u16 = 5;
+pub const DYLD_CHAINED_PTR_64_OFFSET: u16 = 6;
+pub const DYLD_CHAINED_PTR_ARM64E_KERNEL: u16 = 7;
+pub const DYLD_CHAINED_PTR_64_KERNEL_CACHE: u16 = 8;
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND: u16 = 9;
+pub const DYLD_CHAINED_PTR_ARM64E_FIRMWARE: u16 = 10;
+pub const DYLD_CHAINED_PTR_X86_64_KERNEL_CACHE: u16 = 11;
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND24: u16 = 12;
+
+pub const DYLD_CHAINED_IMPORT: u32 = 1;
+pub const DYLD_CHAINED_IMPORT_ADDEND: u32 = 2;
+pub const DYLD_CHAINED_IMPORT_ADDEND64: u32 = 3;
+
+pub const DYLD_CHAINED_PTR_START_NONE: u16 = 0xFFFF;
+pub const DYLD_CHAINED_PTR_START_MULTI: u16 = 0x8000;
+
+/// `dyld_chained_fixups_header`
+#[repr(C)]
+#[derive(Debug, Pread, SizeWith, AutoEnumFields)]
+pub struct DyldChainedFixupsHeader {
+    pub fixups_version: u32,
+    pub starts_offset: u32,
+    pub imports_offset: u32,
+    pub symbols_offset: u32,
+    pub imports_count: u32,
+    pub imports_format: u32,
+    pub symbols_format: u32,
+}
+
+/// `dyld_chained_starts_in_segment`
+#[derive(Debug)]
+pub struct ChainedStartsInSegment {
+    /// Index of segment in load commands
+    pub segment_index: usize,
+    pub page_size: u16,
+    pub pointer_format: u16,
+    pub segment_offset: u64,
+    pub max_valid_pointer: u32,
+    pub page_starts: Vec<u16>,
+}
+
+/// One of `dyld_chained_import`, `dyld_chained_import_addend` or `dyld_chained_import_addend64`
+#[derive(Debug, Clone)]
+pub struct ChainedImport {
+    pub lib_ordinal: i32,
+    pub weak_import: bool,
+    pub name: String,
+    pub addend: i64,
+}
+
+#[derive(Debug)]
+pub struct ChainedFixups {
+    pub header: DyldChainedFixupsHeader,
+    pub starts: Vec<ChainedStartsInSegment>,
+    pub imports: Vec<ChainedImport>,
+}
+
+impl ChainedFixups {
+    /// Parses `LC_DYLD_CHAINED_FIXUPS` payload of the object, if the command exists
+    pub fn parse(object: &MachObject) -> Result<Option<Self>> {
+        let command = object.load_commands_iterator().find_map(|cmd| match cmd.variant {
+            LcVariant::DyldChainedFixups(c) => Some(c),
+            _ => None,
+        });
+        let command = match command {
+            Some(command) => command,
+            None => return Ok(None),
+        };
+
+        let data = object.read_bytes(command.dataoff as u64, command.datasize as usize)?;
+        Self::from_bytes(&data).map(Some)
+    }
+
+    /// All values in chained fixups are little-endian
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let header: DyldChainedFixupsHeader = data.pread_with(0, scroll::LE)?;
+        if header.symbols_format != 0 {
+            return Err(Error::Text(format!(
+                "Compressed chained fixups symbols (format {}) are not supported",
+                header.symbols_format
+            )));
+        }
+
+        Ok(ChainedFixups {
+            starts: Self::parse_starts(data, header.starts_offset as usize)?,
+            imports: Self::parse_imports(data, &header)?,
+            header,
+        })
+    }
+
+    fn parse_starts(data: &[u8], starts_offset: usize) -> Result<Vec<ChainedStartsInSegment>> {
+        let seg_count: u32 = data.pread_with(starts_offset, scroll::LE)?;
+
+        let mut starts = Vec::new();
+        for segment_index in 0..seg_count as usize {
+            let info_offset: u32 = data.pread_with(starts_offset + 4 + segment_index * 4, scroll::LE)?;
+            if info_offset == 0 {
+                continue;
+            }
+
+            let offset = &mut (starts_offset + info_offset as usize);
+            let _size: u32 = data.gread_with(offset, scroll::LE)?;
+            let page_size: u16 = data.gread_with(offset, scroll::LE)?;
+            let pointer_format: u16 = data.gread_with(offset, scroll::LE)?;
+            let segment_offset: u64 = data.gread_with(offset, scroll::LE)?;
+            let max_valid_pointer: u32 = data.gread_with(offset, scroll::LE)?;
+            let page_count: u16 = data.gread_with(offset, scroll::LE)?;
+            let page_starts = (0..page_count)
+                .map(|_| data.gread_with::<u16>(offset, scroll::LE))
+                .collect::<std::result::Result<Vec<u16>, _>>()?;
+
+            starts.push(ChainedStartsInSegment {
+                segment_index,
+                page_size,
+                pointer_format,
+                segment_offset,
+                max_valid_pointer,
+                page_starts,
+            });
+        }
+
+        Ok(starts)
+    }
+
+    fn parse_imports(data: &[u8], header: &DyldChainedFixupsHeader) -> Result<Vec<ChainedImport>> {
+        let offset = &mut (header.imports_offset as usize);
+        let symbols = &data[(header.symbols_offset as usize).min(data.len())..];
+
+        let mut imports = Vec::with_capacity(header.imports_count as usize);
+        for _ in 0..header.imports_count {
+            let (lib_ordinal, weak_import, name_offset, addend) = match header.imports_format {
+                DYLD_CHAINED_IMPORT => {
+                    let raw: u32 = data.gread_with(offset, scroll::LE)?;
+                    (lib_ordinal(raw as u64 & 0xFF, 8), raw & 0x100 != 0, raw >> 9, 0)
+                }
+                DYLD_CHAINED_IMPORT_ADDEND => {
+                    let raw: u32 = data.gread_with(offset, scroll::LE)?;
+                    let addend: i32 = data.gread_with(offset, scroll::LE)?;
+                    (lib_ordinal(raw as u64 & 0xFF, 8), raw & 0x100 != 0, raw >> 9, addend as i64)
+                }
+                DYLD_CHAINED_IMPORT_ADDEND64 => {
+                    let raw: u64 = data.gread_with(offset, scroll::LE)?;
+                    let addend: u64 = data.gread_with(offset, scroll::LE)?;
+                    (lib_ordinal(raw & 0xFFFF, 16), raw & 0x1_0000 != 0, (raw >> 32) as u32, addend as i64)
+                }
+                other => {
+                    return Err(Error::Text(format!("Unknown chained imports format {other}")))
+                }
+            };
+
+            let name = symbols
+                .get(name_offset as usize..)
+                .and_then(|s| s.split(|b| *b == 0).next())
+                .map(|s| String::from_utf8_lossy(s).to_string())
+                .unwrap_or_default();
+
+            imports.push(ChainedImport {
+                lib_ordinal: lib_ordinal as i32,
+                weak_import,
+                name,
+                addend,
+            });
+        }
+
+        Ok(imports)
+    }
+
+    /// Pointer format of fixups in segment with `segment_index`
+    pub fn pointer_format(&self, segment_index: usize) -> Option<u16> {
+        self.starts
+            .iter()
+            .find(|s| s.segment_index == segment_index)
+            .map(|s| s.pointer_format)
+    }
+}
+
+/// Decoded content of location in a fixups chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainedPointer {
+    /// `target` is either vmaddr or offset from image base, see [ChainedPointer::target_is_offset]
+    Rebase { target: u64, high8: u8 },
+    /// Authenticated (arm64e) rebase, `target` is always offset from image base
+    AuthRebase { target: u64, diversity: u16, addr_div: bool, key: u8 },
+    /// `ordinal` is index in [ChainedFixups::imports]
+    Bind { ordinal: u32, addend: i64 },
+    /// Authenticated (arm64e) bind
+    AuthBind { ordinal: u32, diversity: u16, addr_div: bool, key: u8 },
+}
+
+impl ChainedPointer {
+    /// Decodes raw pointer value stored in fixup location.
+    /// Returns pointer and stride-scaled offset to next location (0 for end of chain).
+    pub fn decode(raw: u64, pointer_format: u16) -> Option<(ChainedPointer, u64)> {
+        let bits = |shift: u32, count: u32| (raw >> shift) & ((1u64 << count) - 1);
+
+        match pointer_format {
+            DYLD_CHAINED_PTR_ARM64E
+            | DYLD_CHAINED_PTR_ARM64E_KERNEL
+            | DYLD_CHAINED_PTR_ARM64E_USERLAND
+            | DYLD_CHAINED_PTR_ARM64E_FIRMWARE
+            | DYLD_CHAINED_PTR_ARM64E_USERLAND24 => {
+                let is_auth = bits(63, 1) == 1;
+                let is_bind = bits(62, 1) == 1;
+                let next = bits(51, 11) * Self::stride(pointer_format);
+                let ordinal = match pointer_format {
+                    DYLD_CHAINED_PTR_ARM64E_USERLAND24 => bits(0, 24),
+                    _ => bits(0, 16),
+                } as u32;
+                let diversity = bits(32, 16) as u16;
+                let addr_div = bits(48, 1) == 1;
+                let key = bits(49, 2) as u8;
+
+                let pointer = match (is_auth, is_bind) {
+                    (false, false) => ChainedPointer::Rebase {
+                        target: bits(0, 43),
+                        high8: bits(43, 8) as u8,
+                    },
+                    (true, false) => ChainedPointer::AuthRebase {
+                        target: bits(0, 32),
+                        diversity,
+                        addr_div,
+                        key,
+                    },
+                    (false, true) => ChainedPointer::Bind {
+                        ordinal,
+                        addend: sign_extend(bits(32, 19), 19),
+                    },
+                    (true, true) => ChainedPointer::AuthBind {
+                        ordinal,
+                        diversity,
+                        addr_div,
+                        key,
+                    },
+                };
+                Some((pointer, next))
+            }
+            DYLD_CHAINED_PTR_64
+            | DYLD_CHAINED_PTR_64_OFFSET
+            | DYLD_CHAINED_PTR_64_KERNEL_CACHE
+            | DYLD_CHAINED_PTR_X86_64_KERNEL_CACHE => {
+                let next = bits(51, 12) * Self::stride(pointer_format);
+                let pointer = match bits(63, 1) == 1 {
+                    true => ChainedPointer::Bind {
+                        ordinal: bits(0, 24) as u32,
+                        addend: bits(32, 8) as i64,
+                    },
+                    false => ChainedPointer::Rebase {
+                        target: bits(0, 36),
+                        high8: bits(36, 8) as u8,
+                    },
+                };
+                Some((pointer, next))
+            }
+            DYLD_CHAINED_PTR_32 => {
+                let next = bits(26, 5) * Self::stride(pointer_format);
+                let pointer = match bits(31, 1) == 1 {
+                    true => ChainedPointer::Bind {
+                        ordinal: bits(0, 20) as u32,
+                        addend: bits(20, 6) as i64,
+                    },
+                    false => ChainedPointer::Rebase {
+                        target: bits(0, 26),
+                        high8: 0,
+                    },
+                };
+                Some((pointer, next))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether rebase target of `pointer_format` is offset from image base instead of vmaddr
+    pub fn target_is_offset(pointer_format: u16) -> bool {
+        !matches!(
+            pointer_format,
+            DYLD_CHAINED_PTR_ARM64E | DYLD_CHAINED_PTR_64 | DYLD_CHAINED_PTR_32
+        )
+    }
+
+    /// Distance in bytes between neighbour locations in chain
+    pub fn stride(pointer_format: u16) -> u64 {
+        match pointer_format {
+            DYLD_CHAINED_PTR_ARM64E
+            | DYLD_CHAINED_PTR_ARM64E_USERLAND
+            | DYLD_CHAINED_PTR_ARM64E_USERLAND24 => 8,
+            DYLD_CHAINED_PTR_X86_64_KERNEL_CACHE => 1,
+            _ => 4,
+        }
+    }
+}
+
+/// Library ordinal with special values (self, main executable, flat lookup) as negative numbers
+fn lib_ordinal(value: u64, bits: u32) -> i64 {
+    match value >= (1 << bits) - 0x10 {
+        true => sign_extend(value, bits),
+        false => value as i64,
+    }
+}
+
+fn sign_extend(value: u64, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_chained_pointers() {
+        // Rebase to 0x4010 with next = 2
+        let raw = (2u64 << 51) | 0x4010;
+        assert_eq!(
+            ChainedPointer::decode(raw, DYLD_CHAINED_PTR_64_OFFSET),
+            Some((ChainedPointer::Rebase { target: 0x4010, high8: 0 }, 8))
+        );
+
+        // Bind to import #3 with addend 8
+        let raw = (1u64 << 63) | (8 << 32) | 3;
+        assert_eq!(
+            ChainedPointer::decode(raw, DYLD_CHAINED_PTR_64),
+            Some((ChainedPointer::Bind { ordinal: 3, addend: 8 }, 0))
+        );
+
+        // Authenticated rebase with IA key and address diversity
+        let raw = (1u64 << 63) | (1 << 51) | (1 << 48) | (0x1234 << 32) | 0x8000;
+        assert_eq!(
+            ChainedPointer::decode(raw, DYLD_CHAINED_PTR_ARM64E_USERLAND24),
+            Some((
+                ChainedPointer::AuthRebase { target: 0x8000, diversity: 0x1234, addr_div: true, key: 0 },
+                8
+            ))
+        );
+
+        // Plain arm64e bind with negative addend
+        let raw = (1u64 << 62) | (0x7FFFF << 32) | 1;
+        assert_eq!(
+            ChainedPointer::decode(raw, DYLD_CHAINED_PTR_ARM64E),
+            Some((ChainedPointer::Bind { ordinal: 1, addend: -1 }, 0))
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Data in code (`LC_DATA_IN_CODE`): ranges of code sections, that contain data
+//! (jump tables, literal pools) and must not be decoded as instructions.
+
+use super::filetype_constants::MH_OBJECT;
+use super::LcVariant;
+use super::MachObject;
+use crate::result::Result;
+
+use scroll::{Endian, Pread};
+
+pub const DICE_KIND_DATA: u16 = 0x0001;
+pub const DICE_KIND_JUMP_TABLE8: u16 = 0x0002;
+pub const DICE_KIND_JUMP_TABLE16: u16 = 0x0003;
+pub const DICE_KIND_JUMP_TABLE32: u16 = 0x0004;
+pub const DICE_KIND_ABS_JUMP_TABLE32: u16 = 0x0005;
+
+/// Size of `data_in_code_entry`
+const DATA_IN_CODE_ENTRY_SIZE: usize = 8;
+
+/// `data_in_code_entry`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataInCodeEntry {
+    /// Offset from start of the mach header in linked images, address in `MH_OBJECT`
+    pub offset: u32,
+    /// Virtual address of the range
+    pub address: u64,
+    pub length: u16,
+    /// One of `DICE_KIND_*`
+    pub kind: u16,
+}
+
+impl DataInCodeEntry {
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.address && address - self.address < self.length as u64
+    }
+
+    /// Name of kind as printed by `otool -G`
+    pub fn kind_name(&self) -> String {
+        kind_name(self.kind)
+    }
+
+    /// Size of single item of the data
+    pub fn item_size(&self) -> usize {
+        match self.kind {
+            DICE_KIND_JUMP_TABLE8 => 1,
+            DICE_KIND_JUMP_TABLE16 => 2,
+            _ => 4,
+        }
+    }
+}
+
+/// Name of `DICE_KIND_*` as printed by `otool -G`
+pub fn kind_name(kind: u16) -> String {
+    match kind {
+        DICE_KIND_DATA => "DATA".to_string(),
+        DICE_KIND_JUMP_TABLE8 => "JUMP_TABLE8".to_string(),
+        DICE_KIND_JUMP_TABLE16 => "JUMP_TABLE16".to_string(),
+        DICE_KIND_JUMP_TABLE32 => "JUMP_TABLE32".to_string(),
+        DICE_KIND_ABS_JUMP_TABLE32 => "ABS_JUMP_TABLE32".to_string(),
+        kind => format!("{kind:#x}"),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DataInCode {
+    /// Sorted by address
+    pub entries: Vec<DataInCodeEntry>,
+}
+
+impl DataInCode {
+    /// Parses `LC_DATA_IN_CODE` payload of the object, if the command exists
+    pub fn parse(object: &MachObject) -> Result<Option<Self>> {
+        let command = object
+            .load_commands_iterator()
+            .find_map(|cmd| match cmd.variant {
+                LcVariant::DataInCode(c) => Some(c),
+                _ => None,
+            });
+        let command = match command {
+            Some(command) => command,
+            None => return Ok(None),
+        };
+
+        let data = object.read_bytes(command.dataoff as u64, command.datasize as usize)?;
+        let mut result = Self::from_bytes(&data, object.header().magic.endian())?;
+
+        if object.header().filetype.0 != MH_OBJECT {
+            result
+                .entries
+                .retain_mut(|entry| match object.offset_to_vmaddr(entry.offset as u64) {
+                    Some(address) => {
+                        entry.address = address;
+                        true
+                    }
+                    None => false,
+                });
+        }
+        result.entries.sort_by_key(|entry| entry.address);
+
+        Ok(Some(result))
+    }
+
+    /// Entries of raw payload. Addresses are equal to offsets.
+    pub fn from_bytes(data: &[u8], endian: Endian) -> Result<Self> {
+        let mut entries = Vec::new();
+        for chunk in data.chunks_exact(DATA_IN_CODE_ENTRY_SIZE) {
+            let offset: u32 = chunk.pread_with(0, endian)?;
+            entries.push(DataInCodeEntry {
+                offset,
+                address: offset as u64,
+                length: chunk.pread_with(4, endian)?,
+                kind: chunk.pread_with(6, endian)?,
+            });
+        }
+        Ok(DataInCode { entries })
+    }
+
+    /// Entry containing `address`
+    pub fn entry_for_address(&self, address: u64) -> Option<&DataInCodeEntry> {
+        let index = self
+            .entries
+            .partition_point(|entry| entry.address <= address);
+        self.entries[..index]
+            .last()
+            .filter(|entry| entry.contains(address))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse_single;
+
+    #[test]
+    fn test_parse() {
+        let object = parse_single("testable/disasm_arm64.o");
+        let dice = DataInCode::parse(&object).unwrap().unwrap();
+        assert_eq!(
+            dice.entries,
+            vec![DataInCodeEntry {
+                offset: 0x34,
+                address: 0x34,
+                length: 4,
+                kind: DICE_KIND_JUMP_TABLE8,
+            }]
+        );
+        assert!(dice.entry_for_address(0x37).is_some());
+        assert!(dice.entry_for_address(0x38).is_none());
+        assert!(dice.entry_for_address(0x30).is_none());
+    }
+}
@@ -0,0 +1,219 @@
+//! Debug map of a linked binary: STABS entries left by the static linker, which tell
+//! what object files the binary was linked from and which symbols each of them contributed.
+//! This is what `dsymutil` reads to collect DWARF of the object files.
+//!
+//! Every object file is described by sequence like
+//! `N_SO dir`, `N_SO file`, `N_OSO path`, `N_BNSYM`, `N_FUN name`, `N_FUN size`, `N_ENSYM`,
+//! `N_STSYM`/`N_GSYM` ..., `N_SO` with empty name.
+
+use super::MachObject;
+use super::StabType;
+use crate::result::Result;
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugMapSymbolKind {
+    /// `N_FUN`
+    Function,
+    /// `N_STSYM` or `N_LCSYM`
+    StaticVariable,
+    /// `N_GSYM`, address is taken from the external symbol with the same name
+    GlobalVariable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugMapSymbol {
+    pub name: String,
+    pub kind: DebugMapSymbolKind,
+    /// Address in the binary. `None` for globals without defined symbol.
+    pub address: Option<u64>,
+    /// Known for functions only
+    pub size: Option<u64>,
+}
+
+/// Object file the binary was linked from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugMapObject {
+    /// Path of `N_OSO`, may be `archive.a(member.o)`
+    pub path: String,
+    /// Modification time of the object file
+    pub timestamp: u64,
+    /// Source file of `N_SO` entries, with its directory
+    pub source: Option<String>,
+    pub symbols: Vec<DebugMapSymbol>,
+}
+
+#[derive(Debug, Default)]
+pub struct DebugMap {
+    pub objects: Vec<DebugMapObject>,
+}
+
+impl DebugMap {
+    /// Groups STABS of `LC_SYMTAB` by object files. Empty if the binary has no debug map.
+    pub fn parse(object: &MachObject) -> Result<Self> {
+        let mut map = DebugMap::default();
+        let symtab = match object.symtab() {
+            Some(symtab) => symtab,
+            None => return Ok(map),
+        };
+
+        let mut defined: HashMap<String, u64> = HashMap::new();
+        let mut directory = String::new();
+        let mut source = None;
+        let mut current: Option<DebugMapObject> = None;
+        let mut function: Option<(String, u64)> = None;
+
+        for nlist in symtab.nlist_iterator() {
+            let name = match &nlist.name {
+                Some(name) => name.load_string().unwrap_or_default(),
+                None => String::new(),
+            };
+            let value = nlist.n_value.0;
+
+            let stab = match nlist.n_type.stab_type() {
+                Some(stab) => stab,
+                None => {
+                    if nlist.n_type.is_defined_in_n_sect() {
+                        defined.insert(name, value);
+                    }
+                    continue;
+                }
+            };
+
+            match stab {
+                StabType::SourceFileName => match name.is_empty() {
+                    // End of compile unit
+                    true => {
+                        map.objects.extend(current.take());
+                        directory.clear();
+                        source = None;
+                    }
+                    false => match name.ends_with('/') {
+                        true => directory = name,
+                        false => source = Some(format!("{directory}{name}")),
+                    },
+                },
+                StabType::ObjectFileName => {
+                    map.objects.extend(current.take());
+                    current = Some(DebugMapObject {
+                        path: name,
+                        timestamp: value,
+                        source: source.clone(),
+                        symbols: Vec::new(),
+                    });
+                }
+                StabType::Procedure => match name.is_empty() {
+                    // The second `N_FUN` holds size of the function
+                    true => {
+                        if let (Some(object), Some((name, address))) =
+                            (&mut current, function.take())
+                        {
+                            object.symbols.push(DebugMapSymbol {
+                                name,
+                                kind: DebugMapSymbolKind::Function,
+                                address: Some(address),
+                                size: Some(value),
+                            });
+                        }
+                    }
+                    false => function = Some((name, value)),
+                },
+                StabType::StaticSymbol | StabType::LocalCommon => {
+                    if let Some(object) = &mut current {
+                        object.symbols.push(DebugMapSymbol {
+                            name,
+                            kind: DebugMapSymbolKind::StaticVariable,
+                            address: Some(value),
+                            size: None,
+                        });
+                    }
+                }
+                StabType::GlobalSymbol => {
+                    if let Some(object) = &mut current {
+                        object.symbols.push(DebugMapSymbol {
+                            name,
+                            kind: DebugMapSymbolKind::GlobalVariable,
+                            address: None,
+                            size: None,
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+        map.objects.extend(current.take());
+
+        // External symbols usually follow STABS, so globals are resolved when all entries are read
+        for symbol in map.objects.iter_mut().flat_map(|o| o.symbols.iter_mut()) {
+            if symbol.kind == DebugMapSymbolKind::GlobalVariable {
+                symbol.address = defined.get(&symbol.name).copied();
+            }
+        }
+
+        Ok(map)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Object file that contributed symbol containing `address`
+    pub fn object_for_address(&self, address: u64) -> Option<&DebugMapObject> {
+        self.objects.iter().find(|object| {
+            object
+                .symbols
+                .iter()
+                .any(|symbol| match (symbol.address, symbol.size) {
+                    (Some(start), Some(size)) => (start..start + size).contains(&address),
+                    (Some(start), None) => start == address,
+                    _ => false,
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse_single;
+
+    #[test]
+    fn test_parse() {
+        let object = parse_single("testable/stabs_arm64");
+        let map = DebugMap::parse(&object).unwrap();
+        assert_eq!(map.objects.len(), 2);
+
+        let main = &map.objects[0];
+        assert_eq!(main.path, "/tmp/build/main.o");
+        assert_eq!(main.timestamp, 0x65000000);
+        assert_eq!(main.source.as_deref(), Some("/tmp/src/main.c"));
+        assert_eq!(
+            main.symbols,
+            vec![
+                DebugMapSymbol {
+                    name: "_main".to_string(),
+                    kind: DebugMapSymbolKind::Function,
+                    address: Some(0x100003f80),
+                    size: Some(0x10),
+                },
+                DebugMapSymbol {
+                    name: "_counter".to_string(),
+                    kind: DebugMapSymbolKind::StaticVariable,
+                    address: Some(0x100004000),
+                    size: None,
+                },
+                DebugMapSymbol {
+                    name: "_global".to_string(),
+                    kind: DebugMapSymbolKind::GlobalVariable,
+                    address: Some(0x100004004),
+                    size: None,
+                },
+            ]
+        );
+
+        let util = map.object_for_address(0x100003fa0).unwrap();
+        assert_eq!(util.path, "/tmp/build/util.o");
+        assert_eq!(util.symbols[0].name, "_helper");
+    }
+}
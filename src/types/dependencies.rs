@@ -0,0 +1,337 @@
+//! Resolution of dependent libraries the way `dyld` finds them, inside of a sysroot directory
+//! (an extracted SDK, an app bundle or a copy of device file system).
+//! Absolute install names and `LC_RPATH` entries are looked up relative to the sysroot,
+//! `@loader_path` and `@executable_path` - relative to location of the images on disk.
+
+use super::imports::{dependent_dylibs, DylibLoadKind};
+use super::LcVariant;
+use super::MachObject;
+use super::ObjectType;
+use crate::Parser;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+pub const RPATH_PREFIX: &str = "@rpath/";
+pub const LOADER_PATH_PREFIX: &str = "@loader_path";
+pub const EXECUTABLE_PATH_PREFIX: &str = "@executable_path";
+/// Extension of text-based stubs, that SDKs contain instead of libraries
+pub const TBD_EXTENSION: &str = "tbd";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyStatus {
+    /// Mach-O file with slice for the architecture
+    Resolved(PathBuf),
+    /// Text-based stub of SDK, it has no load commands to follow
+    Stub(PathBuf),
+    /// Already resolved in other branch of the tree, its dependencies are listed there
+    Duplicate(PathBuf),
+    /// File exists, but it isn't Mach-O or has no slice for the architecture
+    Unusable(PathBuf, String),
+    /// No file for install name
+    Unresolved,
+}
+
+impl DependencyStatus {
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            DependencyStatus::Resolved(path)
+            | DependencyStatus::Stub(path)
+            | DependencyStatus::Duplicate(path)
+            | DependencyStatus::Unusable(path, _) => Some(path),
+            DependencyStatus::Unresolved => None,
+        }
+    }
+}
+
+/// Node of dependency tree
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    pub install_name: String,
+    pub kind: DylibLoadKind,
+    pub status: DependencyStatus,
+    pub dependencies: Vec<Dependency>,
+}
+
+impl Dependency {
+    pub fn is_resolved(&self) -> bool {
+        !matches!(
+            self.status,
+            DependencyStatus::Unresolved | DependencyStatus::Unusable(..)
+        )
+    }
+
+    /// Unresolved nodes of the subtree, including this one
+    pub fn unresolved(&self) -> Vec<&Dependency> {
+        let mut result = Vec::new();
+        if !self.is_resolved() {
+            result.push(self);
+        }
+        for dependency in &self.dependencies {
+            result.append(&mut dependency.unresolved());
+        }
+        result
+    }
+}
+
+pub struct DependencyResolver {
+    sysroot: PathBuf,
+}
+
+impl DependencyResolver {
+    pub fn new(sysroot: &Path) -> Self {
+        DependencyResolver {
+            sysroot: sysroot.to_path_buf(),
+        }
+    }
+
+    /// Dependency tree of `object`, located at `path`. Dependencies are opened recursively,
+    /// with the same architecture. If `object` isn't executable, its directory is used for `@executable_path`.
+    pub fn resolve(&self, object: &MachObject, path: &Path) -> Vec<Dependency> {
+        let executable_dir = parent_dir(path);
+        let mut visited = HashSet::new();
+        if let Ok(path) = path.canonicalize() {
+            visited.insert(path);
+        }
+        self.resolve_object(object, path, &executable_dir, &[], &mut visited)
+    }
+
+    fn resolve_object(
+        &self,
+        object: &MachObject,
+        path: &Path,
+        executable_dir: &Path,
+        loader_rpaths: &[PathBuf],
+        visited: &mut HashSet<PathBuf>,
+    ) -> Vec<Dependency> {
+        let loader_dir = parent_dir(path);
+        // Own rpaths go first, then ones of the images up the chain
+        let mut rpaths = self.rpaths(object, &loader_dir, executable_dir);
+        rpaths.extend_from_slice(loader_rpaths);
+
+        let mut result = Vec::new();
+        for dylib in dependent_dylibs(object) {
+            let status =
+                match self.find_install_name(&dylib.name, &loader_dir, executable_dir, &rpaths) {
+                    Some(path) => path,
+                    None => {
+                        result.push(Dependency {
+                            install_name: dylib.name,
+                            kind: dylib.kind,
+                            status: DependencyStatus::Unresolved,
+                            dependencies: Vec::new(),
+                        });
+                        continue;
+                    }
+                };
+
+            let mut dependencies = Vec::new();
+            let status = match status {
+                DependencyStatus::Resolved(path) => {
+                    let key = path.canonicalize().unwrap_or(path.clone());
+                    match visited.insert(key) {
+                        false => DependencyStatus::Duplicate(path),
+                        true => match load_slice(&path, object) {
+                            Ok(dependency) => {
+                                dependencies = self.resolve_object(
+                                    &dependency,
+                                    &path,
+                                    executable_dir,
+                                    &rpaths,
+                                    visited,
+                                );
+                                DependencyStatus::Resolved(path)
+                            }
+                            Err(reason) => DependencyStatus::Unusable(path, reason),
+                        },
+                    }
+                }
+                status => status,
+            };
+            result.push(Dependency {
+                install_name: dylib.name,
+                kind: dylib.kind,
+                status,
+                dependencies,
+            });
+        }
+        result
+    }
+
+    /// Path of existing file for `install_name`, either `Resolved` or `Stub`.
+    /// `rpaths` are already expanded `LC_RPATH` entries of the loader chain.
+    pub fn resolve_install_name(
+        &self,
+        install_name: &str,
+        loader_path: &Path,
+        executable_path: &Path,
+        rpaths: &[PathBuf],
+    ) -> Option<DependencyStatus> {
+        self.find_install_name(
+            install_name,
+            &parent_dir(loader_path),
+            &parent_dir(executable_path),
+            rpaths,
+        )
+    }
+
+    fn find_install_name(
+        &self,
+        install_name: &str,
+        loader_dir: &Path,
+        executable_dir: &Path,
+        rpaths: &[PathBuf],
+    ) -> Option<DependencyStatus> {
+        let candidates: Vec<PathBuf> = match install_name.strip_prefix(RPATH_PREFIX) {
+            Some(tail) => rpaths.iter().map(|rpath| rpath.join(tail)).collect(),
+            None => vec![self.expand(install_name, loader_dir, executable_dir)],
+        };
+
+        candidates
+            .iter()
+            .find(|path| path.is_file())
+            .map(|path| DependencyStatus::Resolved(path.clone()))
+            .or_else(|| {
+                candidates
+                    .iter()
+                    .map(|path| path.with_extension(TBD_EXTENSION))
+                    .find(|path| path.is_file())
+                    .map(DependencyStatus::Stub)
+            })
+    }
+
+    fn rpaths(
+        &self,
+        object: &MachObject,
+        loader_dir: &Path,
+        executable_dir: &Path,
+    ) -> Vec<PathBuf> {
+        object
+            .load_commands_iterator()
+            .filter_map(|cmd| match cmd.variant {
+                LcVariant::Rpath(rpath) => Some(rpath.path.to_string()),
+                _ => None,
+            })
+            .map(|path| self.expand(&path, loader_dir, executable_dir))
+            .collect()
+    }
+
+    /// Location of path without `@rpath`
+    fn expand(&self, path: &str, loader_dir: &Path, executable_dir: &Path) -> PathBuf {
+        if let Some(tail) = path.strip_prefix(LOADER_PATH_PREFIX) {
+            loader_dir.join(tail.trim_start_matches('/'))
+        } else if let Some(tail) = path.strip_prefix(EXECUTABLE_PATH_PREFIX) {
+            executable_dir.join(tail.trim_start_matches('/'))
+        } else {
+            self.sysroot.join(path.trim_start_matches('/'))
+        }
+    }
+}
+
+fn parent_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Slice of file at `path` for architecture of `loader`.
+/// Exact subtype is preferred, any subtype of the same CPU type is accepted otherwise.
+pub(super) fn load_slice(path: &Path, loader: &MachObject) -> std::result::Result<MachObject, String> {
+    let object = Parser::build(path)
+        .and_then(|parser| parser.parse())
+        .map_err(|_| "not a Mach-O file".to_string())?;
+    let objects = match object {
+        ObjectType::MachO(object) => vec![object],
+        ObjectType::Fat(fat) => fat.objects(),
+    };
+
+    let cputype = loader.header().cputype.0;
+    let cpusubtype = loader.header().cpusubtype.masked().0;
+    let same_cpu = |object: &&MachObject| object.header().cputype.0 == cputype;
+    let exact = objects
+        .iter()
+        .filter(same_cpu)
+        .find(|object| object.header().cpusubtype.masked().0 == cpusubtype);
+    match exact.or_else(|| objects.iter().find(same_cpu)) {
+        Some(object) => Ok(object.clone()),
+        None => Err("no matching architecture".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse_single;
+
+    const SYSROOT: &str = "testable/sysroot";
+    const APP: &str = "testable/sysroot/Applications/App/app";
+
+    #[test]
+    fn test_resolve_install_name() {
+        let resolver = DependencyResolver::new(Path::new(SYSROOT));
+        let app = Path::new(APP);
+
+        let status =
+            resolver.resolve_install_name("@executable_path/lib/libfoo.dylib", app, app, &[]);
+        assert_eq!(
+            status,
+            Some(DependencyStatus::Resolved(PathBuf::from(
+                "testable/sysroot/Applications/App/lib/libfoo.dylib"
+            )))
+        );
+
+        let rpaths = vec![
+            PathBuf::from("testable/sysroot/nonexistent"),
+            PathBuf::from("testable/sysroot/usr/local/lib"),
+        ];
+        let status = resolver.resolve_install_name("@rpath/libbaz.dylib", app, app, &rpaths);
+        assert_eq!(
+            status,
+            Some(DependencyStatus::Resolved(PathBuf::from(
+                "testable/sysroot/usr/local/lib/libbaz.dylib"
+            )))
+        );
+
+        let status = resolver.resolve_install_name("/usr/lib/libSystem.B.dylib", app, app, &[]);
+        assert_eq!(
+            status,
+            Some(DependencyStatus::Stub(PathBuf::from(
+                "testable/sysroot/usr/lib/libSystem.B.tbd"
+            )))
+        );
+
+        let status = resolver.resolve_install_name("@rpath/libbaz.dylib", app, app, &[]);
+        assert_eq!(status, None);
+    }
+
+    #[test]
+    fn test_resolve_tree() {
+        let object = parse_single(APP);
+        let resolver = DependencyResolver::new(Path::new(SYSROOT));
+        let dependencies = resolver.resolve(&object, Path::new(APP));
+        assert_eq!(dependencies.len(), 3);
+
+        let foo = &dependencies[0];
+        assert_eq!(foo.install_name, "@rpath/libfoo.dylib");
+        assert!(matches!(foo.status, DependencyStatus::Resolved(_)));
+        assert_eq!(foo.dependencies.len(), 3);
+        // `@rpath` of libbar is found with `LC_RPATH` of the executable
+        let bar = &foo.dependencies[0];
+        assert_eq!(bar.kind, DylibLoadKind::Reexport);
+        assert_eq!(
+            bar.dependencies[0].status,
+            DependencyStatus::Resolved(PathBuf::from(
+                "testable/sysroot/usr/local/lib/libbaz.dylib"
+            ))
+        );
+
+        assert!(matches!(dependencies[1].status, DependencyStatus::Stub(_)));
+
+        let unresolved: Vec<&Dependency> =
+            dependencies.iter().flat_map(|d| d.unresolved()).collect();
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved[0].install_name, "@rpath/libmissing.dylib");
+        assert_eq!(unresolved[0].kind, DylibLoadKind::Weak);
+    }
+}
@@ -0,0 +1,1329 @@
+//! Decoder of the most common A64 instructions: branches, address generation, integer
+//! data processing, loads and stores, pointer authentication and hints.
+//! Syntax follows `llvm-objdump`, except branch targets, which are absolute addresses.
+//! Encodings that aren't supported are decoded as `.long`.
+
+const CONDITIONS: [&str; 16] = [
+    "eq", "ne", "hs", "lo", "mi", "pl", "vs", "vc", "hi", "ls", "ge", "lt", "gt", "le", "al", "nv",
+];
+const SHIFTS: [&str; 4] = ["lsl", "lsr", "asr", "ror"];
+const EXTENDS: [&str; 8] = [
+    "uxtb", "uxth", "uxtw", "uxtx", "sxtb", "sxth", "sxtw", "sxtx",
+];
+
+/// Number of the zero register or stack pointer, depending on instruction
+const REG_31: u32 = 31;
+
+/// Address computation, that can be followed to the referenced address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Arm64Reference {
+    /// `adr` and `ldr` of literal
+    Address(u64),
+    /// `adrp`, address of 4KB page
+    Page { rd: u32, page: u64 },
+    /// `add` of immediate, usually `@PAGEOFF` after `adrp`
+    AddOffset { rd: u32, rn: u32, offset: u64 },
+    /// Load or store with unsigned immediate offset. `rt` is set for loads to general registers.
+    LoadStoreOffset {
+        rt: Option<u32>,
+        rn: u32,
+        offset: u64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Arm64Instruction {
+    pub(super) mnemonic: String,
+    pub(super) operands: String,
+    /// Target of direct branch
+    pub(super) branch_target: Option<u64>,
+    pub(super) reference: Option<Arm64Reference>,
+}
+
+impl Arm64Instruction {
+    fn new(mnemonic: &str, operands: String) -> Self {
+        Arm64Instruction {
+            mnemonic: mnemonic.to_string(),
+            operands,
+            branch_target: None,
+            reference: None,
+        }
+    }
+
+    fn unknown(word: u32) -> Self {
+        Self::new(".long", format!("{word:#010x}"))
+    }
+
+    fn with_branch_target(mut self, target: u64) -> Self {
+        self.branch_target = Some(target);
+        self
+    }
+
+    fn with_reference(mut self, reference: Arm64Reference) -> Self {
+        self.reference = Some(reference);
+        self
+    }
+}
+
+/// Decodes instruction `word` located at `address`
+pub(super) fn decode(word: u32, address: u64) -> Arm64Instruction {
+    let result = match (word >> 25) & 0xf {
+        0b1000 | 0b1001 => decode_data_immediate(word, address),
+        0b1010 | 0b1011 => decode_branch_system(word, address),
+        0b0100 | 0b0110 | 0b1100 | 0b1110 => decode_load_store(word, address),
+        0b0101 | 0b1101 => decode_data_register(word),
+        0b0111 | 0b1111 => decode_fp(word),
+        _ => None,
+    };
+    result.unwrap_or_else(|| Arm64Instruction::unknown(word))
+}
+
+fn bits(word: u32, low: u32, count: u32) -> u32 {
+    (word >> low) & ((1 << count) - 1)
+}
+
+fn sign_extend(value: u64, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
+/// General register, `sp` or zero register for number 31
+fn reg(number: u32, is_64: bool, is_sp: bool) -> String {
+    match (number, is_64, is_sp) {
+        (REG_31, true, true) => "sp".to_string(),
+        (REG_31, false, true) => "wsp".to_string(),
+        (REG_31, true, false) => "xzr".to_string(),
+        (REG_31, false, false) => "wzr".to_string(),
+        (n, true, _) => format!("x{n}"),
+        (n, false, _) => format!("w{n}"),
+    }
+}
+
+fn xreg(number: u32) -> String {
+    reg(number, true, false)
+}
+
+/// Base register of memory operand
+fn base_reg(number: u32) -> String {
+    reg(number, true, true)
+}
+
+fn immediate(value: i64) -> String {
+    format!("#{value}")
+}
+
+fn hex_immediate(value: u64) -> String {
+    format!("#{value:#x}")
+}
+
+fn relative(address: u64, offset: i64) -> u64 {
+    address.wrapping_add(offset as u64)
+}
+
+fn decode_data_immediate(word: u32, address: u64) -> Option<Arm64Instruction> {
+    let is_64 = bits(word, 31, 1) == 1;
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+
+    match bits(word, 23, 3) {
+        // PC-relative addressing
+        0b000 | 0b001 => {
+            let imm = (bits(word, 5, 19) << 2 | bits(word, 29, 2)) as u64;
+            match bits(word, 31, 1) {
+                0 => {
+                    let target = relative(address, sign_extend(imm, 21));
+                    Some(
+                        Arm64Instruction::new("adr", format!("{}, {target:#x}", xreg(rd)))
+                            .with_reference(Arm64Reference::Address(target)),
+                    )
+                }
+                _ => {
+                    let page = relative(address & !0xfff, sign_extend(imm, 21) << 12);
+                    Some(
+                        Arm64Instruction::new("adrp", format!("{}, {page:#x}", xreg(rd)))
+                            .with_reference(Arm64Reference::Page { rd, page }),
+                    )
+                }
+            }
+        }
+        // Add/subtract (immediate)
+        0b010 => {
+            let is_sub = bits(word, 30, 1) == 1;
+            let sets_flags = bits(word, 29, 1) == 1;
+            let shift = bits(word, 22, 1) * 12;
+            let imm = bits(word, 10, 12) as u64;
+            let shifted = match shift {
+                0 => immediate(imm as i64),
+                _ => format!("{}, lsl #{shift}", immediate(imm as i64)),
+            };
+
+            let rd_name = reg(rd, is_64, !sets_flags);
+            let rn_name = reg(rn, is_64, true);
+            let result = match (is_sub, sets_flags) {
+                (false, false) if imm == 0 && (rd == REG_31 || rn == REG_31) => {
+                    Arm64Instruction::new("mov", format!("{rd_name}, {rn_name}"))
+                }
+                (false, true) if rd == REG_31 => {
+                    Arm64Instruction::new("cmn", format!("{rn_name}, {shifted}"))
+                }
+                (true, true) if rd == REG_31 => {
+                    Arm64Instruction::new("cmp", format!("{rn_name}, {shifted}"))
+                }
+                _ => {
+                    let mnemonic = match (is_sub, sets_flags) {
+                        (false, false) => "add",
+                        (false, true) => "adds",
+                        (true, false) => "sub",
+                        (true, true) => "subs",
+                    };
+                    Arm64Instruction::new(mnemonic, format!("{rd_name}, {rn_name}, {shifted}"))
+                }
+            };
+            match (is_64, is_sub, sets_flags) {
+                (true, false, false) => Some(result.with_reference(Arm64Reference::AddOffset {
+                    rd,
+                    rn,
+                    offset: imm << shift,
+                })),
+                _ => Some(result),
+            }
+        }
+        // Logical (immediate)
+        0b100 => {
+            let n = bits(word, 22, 1);
+            if !is_64 && n == 1 {
+                return None;
+            }
+            let value = decode_bit_masks(n, bits(word, 10, 6), bits(word, 16, 6), is_64)?;
+            let opc = bits(word, 29, 2);
+            let rn_name = reg(rn, is_64, false);
+            match opc {
+                0b01 if rn == REG_31 && !is_move_wide_immediate(value, is_64) => {
+                    Some(Arm64Instruction::new(
+                        "mov",
+                        format!("{}, {}", reg(rd, is_64, true), hex_immediate(value)),
+                    ))
+                }
+                0b11 if rd == REG_31 => Some(Arm64Instruction::new(
+                    "tst",
+                    format!("{rn_name}, {}", hex_immediate(value)),
+                )),
+                _ => {
+                    let mnemonic = ["and", "orr", "eor", "ands"][opc as usize];
+                    let rd_name = reg(rd, is_64, opc != 0b11);
+                    Some(Arm64Instruction::new(
+                        mnemonic,
+                        format!("{rd_name}, {rn_name}, {}", hex_immediate(value)),
+                    ))
+                }
+            }
+        }
+        // Move wide (immediate)
+        0b101 => {
+            let hw = bits(word, 21, 2);
+            if !is_64 && hw > 1 {
+                return None;
+            }
+            let imm = bits(word, 5, 16) as u64;
+            let shift = hw * 16;
+            let rd_name = reg(rd, is_64, false);
+            match bits(word, 29, 2) {
+                // movn
+                0b00 => {
+                    let value = match is_64 {
+                        true => !(imm << shift) as i64,
+                        false => !((imm << shift) as u32) as i32 as i64,
+                    };
+                    match (imm == 0 && hw != 0) || (!is_64 && imm == 0xffff) {
+                        true => Some(Arm64Instruction::new(
+                            "movn",
+                            format!("{rd_name}, {}, lsl #{shift}", hex_immediate(imm)),
+                        )),
+                        false => Some(Arm64Instruction::new(
+                            "mov",
+                            format!("{rd_name}, {}", immediate(value)),
+                        )),
+                    }
+                }
+                // movz
+                0b10 => match imm == 0 && hw != 0 {
+                    true => Some(Arm64Instruction::new(
+                        "movz",
+                        format!("{rd_name}, {}, lsl #{shift}", hex_immediate(imm)),
+                    )),
+                    false => Some(Arm64Instruction::new(
+                        "mov",
+                        format!("{rd_name}, {}", immediate((imm << shift) as i64)),
+                    )),
+                },
+                // movk
+                0b11 => {
+                    let mut operands = format!("{rd_name}, {}", hex_immediate(imm));
+                    if shift != 0 {
+                        operands += &format!(", lsl #{shift}");
+                    }
+                    Some(Arm64Instruction::new("movk", operands))
+                }
+                _ => None,
+            }
+        }
+        // Bitfield
+        0b110 => decode_bitfield(word, is_64),
+        // Extract
+        0b111 => {
+            if bits(word, 29, 2) != 0 || bits(word, 22, 1) != bits(word, 31, 1) {
+                return None;
+            }
+            let rm = bits(word, 16, 5);
+            let imms = bits(word, 10, 6);
+            let rd_name = reg(rd, is_64, false);
+            let rn_name = reg(rn, is_64, false);
+            match rn == rm {
+                true => Some(Arm64Instruction::new(
+                    "ror",
+                    format!("{rd_name}, {rn_name}, #{imms}"),
+                )),
+                false => Some(Arm64Instruction::new(
+                    "extr",
+                    format!("{rd_name}, {rn_name}, {}, #{imms}", reg(rm, is_64, false)),
+                )),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `DecodeBitMasks` of the Arm ARM, for logical immediates
+fn decode_bit_masks(n: u32, imms: u32, immr: u32, is_64: bool) -> Option<u64> {
+    let combined = (n << 6) | (!imms & 0x3f);
+    if combined == 0 {
+        return None;
+    }
+    let len = 31 - combined.leading_zeros();
+    if len < 1 {
+        return None;
+    }
+    let size = 1u32 << len;
+    let levels = size - 1;
+    let s = imms & levels;
+    let r = immr & levels;
+    if s == levels {
+        return None;
+    }
+
+    let element_mask = match size {
+        64 => u64::MAX,
+        _ => (1u64 << size) - 1,
+    };
+    let welem = (1u64 << (s + 1)) - 1;
+    let rotated = match r {
+        0 => welem,
+        _ => ((welem >> r) | (welem << (size - r))) & element_mask,
+    };
+
+    let mut value = rotated;
+    let mut width = size;
+    while width < 64 {
+        value |= value << width;
+        width *= 2;
+    }
+    match is_64 {
+        true => Some(value),
+        false => Some(value & 0xffff_ffff),
+    }
+}
+
+/// Whether `orr` with zero register should be printed as `mov` of logical immediate.
+/// Values, that `movz` or `movn` can hold, are printed as `orr` by LLVM.
+fn is_move_wide_immediate(value: u64, is_64: bool) -> bool {
+    let (mask, width) = match is_64 {
+        true => (u64::MAX, 64),
+        false => (0xffff_ffff, 32),
+    };
+    let inverted = !value & mask;
+    (0..width / 16).any(|hw| {
+        let others = mask & !(0xffffu64 << (hw * 16));
+        value & others == 0 || inverted & others == 0
+    })
+}
+
+fn decode_bitfield(word: u32, is_64: bool) -> Option<Arm64Instruction> {
+    if bits(word, 22, 1) != bits(word, 31, 1) {
+        return None;
+    }
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let immr = bits(word, 16, 6);
+    let imms = bits(word, 10, 6);
+    let size = if is_64 { 64 } else { 32 };
+    let rd_name = reg(rd, is_64, false);
+    let rn_name = reg(rn, is_64, false);
+
+    let result = match bits(word, 29, 2) {
+        // sbfm
+        0b00 => match (immr, imms) {
+            (_, s) if s == size - 1 => {
+                Arm64Instruction::new("asr", format!("{rd_name}, {rn_name}, #{immr}"))
+            }
+            (0, 7) | (0, 15) | (0, 31) => {
+                let mnemonic = match imms {
+                    7 => "sxtb",
+                    15 => "sxth",
+                    _ => "sxtw",
+                };
+                Arm64Instruction::new(mnemonic, format!("{rd_name}, {}", reg(rn, false, false)))
+            }
+            _ if imms < immr => Arm64Instruction::new(
+                "sbfiz",
+                format!("{rd_name}, {rn_name}, #{}, #{}", size - immr, imms + 1),
+            ),
+            _ => Arm64Instruction::new(
+                "sbfx",
+                format!("{rd_name}, {rn_name}, #{immr}, #{}", imms - immr + 1),
+            ),
+        },
+        // bfm
+        0b01 => match imms < immr {
+            true if rn == REG_31 => {
+                Arm64Instruction::new("bfc", format!("{rd_name}, #{}, #{}", size - immr, imms + 1))
+            }
+            true => Arm64Instruction::new(
+                "bfi",
+                format!("{rd_name}, {rn_name}, #{}, #{}", size - immr, imms + 1),
+            ),
+            false => Arm64Instruction::new(
+                "bfxil",
+                format!("{rd_name}, {rn_name}, #{immr}, #{}", imms - immr + 1),
+            ),
+        },
+        // ubfm
+        0b10 => match (immr, imms) {
+            (_, s) if s == size - 1 => {
+                Arm64Instruction::new("lsr", format!("{rd_name}, {rn_name}, #{immr}"))
+            }
+            (r, s) if s + 1 == r => {
+                Arm64Instruction::new("lsl", format!("{rd_name}, {rn_name}, #{}", size - 1 - imms))
+            }
+            (0, 7) | (0, 15) if !is_64 => {
+                let mnemonic = match imms {
+                    7 => "uxtb",
+                    _ => "uxth",
+                };
+                Arm64Instruction::new(mnemonic, format!("{rd_name}, {rn_name}"))
+            }
+            _ if imms < immr => Arm64Instruction::new(
+                "ubfiz",
+                format!("{rd_name}, {rn_name}, #{}, #{}", size - immr, imms + 1),
+            ),
+            _ => Arm64Instruction::new(
+                "ubfx",
+                format!("{rd_name}, {rn_name}, #{immr}, #{}", imms - immr + 1),
+            ),
+        },
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn decode_branch_system(word: u32, address: u64) -> Option<Arm64Instruction> {
+    // Unconditional branch (immediate)
+    if word & 0x7c00_0000 == 0x1400_0000 {
+        let target = relative(address, sign_extend((bits(word, 0, 26) << 2) as u64, 28));
+        let mnemonic = match bits(word, 31, 1) {
+            0 => "b",
+            _ => "bl",
+        };
+        return Some(
+            Arm64Instruction::new(mnemonic, format!("{target:#x}")).with_branch_target(target),
+        );
+    }
+
+    // Compare and branch
+    if word & 0x7e00_0000 == 0x3400_0000 {
+        let target = relative(address, sign_extend((bits(word, 5, 19) << 2) as u64, 21));
+        let mnemonic = match bits(word, 24, 1) {
+            0 => "cbz",
+            _ => "cbnz",
+        };
+        let rt = reg(bits(word, 0, 5), bits(word, 31, 1) == 1, false);
+        return Some(
+            Arm64Instruction::new(mnemonic, format!("{rt}, {target:#x}"))
+                .with_branch_target(target),
+        );
+    }
+
+    // Test and branch
+    if word & 0x7e00_0000 == 0x3600_0000 {
+        let target = relative(address, sign_extend((bits(word, 5, 14) << 2) as u64, 16));
+        let mnemonic = match bits(word, 24, 1) {
+            0 => "tbz",
+            _ => "tbnz",
+        };
+        let bit = bits(word, 31, 1) << 5 | bits(word, 19, 5);
+        let rt = reg(bits(word, 0, 5), bit >= 32, false);
+        return Some(
+            Arm64Instruction::new(mnemonic, format!("{rt}, #{bit}, {target:#x}"))
+                .with_branch_target(target),
+        );
+    }
+
+    // Conditional branch
+    if word & 0xff00_0010 == 0x5400_0000 {
+        let target = relative(address, sign_extend((bits(word, 5, 19) << 2) as u64, 21));
+        let mnemonic = format!("b.{}", CONDITIONS[bits(word, 0, 4) as usize]);
+        return Some(
+            Arm64Instruction::new(&mnemonic, format!("{target:#x}")).with_branch_target(target),
+        );
+    }
+
+    // Exception generation
+    if word & 0xff00_0000 == 0xd400_0000 {
+        let imm = bits(word, 5, 16) as u64;
+        let mnemonic = match (bits(word, 21, 3), bits(word, 0, 5)) {
+            (0b000, 0b00001) => "svc",
+            (0b000, 0b00010) => "hvc",
+            (0b000, 0b00011) => "smc",
+            (0b001, 0b00000) => "brk",
+            (0b010, 0b00000) => "hlt",
+            _ => return None,
+        };
+        return Some(Arm64Instruction::new(mnemonic, hex_immediate(imm)));
+    }
+
+    // System
+    if word & 0xffc0_0000 == 0xd500_0000 {
+        return decode_system(word);
+    }
+
+    // Unconditional branch (register)
+    if word & 0xfe00_0000 == 0xd600_0000 {
+        return decode_branch_register(word);
+    }
+
+    None
+}
+
+fn decode_system(word: u32) -> Option<Arm64Instruction> {
+    // Hints
+    if word & 0xffff_f01f == 0xd503_201f {
+        let hint = bits(word, 5, 7);
+        let mnemonic = match hint {
+            0 => "nop",
+            1 => "yield",
+            2 => "wfe",
+            3 => "wfi",
+            4 => "sev",
+            5 => "sevl",
+            7 => "xpaclri",
+            8 => "pacia1716",
+            10 => "pacib1716",
+            12 => "autia1716",
+            14 => "autib1716",
+            24 => "paciaz",
+            25 => "paciasp",
+            26 => "pacibz",
+            27 => "pacibsp",
+            28 => "autiaz",
+            29 => "autiasp",
+            30 => "autibz",
+            31 => "autibsp",
+            32 | 34 | 36 | 38 => {
+                let target = ["", " c", " j", " jc"][((hint - 32) / 2) as usize];
+                return Some(Arm64Instruction::new("bti", target.trim().to_string()));
+            }
+            _ => return Some(Arm64Instruction::new("hint", format!("#{hint}"))),
+        };
+        return Some(Arm64Instruction::new(mnemonic, String::new()));
+    }
+
+    // Barriers
+    if word & 0xffff_f01f == 0xd503_301f {
+        let crm = bits(word, 8, 4);
+        let option = match crm {
+            0b1111 => "sy".to_string(),
+            0b1110 => "st".to_string(),
+            0b1101 => "ld".to_string(),
+            0b1011 => "ish".to_string(),
+            0b1010 => "ishst".to_string(),
+            0b1001 => "ishld".to_string(),
+            0b0111 => "nsh".to_string(),
+            0b0011 => "osh".to_string(),
+            _ => format!("#{crm}"),
+        };
+        return match bits(word, 5, 3) {
+            0b010 => Some(Arm64Instruction::new("clrex", String::new())),
+            0b100 => Some(Arm64Instruction::new("dsb", option)),
+            0b101 => Some(Arm64Instruction::new("dmb", option)),
+            0b110 => Some(Arm64Instruction::new("isb", String::new())),
+            _ => None,
+        };
+    }
+
+    // Move to and from system registers
+    if word & 0xffd0_0000 == 0xd510_0000 {
+        let rt = xreg(bits(word, 0, 5));
+        let register = system_register_name(bits(word, 5, 15));
+        return match bits(word, 21, 1) {
+            0 => Some(Arm64Instruction::new("msr", format!("{register}, {rt}"))),
+            _ => Some(Arm64Instruction::new("mrs", format!("{rt}, {register}"))),
+        };
+    }
+
+    None
+}
+
+/// `o0:op1:CRn:CRm:op2`, where `op0` is `2 + o0`
+fn system_register_name(encoded: u32) -> String {
+    match encoded {
+        0x5e82 => "tpidr_el0".to_string(),
+        0x5e83 => "tpidrro_el0".to_string(),
+        0x5a10 => "nzcv".to_string(),
+        0x5a20 => "fpcr".to_string(),
+        0x5a21 => "fpsr".to_string(),
+        0x5f01 => "cntpct_el0".to_string(),
+        0x5f02 => "cntvct_el0".to_string(),
+        0x5807 => "dczid_el0".to_string(),
+        0x5801 => "ctr_el0".to_string(),
+        _ => format!(
+            "s{}_{}_c{}_c{}_{}",
+            2 + (encoded >> 14),
+            bits(encoded, 11, 3),
+            bits(encoded, 7, 4),
+            bits(encoded, 3, 4),
+            bits(encoded, 0, 3)
+        ),
+    }
+}
+
+fn decode_branch_register(word: u32) -> Option<Arm64Instruction> {
+    let opc = bits(word, 21, 4);
+    let op2 = bits(word, 16, 5);
+    let op3 = bits(word, 10, 6);
+    let rn = bits(word, 5, 5);
+    let op4 = bits(word, 0, 5);
+    if op2 != 0b11111 {
+        return None;
+    }
+
+    let result = match (opc, op3, op4) {
+        (0b0000, 0, 0) => Arm64Instruction::new("br", xreg(rn)),
+        (0b0001, 0, 0) => Arm64Instruction::new("blr", xreg(rn)),
+        (0b0010, 0, 0) => match rn {
+            30 => Arm64Instruction::new("ret", String::new()),
+            _ => Arm64Instruction::new("ret", xreg(rn)),
+        },
+        (0b0100, 0, 0) if rn == REG_31 => Arm64Instruction::new("eret", String::new()),
+        (0b0010, 2, 31) if rn == REG_31 => Arm64Instruction::new("retaa", String::new()),
+        (0b0010, 3, 31) if rn == REG_31 => Arm64Instruction::new("retab", String::new()),
+        (0b0000, 2, 31) => Arm64Instruction::new("braaz", xreg(rn)),
+        (0b0000, 3, 31) => Arm64Instruction::new("brabz", xreg(rn)),
+        (0b0001, 2, 31) => Arm64Instruction::new("blraaz", xreg(rn)),
+        (0b0001, 3, 31) => Arm64Instruction::new("blrabz", xreg(rn)),
+        (0b1000, 2 | 3, _) | (0b1001, 2 | 3, _) => {
+            let mnemonic = match (opc, op3) {
+                (0b1000, 2) => "braa",
+                (0b1000, _) => "brab",
+                (_, 2) => "blraa",
+                _ => "blrab",
+            };
+            Arm64Instruction::new(mnemonic, format!("{}, {}", xreg(rn), reg(op4, true, true)))
+        }
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn decode_load_store(word: u32, address: u64) -> Option<Arm64Instruction> {
+    // Load register (literal)
+    if word & 0x3b00_0000 == 0x1800_0000 {
+        let target = relative(address, sign_extend((bits(word, 5, 19) << 2) as u64, 21));
+        let rt = bits(word, 0, 5);
+        let is_vector = bits(word, 26, 1) == 1;
+        let (mnemonic, rt_name) = match (bits(word, 30, 2), is_vector) {
+            (0b00, false) => ("ldr", reg(rt, false, false)),
+            (0b01, false) => ("ldr", xreg(rt)),
+            (0b10, false) => ("ldrsw", xreg(rt)),
+            (0b11, false) => ("prfm", format!("#{rt}")),
+            (0b00, true) => ("ldr", format!("s{rt}")),
+            (0b01, true) => ("ldr", format!("d{rt}")),
+            (0b10, true) => ("ldr", format!("q{rt}")),
+            _ => return None,
+        };
+        return Some(
+            Arm64Instruction::new(mnemonic, format!("{rt_name}, {target:#x}"))
+                .with_reference(Arm64Reference::Address(target)),
+        );
+    }
+
+    // Load/store exclusive and ordered
+    if word & 0x3f00_0000 == 0x0800_0000 {
+        return decode_load_store_exclusive(word);
+    }
+
+    // Load/store pair
+    if word & 0x3a00_0000 == 0x2800_0000 {
+        return decode_load_store_pair(word);
+    }
+
+    // Load/store register (unsigned immediate)
+    if word & 0x3b00_0000 == 0x3900_0000 {
+        let (mnemonic, rt_name, scale, is_load) = load_store_register(word, false)?;
+        let rt = bits(word, 0, 5);
+        let rn = bits(word, 5, 5);
+        let offset = (bits(word, 10, 12) as u64) << scale;
+        let operands = match offset {
+            0 => format!("{rt_name}, [{}]", base_reg(rn)),
+            _ => format!("{rt_name}, [{}, #{offset}]", base_reg(rn)),
+        };
+        let is_general = bits(word, 26, 1) == 0 && mnemonic != "prfm";
+        return Some(Arm64Instruction::new(&mnemonic, operands).with_reference(
+            Arm64Reference::LoadStoreOffset {
+                rt: match is_load && is_general {
+                    true => Some(rt),
+                    false => None,
+                },
+                rn,
+                offset,
+            },
+        ));
+    }
+
+    // Load/store register (register offset)
+    if word & 0x3b20_0c00 == 0x3820_0800 {
+        let (mnemonic, rt_name, scale, _) = load_store_register(word, false)?;
+        let rn = base_reg(bits(word, 5, 5));
+        let option = bits(word, 13, 3);
+        let is_shifted = bits(word, 12, 1) == 1;
+        let rm = reg(bits(word, 16, 5), option & 1 == 1, false);
+        let extend = match (option, is_shifted) {
+            (0b011, false) => String::new(),
+            (0b011, true) => format!(", lsl #{scale}"),
+            (0b010 | 0b110 | 0b111, false) => format!(", {}", EXTENDS[option as usize]),
+            (0b010 | 0b110 | 0b111, true) => format!(", {} #{scale}", EXTENDS[option as usize]),
+            _ => return None,
+        };
+        return Some(Arm64Instruction::new(
+            &mnemonic,
+            format!("{rt_name}, [{rn}, {rm}{extend}]"),
+        ));
+    }
+
+    // Load/store register (unscaled immediate, immediate post-indexed, immediate pre-indexed)
+    if word & 0x3b20_0000 == 0x3800_0000 {
+        let kind = bits(word, 10, 2);
+        if kind == 0b10 {
+            return None;
+        }
+        let (mnemonic, rt_name, _, _) = load_store_register(word, kind == 0b00)?;
+        let rn = base_reg(bits(word, 5, 5));
+        let offset = sign_extend(bits(word, 12, 9) as u64, 9);
+        let operands = match kind {
+            0b00 if offset == 0 => format!("{rt_name}, [{rn}]"),
+            0b00 => format!("{rt_name}, [{rn}, #{offset}]"),
+            0b01 => format!("{rt_name}, [{rn}], #{offset}"),
+            _ => format!("{rt_name}, [{rn}, #{offset}]!"),
+        };
+        return Some(Arm64Instruction::new(&mnemonic, operands));
+    }
+
+    None
+}
+
+/// Mnemonic, name of transferred register, log2 of access size and whether it's a load,
+/// for single register loads and stores. Mnemonic of unscaled form is `ldur`/`stur`.
+fn load_store_register(word: u32, is_unscaled: bool) -> Option<(String, String, u32, bool)> {
+    let size = bits(word, 30, 2);
+    let is_vector = bits(word, 26, 1) == 1;
+    let opc = bits(word, 22, 2);
+    let rt = bits(word, 0, 5);
+
+    let (base, rt_name, scale, is_load) = match is_vector {
+        true => {
+            let (prefix, scale) = match (size, opc >> 1) {
+                (0b00, 1) => ("q", 4),
+                (0b00, 0) => ("b", 0),
+                (0b01, 0) => ("h", 1),
+                (0b10, 0) => ("s", 2),
+                (0b11, 0) => ("d", 3),
+                _ => return None,
+            };
+            let is_load = opc & 1 == 1;
+            let base = match is_load {
+                true => "ldr",
+                false => "str",
+            };
+            (base.to_string(), format!("{prefix}{rt}"), scale, is_load)
+        }
+        false => {
+            let suffix = match size {
+                0b00 => "b",
+                0b01 => "h",
+                _ => "",
+            };
+            match (opc, size) {
+                (0b00, _) => (
+                    format!("str{suffix}"),
+                    reg(rt, size == 0b11, false),
+                    size,
+                    false,
+                ),
+                (0b01, _) => (
+                    format!("ldr{suffix}"),
+                    reg(rt, size == 0b11, false),
+                    size,
+                    true,
+                ),
+                (0b10, 0b11) => {
+                    let mnemonic = match is_unscaled {
+                        true => "prfum",
+                        false => "prfm",
+                    };
+                    return Some((mnemonic.to_string(), format!("#{rt}"), size, false));
+                }
+                (0b10, 0b10) => ("ldrsw".to_string(), xreg(rt), size, true),
+                (0b10, _) => (format!("ldrs{suffix}"), xreg(rt), size, true),
+                (0b11, 0b00 | 0b01) => (format!("ldrs{suffix}"), reg(rt, false, false), size, true),
+                _ => return None,
+            }
+        }
+    };
+
+    let mnemonic = match is_unscaled {
+        true => base.replacen("ldr", "ldur", 1).replacen("str", "stur", 1),
+        false => base,
+    };
+    Some((mnemonic, rt_name, scale, is_load))
+}
+
+fn decode_load_store_pair(word: u32) -> Option<Arm64Instruction> {
+    let opc = bits(word, 30, 2);
+    let is_vector = bits(word, 26, 1) == 1;
+    let kind = bits(word, 23, 2);
+    let is_load = bits(word, 22, 1) == 1;
+    let rt = bits(word, 0, 5);
+    let rt2 = bits(word, 10, 5);
+    let rn = base_reg(bits(word, 5, 5));
+
+    let (mnemonic, prefix, scale) = match (is_vector, opc, is_load) {
+        (false, 0b00, _) => (pair_mnemonic(kind, is_load), "w", 2),
+        (false, 0b01, true) if kind != 0b00 => ("ldpsw", "x", 2),
+        (false, 0b10, _) => (pair_mnemonic(kind, is_load), "x", 3),
+        (true, 0b00, _) => (pair_mnemonic(kind, is_load), "s", 2),
+        (true, 0b01, _) => (pair_mnemonic(kind, is_load), "d", 3),
+        (true, 0b10, _) => (pair_mnemonic(kind, is_load), "q", 4),
+        _ => return None,
+    };
+    let name = |number: u32| match prefix {
+        "w" => reg(number, false, false),
+        "x" => xreg(number),
+        _ => format!("{prefix}{number}"),
+    };
+
+    let offset = sign_extend(bits(word, 15, 7) as u64, 7) << scale;
+    let registers = format!("{}, {}", name(rt), name(rt2));
+    let operands = match kind {
+        0b01 => format!("{registers}, [{rn}], #{offset}"),
+        0b11 => format!("{registers}, [{rn}, #{offset}]!"),
+        _ if offset == 0 => format!("{registers}, [{rn}]"),
+        _ => format!("{registers}, [{rn}, #{offset}]"),
+    };
+    Some(Arm64Instruction::new(mnemonic, operands))
+}
+
+fn pair_mnemonic(kind: u32, is_load: bool) -> &'static str {
+    match (kind, is_load) {
+        (0b00, false) => "stnp",
+        (0b00, true) => "ldnp",
+        (_, false) => "stp",
+        (_, true) => "ldp",
+    }
+}
+
+fn decode_load_store_exclusive(word: u32) -> Option<Arm64Instruction> {
+    let size = bits(word, 30, 2);
+    let o2 = bits(word, 23, 1);
+    let is_load = bits(word, 22, 1) == 1;
+    let o1 = bits(word, 21, 1);
+    let rs = bits(word, 16, 5);
+    let o0 = bits(word, 15, 1);
+    let rt2 = bits(word, 10, 5);
+    let rn = base_reg(bits(word, 5, 5));
+    let rt = bits(word, 0, 5);
+    if o1 == 1 || rt2 != REG_31 {
+        return None;
+    }
+
+    let suffix = match size {
+        0b00 => "b",
+        0b01 => "h",
+        _ => "",
+    };
+    let rt_name = reg(rt, size == 0b11, false);
+    let base = match (o2, is_load, o0) {
+        (0, false, 0) => "stxr",
+        (0, false, _) => "stlxr",
+        (0, true, 0) => "ldxr",
+        (0, true, _) => "ldaxr",
+        (_, false, 0) => "stllr",
+        (_, false, _) => "stlr",
+        (_, true, 0) => "ldlar",
+        (_, true, _) => "ldar",
+    };
+    let mnemonic = format!("{base}{suffix}");
+    match (o2, is_load) {
+        (0, false) => Some(Arm64Instruction::new(
+            &mnemonic,
+            format!("{}, {rt_name}, [{rn}]", reg(rs, false, false)),
+        )),
+        _ => Some(Arm64Instruction::new(
+            &mnemonic,
+            format!("{rt_name}, [{rn}]"),
+        )),
+    }
+}
+
+fn decode_data_register(word: u32) -> Option<Arm64Instruction> {
+    let is_64 = bits(word, 31, 1) == 1;
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let rm = bits(word, 16, 5);
+    let rd_name = reg(rd, is_64, false);
+    let rn_name = reg(rn, is_64, false);
+    let rm_name = reg(rm, is_64, false);
+
+    // Logical (shifted register)
+    if word & 0x1f00_0000 == 0x0a00_0000 {
+        let opc = bits(word, 29, 2);
+        let n = bits(word, 21, 1);
+        let shift = bits(word, 22, 2);
+        let amount = bits(word, 10, 6);
+        if !is_64 && amount >= 32 {
+            return None;
+        }
+        let shifted = match amount {
+            0 if shift == 0 => rm_name.clone(),
+            _ => format!("{rm_name}, {} #{amount}", SHIFTS[shift as usize]),
+        };
+        let result = match (opc, n) {
+            (0b01, 0) if rn == REG_31 && shift == 0 && amount == 0 => {
+                Arm64Instruction::new("mov", format!("{rd_name}, {rm_name}"))
+            }
+            (0b01, 1) if rn == REG_31 => {
+                Arm64Instruction::new("mvn", format!("{rd_name}, {shifted}"))
+            }
+            (0b11, 0) if rd == REG_31 => {
+                Arm64Instruction::new("tst", format!("{rn_name}, {shifted}"))
+            }
+            _ => {
+                let mnemonic = ["and", "bic", "orr", "orn", "eor", "eon", "ands", "bics"]
+                    [(opc << 1 | n) as usize];
+                Arm64Instruction::new(mnemonic, format!("{rd_name}, {rn_name}, {shifted}"))
+            }
+        };
+        return Some(result);
+    }
+
+    // Add/subtract (shifted register)
+    if word & 0x1f20_0000 == 0x0b00_0000 {
+        let is_sub = bits(word, 30, 1) == 1;
+        let sets_flags = bits(word, 29, 1) == 1;
+        let shift = bits(word, 22, 2);
+        let amount = bits(word, 10, 6);
+        if shift == 0b11 || (!is_64 && amount >= 32) {
+            return None;
+        }
+        let shifted = match amount {
+            0 => rm_name.clone(),
+            _ => format!("{rm_name}, {} #{amount}", SHIFTS[shift as usize]),
+        };
+        let result = match (is_sub, sets_flags) {
+            (false, true) if rd == REG_31 => {
+                Arm64Instruction::new("cmn", format!("{rn_name}, {shifted}"))
+            }
+            (true, true) if rd == REG_31 => {
+                Arm64Instruction::new("cmp", format!("{rn_name}, {shifted}"))
+            }
+            (true, false) if rn == REG_31 => {
+                Arm64Instruction::new("neg", format!("{rd_name}, {shifted}"))
+            }
+            (true, true) if rn == REG_31 => {
+                Arm64Instruction::new("negs", format!("{rd_name}, {shifted}"))
+            }
+            _ => {
+                let mnemonic =
+                    ["add", "adds", "sub", "subs"][((is_sub as usize) << 1) | sets_flags as usize];
+                Arm64Instruction::new(mnemonic, format!("{rd_name}, {rn_name}, {shifted}"))
+            }
+        };
+        return Some(result);
+    }
+
+    // Add/subtract (extended register)
+    if word & 0x1f20_0000 == 0x0b20_0000 {
+        let is_sub = bits(word, 30, 1) == 1;
+        let sets_flags = bits(word, 29, 1) == 1;
+        let option = bits(word, 13, 3);
+        let amount = bits(word, 10, 3);
+        if amount > 4 || bits(word, 22, 2) != 0 {
+            return None;
+        }
+        let rd_name = reg(rd, is_64, !sets_flags);
+        let rn_name = reg(rn, is_64, true);
+        // Only `uxtx` and `sxtx` take 64-bit register
+        let rm_name = reg(rm, is_64 && option & 0b011 == 0b011, false);
+        let is_lsl = (rd == REG_31 || rn == REG_31) && option == if is_64 { 0b011 } else { 0b010 };
+        let extend = match (is_lsl, amount) {
+            (true, 0) => String::new(),
+            (true, _) => format!(", lsl #{amount}"),
+            (false, 0) => format!(", {}", EXTENDS[option as usize]),
+            (false, _) => format!(", {} #{amount}", EXTENDS[option as usize]),
+        };
+        let result = match (is_sub, sets_flags) {
+            (false, true) if rd == REG_31 => {
+                Arm64Instruction::new("cmn", format!("{rn_name}, {rm_name}{extend}"))
+            }
+            (true, true) if rd == REG_31 => {
+                Arm64Instruction::new("cmp", format!("{rn_name}, {rm_name}{extend}"))
+            }
+            _ => {
+                let mnemonic =
+                    ["add", "adds", "sub", "subs"][((is_sub as usize) << 1) | sets_flags as usize];
+                Arm64Instruction::new(mnemonic, format!("{rd_name}, {rn_name}, {rm_name}{extend}"))
+            }
+        };
+        return Some(result);
+    }
+
+    // Add/subtract (with carry)
+    if word & 0x1fe0_fc00 == 0x1a00_0000 {
+        let mnemonic = ["adc", "adcs", "sbc", "sbcs"][bits(word, 29, 2) as usize];
+        return Some(Arm64Instruction::new(
+            mnemonic,
+            format!("{rd_name}, {rn_name}, {rm_name}"),
+        ));
+    }
+
+    // Conditional compare
+    if word & 0x1fe0_0410 == 0x1a40_0000 && bits(word, 29, 1) == 1 {
+        let mnemonic = match bits(word, 30, 1) {
+            0 => "ccmn",
+            _ => "ccmp",
+        };
+        let second = match bits(word, 11, 1) {
+            0 => rm_name,
+            _ => format!("#{rm}"),
+        };
+        let nzcv = bits(word, 0, 4);
+        let cond = CONDITIONS[bits(word, 12, 4) as usize];
+        return Some(Arm64Instruction::new(
+            mnemonic,
+            format!("{rn_name}, {second}, #{nzcv}, {cond}"),
+        ));
+    }
+
+    // Conditional select
+    if word & 0x1fe0_0800 == 0x1a80_0000 && bits(word, 29, 1) == 0 {
+        let cond = bits(word, 12, 4);
+        let inverted = CONDITIONS[(cond ^ 1) as usize];
+        let is_aliased = rn == rm && cond >> 1 != 0b111;
+        let result = match (bits(word, 30, 1), bits(word, 10, 1)) {
+            (0, 0) => Arm64Instruction::new(
+                "csel",
+                format!(
+                    "{rd_name}, {rn_name}, {rm_name}, {}",
+                    CONDITIONS[cond as usize]
+                ),
+            ),
+            (0, _) if is_aliased && rn == REG_31 => {
+                Arm64Instruction::new("cset", format!("{rd_name}, {inverted}"))
+            }
+            (0, _) if is_aliased => {
+                Arm64Instruction::new("cinc", format!("{rd_name}, {rn_name}, {inverted}"))
+            }
+            (_, 0) if is_aliased && rn == REG_31 => {
+                Arm64Instruction::new("csetm", format!("{rd_name}, {inverted}"))
+            }
+            (_, 0) if is_aliased => {
+                Arm64Instruction::new("cinv", format!("{rd_name}, {rn_name}, {inverted}"))
+            }
+            (_, _) if is_aliased && bits(word, 10, 1) == 1 => {
+                Arm64Instruction::new("cneg", format!("{rd_name}, {rn_name}, {inverted}"))
+            }
+            (op, op2) => {
+                let mnemonic = ["csel", "csinc", "csinv", "csneg"][(op << 1 | op2) as usize];
+                Arm64Instruction::new(
+                    mnemonic,
+                    format!(
+                        "{rd_name}, {rn_name}, {rm_name}, {}",
+                        CONDITIONS[cond as usize]
+                    ),
+                )
+            }
+        };
+        return Some(result);
+    }
+
+    // Data-processing (2 source)
+    if word & 0x5fe0_0000 == 0x1ac0_0000 {
+        let mnemonic = match bits(word, 10, 6) {
+            0b000010 => "udiv",
+            0b000011 => "sdiv",
+            0b001000 => "lsl",
+            0b001001 => "lsr",
+            0b001010 => "asr",
+            0b001011 => "ror",
+            0b001100 if is_64 => {
+                return Some(Arm64Instruction::new(
+                    "pacga",
+                    format!("{rd_name}, {rn_name}, {}", reg(rm, true, true)),
+                ))
+            }
+            _ => return None,
+        };
+        return Some(Arm64Instruction::new(
+            mnemonic,
+            format!("{rd_name}, {rn_name}, {rm_name}"),
+        ));
+    }
+
+    // Data-processing (1 source)
+    if word & 0x5fe0_0000 == 0x5ac0_0000 {
+        let opcode = bits(word, 10, 6);
+        return match bits(word, 16, 5) {
+            0b00000 => {
+                let mnemonic = match (opcode, is_64) {
+                    (0b000000, _) => "rbit",
+                    (0b000001, _) => "rev16",
+                    (0b000010, false) => "rev",
+                    (0b000010, true) => "rev32",
+                    (0b000011, true) => "rev",
+                    (0b000100, _) => "clz",
+                    (0b000101, _) => "cls",
+                    _ => return None,
+                };
+                Some(Arm64Instruction::new(
+                    mnemonic,
+                    format!("{rd_name}, {rn_name}"),
+                ))
+            }
+            0b00001 if is_64 => {
+                const KEYS: [&str; 8] = [
+                    "pacia", "pacib", "pacda", "pacdb", "autia", "autib", "autda", "autdb",
+                ];
+                match opcode {
+                    0..=7 => Some(Arm64Instruction::new(
+                        KEYS[opcode as usize],
+                        format!("{rd_name}, {}", reg(rn, true, true)),
+                    )),
+                    8..=15 if rn == REG_31 => {
+                        let key = KEYS[(opcode - 8) as usize];
+                        let mnemonic = format!("{}z{}", &key[..4], &key[4..]);
+                        Some(Arm64Instruction::new(&mnemonic, rd_name))
+                    }
+                    16 if rn == REG_31 => Some(Arm64Instruction::new("xpaci", rd_name)),
+                    17 if rn == REG_31 => Some(Arm64Instruction::new("xpacd", rd_name)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+    }
+
+    // Data-processing (3 source)
+    if word & 0x1f00_0000 == 0x1b00_0000 {
+        let ra = bits(word, 10, 5);
+        let ra_name = reg(ra, is_64, false);
+        let is_sub = bits(word, 15, 1) == 1;
+        let (mnemonic, alias, wide) = match (bits(word, 21, 3), is_sub) {
+            (0b000, false) => ("madd", "mul", false),
+            (0b000, true) => ("msub", "mneg", false),
+            (0b001, false) if is_64 => ("smaddl", "smull", true),
+            (0b001, true) if is_64 => ("smsubl", "smnegl", true),
+            (0b101, false) if is_64 => ("umaddl", "umull", true),
+            (0b101, true) if is_64 => ("umsubl", "umnegl", true),
+            (0b010, false) if is_64 => {
+                return Some(Arm64Instruction::new(
+                    "smulh",
+                    format!("{rd_name}, {rn_name}, {rm_name}"),
+                ))
+            }
+            (0b110, false) if is_64 => {
+                return Some(Arm64Instruction::new(
+                    "umulh",
+                    format!("{rd_name}, {rn_name}, {rm_name}"),
+                ))
+            }
+            _ => return None,
+        };
+        // Long multiplications take 32-bit sources
+        let (rn_name, rm_name) = match wide {
+            true => (reg(rn, false, false), reg(rm, false, false)),
+            false => (rn_name, rm_name),
+        };
+        return match ra {
+            REG_31 => Some(Arm64Instruction::new(
+                alias,
+                format!("{rd_name}, {rn_name}, {rm_name}"),
+            )),
+            _ => Some(Arm64Instruction::new(
+                mnemonic,
+                format!("{rd_name}, {rn_name}, {rm_name}, {ra_name}"),
+            )),
+        };
+    }
+
+    None
+}
+
+/// Scalar floating-point instructions and moves between general and FP registers
+fn decode_fp(word: u32) -> Option<Arm64Instruction> {
+    if word & 0x5f20_0000 != 0x1e20_0000 {
+        return None;
+    }
+    let is_64 = bits(word, 31, 1) == 1;
+    let prefix = match bits(word, 22, 2) {
+        0b00 => "s",
+        0b01 => "d",
+        0b11 => "h",
+        _ => return None,
+    };
+    let rd = bits(word, 0, 5);
+    let rn = bits(word, 5, 5);
+    let rm = bits(word, 16, 5);
+
+    // Floating-point data-processing (2 source)
+    if bits(word, 10, 2) == 0b10 {
+        let mnemonic = match bits(word, 12, 4) {
+            0b0000 => "fmul",
+            0b0001 => "fdiv",
+            0b0010 => "fadd",
+            0b0011 => "fsub",
+            0b0100 => "fmax",
+            0b0101 => "fmin",
+            0b0110 => "fmaxnm",
+            0b0111 => "fminnm",
+            0b1000 => "fnmul",
+            _ => return None,
+        };
+        return Some(Arm64Instruction::new(
+            mnemonic,
+            format!("{prefix}{rd}, {prefix}{rn}, {prefix}{rm}"),
+        ));
+    }
+
+    // Floating-point compare
+    if bits(word, 10, 6) == 0b001000 && bits(word, 0, 3) == 0 {
+        let mnemonic = match bits(word, 4, 1) {
+            0 => "fcmp",
+            _ => "fcmpe",
+        };
+        let second = match bits(word, 3, 1) {
+            0 => format!("{prefix}{rm}"),
+            _ => "#0.0".to_string(),
+        };
+        return Some(Arm64Instruction::new(
+            mnemonic,
+            format!("{prefix}{rn}, {second}"),
+        ));
+    }
+
+    // Floating-point data-processing (1 source)
+    if bits(word, 10, 5) == 0b10000 && bits(word, 21, 1) == 1 {
+        let opcode = bits(word, 15, 6);
+        let (mnemonic, target) = match opcode {
+            0b100000 => ("fmov", prefix),
+            0b100001 => ("fabs", prefix),
+            0b100010 => ("fneg", prefix),
+            0b100011 => ("fsqrt", prefix),
+            0b100100 => ("fcvt", "s"),
+            0b100101 => ("fcvt", "d"),
+            0b100111 => ("fcvt", "h"),
+            _ => return None,
+        };
+        return Some(Arm64Instruction::new(
+            mnemonic,
+            format!("{target}{rd}, {prefix}{rn}"),
+        ));
+    }
+
+    // Conversion between floating-point and integer
+    if bits(word, 10, 6) == 0 {
+        let general_rd = reg(rd, is_64, false);
+        let general_rn = reg(rn, is_64, false);
+        let result = match (bits(word, 19, 2), bits(word, 16, 3)) {
+            (0b00, 0b010) => Arm64Instruction::new("scvtf", format!("{prefix}{rd}, {general_rn}")),
+            (0b00, 0b011) => Arm64Instruction::new("ucvtf", format!("{prefix}{rd}, {general_rn}")),
+            (0b00, 0b111) => Arm64Instruction::new("fmov", format!("{prefix}{rd}, {general_rn}")),
+            (0b00, 0b110) => Arm64Instruction::new("fmov", format!("{general_rd}, {prefix}{rn}")),
+            (0b11, 0b000) => Arm64Instruction::new("fcvtzs", format!("{general_rd}, {prefix}{rn}")),
+            (0b11, 0b001) => Arm64Instruction::new("fcvtzu", format!("{general_rd}, {prefix}{rn}")),
+            _ => return None,
+        };
+        return Some(result);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text(word: u32, address: u64) -> String {
+        let instruction = decode(word, address);
+        format!("{} {}", instruction.mnemonic, instruction.operands)
+            .trim_end()
+            .to_string()
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(text(0xa9bf7bfd, 0), "stp x29, x30, [sp, #-16]!");
+        assert_eq!(text(0x910003fd, 0), "mov x29, sp");
+        assert_eq!(text(0xa8c17bfd, 0), "ldp x29, x30, [sp], #16");
+        assert_eq!(text(0xd65f03c0, 0), "ret");
+        assert_eq!(text(0x7100081f, 0), "cmp w0, #2");
+        assert_eq!(text(0x386069 << 8 | 0x09, 0), "ldrb w9, [x8, x0]");
+        assert_eq!(text(0xd61f0100, 0), "br x8");
+        assert_eq!(text(0x52800000, 0), "mov w0, #0");
+        assert_eq!(text(0xd503237f, 0), "pacibsp");
+        assert_eq!(text(0xd65f0fff, 0), "retab");
+        assert_eq!(text(0xf9400108, 0), "ldr x8, [x8]");
+        assert_eq!(text(0xb2400fe0, 0), "orr x0, xzr, #0xf");
+        assert_eq!(text(0x1a9f17e0, 0), "cset w0, eq");
+        assert_eq!(text(0x9b027c20, 0), "mul x0, x1, x2");
+        assert_eq!(text(0xd53bd068, 0), "mrs x8, tpidrro_el0");
+        assert_eq!(text(0xffffffff, 0), ".long 0xffffffff");
+    }
+
+    #[test]
+    fn test_references() {
+        let bl = decode(0x94000003, 0x14);
+        assert_eq!(bl.branch_target, Some(0x20));
+
+        let b_hi = decode(0x540000a8, 0x24);
+        assert_eq!(b_hi.mnemonic, "b.hi");
+        assert_eq!(b_hi.branch_target, Some(0x38));
+
+        let adrp = decode(0xb0000000, 0x100003f88);
+        assert_eq!(
+            adrp.reference,
+            Some(Arm64Reference::Page {
+                rd: 0,
+                page: 0x100004000
+            })
+        );
+        let add = decode(0x91010000, 0x100003f8c);
+        assert_eq!(
+            add.reference,
+            Some(Arm64Reference::AddOffset {
+                rd: 0,
+                rn: 0,
+                offset: 0x40
+            })
+        );
+
+        let adr = decode(0x10000068, 0x28);
+        assert_eq!(adr.reference, Some(Arm64Reference::Address(0x34)));
+    }
+}
@@ -0,0 +1,426 @@
+//! Disassembly of code of arm64 and x86 objects, annotated like `otool -tV` does it:
+//! branch targets and referenced addresses (`adrp`/`add` pairs, literals, RIP-relative operands)
+//! are resolved to symbols, stubs, symbol pointers and C strings.
+//! Ranges of `LC_DATA_IN_CODE` are emitted as data instead of instructions.
+
+mod arm64;
+mod x86;
+
+use super::data_in_code::*;
+use super::filetype_constants::MH_OBJECT;
+use super::function_starts::FunctionStarts;
+use super::indirect_symbols::*;
+use super::reloc::RelocationResolver;
+use super::section_constants::*;
+use super::symbolicator::{SymbolAddress, Symbolicator};
+use super::MachObject;
+use crate::cpu_constants::*;
+use crate::result::{Error, Result};
+
+use arm64::Arm64Reference;
+use x86::X86Decoder;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::Range;
+
+/// Size of A64 instruction
+const ARM64_INSTRUCTION_SIZE: usize = 4;
+/// Maximum length of x86 instruction
+const X86_MAX_INSTRUCTION_SIZE: usize = 15;
+
+/// What the referenced address is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Annotation {
+    /// Symbol containing the address, with offset from its start
+    Symbol { name: String, offset: u64 },
+    /// Entry of `S_SYMBOL_STUBS` section
+    Stub(String),
+    /// Entry of `S_*_SYMBOL_POINTERS` section
+    Pointer(String),
+    /// Contents of `S_CSTRING_LITERALS` section
+    CString(String),
+    /// Target of relocation of the instruction, for `MH_OBJECT`
+    Relocation(String),
+}
+
+impl Display for Annotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Annotation::Symbol { name, offset: 0 } => write!(f, "{name}"),
+            Annotation::Symbol { name, offset } => write!(f, "{name}+{offset:#x}"),
+            Annotation::Stub(name) => write!(f, "symbol stub for: {name}"),
+            Annotation::Pointer(name) => write!(f, "literal pool symbol address: {name}"),
+            Annotation::CString(string) => {
+                write!(f, "literal pool for: \"{}\"", string.escape_debug())
+            }
+            Annotation::Relocation(target) => write!(f, "{target}"),
+        }
+    }
+}
+
+/// Decoded instruction or item of data in code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisasmInstruction {
+    pub address: u64,
+    pub bytes: Vec<u8>,
+    pub mnemonic: String,
+    pub operands: String,
+    /// Branch target or referenced address
+    pub target: Option<u64>,
+    pub annotation: Option<Annotation>,
+    /// One of `DICE_KIND_*`, if the bytes are data in code
+    pub data_kind: Option<u16>,
+}
+
+enum Architecture {
+    Arm64,
+    /// Bitness of mode
+    X86(u32),
+}
+
+pub struct Disassembler {
+    object: MachObject,
+    architecture: Architecture,
+    /// Sorted by address
+    symbols: Vec<SymbolAddress>,
+    function_starts: Vec<u64>,
+    /// Address ranges and types of sections
+    sections: Vec<(Range<u64>, u32)>,
+    indirect_symbols: HashMap<u64, IndirectSymbol>,
+    /// Targets of relocations by addresses of relocated instructions
+    relocations: HashMap<u64, String>,
+    data_in_code: DataInCode,
+}
+
+impl Disassembler {
+    pub fn build(object: &MachObject) -> Result<Self> {
+        let cputype = object.header().cputype;
+        let architecture = match cputype {
+            CPU_TYPE_ARM64 | CPU_TYPE_ARM64_32 => Architecture::Arm64,
+            CPU_TYPE_X86_64 => Architecture::X86(64),
+            CPU_TYPE_X86 => Architecture::X86(32),
+            _ => {
+                return Err(Error::Text(format!(
+                    "Disassembly is not supported for CPU type {cputype}"
+                )))
+            }
+        };
+
+        let symbolicator = Symbolicator::build(object, None)?;
+        let function_starts = match FunctionStarts::parse(object)? {
+            Some(starts) => starts.addresses,
+            None => Vec::new(),
+        };
+        let sections = object
+            .sections()
+            .iter()
+            .map(|sect| (sect.addr.0..sect.addr.0 + sect.size.0, sect.section_type()))
+            .collect();
+        let indirect_symbols = indirect_symbols(object)?
+            .into_iter()
+            .map(|symbol| (symbol.address, symbol))
+            .collect();
+
+        let mut relocations = HashMap::new();
+        if object.header().filetype.0 == MH_OBJECT {
+            let resolver = RelocationResolver::build(object)?;
+            for section in object.sections() {
+                for reloc in resolver.resolve(&section)? {
+                    relocations
+                        .insert(section.addr.0 + reloc.address as u64, reloc.target_string());
+                }
+            }
+        }
+
+        Ok(Disassembler {
+            object: object.clone(),
+            architecture,
+            symbols: symbolicator.symbols().to_vec(),
+            function_starts,
+            sections,
+            indirect_symbols,
+            relocations,
+            data_in_code: DataInCode::parse(object)?.unwrap_or_default(),
+        })
+    }
+
+    /// Name of symbol starting at `address`
+    pub fn label(&self, address: u64) -> Option<&str> {
+        let index = self.symbols.partition_point(|s| s.address < address);
+        self.symbols
+            .get(index)
+            .filter(|symbol| symbol.address == address)
+            .map(|symbol| symbol.name.as_str())
+    }
+
+    /// Address range of symbol `name` (with or without leading underscore).
+    /// The range ends at the next symbol, function start or end of section.
+    pub fn symbol_range(&self, name: &str) -> Option<Range<u64>> {
+        let underscored = format!("_{name}");
+        let start = self
+            .symbols
+            .iter()
+            .find(|s| s.name == name)
+            .or_else(|| self.symbols.iter().find(|s| s.name == underscored))?
+            .address;
+        let section_end = self.section_range(start)?.end;
+
+        let next_symbol = self
+            .symbols
+            .iter()
+            .map(|s| s.address)
+            .find(|address| *address > start);
+        let next_start = self
+            .function_starts
+            .iter()
+            .copied()
+            .find(|address| *address > start);
+        let end = [next_symbol, next_start, Some(section_end)]
+            .into_iter()
+            .flatten()
+            .min()?;
+        Some(start..end)
+    }
+
+    /// Address range of section containing `address`
+    pub fn section_range(&self, address: u64) -> Option<Range<u64>> {
+        self.sections
+            .iter()
+            .find(|(range, _)| range.contains(&address))
+            .map(|(range, _)| range.clone())
+    }
+
+    /// Decodes instructions in `range`, which should be in the same segment
+    pub fn disassemble(&self, range: Range<u64>) -> Result<Vec<DisasmInstruction>> {
+        let size = range.end.saturating_sub(range.start) as usize;
+        let bytes = self.object.read_bytes_at_vmaddr(range.start, size)?;
+
+        let mut x86_decoder = match self.architecture {
+            Architecture::X86(bitness) => Some(X86Decoder::new(bitness)),
+            Architecture::Arm64 => None,
+        };
+        // Pages of `adrp` by register numbers
+        let mut pages: [Option<u64>; 32] = [None; 32];
+
+        let mut result = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let address = range.start + offset as u64;
+            if self.label(address).is_some() {
+                pages = [None; 32];
+            }
+
+            if let Some(entry) = self.data_in_code.entry_for_address(address) {
+                let remaining = (entry.address + entry.length as u64 - address) as usize;
+                let length = entry.item_size().min(remaining).min(bytes.len() - offset);
+                result.push(data_item(address, &bytes[offset..offset + length], entry));
+                offset += length;
+                continue;
+            }
+
+            let mut instruction = match &mut x86_decoder {
+                Some(decoder) => {
+                    let end = bytes.len().min(offset + X86_MAX_INSTRUCTION_SIZE);
+                    let decoded = decoder.decode(&bytes[offset..end], address);
+                    DisasmInstruction {
+                        address,
+                        bytes: bytes[offset..offset + decoded.length].to_vec(),
+                        mnemonic: decoded.mnemonic,
+                        operands: decoded.operands,
+                        target: decoded.target,
+                        annotation: None,
+                        data_kind: None,
+                    }
+                }
+                None if bytes.len() - offset < ARM64_INSTRUCTION_SIZE => DisasmInstruction {
+                    address,
+                    bytes: bytes[offset..offset + 1].to_vec(),
+                    mnemonic: ".byte".to_string(),
+                    operands: format!("{:#04x}", bytes[offset]),
+                    target: None,
+                    annotation: None,
+                    data_kind: None,
+                },
+                None => {
+                    let chunk = &bytes[offset..offset + ARM64_INSTRUCTION_SIZE];
+                    let word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                    let decoded = arm64::decode(word, address);
+                    DisasmInstruction {
+                        address,
+                        bytes: chunk.to_vec(),
+                        mnemonic: decoded.mnemonic,
+                        operands: decoded.operands,
+                        target: decoded
+                            .branch_target
+                            .or_else(|| follow_reference(decoded.reference, &mut pages)),
+                        annotation: None,
+                        data_kind: None,
+                    }
+                }
+            };
+
+            // Code of `MH_OBJECT` isn't fixed up yet, so relocations tell more than targets
+            instruction.annotation = match self.relocations.get(&address) {
+                Some(target) => Some(Annotation::Relocation(target.clone())),
+                None => match instruction.target {
+                    Some(target) => self.annotation(target),
+                    None => None,
+                },
+            };
+            offset += instruction.bytes.len();
+            result.push(instruction);
+        }
+
+        Ok(result)
+    }
+
+    /// Resolves referenced address
+    pub fn annotation(&self, address: u64) -> Option<Annotation> {
+        if let Some(symbol) = self.indirect_symbols.get(&address) {
+            let name = symbol.name.clone()?;
+            return match symbol.is_stub {
+                true => Some(Annotation::Stub(name)),
+                false => Some(Annotation::Pointer(name)),
+            };
+        }
+
+        let (section, section_type) = self
+            .sections
+            .iter()
+            .find(|(range, _)| range.contains(&address))?;
+        if *section_type == S_CSTRING_LITERALS {
+            return self
+                .object
+                .read_cstring_at_vmaddr(address)
+                .ok()
+                .map(Annotation::CString);
+        }
+
+        let index = self.symbols.partition_point(|s| s.address <= address);
+        let symbol = self.symbols[..index].last()?;
+        match section.contains(&symbol.address) {
+            true => Some(Annotation::Symbol {
+                name: symbol.name.clone(),
+                offset: address - symbol.address,
+            }),
+            false => None,
+        }
+    }
+}
+
+/// Follows `adrp` to `add` or load, returns referenced address
+fn follow_reference(
+    reference: Option<Arm64Reference>,
+    pages: &mut [Option<u64>; 32],
+) -> Option<u64> {
+    match reference? {
+        Arm64Reference::Address(address) => Some(address),
+        Arm64Reference::Page { rd, page } => {
+            pages[rd as usize] = Some(page);
+            None
+        }
+        Arm64Reference::AddOffset { rd, rn, offset } => {
+            let target = pages[rn as usize].map(|page| page + offset);
+            pages[rd as usize] = None;
+            target
+        }
+        Arm64Reference::LoadStoreOffset { rt, rn, offset } => {
+            let target = pages[rn as usize].map(|page| page + offset);
+            if let Some(rt) = rt {
+                pages[rt as usize] = None;
+            }
+            target
+        }
+    }
+}
+
+fn data_item(address: u64, bytes: &[u8], entry: &DataInCodeEntry) -> DisasmInstruction {
+    let value = bytes
+        .iter()
+        .rev()
+        .fold(0u64, |value, byte| value << 8 | *byte as u64);
+    let mnemonic = match bytes.len() {
+        1 => ".byte",
+        2 => ".short",
+        _ => ".long",
+    };
+    DisasmInstruction {
+        address,
+        bytes: bytes.to_vec(),
+        mnemonic: mnemonic.to_string(),
+        operands: format!("{value:#x}"),
+        target: None,
+        annotation: None,
+        data_kind: Some(entry.kind),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse;
+
+    fn parse_object(path: &str, arch: &str) -> MachObject {
+        parse(path).mach_object_with_arch(arch).unwrap()
+    }
+
+    fn find(instructions: &[DisasmInstruction], address: u64) -> &DisasmInstruction {
+        instructions.iter().find(|i| i.address == address).unwrap()
+    }
+
+    #[test]
+    fn test_object() {
+        let object = parse_object("testable/disasm_arm64.o", "arm64");
+        let disassembler = Disassembler::build(&object).unwrap();
+        let range = disassembler.symbol_range("main").unwrap();
+        assert_eq!(range, 0..0x20);
+
+        let range = disassembler.section_range(0).unwrap();
+        let instructions = disassembler.disassemble(range).unwrap();
+        assert_eq!(disassembler.label(0x20), Some("_helper"));
+
+        let puts = find(&instructions, 0x10);
+        assert_eq!(puts.mnemonic, "bl");
+        assert_eq!(
+            puts.annotation,
+            Some(Annotation::Relocation("_puts".to_string()))
+        );
+
+        let jump = find(&instructions, 0x24);
+        assert_eq!(jump.target, Some(0x38));
+        assert_eq!(
+            jump.annotation,
+            Some(Annotation::Symbol {
+                name: "_helper".to_string(),
+                offset: 0x18
+            })
+        );
+
+        let table: Vec<&DisasmInstruction> = instructions
+            .iter()
+            .filter(|i| i.data_kind.is_some())
+            .collect();
+        assert_eq!(table.len(), 4);
+        assert_eq!(table[0].address, 0x34);
+        assert_eq!(table[3].operands, "0x3");
+        assert_eq!(find(&instructions, 0x38).operands, "w0, #0");
+    }
+
+    #[test]
+    fn test_stubs() {
+        let object = parse_object("testable/cat", "x86_64");
+        let disassembler = Disassembler::build(&object).unwrap();
+        let instructions = disassembler.disassemble(0x10000337e..0x100003383).unwrap();
+        assert_eq!(
+            instructions[0].annotation,
+            Some(Annotation::Stub("_setlocale".to_string()))
+        );
+
+        let instructions = disassembler.disassemble(0x100003383..0x10000338a).unwrap();
+        assert_eq!(
+            instructions[0].annotation,
+            Some(Annotation::CString("belnstuv".to_string()))
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Decoding of i386 and x86_64 instructions with `iced-x86`, in AT&T syntax as `otool` prints it
+
+use iced_x86::{Decoder, DecoderOptions, Formatter, GasFormatter, Instruction, OpKind};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct X86Instruction {
+    pub(super) length: usize,
+    pub(super) mnemonic: String,
+    pub(super) operands: String,
+    /// Target of direct branch or address of RIP-relative memory operand
+    pub(super) target: Option<u64>,
+}
+
+pub(super) struct X86Decoder {
+    bitness: u32,
+    formatter: GasFormatter,
+}
+
+impl X86Decoder {
+    pub(super) fn new(bitness: u32) -> Self {
+        let mut formatter = GasFormatter::new();
+        let options = formatter.options_mut();
+        options.set_gas_show_mnemonic_size_suffix(true);
+        options.set_space_after_operand_separator(true);
+        options.set_uppercase_hex(false);
+        options.set_branch_leading_zeros(false);
+        options.set_signed_immediate_operands(true);
+        options.set_rip_relative_addresses(true);
+        X86Decoder { bitness, formatter }
+    }
+
+    /// Decodes single instruction at start of `bytes`, located at `address`
+    pub(super) fn decode(&mut self, bytes: &[u8], address: u64) -> X86Instruction {
+        let mut decoder = Decoder::with_ip(self.bitness, bytes, address, DecoderOptions::NONE);
+        let instruction = decoder.decode();
+        if instruction.is_invalid() {
+            return X86Instruction {
+                length: 1,
+                mnemonic: ".byte".to_string(),
+                operands: format!("{:#04x}", bytes.first().copied().unwrap_or_default()),
+                target: None,
+            };
+        }
+
+        let mut mnemonic = String::new();
+        self.formatter.format_mnemonic(&instruction, &mut mnemonic);
+        let mut operands = String::new();
+        self.formatter
+            .format_all_operands(&instruction, &mut operands);
+
+        X86Instruction {
+            length: instruction.len(),
+            mnemonic,
+            operands,
+            target: target(&instruction),
+        }
+    }
+}
+
+fn target(instruction: &Instruction) -> Option<u64> {
+    let is_branch = (0..instruction.op_count()).any(|index| {
+        matches!(
+            instruction.op_kind(index),
+            OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64
+        )
+    });
+    if is_branch {
+        return Some(instruction.near_branch_target());
+    }
+    match instruction.is_ip_rel_memory_operand() {
+        true => Some(instruction.ip_rel_memory_address()),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let mut decoder = X86Decoder::new(64);
+
+        let call = decoder.decode(&[0xe8, 0xd1, 0x09, 0x00, 0x00], 0x10000337e);
+        assert_eq!(call.length, 5);
+        assert_eq!(call.mnemonic, "callq");
+        assert_eq!(call.target, Some(0x100003d54));
+
+        let lea = decoder.decode(&[0x48, 0x8d, 0x35, 0xb1, 0x0b, 0x00, 0x00], 0x100003372);
+        assert_eq!(lea.mnemonic, "leaq");
+        assert_eq!(lea.target, Some(0x100003f2a));
+
+        let invalid = decoder.decode(&[0x06], 0);
+        assert_eq!(invalid.mnemonic, ".byte");
+        assert_eq!(invalid.length, 1);
+    }
+}
@@ -0,0 +1,191 @@
+//! Pairing of binaries with their `.dSYM` bundles by UUIDs of `LC_UUID`.
+//! DWARF files of bundle are in `Foo.dSYM/Contents/Resources/DWARF/`, one (possibly fat) file per binary.
+
+use super::MachObject;
+use crate::result::Result;
+use crate::Parser;
+
+use std::path::{Path, PathBuf};
+
+pub const DSYM_EXTENSION: &str = "dSYM";
+/// Location of DWARF files inside of `.dSYM` bundle
+pub const DSYM_DWARF_DIR: &str = "Contents/Resources/DWARF";
+
+/// Single architecture of DWARF file
+#[derive(Debug, Clone)]
+pub struct DsymSlice {
+    pub path: PathBuf,
+    pub cputype: u32,
+    /// Without capability bits
+    pub cpusubtype: u32,
+    pub arch: Option<String>,
+    pub uuid: Option<[u8; 16]>,
+}
+
+impl DsymSlice {
+    fn new(path: &Path, object: &MachObject) -> Self {
+        DsymSlice {
+            path: path.to_path_buf(),
+            cputype: object.header().cputype.0,
+            cpusubtype: object.header().cpusubtype.masked().0,
+            arch: object.header().printable_cpu().map(|cpu| cpu.to_string()),
+            uuid: object.uuid(),
+        }
+    }
+
+    /// File name of DWARF file, which is the name of the binary
+    pub fn name(&self) -> Option<String> {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DsymMatchStatus {
+    /// DWARF file with the same UUID
+    Matched(PathBuf),
+    /// No DWARF file with the same UUID, but there is one for the binary with the same name and arch
+    Mismatched(PathBuf, Option<[u8; 16]>),
+    Missing,
+    /// Slice of the binary has no `LC_UUID`
+    NoUuid,
+}
+
+/// Match of single slice of the binary
+#[derive(Debug, Clone)]
+pub struct DsymMatch {
+    pub arch: Option<String>,
+    pub uuid: Option<[u8; 16]>,
+    pub status: DsymMatchStatus,
+}
+
+impl DsymMatch {
+    pub fn is_matched(&self) -> bool {
+        matches!(self.status, DsymMatchStatus::Matched(_))
+    }
+}
+
+/// DWARF files of `.dSYM` bundle at `bundle`
+pub fn dwarf_files(bundle: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(bundle.join(DSYM_DWARF_DIR))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Slices of all DWARF files of `.dSYM` bundles in `dirs` and their subdirectories.
+/// `dirs` may also contain bundles and DWARF files themselves. Files that aren't Mach-O are skipped.
+pub fn find_dsym_slices(dirs: &[PathBuf]) -> Result<Vec<DsymSlice>> {
+    let mut files = Vec::new();
+    for dir in dirs {
+        match dir.is_dir() {
+            true => collect_dwarf_files(dir, &mut files)?,
+            false => files.push(dir.clone()),
+        }
+    }
+
+    let mut result = Vec::new();
+    for file in files {
+        let object = match Parser::build(&file).and_then(|parser| parser.parse()) {
+            Ok(object) => object,
+            Err(_) => continue,
+        };
+        result.extend(
+            object
+                .mach_objects()
+                .iter()
+                .map(|slice| DsymSlice::new(&file, slice)),
+        );
+    }
+    Ok(result)
+}
+
+fn collect_dwarf_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if is_dsym_bundle(dir) {
+        files.append(&mut dwarf_files(dir)?);
+        return Ok(());
+    }
+
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        // Symlinks are skipped to avoid cycles
+        .filter(|path| path.is_dir() && !path.is_symlink())
+        .collect();
+    entries.sort();
+    for entry in entries {
+        collect_dwarf_files(&entry, files)?;
+    }
+    Ok(())
+}
+
+pub fn is_dsym_bundle(path: &Path) -> bool {
+    path.is_dir() && path.extension().is_some_and(|ext| ext == DSYM_EXTENSION)
+}
+
+/// Matches every slice of the binary with `slices` by UUID.
+/// `name` is the file name of the binary, used to report DWARF files with other UUIDs as mismatched.
+pub fn match_dsyms(objects: &[MachObject], name: &str, slices: &[DsymSlice]) -> Vec<DsymMatch> {
+    objects
+        .iter()
+        .map(|object| {
+            let slice = DsymSlice::new(Path::new(name), object);
+            let status = match slice.uuid {
+                Some(uuid) => match slices.iter().find(|s| s.uuid == Some(uuid)) {
+                    Some(matched) => DsymMatchStatus::Matched(matched.path.clone()),
+                    None => match slices.iter().find(|s| {
+                        s.name().as_deref() == Some(name)
+                            && s.cputype == slice.cputype
+                            && s.cpusubtype == slice.cpusubtype
+                    }) {
+                        Some(other) => DsymMatchStatus::Mismatched(other.path.clone(), other.uuid),
+                        None => DsymMatchStatus::Missing,
+                    },
+                },
+                None => DsymMatchStatus::NoUuid,
+            };
+            DsymMatch {
+                arch: slice.arch,
+                uuid: slice.uuid,
+                status,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse;
+
+    #[test]
+    fn test_match_dsyms() {
+        let object = parse("testable/cat");
+        let slices = object.mach_objects();
+        let dwarf_slice = |name: &str, index: usize, uuid: Option<[u8; 16]>| {
+            let path = PathBuf::from(format!("{name}.dSYM/{DSYM_DWARF_DIR}/{name}"));
+            let mut slice = DsymSlice::new(&path, &slices[index]);
+            slice.uuid = uuid;
+            slice
+        };
+        let uuid = slices[0].uuid().unwrap();
+        let other_uuid = Some([0xaa; 16]);
+
+        let matched = dwarf_slice("cat", 0, Some(uuid));
+        let other = dwarf_slice("dog", 1, other_uuid);
+        let matches = match_dsyms(&slices, "cat", &[other.clone(), matched.clone()]);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].status, DsymMatchStatus::Matched(matched.path));
+        assert_eq!(matches[1].status, DsymMatchStatus::Missing);
+
+        let mismatched = dwarf_slice("cat", 1, other_uuid);
+        let matches = match_dsyms(&slices, "cat", std::slice::from_ref(&mismatched));
+        assert_eq!(matches[0].status, DsymMatchStatus::Missing);
+        assert_eq!(
+            matches[1].status,
+            DsymMatchStatus::Mismatched(mismatched.path, other_uuid)
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Apple accelerator tables (`__apple_names`, `__apple_types`, `__apple_namespac`, `__apple_objc`).
+//! Hash tables of names pointing to DIEs, described in `llvm/CodeGen/AccelTable.h`.
+
+use crate::result::{Error, Result};
+
+use scroll::{Endian, Pread};
+
+/// `'HASH'`
+pub const APPLE_ACCEL_MAGIC: u32 = 0x48415348;
+/// Hash function of names, the only one supported by `dsymutil`
+pub const APPLE_HASH_DJB: u16 = 0;
+
+/// Atom types
+pub const DW_ATOM_NULL: u16 = 0;
+pub const DW_ATOM_DIE_OFFSET: u16 = 1;
+pub const DW_ATOM_CU_OFFSET: u16 = 2;
+pub const DW_ATOM_DIE_TAG: u16 = 3;
+pub const DW_ATOM_TYPE_FLAGS: u16 = 5;
+pub const DW_ATOM_QUAL_NAME_HASH: u16 = 6;
+
+/// Forms of atoms
+const DW_FORM_DATA1: u16 = 0x0b;
+const DW_FORM_DATA2: u16 = 0x05;
+const DW_FORM_DATA4: u16 = 0x06;
+const DW_FORM_DATA8: u16 = 0x07;
+const DW_FORM_REF1: u16 = 0x11;
+const DW_FORM_REF2: u16 = 0x12;
+const DW_FORM_REF4: u16 = 0x13;
+const DW_FORM_REF8: u16 = 0x14;
+const DW_FORM_FLAG: u16 = 0x0c;
+
+const HEADER_SIZE: usize = 20;
+/// Sanity limit for count of DIEs of single name
+const MAX_DIE_COUNT: u32 = 0x10_0000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppleAccelAtom {
+    pub atom_type: u16,
+    pub form: u16,
+}
+
+/// Name and DIEs with that name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppleAccelEntry {
+    pub name: String,
+    pub hash: u32,
+    /// Offsets of DIEs in `__debug_info`
+    pub die_offsets: Vec<u64>,
+    /// `DW_TAG_*` of DIEs, if the table has `DW_ATOM_die_tag`
+    pub die_tags: Vec<u16>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AppleAccelTable {
+    pub version: u16,
+    pub hash_function: u16,
+    pub bucket_count: u32,
+    pub die_offset_base: u32,
+    pub atoms: Vec<AppleAccelAtom>,
+    pub entries: Vec<AppleAccelEntry>,
+}
+
+impl AppleAccelTable {
+    /// Parses table `data`. Names are read from `debug_str`.
+    pub fn parse(data: &[u8], debug_str: &[u8], endian: Endian) -> Result<Self> {
+        let magic: u32 = data.pread_with(0, endian)?;
+        if magic != APPLE_ACCEL_MAGIC {
+            return Err(Error::BadMagic(magic));
+        }
+
+        let mut table = AppleAccelTable {
+            version: data.pread_with(4, endian)?,
+            hash_function: data.pread_with(6, endian)?,
+            bucket_count: data.pread_with(8, endian)?,
+            ..Default::default()
+        };
+        let hashes_count: u32 = data.pread_with(12, endian)?;
+        let header_data_len: u32 = data.pread_with(16, endian)?;
+
+        table.die_offset_base = data.pread_with(HEADER_SIZE, endian)?;
+        let atom_count: u32 = data.pread_with(HEADER_SIZE + 4, endian)?;
+        for index in 0..atom_count as usize {
+            let offset = HEADER_SIZE + 8 + index * 4;
+            table.atoms.push(AppleAccelAtom {
+                atom_type: data.pread_with(offset, endian)?,
+                form: data.pread_with(offset + 2, endian)?,
+            });
+        }
+
+        let buckets = HEADER_SIZE + header_data_len as usize;
+        let hashes = buckets + table.bucket_count as usize * 4;
+        let offsets = hashes + hashes_count as usize * 4;
+
+        for index in 0..hashes_count as usize {
+            let hash: u32 = data.pread_with(hashes + index * 4, endian)?;
+            let offset: u32 = data.pread_with(offsets + index * 4, endian)?;
+            table.parse_hash_data(data, offset as usize, hash, debug_str, endian)?;
+        }
+
+        Ok(table)
+    }
+
+    /// Data of single hash: names with the hash, each of them with list of DIEs, terminated with zero
+    fn parse_hash_data(
+        &mut self,
+        data: &[u8],
+        mut offset: usize,
+        hash: u32,
+        debug_str: &[u8],
+        endian: Endian,
+    ) -> Result<()> {
+        loop {
+            let str_offset: u32 = data.pread_with(offset, endian)?;
+            if str_offset == 0 {
+                return Ok(());
+            }
+            let count: u32 = data.pread_with(offset + 4, endian)?;
+            if count > MAX_DIE_COUNT {
+                return Err(Error::Text(format!(
+                    "Too many DIEs ({count}) in accelerator table at {offset:#x}"
+                )));
+            }
+            offset += 8;
+
+            let mut entry = AppleAccelEntry {
+                name: read_cstring(debug_str, str_offset as usize),
+                hash,
+                die_offsets: Vec::with_capacity(count as usize),
+                die_tags: Vec::new(),
+            };
+            for _ in 0..count {
+                for atom in &self.atoms {
+                    let (value, size) = read_atom(data, offset, atom.form, endian)?;
+                    offset += size;
+                    match atom.atom_type {
+                        DW_ATOM_DIE_OFFSET => {
+                            entry.die_offsets.push(value + self.die_offset_base as u64)
+                        }
+                        DW_ATOM_DIE_TAG => entry.die_tags.push(value as u16),
+                        _ => (),
+                    }
+                }
+            }
+            self.entries.push(entry);
+        }
+    }
+
+    /// Looks up entry of name by its hash
+    pub fn find(&self, name: &str) -> Option<&AppleAccelEntry> {
+        let hash = djb_hash(name);
+        self.entries
+            .iter()
+            .find(|entry| entry.hash == hash && entry.name == name)
+    }
+}
+
+/// Hash function of names, `APPLE_HASH_DJB`
+pub fn djb_hash(name: &str) -> u32 {
+    name.bytes().fold(5381u32, |hash, b| {
+        hash.wrapping_mul(33).wrapping_add(b as u32)
+    })
+}
+
+/// Returns value and size of atom
+fn read_atom(data: &[u8], offset: usize, form: u16, endian: Endian) -> Result<(u64, usize)> {
+    match form {
+        DW_FORM_DATA1 | DW_FORM_REF1 | DW_FORM_FLAG => {
+            Ok((data.pread_with::<u8>(offset, endian)? as u64, 1))
+        }
+        DW_FORM_DATA2 | DW_FORM_REF2 => Ok((data.pread_with::<u16>(offset, endian)? as u64, 2)),
+        DW_FORM_DATA4 | DW_FORM_REF4 => Ok((data.pread_with::<u32>(offset, endian)? as u64, 4)),
+        DW_FORM_DATA8 | DW_FORM_REF8 => Ok((data.pread_with::<u64>(offset, endian)?, 8)),
+        other => Err(Error::Text(format!(
+            "Unsupported form {other:#x} of accelerator table atom"
+        ))),
+    }
+}
+
+fn read_cstring(data: &[u8], offset: usize) -> String {
+    let bytes = data.get(offset..).unwrap_or_default();
+    let bytes = match bytes.iter().position(|b| *b == 0) {
+        Some(end) => &bytes[..end],
+        None => bytes,
+    };
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_djb_hash() {
+        assert_eq!(djb_hash(""), 5381);
+        assert_eq!(djb_hash("main"), 0x7c9a7f6a);
+    }
+}
@@ -0,0 +1,541 @@
+//! DWARF debug info stored in `__DWARF,__debug_*` sections of object files and dSYM companions.
+//! Parsing of units, DIEs and line programs is done by `gimli` over contents of [super::Section]s.
+
+pub mod apple;
+
+use super::MachObject;
+use crate::result::{Error, Result};
+use apple::AppleAccelTable;
+
+use gimli::{AttributeValue, EndianRcSlice, RunTimeEndian, SectionId};
+use gimli::{Reader as _, Section as _};
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+pub const DWARF_SEGNAME: &str = "__DWARF";
+pub const APPLE_NAMES_SECTNAME: &str = "__apple_names";
+pub const APPLE_TYPES_SECTNAME: &str = "__apple_types";
+pub const APPLE_NAMESPACES_SECTNAME: &str = "__apple_namespac";
+pub const APPLE_OBJC_SECTNAME: &str = "__apple_objc";
+
+/// Mach-O section names are limited to 16 characters
+const SECTNAME_MAX_LEN: usize = 16;
+/// Limit of `DW_AT_abstract_origin` chain
+const MAX_ORIGIN_DEPTH: usize = 16;
+
+type Reader = EndianRcSlice<RunTimeEndian>;
+type Unit = gimli::Unit<Reader>;
+type Entry<'a, 'u> = gimli::DebuggingInformationEntry<'a, 'u, Reader>;
+
+/// Compile unit header and attributes of its root DIE
+#[derive(Debug, Clone)]
+pub struct DwarfCompileUnit {
+    /// Offset of the unit in `__debug_info`
+    pub offset: u64,
+    pub version: u16,
+    pub name: Option<String>,
+    pub comp_dir: Option<String>,
+    pub producer: Option<String>,
+    /// `DW_LANG_*`
+    pub language: Option<String>,
+    pub ranges: Vec<Range<u64>>,
+}
+
+/// Debugging information entry
+#[derive(Debug, Clone)]
+pub struct DwarfEntry {
+    /// Offset of the entry in `__debug_info`
+    pub offset: u64,
+    /// Depth in the tree of the unit, root DIE is at 0
+    pub depth: isize,
+    /// `DW_TAG_*`
+    pub tag: String,
+    /// Names of attributes and their values
+    pub attributes: Vec<(String, String)>,
+}
+
+impl DwarfEntry {
+    pub fn name(&self) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(name, _)| name == "DW_AT_name")
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Row of line table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DwarfLineRow {
+    pub address: u64,
+    pub file: String,
+    pub line: u64,
+    pub column: u64,
+    pub end_sequence: bool,
+}
+
+/// Source location of address
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DwarfLocation {
+    pub address: u64,
+    /// Name of innermost function (or inlined function) containing address
+    pub function: Option<String>,
+    pub linkage_name: Option<String>,
+    /// Start address of the function
+    pub function_address: Option<u64>,
+    pub file: Option<String>,
+    pub line: Option<u64>,
+    pub column: Option<u64>,
+    /// Name of compile unit
+    pub unit: Option<String>,
+}
+
+/// DWARF of single [MachObject]
+pub struct DwarfInfo {
+    dwarf: gimli::Dwarf<Reader>,
+    /// Contents of `__apple_*` sections by section name
+    apple_tables: HashMap<String, Rc<[u8]>>,
+    debug_str: Rc<[u8]>,
+    endian: scroll::Endian,
+}
+
+impl DwarfInfo {
+    /// Loads `__debug_*` and `__apple_*` sections of `object`
+    pub fn parse(object: &MachObject) -> Result<Self> {
+        let endian = object.header().magic.endian();
+        let gimli_endian = match endian {
+            scroll::Endian::Little => RunTimeEndian::Little,
+            scroll::Endian::Big => RunTimeEndian::Big,
+        };
+
+        let mut contents: HashMap<String, Rc<[u8]>> = HashMap::new();
+        for section in object.sections() {
+            let sectname = section.sectname.to_string();
+            if section.segname.to_string() != DWARF_SEGNAME || contents.contains_key(&sectname) {
+                continue;
+            }
+            contents.insert(sectname, section.read_data()?.into());
+        }
+
+        let empty: Rc<[u8]> = Rc::new([]);
+        let section_data = |id: SectionId| -> Rc<[u8]> {
+            contents
+                .get(&macho_sectname(id))
+                .cloned()
+                .unwrap_or(empty.clone())
+        };
+        let dwarf = gimli::Dwarf::load(|id| -> std::result::Result<Reader, gimli::Error> {
+            Ok(EndianRcSlice::new(section_data(id), gimli_endian))
+        })?;
+        let debug_str = section_data(SectionId::DebugStr);
+
+        let apple_tables = contents
+            .into_iter()
+            .filter(|(name, _)| name.starts_with("__apple_"))
+            .collect();
+
+        Ok(DwarfInfo {
+            dwarf,
+            apple_tables,
+            debug_str,
+            endian,
+        })
+    }
+
+    /// `true` if there is no `__debug_info`
+    pub fn is_empty(&self) -> bool {
+        self.dwarf.debug_info.reader().is_empty()
+    }
+
+    pub fn compile_units(&self) -> Result<Vec<DwarfCompileUnit>> {
+        let mut result = Vec::new();
+        let mut headers = self.dwarf.units();
+        while let Some(header) = headers.next()? {
+            let unit = self.dwarf.unit(header)?;
+            result.push(self.compile_unit(&unit)?);
+        }
+        Ok(result)
+    }
+
+    fn compile_unit(&self, unit: &Unit) -> Result<DwarfCompileUnit> {
+        let mut entries = unit.entries();
+        let root = match entries.next_dfs()? {
+            Some((_, root)) => root,
+            None => return Err(Error::Text("Compile unit without DIEs".to_string())),
+        };
+
+        let language = match root.attr_value(gimli::DW_AT_language)? {
+            Some(AttributeValue::Language(language)) => Some(language.to_string()),
+            _ => None,
+        };
+
+        Ok(DwarfCompileUnit {
+            offset: unit_offset(unit),
+            version: unit.header.version(),
+            name: self.string_attr(unit, root, gimli::DW_AT_name)?,
+            comp_dir: self.string_attr(unit, root, gimli::DW_AT_comp_dir)?,
+            producer: self.string_attr(unit, root, gimli::DW_AT_producer)?,
+            language,
+            ranges: self.ranges(self.dwarf.unit_ranges(unit)?)?,
+        })
+    }
+
+    /// All DIEs of the unit at `unit_offset`, in depth-first order
+    pub fn entries(&self, unit_offset: u64) -> Result<Vec<DwarfEntry>> {
+        let unit = self.unit_at(unit_offset)?;
+        let mut result = Vec::new();
+        let mut depth = 0;
+        let mut entries = unit.entries();
+        while let Some((delta, entry)) = entries.next_dfs()? {
+            depth += delta;
+            let mut attributes = Vec::new();
+            let mut attrs = entry.attrs();
+            while let Some(attr) = attrs.next()? {
+                attributes.push((
+                    attr.name().to_string(),
+                    self.attr_value_string(&unit, attr.value()),
+                ));
+            }
+            result.push(DwarfEntry {
+                offset: entry
+                    .offset()
+                    .to_debug_info_offset(&unit.header)
+                    .map(|o| o.0 as u64)
+                    .unwrap_or_default(),
+                depth,
+                tag: entry.tag().to_string(),
+                attributes,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Rows of line program of the unit at `unit_offset`
+    pub fn line_rows(&self, unit_offset: u64) -> Result<Vec<DwarfLineRow>> {
+        let unit = self.unit_at(unit_offset)?;
+        self.unit_line_rows(&unit)
+    }
+
+    fn unit_line_rows(&self, unit: &Unit) -> Result<Vec<DwarfLineRow>> {
+        let program = match &unit.line_program {
+            Some(program) => program.clone(),
+            None => return Ok(Vec::new()),
+        };
+
+        let mut result = Vec::new();
+        let mut file_names: HashMap<u64, String> = HashMap::new();
+        let mut rows = program.rows();
+        while let Some((header, row)) = rows.next_row()? {
+            let file = match file_names.get(&row.file_index()) {
+                Some(file) => file.clone(),
+                None => {
+                    let file = match row.file(header) {
+                        Some(entry) => self.file_path(unit, header, entry)?,
+                        None => String::new(),
+                    };
+                    file_names.insert(row.file_index(), file.clone());
+                    file
+                }
+            };
+            result.push(DwarfLineRow {
+                address: row.address(),
+                file,
+                line: row.line().map(|l| l.get()).unwrap_or(0),
+                column: match row.column() {
+                    gimli::ColumnType::LeftEdge => 0,
+                    gimli::ColumnType::Column(column) => column.get(),
+                },
+                end_sequence: row.end_sequence(),
+            });
+        }
+        Ok(result)
+    }
+
+    /// Function and source line of `address`
+    pub fn lookup(&self, address: u64) -> Result<Option<DwarfLocation>> {
+        let mut headers = self.dwarf.units();
+        while let Some(header) = headers.next()? {
+            let unit = self.dwarf.unit(header)?;
+            let ranges = self.ranges(self.dwarf.unit_ranges(&unit)?)?;
+            if !ranges.iter().any(|range| range.contains(&address)) {
+                continue;
+            }
+
+            let mut location = DwarfLocation {
+                address,
+                ..Default::default()
+            };
+            self.lookup_function(&unit, address, &mut location)?;
+            self.lookup_line(&unit, address, &mut location)?;
+
+            let mut entries = unit.entries();
+            if let Some((_, root)) = entries.next_dfs()? {
+                location.unit = self.string_attr(&unit, root, gimli::DW_AT_name)?;
+            }
+            return Ok(Some(location));
+        }
+        Ok(None)
+    }
+
+    fn lookup_function(
+        &self,
+        unit: &Unit,
+        address: u64,
+        location: &mut DwarfLocation,
+    ) -> Result<()> {
+        let mut entries = unit.entries();
+        let mut depth = 0;
+        // Functions nested deeper are inlined into the ones found before
+        let mut found_depth = None;
+        while let Some((delta, entry)) = entries.next_dfs()? {
+            depth += delta;
+            if found_depth.is_some_and(|found| depth <= found) {
+                break;
+            }
+            if !matches!(
+                entry.tag(),
+                gimli::DW_TAG_subprogram | gimli::DW_TAG_inlined_subroutine
+            ) {
+                continue;
+            }
+
+            let ranges = self.ranges(self.dwarf.die_ranges(unit, entry)?)?;
+            if let Some(range) = ranges.iter().find(|range| range.contains(&address)) {
+                found_depth = Some(depth);
+                location.function = self.function_name(unit, entry, gimli::DW_AT_name)?;
+                location.linkage_name = self
+                    .function_name(unit, entry, gimli::DW_AT_linkage_name)?
+                    .or(self.function_name(unit, entry, gimli::DW_AT_MIPS_linkage_name)?);
+                location.function_address = Some(range.start);
+            }
+        }
+        Ok(())
+    }
+
+    /// Row with the greatest address not greater than `address` in the same sequence
+    fn lookup_line(&self, unit: &Unit, address: u64, location: &mut DwarfLocation) -> Result<()> {
+        let rows = self.unit_line_rows(unit)?;
+        let row = rows
+            .windows(2)
+            .filter(|pair| !pair[0].end_sequence)
+            .find(|pair| pair[0].address <= address && address < pair[1].address)
+            .map(|pair| &pair[0]);
+
+        if let Some(row) = row {
+            location.file = Some(row.file.clone());
+            location.line = Some(row.line);
+            location.column = Some(row.column);
+        }
+        Ok(())
+    }
+
+    /// Name of function, following `DW_AT_abstract_origin` and `DW_AT_specification`
+    fn function_name(
+        &self,
+        unit: &Unit,
+        entry: &Entry,
+        attr: gimli::DwAt,
+    ) -> Result<Option<String>> {
+        let mut current = entry.clone();
+        for _ in 0..MAX_ORIGIN_DEPTH {
+            if let Some(name) = self.string_attr(unit, &current, attr)? {
+                return Ok(Some(name));
+            }
+            let origin = match current.attr_value(gimli::DW_AT_abstract_origin)? {
+                Some(origin) => Some(origin),
+                None => current.attr_value(gimli::DW_AT_specification)?,
+            };
+            current = match origin {
+                Some(AttributeValue::UnitRef(offset)) => unit.entry(offset)?,
+                _ => return Ok(None),
+            };
+        }
+        Ok(None)
+    }
+
+    /// Parsed accelerator table of `__apple_*` section, like [APPLE_NAMES_SECTNAME]
+    pub fn apple_table(&self, sectname: &str) -> Result<Option<AppleAccelTable>> {
+        match self.apple_tables.get(sectname) {
+            Some(data) => AppleAccelTable::parse(data, &self.debug_str, self.endian).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn apple_names(&self) -> Result<Option<AppleAccelTable>> {
+        self.apple_table(APPLE_NAMES_SECTNAME)
+    }
+
+    pub fn apple_types(&self) -> Result<Option<AppleAccelTable>> {
+        self.apple_table(APPLE_TYPES_SECTNAME)
+    }
+
+    /// String of `__debug_str` at `offset`
+    pub fn debug_str(&self, offset: u64) -> Result<String> {
+        let string = self
+            .dwarf
+            .debug_str
+            .get_str(gimli::DebugStrOffset(offset as usize))?;
+        Ok(string.to_string_lossy()?.to_string())
+    }
+}
+
+impl DwarfInfo {
+    fn unit_at(&self, offset: u64) -> Result<Unit> {
+        let header = self
+            .dwarf
+            .debug_info
+            .header_from_offset(gimli::DebugInfoOffset(offset as usize))?;
+        Ok(self.dwarf.unit(header)?)
+    }
+
+    fn string_attr(&self, unit: &Unit, entry: &Entry, attr: gimli::DwAt) -> Result<Option<String>> {
+        match entry.attr_value(attr)? {
+            Some(value) => match self.dwarf.attr_string(unit, value) {
+                Ok(string) => Ok(Some(string.to_string_lossy()?.to_string())),
+                Err(_) => Ok(None),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn file_path(
+        &self,
+        unit: &Unit,
+        header: &gimli::LineProgramHeader<Reader>,
+        file: &gimli::FileEntry<Reader>,
+    ) -> Result<String> {
+        let name = self.dwarf.attr_string(unit, file.path_name())?;
+        let name = name.to_string_lossy()?.to_string();
+        if name.starts_with('/') {
+            return Ok(name);
+        }
+
+        match file.directory(header) {
+            Some(directory) => {
+                let directory = self.dwarf.attr_string(unit, directory)?;
+                let directory = directory.to_string_lossy()?;
+                match directory.is_empty() {
+                    true => Ok(name),
+                    false => Ok(format!("{}/{name}", directory.trim_end_matches('/'))),
+                }
+            }
+            None => Ok(name),
+        }
+    }
+
+    fn ranges(&self, mut iter: gimli::RangeIter<Reader>) -> Result<Vec<Range<u64>>> {
+        let mut result = Vec::new();
+        while let Some(range) = iter.next()? {
+            if range.begin < range.end {
+                result.push(range.begin..range.end);
+            }
+        }
+        Ok(result)
+    }
+
+    fn attr_value_string(&self, unit: &Unit, value: AttributeValue<Reader>) -> String {
+        if let Ok(string) = self.dwarf.attr_string(unit, value.clone()) {
+            if let Ok(string) = string.to_string_lossy() {
+                return string.to_string();
+            }
+        }
+
+        match value {
+            AttributeValue::Addr(address) => format!("{address:#x}"),
+            AttributeValue::Udata(value) => value.to_string(),
+            AttributeValue::Sdata(value) => value.to_string(),
+            AttributeValue::Data1(value) => value.to_string(),
+            AttributeValue::Data2(value) => value.to_string(),
+            AttributeValue::Data4(value) => value.to_string(),
+            AttributeValue::Data8(value) => value.to_string(),
+            AttributeValue::Flag(value) => value.to_string(),
+            AttributeValue::UnitRef(offset) => match offset.to_debug_info_offset(&unit.header) {
+                Some(offset) => format!("<{:#x}>", offset.0),
+                None => format!("<unit+{:#x}>", offset.0),
+            },
+            AttributeValue::DebugInfoRef(offset) => format!("<{:#x}>", offset.0),
+            AttributeValue::DebugLineRef(offset) => format!("{:#x}", offset.0),
+            AttributeValue::Language(language) => language.to_string(),
+            AttributeValue::Encoding(encoding) => encoding.to_string(),
+            AttributeValue::FileIndex(index) => index.to_string(),
+            AttributeValue::Exprloc(_) => "<expression>".to_string(),
+            other => format!("{other:?}"),
+        }
+    }
+}
+
+fn unit_offset(unit: &Unit) -> u64 {
+    unit.header
+        .offset()
+        .as_debug_info_offset()
+        .map(|o| o.0 as u64)
+        .unwrap_or_default()
+}
+
+/// `.debug_str_offsets` is `__debug_str_offs` in Mach-O
+fn macho_sectname(id: SectionId) -> String {
+    let name = format!("__{}", id.name().trim_start_matches('.'));
+    name.chars().take(SECTNAME_MAX_LEN).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse_single;
+
+    fn parse_fixture() -> DwarfInfo {
+        DwarfInfo::parse(&parse_single("testable/dwarf_arm64.o")).unwrap()
+    }
+
+    #[test]
+    fn test_compile_units() {
+        let dwarf = parse_fixture();
+        let units = dwarf.compile_units().unwrap();
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].name.as_deref(), Some("main.c"));
+        assert_eq!(units[0].comp_dir.as_deref(), Some("/tmp/src"));
+        assert_eq!(units[0].language.as_deref(), Some("DW_LANG_C99"));
+        assert_eq!(units[0].ranges, vec![0..0x24]);
+
+        let entries = dwarf.entries(units[0].offset).unwrap();
+        let functions: Vec<&str> = entries
+            .iter()
+            .filter(|e| e.tag == "DW_TAG_subprogram")
+            .filter_map(|e| e.name())
+            .collect();
+        assert_eq!(functions, vec!["add", "main"]);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let dwarf = parse_fixture();
+        let location = dwarf.lookup(0x4).unwrap().unwrap();
+        assert_eq!(location.function.as_deref(), Some("add"));
+        assert_eq!(location.function_address, Some(0));
+        assert_eq!(location.file.as_deref(), Some("/tmp/src/main.c"));
+        assert_eq!(location.line, Some(5));
+        assert_eq!(location.unit.as_deref(), Some("main.c"));
+
+        let location = dwarf.lookup(0x10).unwrap().unwrap();
+        assert_eq!(location.function.as_deref(), Some("main"));
+
+        assert_eq!(dwarf.lookup(0x1000).unwrap(), None);
+    }
+
+    #[test]
+    fn test_apple_tables() {
+        let dwarf = parse_fixture();
+        let names = dwarf.apple_names().unwrap().unwrap();
+        let entry = names.find("main").unwrap();
+        let entries = dwarf.entries(0).unwrap();
+        let die = entries
+            .iter()
+            .find(|e| e.offset == entry.die_offsets[0])
+            .unwrap();
+        assert_eq!(die.name(), Some("main"));
+
+        let types = dwarf.apple_types().unwrap().unwrap();
+        assert!(types.find("int").is_some());
+    }
+}
@@ -170,45 +170,3 @@ pub struct RelocationInfo {
     /// `PPC_RELOC_LO14_SECTDIFF` — Section difference form of `PPC_RELOC_LO14`.
     pub r_type: u32,
 }
-
-/// `scattered_relocation_info`
-/// Describes an item in the file—using a nonzero constant in its relocatable expression or 
-/// two addresses in its relocatable expression—that needs to be updated if the addresses that it uses are changed. 
-/// This information is needed to reconstruct the addresses that make up the relocatable expression’s value in order
-/// to change the addresses independently of each other. Declared in /usr/include/mach-o/reloc.h.
-pub struct ScatteredRelocationInfo {
-    pub bit_field: ScatteredRelocationInfoBitField,
-    pub r_value: i32,
-}
-
-pub struct ScatteredRelocationInfoBitField(pub u32);
-
-/* 
-struct scattered_relocation_info {
-#ifdef __BIG_ENDIAN__
-   uint32_t r_scattered : 1, r_pcrel : 1, r_length : 2, r_type : 4, r_address : 24;
-   int32_t r_value;
-#endif /* __BIG_ENDIAN__ */
-#ifdef __LITTLE_ENDIAN__
-   uint32_t r_address : 24, r_type : 4, r_length : 2, r_pcrel : 1, r_scattered : 1;
-   int32_t r_value;
-#endif  /* __LITTLE_ENDIAN__ */
-};
-*/
-impl ScatteredRelocationInfoBitField {
-    pub fn r_scattered() -> u32 {
-        todo!()
-    }
-    pub fn r_pcrel() -> u32 {
-        todo!()
-    }
-    pub fn r_length() -> u32 {
-        todo!()
-    }
-    pub fn r_type() -> u32 {
-        todo!()
-    }
-    pub fn r_address() -> u32 {
-        todo!()
-    }
-}
\ No newline at end of file
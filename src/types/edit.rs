@@ -0,0 +1,364 @@
+//! Editing of install names and rpaths, like `install_name_tool` does.
+//!
+//! Edited commands are rebuilt with `cmdsize` padded to 8 bytes for 64 bit images and
+//! to 4 bytes for 32 bit ones. Commands may grow only into the header padding, the space
+//! between load commands and the first section data. Any edit invalidates code signature,
+//! so signed images have to get rid of it with [MachImage::remove_code_signature] first.
+
+use super::constants::SEG_LINKEDIT;
+use super::writer::{MachImage, RawLoadCommand};
+use super::{
+    LC_CODE_SIGNATURE, LC_ID_DYLIB, LC_LAZY_LOAD_DYLIB, LC_LOAD_DYLIB, LC_LOAD_UPWARD_DYLIB,
+    LC_LOAD_WEAK_DYLIB, LC_REEXPORT_DYLIB, LC_RPATH,
+};
+use crate::result::{Error, Result};
+
+use scroll::Pwrite;
+
+/// `cmd`, `cmdsize`, offset of name, timestamp, current and compatibility versions
+const DYLIB_COMMAND_SIZE: usize = 24;
+/// `cmd`, `cmdsize` and offset of path
+const RPATH_COMMAND_SIZE: usize = 12;
+
+const DYLIB_LOAD_COMMANDS: [u32; 5] = [
+    LC_LOAD_DYLIB,
+    LC_LOAD_WEAK_DYLIB,
+    LC_REEXPORT_DYLIB,
+    LC_LAZY_LOAD_DYLIB,
+    LC_LOAD_UPWARD_DYLIB,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    /// Install name of `LC_ID_DYLIB`
+    SetId(String),
+    /// Install name of dependent library, it's an error if image doesn't depend on `old`
+    ChangeDylib {
+        old: String,
+        new: String,
+    },
+    AddRpath(String),
+    DeleteRpath(String),
+    ChangeRpath {
+        old: String,
+        new: String,
+    },
+}
+
+impl MachImage {
+    /// Applies `edit` to load commands. Image is left unchanged on error
+    pub fn apply_edit(&mut self, edit: &Edit) -> Result<()> {
+        match edit {
+            Edit::SetId(name) => {
+                let index = match self.find_command(|cmd, _| cmd == LC_ID_DYLIB) {
+                    Some(index) => index,
+                    None => return Err(Error::Text("Image has no LC_ID_DYLIB".to_string())),
+                };
+                self.replace_string(index, DYLIB_COMMAND_SIZE, name)
+            }
+            Edit::ChangeDylib { old, new } => {
+                let mut indices = Vec::new();
+                for (index, command) in self.load_commands.iter().enumerate() {
+                    if DYLIB_LOAD_COMMANDS.contains(&command.cmd)
+                        && self.command_string(command)? == *old
+                    {
+                        indices.push(index);
+                    }
+                }
+                if indices.is_empty() {
+                    return Err(Error::Text(format!("No dependent library {old}")));
+                }
+                let backup = self.load_commands.clone();
+                for index in indices {
+                    if let Err(err) = self.replace_string(index, DYLIB_COMMAND_SIZE, new) {
+                        self.load_commands = backup;
+                        return Err(err);
+                    }
+                }
+                Ok(())
+            }
+            Edit::AddRpath(path) => {
+                if self.find_rpath(path)?.is_some() {
+                    return Err(Error::Text(format!("LC_RPATH {path} already exists")));
+                }
+                let command = self.string_command(LC_RPATH, &[], path)?;
+                self.check_growth(command.bytes.len())?;
+                self.load_commands.push(command);
+                Ok(())
+            }
+            Edit::DeleteRpath(path) => match self.find_rpath(path)? {
+                Some(index) => {
+                    self.load_commands.remove(index);
+                    Ok(())
+                }
+                None => Err(Error::Text(format!("No LC_RPATH {path}"))),
+            },
+            Edit::ChangeRpath { old, new } => {
+                if self.find_rpath(new)?.is_some() {
+                    return Err(Error::Text(format!("LC_RPATH {new} already exists")));
+                }
+                match self.find_rpath(old)? {
+                    Some(index) => self.replace_string(index, RPATH_COMMAND_SIZE, new),
+                    None => Err(Error::Text(format!("No LC_RPATH {old}"))),
+                }
+            }
+        }
+    }
+
+    pub fn has_code_signature(&self) -> bool {
+        self.load_commands
+            .iter()
+            .any(|command| command.cmd == LC_CODE_SIGNATURE)
+    }
+
+    /// Removes `LC_CODE_SIGNATURE`. If the signature is at the end of the image,
+    /// like linkers and `codesign` put it, its data is cut off and `__LINKEDIT` shrinks.
+    /// Returns `false` if image isn't signed
+    pub fn remove_code_signature(&mut self) -> Result<bool> {
+        let index = match self.find_command(|cmd, _| cmd == LC_CODE_SIGNATURE) {
+            Some(index) => index,
+            None => return Ok(false),
+        };
+        let endian = self.endian();
+        let command = &self.load_commands[index];
+        let dataoff = command.field(8, endian)?;
+        let datasize = command.field(12, endian)?;
+        let data_end = dataoff as usize + datasize as usize;
+
+        if data_end == self.data().len() {
+            if let Some(linkedit) = self.segment_index(SEG_LINKEDIT) {
+                let (vmsize, fileoff, _) = self.segment_sizes(linkedit)?;
+                let filesize = (dataoff as u64).saturating_sub(fileoff);
+                self.set_segment_sizes(linkedit, vmsize, filesize)?;
+            }
+            self.data_mut().truncate(dataoff as usize);
+        }
+
+        self.load_commands.remove(index);
+        Ok(true)
+    }
+
+    /// Install name of `LC_ID_DYLIB`
+    pub fn install_name(&self) -> Result<Option<String>> {
+        match self.load_commands.iter().find(|c| c.cmd == LC_ID_DYLIB) {
+            Some(command) => Ok(Some(self.command_string(command)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Install names of `LC_*_DYLIB` commands of dependent libraries
+    pub fn dylib_names(&self) -> Result<Vec<String>> {
+        self.load_commands
+            .iter()
+            .filter(|command| DYLIB_LOAD_COMMANDS.contains(&command.cmd))
+            .map(|command| self.command_string(command))
+            .collect()
+    }
+
+    pub fn rpaths(&self) -> Result<Vec<String>> {
+        self.load_commands
+            .iter()
+            .filter(|command| command.cmd == LC_RPATH)
+            .map(|command| self.command_string(command))
+            .collect()
+    }
+
+    fn find_command(&self, predicate: impl Fn(u32, &[u8]) -> bool) -> Option<usize> {
+        self.load_commands
+            .iter()
+            .position(|command| predicate(command.cmd, &command.bytes))
+    }
+
+    fn find_rpath(&self, path: &str) -> Result<Option<usize>> {
+        for (index, command) in self.load_commands.iter().enumerate() {
+            if command.cmd == LC_RPATH && self.command_string(command)? == path {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+
+    /// String of dylib or rpath command, its offset is stored right after `cmdsize`
+    fn command_string(&self, command: &RawLoadCommand) -> Result<String> {
+        let offset = command.field(8, self.endian())?;
+        let bytes = match command.bytes.get(offset as usize..) {
+            Some(bytes) => bytes,
+            None => {
+                return Err(Error::Text(format!(
+                    "String offset {offset} is out of load command of size {}",
+                    command.cmdsize()
+                )))
+            }
+        };
+        let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+        Ok(String::from_utf8_lossy(&bytes[..end]).to_string())
+    }
+
+    /// Rebuilds command at `index` with `string`, fields between string offset and
+    /// `fixed_size` are kept
+    fn replace_string(&mut self, index: usize, fixed_size: usize, string: &str) -> Result<()> {
+        let old = &self.load_commands[index];
+        let fields = match old.bytes.get(RPATH_COMMAND_SIZE..fixed_size) {
+            Some(fields) => fields.to_vec(),
+            None => {
+                return Err(Error::Text(format!(
+                    "Load command of size {} is shorter than {fixed_size} bytes",
+                    old.cmdsize()
+                )))
+            }
+        };
+        let command = self.string_command(old.cmd, &fields, string)?;
+        let growth = command.bytes.len().saturating_sub(old.bytes.len());
+        self.check_growth(growth)?;
+        self.load_commands[index] = command;
+        Ok(())
+    }
+
+    /// Command of `cmd`, `cmdsize`, string offset, `fields` and `string`
+    fn string_command(&self, cmd: u32, fields: &[u8], string: &str) -> Result<RawLoadCommand> {
+        let alignment = match self.is_64() {
+            true => 8,
+            false => 4,
+        };
+        let offset = RPATH_COMMAND_SIZE + fields.len();
+        let size = (offset + string.len() + 1 + alignment - 1) & !(alignment - 1);
+
+        let mut bytes = vec![0u8; size];
+        let endian = self.endian();
+        bytes.pwrite_with(cmd, 0, endian)?;
+        bytes.pwrite_with(size as u32, 4, endian)?;
+        bytes.pwrite_with(offset as u32, 8, endian)?;
+        bytes[RPATH_COMMAND_SIZE..offset].copy_from_slice(fields);
+        bytes[offset..offset + string.len()].copy_from_slice(string.as_bytes());
+        Ok(RawLoadCommand { cmd, bytes })
+    }
+
+    pub(super) fn check_growth(&self, growth: usize) -> Result<()> {
+        let padding = self.header_padding();
+        match growth > padding {
+            true => Err(Error::Text(format!(
+                "Not enough header padding: {growth} bytes needed, {padding} available. Relink with -headerpad_max_install_names"
+            ))),
+            false => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{read_single, reread};
+    use crate::writer::FileImage;
+
+    #[test]
+    fn test_edits() {
+        let mut image = read_single("testable/edit/libedit.dylib");
+        let edits = [
+            Edit::SetId("@rpath/libedit.dylib".to_string()),
+            Edit::ChangeDylib {
+                old: "/usr/local/lib/liba.dylib".to_string(),
+                new: "@rpath/liba.dylib".to_string(),
+            },
+            Edit::ChangeRpath {
+                old: "/usr/local/lib".to_string(),
+                new: "@loader_path/../Frameworks/with/a/longer/path".to_string(),
+            },
+            Edit::AddRpath("@executable_path".to_string()),
+        ];
+        for edit in &edits {
+            image.apply_edit(edit).unwrap();
+        }
+        assert!(image
+            .load_commands
+            .iter()
+            .all(|command| command.cmdsize() % 8 == 0));
+
+        let mut image = reread(&FileImage::Thin(image), "schnauzer_edit_test.dylib").remove(0);
+        assert_eq!(
+            image.install_name().unwrap().as_deref(),
+            Some("@rpath/libedit.dylib")
+        );
+        assert_eq!(
+            image.dylib_names().unwrap(),
+            vec!["@rpath/liba.dylib", "/usr/lib/libSystem.B.dylib"]
+        );
+        assert_eq!(
+            image.rpaths().unwrap(),
+            vec![
+                "@loader_path/../Frameworks/with/a/longer/path",
+                "@executable_path"
+            ]
+        );
+
+        image
+            .apply_edit(&Edit::DeleteRpath("@executable_path".to_string()))
+            .unwrap();
+        assert_eq!(image.rpaths().unwrap().len(), 1);
+        assert!(image
+            .apply_edit(&Edit::DeleteRpath("@executable_path".to_string()))
+            .is_err());
+        assert!(image
+            .apply_edit(&Edit::AddRpath(
+                "@loader_path/../Frameworks/with/a/longer/path".to_string()
+            ))
+            .is_err());
+    }
+
+    #[test]
+    fn test_invalid_edits() {
+        let mut image = read_single("testable/edit/libedit.dylib");
+        let commands = image.load_commands.clone();
+        let change = Edit::ChangeDylib {
+            old: "/usr/local/lib/libmissing.dylib".to_string(),
+            new: "@rpath/libmissing.dylib".to_string(),
+        };
+        assert_eq!(
+            image.apply_edit(&change).unwrap_err().to_string(),
+            "No dependent library /usr/local/lib/libmissing.dylib"
+        );
+        assert!(image.load_commands == commands);
+
+        // Malformed LC_ID_DYLIB without versions
+        let index = image.find_command(|cmd, _| cmd == LC_ID_DYLIB).unwrap();
+        image.load_commands[index].bytes.truncate(16);
+        assert!(image
+            .apply_edit(&Edit::SetId("@rpath/libedit.dylib".to_string()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_header_padding() {
+        let mut image = read_single("testable/edit/libedit.dylib");
+        let commands = image.load_commands.clone();
+        let name = "x".repeat(image.header_padding() + 64);
+        assert!(image.apply_edit(&Edit::SetId(name.clone())).is_err());
+        assert!(image.apply_edit(&Edit::AddRpath(name)).is_err());
+        assert_eq!(image.load_commands, commands);
+
+        let name = "x".repeat(image.header_padding() - 16);
+        image.apply_edit(&Edit::AddRpath(name)).unwrap();
+        assert!(image.header_padding() < 8);
+        assert!(image.to_bytes().is_ok());
+    }
+
+    #[test]
+    fn test_code_signature() {
+        let mut image = read_single("testable/edit/libedit_signed.dylib");
+        assert!(image.has_code_signature());
+        assert!(image.remove_code_signature().unwrap());
+        assert!(!image.has_code_signature());
+        assert!(!image.remove_code_signature().unwrap());
+
+        let image = reread(
+            &FileImage::Thin(image),
+            "schnauzer_edit_signature_test.dylib",
+        )
+        .remove(0);
+        let unsigned = read_single("testable/edit/libedit.dylib");
+        // Signature is cut off, padding before it is left in `__LINKEDIT`
+        assert_eq!(image.data().len(), 2048 + 48);
+        let linkedit = image.segment_index(SEG_LINKEDIT).unwrap();
+        assert_eq!(image.segment_sizes(linkedit).unwrap().2, 48);
+        assert_eq!(image.load_commands[2..], unsigned.load_commands[2..]);
+    }
+}
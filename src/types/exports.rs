@@ -0,0 +1,233 @@
+//! Exported symbols: trie of `LC_DYLD_EXPORTS_TRIE` or export info of `LC_DYLD_INFO(_ONLY)`,
+//! as described in `mach-o/loader.h`.
+
+use super::LcVariant;
+use super::MachObject;
+use crate::result::{Error, Result};
+
+use scroll::{Pread, Uleb128};
+
+use std::collections::HashSet;
+
+pub const EXPORT_SYMBOL_FLAGS_KIND_MASK: u64 = 0x03;
+pub const EXPORT_SYMBOL_FLAGS_KIND_REGULAR: u64 = 0x00;
+pub const EXPORT_SYMBOL_FLAGS_KIND_THREAD_LOCAL: u64 = 0x01;
+pub const EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE: u64 = 0x02;
+pub const EXPORT_SYMBOL_FLAGS_WEAK_DEFINITION: u64 = 0x04;
+pub const EXPORT_SYMBOL_FLAGS_REEXPORT: u64 = 0x08;
+pub const EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER: u64 = 0x10;
+pub const EXPORT_SYMBOL_FLAGS_STATIC_RESOLVER: u64 = 0x20;
+
+/// Terminal node of the export trie
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportEntry {
+    pub name: String,
+    pub flags: u64,
+    /// Offset from mach header, meaningless for re-exports
+    pub address: u64,
+    /// Ordinal of dylib the symbol is re-exported from
+    pub reexport_ordinal: Option<u64>,
+    /// Name of the symbol in that dylib, if differs
+    pub reexport_name: Option<String>,
+    /// Offset of resolver function for `EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER`
+    pub resolver: Option<u64>,
+}
+
+impl ExportEntry {
+    pub fn kind(&self) -> u64 {
+        self.flags & EXPORT_SYMBOL_FLAGS_KIND_MASK
+    }
+
+    pub fn is_weak_definition(&self) -> bool {
+        self.flags & EXPORT_SYMBOL_FLAGS_WEAK_DEFINITION != 0
+    }
+
+    pub fn is_reexport(&self) -> bool {
+        self.flags & EXPORT_SYMBOL_FLAGS_REEXPORT != 0
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Exports {
+    /// Entries in order of trie traversal
+    pub entries: Vec<ExportEntry>,
+}
+
+impl Exports {
+    /// Parses export trie of the object, if any
+    pub fn parse(object: &MachObject) -> Result<Option<Self>> {
+        let location = object
+            .load_commands_iterator()
+            .find_map(|cmd| match cmd.variant {
+                LcVariant::DyldExportsTrie(c) => Some((c.dataoff, c.datasize)),
+                LcVariant::DyldInfo(c) | LcVariant::DyldInfoOnly(c) if c.export_size > 0 => {
+                    Some((c.export_off, c.export_size))
+                }
+                _ => None,
+            });
+        let (offset, size) = match location {
+            Some(location) => location,
+            None => return Ok(None),
+        };
+
+        let data = object.read_bytes(offset as u64, size as usize)?;
+        Self::from_bytes(&data).map(Some)
+    }
+
+    /// Exports of the trie or, if the object has none, external symbols of `LC_SYMTAB`
+    /// with equivalent flags
+    pub fn parse_or_symtab(object: &MachObject) -> Result<Self> {
+        if let Some(exports) = Self::parse(object)? {
+            return Ok(exports);
+        }
+
+        let mut exports = Exports::default();
+        let symtab = match object.symtab() {
+            Some(symtab) => symtab,
+            None => return Ok(exports),
+        };
+        let header_address = object
+            .segments()
+            .iter()
+            .find(|seg| seg.fileoff.0 == 0 && seg.filesize.0 > 0)
+            .map(|seg| seg.vmaddr.0)
+            .unwrap_or_default();
+        for nlist in symtab.nlist_iterator() {
+            let ntype = &nlist.n_type;
+            if ntype.is_stab() || !ntype.is_external() || ntype.is_undefined() {
+                continue;
+            }
+            let name = match &nlist.name {
+                Some(name) => name.load_string()?,
+                None => continue,
+            };
+            let mut flags = match ntype.is_absolute() {
+                true => EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE,
+                false => EXPORT_SYMBOL_FLAGS_KIND_REGULAR,
+            };
+            if nlist.is_weak_def() {
+                flags |= EXPORT_SYMBOL_FLAGS_WEAK_DEFINITION;
+            }
+            exports.entries.push(ExportEntry {
+                name,
+                flags,
+                address: match ntype.is_absolute() {
+                    true => nlist.n_value.0,
+                    false => nlist.n_value.0.wrapping_sub(header_address),
+                },
+                reexport_ordinal: None,
+                reexport_name: None,
+                resolver: None,
+            });
+        }
+        Ok(exports)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut exports = Exports::default();
+        if data.is_empty() {
+            return Ok(exports);
+        }
+
+        // Node offset and accumulated prefix
+        let mut stack = vec![(0usize, String::new())];
+        let mut visited = HashSet::new();
+        while let Some((node_offset, prefix)) = stack.pop() {
+            if !visited.insert(node_offset) {
+                return Err(Error::Text(format!(
+                    "Loop in export trie at {node_offset:#x}"
+                )));
+            }
+
+            let offset = &mut { node_offset };
+            let terminal_size = Uleb128::read(data, offset)? as usize;
+            let children_offset = *offset + terminal_size;
+            if terminal_size > 0 {
+                exports
+                    .entries
+                    .push(Self::parse_terminal(data, offset, prefix.clone())?);
+            }
+
+            *offset = children_offset;
+            let children_count: u8 = data.gread(offset)?;
+            let mut children = Vec::with_capacity(children_count as usize);
+            for _ in 0..children_count {
+                let edge = read_cstring(data, offset)?;
+                let child_offset = Uleb128::read(data, offset)? as usize;
+                children.push((child_offset, format!("{prefix}{edge}")));
+            }
+            // Reversed, so children are visited in order
+            stack.extend(children.into_iter().rev());
+        }
+
+        Ok(exports)
+    }
+
+    fn parse_terminal(data: &[u8], offset: &mut usize, name: String) -> Result<ExportEntry> {
+        let flags = Uleb128::read(data, offset)?;
+        let mut entry = ExportEntry {
+            name,
+            flags,
+            address: 0,
+            reexport_ordinal: None,
+            reexport_name: None,
+            resolver: None,
+        };
+
+        if entry.is_reexport() {
+            entry.reexport_ordinal = Some(Uleb128::read(data, offset)?);
+            let name = read_cstring(data, offset)?;
+            if !name.is_empty() {
+                entry.reexport_name = Some(name);
+            }
+        } else {
+            entry.address = Uleb128::read(data, offset)?;
+            if flags & EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER != 0 {
+                entry.resolver = Some(Uleb128::read(data, offset)?);
+            }
+        }
+
+        Ok(entry)
+    }
+}
+
+fn read_cstring(data: &[u8], offset: &mut usize) -> Result<String> {
+    let end = data
+        .get(*offset..)
+        .and_then(|tail| tail.iter().position(|b| *b == 0))
+        .ok_or(Error::BadBufferLength)?;
+    let string = String::from_utf8_lossy(&data[*offset..*offset + end]).to_string();
+    *offset += end + 1;
+    Ok(string)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_bytes() {
+        #[rustfmt::skip]
+        let data = [
+            // Root: not terminal, one child `_` at 0x05
+            0x00, 0x01, b'_', 0x00, 0x05,
+            // `_`: not terminal, children `main` at 0x12 and `foo` at 0x17
+            0x00, 0x02, b'm', b'a', b'i', b'n', 0x00, 0x12, b'f', b'o', b'o', 0x00, 0x17,
+            // `_main`: regular at 0x3f50
+            0x03, 0x00, 0xd0, 0x7e, 0x00,
+            // `_foo`: re-export of `_bar` from dylib 1
+            0x07, 0x08, 0x01, b'_', b'b', b'a', b'r', 0x00, 0x00,
+        ];
+        let exports = Exports::from_bytes(&data).unwrap();
+        assert_eq!(exports.entries.len(), 2);
+
+        assert_eq!(exports.entries[0].name, "_main");
+        assert_eq!(exports.entries[0].address, 0x3f50);
+        assert_eq!(exports.entries[0].kind(), EXPORT_SYMBOL_FLAGS_KIND_REGULAR);
+
+        assert_eq!(exports.entries[1].name, "_foo");
+        assert!(exports.entries[1].is_reexport());
+        assert_eq!(exports.entries[1].reexport_ordinal, Some(1));
+        assert_eq!(exports.entries[1].reexport_name.as_deref(), Some("_bar"));
+    }
+}
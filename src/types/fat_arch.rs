@@ -22,16 +22,15 @@ pub struct FatArch {
 }
 
 impl FatArch {
-    pub(super) fn parse(reader: RcReader, base_offset: usize) -> Result<FatArch> {
-        const ENDIAN: scroll::Endian = scroll::BE;
+    pub(super) fn parse(reader: RcReader, base_offset: usize, endian: scroll::Endian) -> Result<FatArch> {
         let mut reader_mut = reader.borrow_mut();
         reader_mut.seek(SeekFrom::Start(base_offset as u64))?;
 
-        let cpu_type: CPUType = reader_mut.ioread_with(ENDIAN)?;
-        let cpu_subtype: CPUSubtype = reader_mut.ioread_with(ENDIAN)?;
-        let offset: u32 = reader_mut.ioread_with(ENDIAN)?;
-        let size: u32 = reader_mut.ioread_with(ENDIAN)?;
-        let align: u32 = reader_mut.ioread_with(ENDIAN)?;
+        let cpu_type: CPUType = reader_mut.ioread_with(endian)?;
+        let cpu_subtype: CPUSubtype = reader_mut.ioread_with(endian)?;
+        let offset: u32 = reader_mut.ioread_with(endian)?;
+        let size: u32 = reader_mut.ioread_with(endian)?;
+        let align: u32 = reader_mut.ioread_with(endian)?;
 
         Ok(FatArch {
             reader: reader.clone(),
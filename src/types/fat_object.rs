@@ -2,12 +2,13 @@ use crate::MachObject;
 
 use super::constants::*;
 use super::FatArch;
+use super::Magic;
 use super::RcReader;
 use super::Result;
-use scroll::IOread;
+use scroll::{Endian, IOread};
 
 use std::fmt::Debug;
-use std::io::{Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom};
 
 /// Represents `fat_header` (but does not include magic)
 /// Some files contains multiple architectures
@@ -16,18 +17,25 @@ pub struct FatObject {
 
     pub nfat_arch: u32,
     arch_list_offset: usize,
+    /// Fat header is big endian, unless magic is reversed
+    pub(crate) endian: Endian,
 }
 
 impl FatObject {
-    pub(super) fn parse(reader: RcReader) -> Result<FatObject> {
+    pub(super) fn parse(reader: RcReader, magic: Magic) -> Result<FatObject> {
+        let endian = match magic.is_reverse() {
+            true => scroll::LE,
+            false => scroll::BE,
+        };
         let offset = BYTES_PER_MAGIC;
         reader.borrow_mut().seek(SeekFrom::Start(offset as u64))?;
-        let nfat_arch: u32 = reader.borrow_mut().ioread_with(scroll::BE)?;
+        let nfat_arch: u32 = reader.borrow_mut().ioread_with(endian)?;
 
         Ok(FatObject {
             reader: reader.clone(),
             arch_list_offset: BYTES_PER_FAT_HEADER,
             nfat_arch,
+            endian,
         })
     }
 }
@@ -35,7 +43,24 @@ impl FatObject {
 impl FatObject {
     /// Iterate over architectures
     pub fn arch_iterator(&self) -> FatArchIterator {
-        FatArchIterator::build(self.reader.clone(), self.nfat_arch, self.arch_list_offset).unwrap()
+        FatArchIterator::build(
+            self.reader.clone(),
+            self.nfat_arch,
+            self.arch_list_offset,
+            self.endian,
+        )
+        .unwrap()
+    }
+
+    /// Bytes from `offset` to the end of file
+    pub(crate) fn read_from(&self, offset: u64) -> Result<Vec<u8>> {
+        let mut reader = self.reader.borrow_mut();
+        reader.seek(SeekFrom::Start(offset))?;
+
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        Ok(buf)
     }
 
     /// Collect all existing objects
@@ -65,16 +90,23 @@ pub struct FatArchIterator {
     nfat_arch: u32,
 
     base_offset: usize,
+    endian: Endian,
 
     current: usize,
 }
 
 impl FatArchIterator {
-    fn build(reader: RcReader, nfat_arch: u32, base_offset: usize) -> Result<FatArchIterator> {
+    fn build(
+        reader: RcReader,
+        nfat_arch: u32,
+        base_offset: usize,
+        endian: Endian,
+    ) -> Result<FatArchIterator> {
         Ok(FatArchIterator {
             reader,
             nfat_arch,
             base_offset,
+            endian,
             current: 0,
         })
     }
@@ -91,6 +123,6 @@ impl Iterator for FatArchIterator {
 
         self.current += 1;
 
-        Some(FatArch::parse(self.reader.clone(), offset).unwrap())
+        Some(FatArch::parse(self.reader.clone(), offset, self.endian).unwrap())
     }
 }
@@ -0,0 +1,74 @@
+//! Function starts (`LC_FUNCTION_STARTS`): ULEB128 deltas between addresses of functions,
+//! the first one relative to `__TEXT` segment, terminated with zero.
+
+use super::constants::SEG_TEXT;
+use super::LcVariant;
+use super::MachObject;
+use crate::result::Result;
+
+use scroll::Uleb128;
+
+#[derive(Debug, Default)]
+pub struct FunctionStarts {
+    /// Sorted virtual addresses of functions
+    pub addresses: Vec<u64>,
+}
+
+impl FunctionStarts {
+    /// Parses `LC_FUNCTION_STARTS` payload of the object, if the command exists
+    pub fn parse(object: &MachObject) -> Result<Option<Self>> {
+        let command = object
+            .load_commands_iterator()
+            .find_map(|cmd| match cmd.variant {
+                LcVariant::FunctionStarts(c) => Some(c),
+                _ => None,
+            });
+        let command = match command {
+            Some(command) => command,
+            None => return Ok(None),
+        };
+
+        let text_vmaddr = object
+            .segments()
+            .iter()
+            .find(|seg| seg.segname.to_string() == SEG_TEXT)
+            .map(|seg| seg.vmaddr.0)
+            .unwrap_or_default();
+
+        let data = object.read_bytes(command.dataoff as u64, command.datasize as usize)?;
+        Self::from_bytes(&data, text_vmaddr).map(Some)
+    }
+
+    pub fn from_bytes(data: &[u8], text_vmaddr: u64) -> Result<Self> {
+        let mut starts = FunctionStarts::default();
+        let mut address = text_vmaddr;
+
+        let offset = &mut 0usize;
+        while *offset < data.len() {
+            let delta = Uleb128::read(data, offset)?;
+            if delta == 0 {
+                break;
+            }
+            address = address.wrapping_add(delta);
+            starts.addresses.push(address);
+        }
+
+        Ok(starts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_bytes() {
+        // 0x3f50, then +0x30, then +0x100 (two bytes ULEB128)
+        let data = [0xd0, 0x7e, 0x30, 0x80, 0x02, 0x00, 0x00, 0x00];
+        let starts = FunctionStarts::from_bytes(&data, 0x1_0000_0000).unwrap();
+        assert_eq!(
+            starts.addresses,
+            vec![0x1_0000_3f50, 0x1_0000_3f80, 0x1_0000_4080]
+        );
+    }
+}
@@ -0,0 +1,361 @@
+//! Virtual memory view of [MachObject], used to follow pointers in ObjC and Swift metadata.
+//! Pointers are decoded according to fixups format of the object:
+//! chained fixups, dyld info binds (including arm64e threaded binds) or relocations of `MH_OBJECT`.
+
+use crate::chained_fixups::*;
+use crate::cpu_constants::*;
+use crate::filetype_constants::MH_OBJECT;
+use crate::reloc::{RelocationResolver, RelocationTarget};
+use crate::result::{Error, Result};
+use crate::{BindInfo, MachObject};
+
+use scroll::{Endian, Pread};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Value of pointer after fixups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Pointer {
+    Null,
+    Address(u64),
+    /// Bound to symbol defined outside of the object
+    Symbol(String),
+}
+
+struct Segment {
+    vmaddr: u64,
+    vmsize: u64,
+    filesize: u64,
+    /// Chained fixups pointer format
+    pointer_format: Option<u16>,
+}
+
+enum Binds {
+    None,
+    /// Names of chained fixups imports by ordinal
+    Chained(Vec<String>),
+    /// Names of symbols by location and threaded binds table
+    Opcodes {
+        located: HashMap<u64, String>,
+        threaded: Vec<String>,
+    },
+    /// Targets of pointer relocations and values of `*_SUBTRACTOR` pairs by location
+    Relocations {
+        pointers: HashMap<u64, Pointer>,
+        differences: HashMap<u64, i64>,
+    },
+}
+
+pub(super) struct Image {
+    object: MachObject,
+    segments: Vec<Segment>,
+    /// Contents of segments loaded on demand, by segment index
+    contents: RefCell<HashMap<usize, Rc<Vec<u8>>>>,
+    binds: Binds,
+    /// Address of segment which maps start of the file
+    base_address: u64,
+    is_64: bool,
+    endian: Endian,
+}
+
+impl Image {
+    pub(super) fn build(object: &MachObject) -> Result<Self> {
+        let header = object.header();
+        let is_arm64e = header.cputype == CPU_TYPE_ARM64
+            && header.cpusubtype.masked() == CPU_SUBTYPE_ARM64E;
+
+        let chained = ChainedFixups::parse(object)?;
+
+        let mut segments: Vec<Segment> = object
+            .segments()
+            .iter()
+            .enumerate()
+            .map(|(index, seg)| Segment {
+                vmaddr: seg.vmaddr.0,
+                vmsize: seg.vmsize.0,
+                filesize: seg.filesize.0,
+                pointer_format: chained.as_ref().and_then(|c| c.pointer_format(index)),
+            })
+            .collect();
+
+        let base_address = object.offset_to_vmaddr(0).unwrap_or(0);
+
+        let binds = match (chained, header.filetype.0) {
+            (Some(chained), _) => {
+                Binds::Chained(chained.imports.into_iter().map(|i| i.name).collect())
+            }
+            (None, MH_OBJECT) => Self::relocations(object)?,
+            (None, _) => match BindInfo::parse(object)? {
+                Some(info) => {
+                    // Old arm64e binaries encode rebases and binds in place
+                    if is_arm64e || !info.threaded.is_empty() {
+                        segments
+                            .iter_mut()
+                            .for_each(|s| s.pointer_format = Some(DYLD_CHAINED_PTR_ARM64E));
+                    }
+                    let located = info
+                        .records
+                        .into_iter()
+                        .filter_map(|r| {
+                            let segment = segments.get(r.segment_index)?;
+                            Some((segment.vmaddr + r.segment_offset, r.symbol_name))
+                        })
+                        .collect();
+                    let threaded = info.threaded.into_iter().map(|r| r.symbol_name).collect();
+                    Binds::Opcodes { located, threaded }
+                }
+                None => Binds::None,
+            },
+        };
+
+        Ok(Image {
+            object: object.clone(),
+            segments,
+            contents: RefCell::new(HashMap::new()),
+            binds,
+            base_address,
+            is_64: header.magic.is_64(),
+            endian: header.magic.endian(),
+        })
+    }
+
+    /// In `MH_OBJECT` pointers to symbols and relative offsets are stored as relocations
+    fn relocations(object: &MachObject) -> Result<Binds> {
+        let defined: Vec<Option<u64>> = match object.symtab() {
+            Some(symtab) => symtab
+                .nlist_iterator()
+                .map(|nlist| match nlist.n_type.is_defined_in_n_sect() && !nlist.n_type.is_stab() {
+                    true => Some(nlist.n_value.0),
+                    false => None,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let address = |target: &RelocationTarget| match target {
+            RelocationTarget::Symbol { index, .. } => defined.get(*index).copied().flatten(),
+            _ => None,
+        };
+
+        let resolver = RelocationResolver::build(object)?;
+        let mut pointers = HashMap::new();
+        let mut differences = HashMap::new();
+        for section in object.sections() {
+            for reloc in resolver.resolve(&section)? {
+                let location = section.addr.0 + reloc.address as u64;
+                let addend = reloc.addend.unwrap_or(0);
+                if reloc.pcrel {
+                    continue;
+                }
+
+                if let Some(subtrahend) = &reloc.subtrahend {
+                    if let (Some(target), Some(subtrahend)) = (address(&reloc.target), address(subtrahend)) {
+                        differences.insert(location, target as i64 - subtrahend as i64 + addend);
+                    }
+                    continue;
+                }
+
+                // Items with non-symbol targets already contain address of target
+                if let RelocationTarget::Symbol { name, .. } = &reloc.target {
+                    let pointer = match address(&reloc.target) {
+                        Some(value) => Pointer::Address(value.wrapping_add(addend as u64)),
+                        None => Pointer::Symbol(name.clone()),
+                    };
+                    pointers.insert(location, pointer);
+                }
+            }
+        }
+
+        Ok(Binds::Relocations { pointers, differences })
+    }
+}
+
+impl Image {
+    pub(super) fn pointer_size(&self) -> u64 {
+        match self.is_64 {
+            true => 8,
+            false => 4,
+        }
+    }
+
+    fn segment_index(&self, address: u64) -> Option<usize> {
+        self.segments
+            .iter()
+            .position(|s| address >= s.vmaddr && address < s.vmaddr + s.vmsize)
+    }
+
+    fn segment_contents(&self, index: usize) -> Result<Rc<Vec<u8>>> {
+        if let Some(contents) = self.contents.borrow().get(&index) {
+            return Ok(contents.clone());
+        }
+
+        // Only file contents are cached, zero-fill part may be huge
+        let segment = &self.segments[index];
+        let size = segment.filesize.min(segment.vmsize) as usize;
+        let contents = Rc::new(self.object.read_bytes_at_vmaddr(segment.vmaddr, size)?);
+        self.contents.borrow_mut().insert(index, contents.clone());
+        Ok(contents)
+    }
+
+    /// Reads `size` bytes at `address`. Zero-fill part of segment is read as zeros.
+    pub(super) fn read_bytes(&self, address: u64, size: usize) -> Result<Vec<u8>> {
+        let index = self.segment_index(address).ok_or_else(|| not_mapped(address))?;
+        let segment = &self.segments[index];
+        let offset = address - segment.vmaddr;
+        if offset + size as u64 > segment.vmsize {
+            return Err(not_mapped(address + size as u64));
+        }
+
+        let contents = self.segment_contents(index)?;
+        let start = (offset as usize).min(contents.len());
+        let end = (offset as usize + size).min(contents.len());
+
+        let mut result = contents[start..end].to_vec();
+        result.resize(size, 0);
+        Ok(result)
+    }
+
+    pub(super) fn read_u32(&self, address: u64) -> Result<u32> {
+        Ok(self.read_bytes(address, 4)?.pread_with(0, self.endian)?)
+    }
+
+    /// Reads 32-bit integer, like relative offset, and applies relocations of `MH_OBJECT`
+    pub(super) fn read_i32(&self, address: u64) -> Result<i32> {
+        if let Binds::Relocations { differences, .. } = &self.binds {
+            if let Some(value) = differences.get(&address) {
+                return Ok(*value as i32);
+            }
+        }
+        Ok(self.read_bytes(address, 4)?.pread_with(0, self.endian)?)
+    }
+
+    pub(super) fn read_u16(&self, address: u64) -> Result<u16> {
+        Ok(self.read_bytes(address, 2)?.pread_with(0, self.endian)?)
+    }
+
+    /// Reads relative direct pointer, that is `int32_t` offset from its own address
+    pub(super) fn read_relative(&self, address: u64) -> Result<Option<u64>> {
+        match self.read_i32(address)? {
+            0 => Ok(None),
+            offset => Ok(Some(address.wrapping_add(offset as i64 as u64))),
+        }
+    }
+
+    /// Reads relative indirectable pointer. Offset with the lowest bit set points to pointer to target.
+    pub(super) fn read_relative_indirectable(&self, address: u64) -> Result<Pointer> {
+        let offset = self.read_i32(address)?;
+        if offset == 0 {
+            return Ok(Pointer::Null);
+        }
+
+        let target = address.wrapping_add((offset & !1) as i64 as u64);
+        match offset & 1 {
+            0 => Ok(Pointer::Address(target)),
+            _ => self.read_pointer(target),
+        }
+    }
+
+    /// Reads pointer-sized integer without applying fixups
+    pub(super) fn read_word(&self, address: u64) -> Result<u64> {
+        let bytes = self.read_bytes(address, self.pointer_size() as usize)?;
+        match self.is_64 {
+            true => Ok(bytes.pread_with::<u64>(0, self.endian)?),
+            false => Ok(bytes.pread_with::<u32>(0, self.endian)? as u64),
+        }
+    }
+
+    /// Reads zero terminated string at `address`
+    pub(super) fn read_cstring(&self, address: u64) -> Result<String> {
+        let index = self.segment_index(address).ok_or_else(|| not_mapped(address))?;
+        let contents = self.segment_contents(index)?;
+        let start = (address - self.segments[index].vmaddr) as usize;
+
+        let bytes = contents.get(start..).unwrap_or_default();
+        let bytes = match bytes.iter().position(|b| *b == 0) {
+            Some(end) => &bytes[..end],
+            None => bytes,
+        };
+        Ok(String::from_utf8_lossy(bytes).to_string())
+    }
+
+    /// Reads pointer at `address` and applies fixups
+    pub(super) fn read_pointer(&self, address: u64) -> Result<Pointer> {
+        let raw = self.read_word(address)?;
+
+        match &self.binds {
+            Binds::Relocations { pointers, .. } => {
+                if let Some(pointer) = pointers.get(&address) {
+                    return Ok(pointer.clone());
+                }
+            }
+            Binds::Opcodes { located, .. } => {
+                if let Some(name) = located.get(&address) {
+                    return Ok(Pointer::Symbol(name.clone()));
+                }
+            }
+            _ => (),
+        }
+
+        if raw == 0 {
+            return Ok(Pointer::Null);
+        }
+
+        let pointer_format = self
+            .segment_index(address)
+            .and_then(|index| self.segments[index].pointer_format);
+        let pointer_format = match pointer_format {
+            Some(pointer_format) => pointer_format,
+            None => return Ok(Pointer::Address(raw)),
+        };
+
+        let pointer = match ChainedPointer::decode(raw, pointer_format) {
+            Some((pointer, _)) => pointer,
+            None => {
+                return Err(Error::Text(format!(
+                    "Unsupported chained pointer format {pointer_format}"
+                )))
+            }
+        };
+
+        match pointer {
+            ChainedPointer::Rebase { target, high8 } => {
+                let target = match ChainedPointer::target_is_offset(pointer_format) {
+                    true => self.base_address + target,
+                    false => target,
+                };
+                Ok(Pointer::Address(target | (high8 as u64) << 56))
+            }
+            ChainedPointer::AuthRebase { target, .. } => {
+                Ok(Pointer::Address(self.base_address + target))
+            }
+            ChainedPointer::Bind { ordinal, .. } | ChainedPointer::AuthBind { ordinal, .. } => {
+                let names = match &self.binds {
+                    Binds::Chained(names) => names,
+                    Binds::Opcodes { threaded, .. } => threaded,
+                    _ => return Err(Error::Text(format!("Unexpected bind at {:#x}", address))),
+                };
+                match names.get(ordinal as usize) {
+                    Some(name) => Ok(Pointer::Symbol(name.clone())),
+                    None => Err(Error::Text(format!(
+                        "Bind ordinal {ordinal} at {:#x} is out of range",
+                        address
+                    ))),
+                }
+            }
+        }
+    }
+
+    /// Reads pointer at `address`, returns its target if it's inside the object
+    pub(super) fn read_address(&self, address: u64) -> Result<Option<u64>> {
+        match self.read_pointer(address)? {
+            Pointer::Address(target) => Ok(Some(target)),
+            _ => Ok(None),
+        }
+    }
+}
+
+fn not_mapped(address: u64) -> Error {
+    Error::Text(format!("Address {:#x} is not mapped", address))
+}
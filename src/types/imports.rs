@@ -0,0 +1,257 @@
+//! Imported (undefined external) symbols and libraries providing them.
+//! In two-level namespace images `GET_LIBRARY_ORDINAL` of symbol's `n_desc` is 1-based index
+//! in the ordered list of dylib load commands (`LC_LOAD_DYLIB`, `LC_LOAD_WEAK_DYLIB`,
+//! `LC_REEXPORT_DYLIB`, `LC_LOAD_UPWARD_DYLIB` and `LC_LAZY_LOAD_DYLIB`).
+
+use super::nlist::constants::*;
+use super::LcVariant;
+use super::MachObject;
+use super::Version32;
+use crate::result::Result;
+
+use std::fmt::Display;
+
+/// Load command, that brings the dependent library
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DylibLoadKind {
+    /// `LC_LOAD_DYLIB`
+    Load,
+    /// `LC_LOAD_WEAK_DYLIB`
+    Weak,
+    /// `LC_REEXPORT_DYLIB`
+    Reexport,
+    /// `LC_LOAD_UPWARD_DYLIB`
+    Upward,
+    /// `LC_LAZY_LOAD_DYLIB`
+    Lazy,
+}
+
+impl Display for DylibLoadKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            DylibLoadKind::Load => "load",
+            DylibLoadKind::Weak => "weak",
+            DylibLoadKind::Reexport => "reexport",
+            DylibLoadKind::Upward => "upward",
+            DylibLoadKind::Lazy => "lazy",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Library the image depends on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependentDylib {
+    pub name: String,
+    pub kind: DylibLoadKind,
+    pub current_version: u32,
+    pub compatibility_version: u32,
+}
+
+impl DependentDylib {
+    pub fn current_version(&self) -> Version32 {
+        Version32(self.current_version)
+    }
+
+    pub fn compatibility_version(&self) -> Version32 {
+        Version32(self.compatibility_version)
+    }
+}
+
+/// Dependent libraries in order of their load commands, so library ordinal `n` is at index `n - 1`
+pub fn dependent_dylibs(object: &MachObject) -> Vec<DependentDylib> {
+    object
+        .load_commands_iterator()
+        .filter_map(|cmd| {
+            let (kind, dylib) = match cmd.variant {
+                LcVariant::LoadDylib(c) => (DylibLoadKind::Load, c),
+                LcVariant::LoadWeakDylib(c) => (DylibLoadKind::Weak, c),
+                LcVariant::ReexportDylib(c) => (DylibLoadKind::Reexport, c),
+                LcVariant::LoadUpwardDylib(c) => (DylibLoadKind::Upward, c),
+                LcVariant::LazyLoadDylib(c) => (DylibLoadKind::Lazy, c),
+                _ => return None,
+            };
+            Some(DependentDylib {
+                name: dylib.name.to_string(),
+                kind,
+                current_version: dylib.current_version.0,
+                compatibility_version: dylib.compatibility_version.0,
+            })
+        })
+        .collect()
+}
+
+/// Where imported symbol is looked up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportLibrary {
+    /// `SELF_LIBRARY_ORDINAL`
+    SelfImage,
+    /// Library with 1-based ordinal
+    Dylib(u8, DependentDylib),
+    /// `DYNAMIC_LOOKUP_ORDINAL`
+    DynamicLookup,
+    /// `EXECUTABLE_ORDINAL`
+    Executable,
+    /// Image isn't two-level namespace, all images are searched
+    FlatNamespace,
+    /// Ordinal without corresponding load command
+    Invalid(u8),
+}
+
+impl ImportLibrary {
+    /// Resolves `GET_LIBRARY_ORDINAL` value against `dylibs` in order of load commands
+    pub fn resolve(ordinal: u8, dylibs: &[DependentDylib]) -> Self {
+        match ordinal {
+            SELF_LIBRARY_ORDINAL => ImportLibrary::SelfImage,
+            DYNAMIC_LOOKUP_ORDINAL => ImportLibrary::DynamicLookup,
+            EXECUTABLE_ORDINAL => ImportLibrary::Executable,
+            ordinal => match dylibs.get(ordinal as usize - 1) {
+                Some(dylib) if ordinal <= MAX_LIBRARY_ORDINAL => {
+                    ImportLibrary::Dylib(ordinal, dylib.clone())
+                }
+                _ => ImportLibrary::Invalid(ordinal),
+            },
+        }
+    }
+
+    /// Value to sort libraries in order of ordinals
+    fn sort_key(&self) -> u16 {
+        match self {
+            ImportLibrary::FlatNamespace | ImportLibrary::SelfImage => 0,
+            ImportLibrary::Dylib(ordinal, _) | ImportLibrary::Invalid(ordinal) => *ordinal as u16,
+            ImportLibrary::DynamicLookup => DYNAMIC_LOOKUP_ORDINAL as u16,
+            ImportLibrary::Executable => EXECUTABLE_ORDINAL as u16,
+        }
+    }
+}
+
+impl Display for ImportLibrary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportLibrary::SelfImage => write!(f, "this image"),
+            ImportLibrary::Dylib(_, dylib) => write!(f, "{}", dylib.name),
+            ImportLibrary::DynamicLookup => write!(f, "dynamic lookup"),
+            ImportLibrary::Executable => write!(f, "main executable"),
+            ImportLibrary::FlatNamespace => write!(f, "flat namespace"),
+            ImportLibrary::Invalid(ordinal) => write!(f, "invalid ordinal {ordinal}"),
+        }
+    }
+}
+
+/// Undefined external symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    pub name: String,
+    pub library: ImportLibrary,
+    /// `N_WEAK_REF`: address is set to 0 if the symbol isn't found
+    pub is_weak: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Imports {
+    pub dylibs: Vec<DependentDylib>,
+    /// In order of the symbol table
+    pub symbols: Vec<Import>,
+}
+
+impl Imports {
+    pub fn parse(object: &MachObject) -> Result<Self> {
+        let dylibs = dependent_dylibs(object);
+        let two_level = object.header().flags.is_two_level_name_space();
+
+        let mut symbols = Vec::new();
+        if let Some(symtab) = object.symtab() {
+            for nlist in symtab.nlist_iterator() {
+                let ntype = &nlist.n_type;
+                // Undefined symbols with non-zero value are common symbols of `MH_OBJECT`
+                let is_import = !ntype.is_stab()
+                    && ntype.is_external()
+                    && (ntype.is_undefined() || ntype.is_prebound())
+                    && (nlist.n_value.0 == 0 || ntype.is_prebound());
+                if !is_import {
+                    continue;
+                }
+                let name = match &nlist.name {
+                    Some(name) => name.load_string()?,
+                    None => continue,
+                };
+                let library = match two_level {
+                    true => ImportLibrary::resolve(nlist.library_ordinal(), &dylibs),
+                    false => ImportLibrary::FlatNamespace,
+                };
+                symbols.push(Import {
+                    name,
+                    library,
+                    is_weak: nlist.is_weak_ref(),
+                });
+            }
+        }
+
+        Ok(Imports { dylibs, symbols })
+    }
+
+    /// Symbols grouped by library, in order of library ordinals
+    pub fn by_library(&self) -> Vec<(&ImportLibrary, Vec<&Import>)> {
+        let mut result: Vec<(&ImportLibrary, Vec<&Import>)> = Vec::new();
+        for symbol in &self.symbols {
+            match result.iter_mut().find(|(lib, _)| **lib == symbol.library) {
+                Some((_, symbols)) => symbols.push(symbol),
+                None => result.push((&symbol.library, vec![symbol])),
+            }
+        }
+        result.sort_by_key(|(lib, _)| lib.sort_key());
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse;
+
+    #[test]
+    fn test_resolve() {
+        let dylibs = vec![DependentDylib {
+            name: "/usr/lib/libSystem.B.dylib".to_string(),
+            kind: DylibLoadKind::Load,
+            current_version: 0,
+            compatibility_version: 0,
+        }];
+        assert_eq!(ImportLibrary::resolve(0, &dylibs), ImportLibrary::SelfImage);
+        assert_eq!(
+            ImportLibrary::resolve(1, &dylibs),
+            ImportLibrary::Dylib(1, dylibs[0].clone())
+        );
+        assert_eq!(
+            ImportLibrary::resolve(2, &dylibs),
+            ImportLibrary::Invalid(2)
+        );
+        assert_eq!(
+            ImportLibrary::resolve(0xfe, &dylibs),
+            ImportLibrary::DynamicLookup
+        );
+        assert_eq!(
+            ImportLibrary::resolve(0xff, &dylibs),
+            ImportLibrary::Executable
+        );
+    }
+
+    #[test]
+    fn test_imports() {
+        let object = parse("testable/cat")
+            .mach_object_with_arch("x86_64")
+            .unwrap();
+        let imports = Imports::parse(&object).unwrap();
+        let setlocale = imports
+            .symbols
+            .iter()
+            .find(|s| s.name == "_setlocale")
+            .unwrap();
+        assert!(!setlocale.is_weak);
+        match &setlocale.library {
+            ImportLibrary::Dylib(1, dylib) => assert_eq!(dylib.name, "/usr/lib/libSystem.B.dylib"),
+            library => panic!("Unexpected library {library:?}"),
+        }
+        assert_eq!(imports.by_library().len(), 1);
+    }
+}
@@ -0,0 +1,112 @@
+//! Indirect symbol table of `LC_DYSYMTAB`: symbols of stubs and symbol pointers.
+//! Section of type `S_SYMBOL_STUBS` or `S_*_SYMBOL_POINTERS` takes `reserved1` as index
+//! of its first entry in the table, with one entry per stub or pointer.
+
+use super::section_constants::*;
+use super::LcVariant;
+use super::MachObject;
+use crate::result::Result;
+
+use scroll::Pread;
+
+/// Entry for symbol, that was made local by `strip` or linker
+pub const INDIRECT_SYMBOL_LOCAL: u32 = 0x80000000;
+/// Entry for absolute symbol
+pub const INDIRECT_SYMBOL_ABS: u32 = 0x40000000;
+
+/// Stub or symbol pointer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndirectSymbol {
+    /// Address of the stub or the pointer
+    pub address: u64,
+    /// `true` for entries of `S_SYMBOL_STUBS` sections
+    pub is_stub: bool,
+    /// Raw entry of indirect symbol table: index in `LcSymtab` or `INDIRECT_SYMBOL_*`
+    pub index: u32,
+    /// `None` for local and absolute entries
+    pub name: Option<String>,
+}
+
+/// Stubs and symbol pointers of all sections, in order of appearance
+pub fn indirect_symbols(object: &MachObject) -> Result<Vec<IndirectSymbol>> {
+    let dysymtab = object
+        .load_commands_iterator()
+        .find_map(|cmd| match cmd.variant {
+            LcVariant::Dysimtab(c) => Some(c),
+            _ => None,
+        });
+    let dysymtab = match dysymtab {
+        Some(dysymtab) if dysymtab.nindirectsyms > 0 => dysymtab,
+        _ => return Ok(Vec::new()),
+    };
+
+    let endian = object.header().magic.endian();
+    let table = object.read_bytes(
+        dysymtab.indirectsymoff as u64,
+        dysymtab.nindirectsyms as usize * std::mem::size_of::<u32>(),
+    )?;
+    let names: Vec<Option<String>> = match object.symtab() {
+        Some(symtab) => symtab
+            .nlist_iterator()
+            .map(|nlist| nlist.name.and_then(|name| name.load_string().ok()))
+            .collect(),
+        None => Vec::new(),
+    };
+    let pointer_size = match object.header().magic.is_64() {
+        true => 8,
+        false => 4,
+    };
+
+    let mut result = Vec::new();
+    for section in object.sections() {
+        let (is_stub, entry_size) = match section.section_type() {
+            S_SYMBOL_STUBS => (true, section.reserved2 as u64),
+            S_NON_LAZY_SYMBOL_POINTERS
+            | S_LAZY_SYMBOL_POINTERS
+            | S_LAZY_DYLIB_SYMBOL_POINTERS
+            | S_THREAD_LOCAL_VARIABLE_POINTERS => (false, pointer_size),
+            _ => continue,
+        };
+        if entry_size == 0 {
+            continue;
+        }
+
+        for number in 0..section.size.0 / entry_size {
+            let position = (section.reserved1 as usize + number as usize) * 4;
+            let index: u32 = match table.pread_with(position, endian) {
+                Ok(index) => index,
+                Err(_) => break,
+            };
+            let name = match index & (INDIRECT_SYMBOL_LOCAL | INDIRECT_SYMBOL_ABS) {
+                0 => names.get(index as usize).cloned().flatten(),
+                _ => None,
+            };
+            result.push(IndirectSymbol {
+                address: section.addr.0 + number * entry_size,
+                is_stub,
+                index,
+                name,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse;
+
+    #[test]
+    fn test_indirect_symbols() {
+        let object = parse("testable/cat")
+            .mach_object_with_arch("x86_64")
+            .unwrap();
+        let symbols = indirect_symbols(&object).unwrap();
+        let setlocale = symbols.iter().find(|s| s.address == 0x100003d54).unwrap();
+        assert!(setlocale.is_stub);
+        assert_eq!(setlocale.name.as_deref(), Some("_setlocale"));
+        assert!(symbols.iter().any(|s| !s.is_stub));
+    }
+}
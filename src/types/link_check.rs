@@ -0,0 +1,314 @@
+//! Check, that every imported symbol is exported by the library its ordinal refers to,
+//! so `dyld` wouldn't fail with "symbol not found" at launch. Exports of a library include ones
+//! of libraries it re-exports: with `LC_REEXPORT_DYLIB` or, for older umbrellas,
+//! with `LC_SUB_UMBRELLA`, `LC_SUB_LIBRARY` and `LC_SUB_FRAMEWORK`.
+
+use super::abi::arch_name;
+use super::dependencies::*;
+use super::exports::Exports;
+use super::filetype_constants::MH_EXECUTE;
+use super::imports::*;
+use super::tbd;
+use super::LcVariant;
+use super::MachObject;
+use crate::result::Result;
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkSeverity {
+    /// Weak import, that is allowed to be missing
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkProblem {
+    /// Symbol isn't exported by the library and libraries it re-exports
+    SymbolNotFound,
+    /// Library of the symbol isn't resolved
+    LibraryNotFound,
+    /// Ordinal doesn't refer to any library
+    InvalidOrdinal,
+}
+
+#[derive(Debug, Clone)]
+pub struct LinkIssue {
+    pub import: Import,
+    pub problem: LinkProblem,
+    pub severity: LinkSeverity,
+}
+
+pub struct LinkChecker {
+    resolver: DependencyResolver,
+}
+
+impl LinkChecker {
+    pub fn new(sysroot: &Path) -> Self {
+        LinkChecker {
+            resolver: DependencyResolver::new(sysroot),
+        }
+    }
+
+    /// Issues of imports of `object`, located at `path`, in order of the symbol table
+    pub fn check(&self, object: &MachObject, path: &Path) -> Result<Vec<LinkIssue>> {
+        let imports = Imports::parse(object)?;
+        let dependencies = self.resolver.resolve(object, path);
+        let mut collector = ExportsCollector::new(object, &dependencies);
+
+        let mut issues = Vec::new();
+        for import in imports.symbols {
+            let weak_severity = match import.is_weak {
+                true => LinkSeverity::Warning,
+                false => LinkSeverity::Error,
+            };
+            let issue = match &import.library {
+                ImportLibrary::SelfImage => None,
+                ImportLibrary::Executable => match object.header().filetype.0 {
+                    MH_EXECUTE => match image_exports(object).contains(&import.name) {
+                        true => None,
+                        false => Some((LinkProblem::SymbolNotFound, weak_severity)),
+                    },
+                    // Executable loading the image is unknown
+                    _ => None,
+                },
+                ImportLibrary::Invalid(_) => {
+                    Some((LinkProblem::InvalidOrdinal, LinkSeverity::Error))
+                }
+                ImportLibrary::Dylib(ordinal, _) => {
+                    let dependency = &dependencies[*ordinal as usize - 1];
+                    match dependency.is_resolved() {
+                        true => match collector.exports(dependency).contains(&import.name) {
+                            true => None,
+                            false => Some((LinkProblem::SymbolNotFound, weak_severity)),
+                        },
+                        false => match dependency.kind {
+                            DylibLoadKind::Weak => {
+                                Some((LinkProblem::LibraryNotFound, LinkSeverity::Warning))
+                            }
+                            _ => Some((LinkProblem::LibraryNotFound, weak_severity)),
+                        },
+                    }
+                }
+                ImportLibrary::FlatNamespace | ImportLibrary::DynamicLookup => {
+                    match collector.any_exports(&import.name) {
+                        true => None,
+                        // Dynamic lookup may find the symbol in images loaded at runtime
+                        false if import.library == ImportLibrary::DynamicLookup => {
+                            Some((LinkProblem::SymbolNotFound, LinkSeverity::Warning))
+                        }
+                        false => Some((LinkProblem::SymbolNotFound, weak_severity)),
+                    }
+                }
+            };
+            if let Some((problem, severity)) = issue {
+                issues.push(LinkIssue {
+                    import,
+                    problem,
+                    severity,
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Collects exports of libraries in dependency tree, following re-exports
+struct ExportsCollector<'a> {
+    loader: &'a MachObject,
+    /// First occurrences of libraries in the tree, the ones with dependencies
+    nodes: HashMap<PathBuf, &'a Dependency>,
+    all_nodes: Vec<&'a Dependency>,
+    cache: HashMap<PathBuf, Rc<HashSet<String>>>,
+    /// Guards against cycles of re-exports
+    in_progress: HashSet<PathBuf>,
+}
+
+impl<'a> ExportsCollector<'a> {
+    fn new(loader: &'a MachObject, dependencies: &'a [Dependency]) -> Self {
+        let mut collector = ExportsCollector {
+            loader,
+            nodes: HashMap::new(),
+            all_nodes: Vec::new(),
+            cache: HashMap::new(),
+            in_progress: HashSet::new(),
+        };
+        for dependency in dependencies {
+            collector.add_node(dependency);
+        }
+        collector
+    }
+
+    fn add_node(&mut self, dependency: &'a Dependency) {
+        self.all_nodes.push(dependency);
+        if let DependencyStatus::Resolved(path) = &dependency.status {
+            self.nodes.entry(canonical(path)).or_insert(dependency);
+        }
+        for child in &dependency.dependencies {
+            self.add_node(child);
+        }
+    }
+
+    /// Whether any library of the tree exports `name`
+    fn any_exports(&mut self, name: &str) -> bool {
+        let nodes = self.all_nodes.clone();
+        nodes
+            .iter()
+            .any(|dependency| self.exports(dependency).contains(name))
+    }
+
+    fn exports(&mut self, dependency: &Dependency) -> Rc<HashSet<String>> {
+        let path = match dependency.status.path() {
+            Some(path) if dependency.is_resolved() => canonical(path),
+            _ => return Rc::new(HashSet::new()),
+        };
+        if let Some(exports) = self.cache.get(&path) {
+            return exports.clone();
+        }
+        if !self.in_progress.insert(path.clone()) {
+            return Rc::new(HashSet::new());
+        }
+
+        let exports = match &dependency.status {
+            DependencyStatus::Stub(stub) => match std::fs::read_to_string(stub) {
+                Ok(text) => tbd::exported_symbols(&text, &arch_name(self.loader)),
+                Err(_) => HashSet::new(),
+            },
+            _ => self.library_exports(&path, dependency),
+        };
+        let exports = Rc::new(exports);
+
+        self.in_progress.remove(&path);
+        self.cache.insert(path, exports.clone());
+        exports
+    }
+
+    fn library_exports(&mut self, path: &Path, dependency: &Dependency) -> HashSet<String> {
+        let node = match self.nodes.get(path) {
+            Some(node) => *node,
+            None => return HashSet::new(),
+        };
+        let object = match load_slice(path, self.loader) {
+            Ok(object) => object,
+            Err(_) => return HashSet::new(),
+        };
+
+        let mut exports = image_exports(&object);
+        let sub_names = sub_names(&object);
+        for child in &node.dependencies {
+            let is_reexported = child.kind == DylibLoadKind::Reexport
+                || sub_names.contains(&short_name(&child.install_name))
+                || self.umbrella_of(child) == Some(short_name(&dependency.install_name));
+            if is_reexported {
+                exports.extend(self.exports(child).iter().cloned());
+            }
+        }
+        exports
+    }
+
+    /// `LC_SUB_FRAMEWORK` of the library
+    fn umbrella_of(&self, dependency: &Dependency) -> Option<String> {
+        let path = match &dependency.status {
+            DependencyStatus::Resolved(path) | DependencyStatus::Duplicate(path) => path,
+            _ => return None,
+        };
+        load_slice(path, self.loader)
+            .ok()?
+            .load_commands_iterator()
+            .find_map(|cmd| match cmd.variant {
+                LcVariant::Subframework(c) => Some(c.umbrella.to_string()),
+                _ => None,
+            })
+    }
+}
+
+/// Names of `LC_SUB_UMBRELLA` and `LC_SUB_LIBRARY`
+fn sub_names(object: &MachObject) -> Vec<String> {
+    object
+        .load_commands_iterator()
+        .filter_map(|cmd| match cmd.variant {
+            LcVariant::Subumbrella(c) => Some(c.sub_umbrella.to_string()),
+            LcVariant::Sublibrary(c) => Some(c.sub_library.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Names of symbols exported by the image itself
+pub fn image_exports(object: &MachObject) -> HashSet<String> {
+    match Exports::parse_or_symtab(object) {
+        Ok(exports) => exports
+            .entries
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect(),
+        Err(_) => HashSet::new(),
+    }
+}
+
+/// Name, that `LC_SUB_*` commands refer library by:
+/// `Foo` for `/Foo.framework/Versions/A/Foo`, `libfoo` for `/usr/lib/libfoo.A.dylib`
+pub fn short_name(install_name: &str) -> String {
+    let leaf = install_name.rsplit('/').next().unwrap_or(install_name);
+    match leaf.split_once('.') {
+        Some((name, _)) => name.to_string(),
+        None => leaf.to_string(),
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or(path.to_path_buf())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse_single;
+
+    #[test]
+    fn test_short_name() {
+        assert_eq!(
+            short_name("/System/Library/Frameworks/Foo.framework/Versions/A/Foo"),
+            "Foo"
+        );
+        assert_eq!(short_name("/usr/lib/libfoo.A.dylib"), "libfoo");
+        assert_eq!(short_name("@loader_path/libqux.dylib"), "libqux");
+    }
+
+    #[test]
+    fn test_check() {
+        let path = Path::new("testable/sysroot/Applications/App/app");
+        let object = parse_single(path);
+        let checker = LinkChecker::new(Path::new("testable/sysroot"));
+        let issues: Vec<(String, LinkProblem, LinkSeverity)> = checker
+            .check(&object, path)
+            .unwrap()
+            .into_iter()
+            .map(|issue| (issue.import.name, issue.problem, issue.severity))
+            .collect();
+        // `_bar` is re-exported, `_qux` is of sub-library, `_printf` is in text stub
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "_gone".to_string(),
+                    LinkProblem::SymbolNotFound,
+                    LinkSeverity::Error
+                ),
+                (
+                    "_weak_gone".to_string(),
+                    LinkProblem::SymbolNotFound,
+                    LinkSeverity::Warning
+                ),
+                (
+                    "_optional".to_string(),
+                    LinkProblem::LibraryNotFound,
+                    LinkSeverity::Warning
+                ),
+            ]
+        );
+    }
+}
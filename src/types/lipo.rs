@@ -0,0 +1,205 @@
+//! Operations on slices of universal files, like `lipo` does: thinning to single architecture,
+//! extraction, removal and replacement of slices and creation of universal file from thin ones.
+//!
+//! Slices are laid out anew after any operation, so removed slices don't leave holes.
+
+use super::abi::header_arch_name;
+use super::writer::{FatLayout, FatSlice, FileImage, MachImage};
+use super::CPUType;
+use crate::result::{Error, Result};
+
+/// Alignment of slice in universal file as power of 2, the same one `lipo` uses:
+/// page size of the architecture
+pub fn slice_align(cputype: CPUType) -> u32 {
+    cputype.page_size().trailing_zeros()
+}
+
+impl FileImage {
+    /// Universal file of `images`, slices are ordered by alignment like `lipo` does
+    pub fn create(images: Vec<MachImage>) -> Result<Self> {
+        if images.is_empty() {
+            return Err(Error::Text(
+                "No files to create universal file of".to_string(),
+            ));
+        }
+        let mut archs: Vec<String> = Vec::new();
+        for image in &images {
+            let arch = header_arch_name(&image.header);
+            if archs.contains(&arch) {
+                return Err(Error::Text(format!(
+                    "Files have the same architecture {arch}"
+                )));
+            }
+            archs.push(arch);
+        }
+
+        let mut slices: Vec<FatSlice> = images
+            .into_iter()
+            .map(|image| {
+                let align = slice_align(image.header.cputype);
+                FatSlice::new(image, align)
+            })
+            .collect();
+        slices.sort_by_key(|slice| slice.align);
+        Ok(FileImage::Fat(slices, FatLayout::default()))
+    }
+
+    /// Names of architectures like `arm64`, or CPU type and subtype for unknown ones
+    pub fn arch_names(&self) -> Vec<String> {
+        match self {
+            FileImage::Thin(image) => vec![header_arch_name(&image.header)],
+            FileImage::Fat(slices, _) => slices
+                .iter()
+                .map(|slice| header_arch_name(&slice.image.header))
+                .collect(),
+        }
+    }
+
+    /// Images of all architectures
+    pub fn into_images(self) -> Vec<MachImage> {
+        match self {
+            FileImage::Thin(image) => vec![image],
+            FileImage::Fat(slices, _) => slices.into_iter().map(|slice| slice.image).collect(),
+        }
+    }
+
+    /// Thin image of `arch`
+    pub fn thin(self, arch: &str) -> Result<MachImage> {
+        let (mut slices, _) = self.into_slices()?;
+        let index = slice_index(&slices, arch)?;
+        Ok(slices.swap_remove(index).image)
+    }
+
+    /// Universal file with only slices of `archs`
+    pub fn extract(self, archs: &[String]) -> Result<Self> {
+        let (slices, layout) = self.into_slices()?;
+        for arch in archs {
+            slice_index(&slices, arch)?;
+        }
+        let slices = slices
+            .into_iter()
+            .filter(|slice| archs.contains(&header_arch_name(&slice.image.header)))
+            .map(relocated)
+            .collect();
+        Ok(FileImage::Fat(slices, layout))
+    }
+
+    /// Universal file without slices of `archs`
+    pub fn remove(self, archs: &[String]) -> Result<Self> {
+        let (slices, layout) = self.into_slices()?;
+        for arch in archs {
+            slice_index(&slices, arch)?;
+        }
+        let slices: Vec<FatSlice> = slices
+            .into_iter()
+            .filter(|slice| !archs.contains(&header_arch_name(&slice.image.header)))
+            .map(relocated)
+            .collect();
+        if slices.is_empty() {
+            return Err(Error::Text("Can't remove all architectures".to_string()));
+        }
+        Ok(FileImage::Fat(slices, layout))
+    }
+
+    /// Universal file with slice of the same architecture as `image` replaced by it
+    pub fn replace(self, image: MachImage) -> Result<Self> {
+        let (mut slices, layout) = self.into_slices()?;
+        let arch = header_arch_name(&image.header);
+        let index = slice_index(&slices, &arch)?;
+        let align = slices[index].align;
+        slices[index] = FatSlice::new(image, align);
+        let slices = slices.into_iter().map(relocated).collect();
+        Ok(FileImage::Fat(slices, layout))
+    }
+
+    fn into_slices(self) -> Result<(Vec<FatSlice>, FatLayout)> {
+        match self {
+            FileImage::Thin(_) => Err(Error::Text("File isn't universal".to_string())),
+            FileImage::Fat(slices, layout) => Ok((slices, layout)),
+        }
+    }
+}
+
+fn slice_index(slices: &[FatSlice], arch: &str) -> Result<usize> {
+    match slices
+        .iter()
+        .position(|slice| header_arch_name(&slice.image.header) == arch)
+    {
+        Some(index) => Ok(index),
+        None => Err(Error::Text(format!("File has no {arch} slice"))),
+    }
+}
+
+/// The same slice without offset, that it was read at
+fn relocated(slice: FatSlice) -> FatSlice {
+    let mut result = FatSlice::new(slice.image, slice.align);
+    result.cputype = slice.cputype;
+    result.cpusubtype = slice.cpusubtype;
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{read, read_single};
+
+    fn slice_layout(file: &FileImage) -> Vec<(String, u32)> {
+        match file {
+            FileImage::Fat(slices, _) => file
+                .arch_names()
+                .into_iter()
+                .zip(slices.iter().map(|slice| slice.align))
+                .collect(),
+            FileImage::Thin(_) => panic!("Expected universal file"),
+        }
+    }
+
+    #[test]
+    fn test_create() {
+        let path = "testable/abi/libabi_old.dylib";
+        let arm64 = read(path).thin("arm64").unwrap();
+        let x86_64 = read(path).thin("x86_64").unwrap();
+        assert!(read(path).thin("arm64e").is_err());
+        assert!(FileImage::Thin(arm64.clone()).thin("arm64").is_err());
+
+        let file = FileImage::create(vec![arm64.clone(), x86_64.clone()]).unwrap();
+        assert_eq!(
+            slice_layout(&file),
+            vec![("x86_64".to_string(), 12), ("arm64".to_string(), 14)]
+        );
+        let bytes = file.to_bytes().unwrap();
+        // Fat header, x86_64 at page of 4K and arm64 at page of 16K
+        assert_eq!(bytes.len(), 0x4000 + arm64.data().len());
+        assert_eq!(&bytes[0x1000..0x1000 + x86_64.data().len()], x86_64.data());
+
+        let thin = file.thin("arm64").unwrap();
+        assert!(thin.to_bytes().unwrap() == arm64.to_bytes().unwrap());
+        assert!(FileImage::create(vec![arm64.clone(), arm64]).is_err());
+    }
+
+    #[test]
+    fn test_slices() {
+        let path = "testable/abi/libabi_old.dylib";
+        let archs = ["x86_64".to_string()];
+
+        let file = read(path).remove(&archs).unwrap();
+        assert_eq!(slice_layout(&file), vec![("arm64".to_string(), 14)]);
+        let bytes = file.to_bytes().unwrap();
+        assert_eq!(
+            bytes.len(),
+            0x4000 + read(path).thin("arm64").unwrap().data().len()
+        );
+        assert!(file.remove(&["arm64".to_string()]).is_err());
+
+        let file = read(path).extract(&archs).unwrap();
+        assert_eq!(slice_layout(&file), vec![("x86_64".to_string(), 12)]);
+
+        let new = read_single("testable/abi/libabi_new.dylib");
+        let file = read(path).replace(new.clone()).unwrap();
+        assert_eq!(
+            slice_layout(&file),
+            vec![("arm64".to_string(), 14), ("x86_64".to_string(), 12)]
+        );
+        assert!(file.thin("arm64").unwrap().data() == new.data());
+    }
+}
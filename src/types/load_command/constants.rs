@@ -66,4 +66,7 @@ pub const LC_LINKER_OPTIMIZATION_HINT: u32 = 0x2E;
 pub const LC_VERSION_MIN_TVOS: u32 = 0x2F;
 pub const LC_VERSION_MIN_WATCHOS: u32 = 0x30;
 pub const LC_NOTE: u32 = 0x31;
-pub const LC_BUILD_VERSION: u32 = 0x32;
\ No newline at end of file
+pub const LC_BUILD_VERSION: u32 = 0x32;
+pub const LC_DYLD_EXPORTS_TRIE: u32 = 0x33 | LC_REQ_DYLD;
+pub const LC_DYLD_CHAINED_FIXUPS: u32 = 0x34 | LC_REQ_DYLD;
+pub const LC_ATOM_INFO: u32 = 0x36;
\ No newline at end of file
@@ -1,10 +1,11 @@
 use crate::X64Context;
+use crate::CPUType;
 
 use super::fmt_ext::*;
 use super::Section;
 use super::RcReader;
 use super::Result;
-use scroll::{Endian, IOread};
+use scroll::IOread;
 
 use std::fmt::Debug;
 use std::io::{Seek, SeekFrom};
@@ -124,6 +125,7 @@ impl LoadCommand {
         base_offset: usize,
         endian: scroll::Endian,
         is_64: bool,
+        cputype: CPUType,
         object_file_offset: u64,
     ) -> Result<LoadCommand> {
         let mut reader_mut = reader.borrow_mut();
@@ -134,13 +136,17 @@ impl LoadCommand {
 
         std::mem::drop(reader_mut);
 
+        let ctx = match is_64 {
+            true => X64Context::On(endian),
+            false => X64Context::Off(endian),
+        };
         let variant = LcVariant::parse(
             reader.clone(),
             cmd,
             cmdsize,
             base_offset,
-            endian,
-            is_64,
+            ctx,
+            cputype,
             object_file_offset,
         )?;
 
@@ -168,6 +174,10 @@ pub enum LcVariant {
     LoadWeakDylib(LcDylib),
     /// LC_REEXPORT_DYLIB
     ReexportDylib(LcDylib),
+    /// LC_LAZY_LOAD_DYLIB
+    LazyLoadDylib(LcDylib),
+    /// LC_LOAD_UPWARD_DYLIB
+    LoadUpwardDylib(LcDylib),
     /// LC_SUB_FRAMEWORK
     Subframework(LcSubframework),
     /// LC_SUB_CLIENT
@@ -216,6 +226,10 @@ pub enum LcVariant {
     DylibCodeSignature(LcLinkEditData),
     /// LC_LINKER_OPTIMIZATION_HINT,
     LinkerOptimizationHint(LcLinkEditData),
+    /// LC_DYLD_EXPORTS_TRIE,
+    DyldExportsTrie(LcLinkEditData),
+    /// LC_DYLD_CHAINED_FIXUPS,
+    DyldChainedFixups(LcLinkEditData),
     /// LC_ENCRYPTION_INFO
     EncryptionInfo(LcEncryptionInfo),
     /// LC_ENCRYPTION_INFO_64
@@ -256,10 +270,11 @@ impl LcVariant {
         cmd: u32,
         cmdsize: u32,
         command_offset: usize,
-        endian: Endian,
-        is_64: bool,
+        ctx: X64Context,
+        cputype: CPUType,
         object_file_offset: u64,
     ) -> Result<Self> {
+        let endian = *ctx.endian();
         let reader_clone = reader.clone();
         let mut reader_mut = reader.borrow_mut();
         let base_offset = reader_mut.stream_position()? as usize;
@@ -267,12 +282,12 @@ impl LcVariant {
         match cmd {
             LC_SEGMENT => {
                 std::mem::drop(reader_mut);
-                let c = LcSegment::parse(reader_clone, base_offset, object_file_offset, X64Context::Off(endian))?;
+                let c = LcSegment::parse(reader_clone, base_offset, object_file_offset, X64Context::Off(endian), cputype)?;
                 Ok(Self::Segment32(c))
             }
             LC_SEGMENT_64 => {
                 std::mem::drop(reader_mut);
-                let c = LcSegment::parse(reader_clone, base_offset, object_file_offset, X64Context::On(endian))?;
+                let c = LcSegment::parse(reader_clone, base_offset, object_file_offset, X64Context::On(endian), cputype)?;
                 Ok(Self::Segment64(c))
             }
             LC_ID_DYLIB => {
@@ -295,6 +310,16 @@ impl LcVariant {
                 let c = LcDylib::parse(reader_clone, command_offset, base_offset, endian)?;
                 Ok(Self::ReexportDylib(c))
             }
+            LC_LAZY_LOAD_DYLIB => {
+                std::mem::drop(reader_mut);
+                let c = LcDylib::parse(reader_clone, command_offset, base_offset, endian)?;
+                Ok(Self::LazyLoadDylib(c))
+            }
+            LC_LOAD_UPWARD_DYLIB => {
+                std::mem::drop(reader_mut);
+                let c = LcDylib::parse(reader_clone, command_offset, base_offset, endian)?;
+                Ok(Self::LoadUpwardDylib(c))
+            }
             LC_SUB_FRAMEWORK => {
                 std::mem::drop(reader_mut);
                 let c = LcSubframework::parse(reader_clone, command_offset, base_offset, endian)?;
@@ -356,7 +381,7 @@ impl LcVariant {
             LC_SYMTAB => {
                 std::mem::drop(reader_mut);
                 let c =
-                    LcSymtab::parse(reader_clone, is_64, base_offset, endian, object_file_offset)?;
+                    LcSymtab::parse(reader_clone, ctx.is_64(), base_offset, endian, object_file_offset)?;
                 Ok(Self::Symtab(c))
             }
             LC_DYSYMTAB => {
@@ -404,6 +429,14 @@ impl LcVariant {
                 let c = reader_mut.ioread_with(endian)?;
                 Ok(Self::LinkerOptimizationHint(c))
             }
+            LC_DYLD_EXPORTS_TRIE => {
+                let c = reader_mut.ioread_with(endian)?;
+                Ok(Self::DyldExportsTrie(c))
+            }
+            LC_DYLD_CHAINED_FIXUPS => {
+                let c = reader_mut.ioread_with(endian)?;
+                Ok(Self::DyldChainedFixups(c))
+            }
             LC_ENCRYPTION_INFO => {
                 let c = reader_mut.ioread_with(endian)?;
                 Ok(Self::EncryptionInfo(c))
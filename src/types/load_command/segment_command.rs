@@ -32,10 +32,11 @@ pub struct LcSegment {
     object_file_offset: u64,
     sects_offset: u64,
     ctx: X64Context,
+    cputype: CPUType,
 }
 
 impl LcSegment {
-    pub(super) fn parse(reader: RcReader, base_offset: usize, object_file_offset: u64, ctx: X64Context) -> Result<Self> {
+    pub(super) fn parse(reader: RcReader, base_offset: usize, object_file_offset: u64, ctx: X64Context, cputype: CPUType) -> Result<Self> {
         let endian = *ctx.endian();
         let reader_clone = reader.clone();
         let mut reader_mut = reader.borrow_mut();
@@ -72,6 +73,7 @@ impl LcSegment {
             object_file_offset,
             sects_offset,
             ctx,
+            cputype,
         })
     }
 }
@@ -100,6 +102,7 @@ impl LcSegment {
             self.sects_offset,
             self.object_file_offset,
             self.ctx,
+            self.cputype,
         )
     }
 }
@@ -111,12 +114,13 @@ pub struct SectionIterator {
     base_offset: u64,
     object_file_offset: u64,
     ctx: X64Context,
+    cputype: CPUType,
 
     current: u32,
 }
 
 impl SectionIterator {
-    fn new(reader: RcReader, nsects: u32, base_offset: u64, object_file_offset: u64, ctx: X64Context) -> Self {
+    fn new(reader: RcReader, nsects: u32, base_offset: u64, object_file_offset: u64, ctx: X64Context, cputype: CPUType) -> Self {
         SectionIterator {
             reader,
             nsects,
@@ -124,6 +128,7 @@ impl SectionIterator {
             object_file_offset,
             current: 0,
             ctx,
+            cputype,
         }
     }
 }
@@ -146,7 +151,7 @@ impl Iterator for SectionIterator {
 
         std::mem::drop(reader_mut);
 
-        match Section::parse(self.reader.clone(), self.ctx, self.object_file_offset) {
+        match Section::parse(self.reader.clone(), self.ctx, self.cputype, self.object_file_offset) {
             Ok(sect) => Some(sect),
             Err(_) => return None,
         }
@@ -1,10 +1,16 @@
+use super::LcSegment;
+use super::LcSymtab;
+use super::LcVariant;
 use super::LoadCommand;
 use super::MachHeader;
+use super::CPUType;
+use super::Section;
 use super::RcReader;
 use super::Result;
+use crate::result::Error;
 
 use std::fmt::Debug;
-use std::io::{Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom};
 
 #[derive(Clone)]
 pub struct MachObject {
@@ -57,6 +63,7 @@ impl MachObject {
             self.header.sizeofcmds,
             self.header.magic.endian(),
             self.header.magic.is_64(),
+            self.header.cputype,
             self.base_offset,
         )
     }
@@ -64,6 +71,155 @@ impl MachObject {
     pub fn segments_iterator(&self) -> SegmentIterator {
         SegmentIterator
     }
+
+    /// Sections of all segments in order of appearance.
+    /// Index of section in result is section ordinal (as in `n_sect` or `r_symbolnum`) minus one
+    pub fn sections(&self) -> Vec<Section> {
+        self.load_commands_iterator()
+            .flat_map(|cmd| match cmd.variant {
+                LcVariant::Segment32(seg) | LcVariant::Segment64(seg) => {
+                    seg.sections_iterator().collect()
+                }
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// Returns `LC_SYMTAB` command if exists
+    pub fn symtab(&self) -> Option<LcSymtab> {
+        self.load_commands_iterator().find_map(|cmd| match cmd.variant {
+            LcVariant::Symtab(symtab) => Some(symtab),
+            _ => None,
+        })
+    }
+
+    /// Returns UUID of `LC_UUID` command if exists
+    pub fn uuid(&self) -> Option<[u8; 16]> {
+        self.load_commands_iterator().find_map(|cmd| match cmd.variant {
+            LcVariant::Uuid(uuid) => Some(uuid.uuid.0),
+            _ => None,
+        })
+    }
+
+    /// Segments in order of appearance.
+    /// Index of segment in result is segment index used by dyld info and chained fixups
+    pub fn segments(&self) -> Vec<LcSegment> {
+        self.load_commands_iterator()
+            .filter_map(|cmd| match cmd.variant {
+                LcVariant::Segment32(seg) | LcVariant::Segment64(seg) => Some(seg),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Segment whose virtual memory range contains `vmaddr`
+    pub fn segment_for_vmaddr(&self, vmaddr: u64) -> Option<LcSegment> {
+        self.segments()
+            .into_iter()
+            .find(|seg| vmaddr >= seg.vmaddr.0 && vmaddr - seg.vmaddr.0 < seg.vmsize.0)
+    }
+
+    /// Section containing `vmaddr`
+    pub fn section_for_vmaddr(&self, vmaddr: u64) -> Option<Section> {
+        self.sections()
+            .into_iter()
+            .find(|sect| vmaddr >= sect.addr.0 && vmaddr - sect.addr.0 < sect.size.0)
+    }
+
+    /// Offset of `vmaddr` from start of the object (not of the fat file).
+    /// `None` if the address isn't mapped or it's in zero-fill part of segment.
+    pub fn vmaddr_to_offset(&self, vmaddr: u64) -> Option<u64> {
+        let segment = self.segment_for_vmaddr(vmaddr)?;
+        let delta = vmaddr - segment.vmaddr.0;
+        match delta < segment.filesize.0 {
+            true => Some(segment.fileoff.0 + delta),
+            false => None,
+        }
+    }
+
+    /// Virtual address, that offset from start of the object (not of the fat file) is mapped to
+    pub fn offset_to_vmaddr(&self, offset: u64) -> Option<u64> {
+        self.segments()
+            .into_iter()
+            .filter(|seg| seg.vmsize.0 > 0)
+            .find(|seg| offset >= seg.fileoff.0 && offset - seg.fileoff.0 < seg.filesize.0)
+            .map(|seg| seg.vmaddr.0 + (offset - seg.fileoff.0))
+    }
+
+    /// Reads `size` bytes at `vmaddr`. Zero-fill part of segment is read as zeros.
+    /// The whole range should be in the same segment.
+    pub fn read_bytes_at_vmaddr(&self, vmaddr: u64, size: usize) -> Result<Vec<u8>> {
+        let segment = match self.segment_for_vmaddr(vmaddr) {
+            Some(segment) => segment,
+            None => return Err(not_mapped(vmaddr)),
+        };
+        let delta = vmaddr - segment.vmaddr.0;
+        if delta + size as u64 > segment.vmsize.0 {
+            return Err(not_mapped(vmaddr + size as u64));
+        }
+
+        let in_file = segment.filesize.0.saturating_sub(delta).min(size as u64);
+        let mut result = match in_file {
+            0 => Vec::with_capacity(size),
+            _ => self.read_bytes(segment.fileoff.0 + delta, in_file as usize)?,
+        };
+        result.resize(size, 0);
+        Ok(result)
+    }
+
+    /// Reads zero terminated string at `vmaddr`.
+    /// String ends at the end of segment data, if there is no terminating zero.
+    pub fn read_cstring_at_vmaddr(&self, vmaddr: u64) -> Result<String> {
+        const CHUNK_SIZE: u64 = 256;
+
+        let segment = match self.segment_for_vmaddr(vmaddr) {
+            Some(segment) => segment,
+            None => return Err(not_mapped(vmaddr)),
+        };
+        let end = segment.fileoff.0 + segment.filesize.0;
+        let mut offset = segment.fileoff.0 + (vmaddr - segment.vmaddr.0);
+
+        let mut bytes = Vec::new();
+        while offset < end {
+            let chunk = self.read_bytes(offset, CHUNK_SIZE.min(end - offset) as usize)?;
+            match chunk.iter().position(|b| *b == 0) {
+                Some(zero) => {
+                    bytes.extend_from_slice(&chunk[..zero]);
+                    break;
+                }
+                None => bytes.extend_from_slice(&chunk),
+            }
+            offset += chunk.len() as u64;
+        }
+
+        Ok(String::from_utf8_lossy(&bytes).to_string())
+    }
+
+    /// Reads everything from start of the object to the end of file
+    pub(crate) fn read_to_end(&self) -> Result<Vec<u8>> {
+        let mut reader = self.reader.borrow_mut();
+        reader.seek(SeekFrom::Start(self.base_offset))?;
+
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        Ok(buf)
+    }
+
+    /// Reads `size` bytes at `offset` from start of the object (not of the fat file)
+    pub(crate) fn read_bytes(&self, offset: u64, size: usize) -> Result<Vec<u8>> {
+        let mut reader = self.reader.borrow_mut();
+        reader.seek(SeekFrom::Start(self.base_offset + offset))?;
+
+        let mut buf = vec![0u8; size];
+        reader.read_exact(&mut buf)?;
+
+        Ok(buf)
+    }
+}
+
+fn not_mapped(vmaddr: u64) -> Error {
+    Error::Text(format!("Address {vmaddr:#x} is not mapped"))
 }
 
 impl Debug for MachObject {
@@ -83,6 +239,7 @@ pub struct LoadCommandIterator {
     end_offset: usize,
     endian: scroll::Endian,
     is_64: bool,
+    cputype: CPUType,
     object_file_offset: u64,
 }
 
@@ -93,6 +250,7 @@ impl LoadCommandIterator {
         size_of_cmds: u32,
         endian: scroll::Endian,
         is_64: bool,
+        cputype: CPUType,
         object_file_offset: u64,
     ) -> LoadCommandIterator {
         LoadCommandIterator {
@@ -101,6 +259,7 @@ impl LoadCommandIterator {
             end_offset: base_offset + size_of_cmds as usize,
             endian,
             is_64,
+            cputype,
             object_file_offset,
         }
     }
@@ -119,6 +278,7 @@ impl Iterator for LoadCommandIterator {
             self.current_offset,
             self.endian,
             self.is_64,
+            self.cputype,
             self.object_file_offset,
         )
         .unwrap();
@@ -130,3 +290,53 @@ impl Iterator for LoadCommandIterator {
 }
 
 pub struct SegmentIterator;
+
+#[cfg(test)]
+mod test {
+    use crate::test_utils::parse;
+    use crate::*;
+
+    fn parse_slice(arch: &str) -> MachObject {
+        parse("testable/cat").mach_object_with_arch(arch).unwrap()
+    }
+
+    #[test]
+    fn test_vmaddr_translation() {
+        // Second slice of fat file, offsets are relative to the slice
+        let object = parse_slice("arm64e");
+        assert_eq!(object.vmaddr_to_offset(0x100000000), Some(0));
+        assert_eq!(object.vmaddr_to_offset(0x100004010), Some(0x4010));
+        assert_eq!(object.offset_to_vmaddr(0x4010), Some(0x100004010));
+        // __PAGEZERO has no file contents
+        assert_eq!(object.vmaddr_to_offset(0x1000), None);
+        assert_eq!(object.vmaddr_to_offset(0x200000000), None);
+
+        let segment = object.segment_for_vmaddr(0x100000010).unwrap();
+        assert_eq!(segment.segname.to_string(), "__TEXT");
+        let section = object.section_for_vmaddr(0x100000000);
+        assert!(section.is_none());
+    }
+
+    #[test]
+    fn test_read_at_vmaddr() {
+        let object = parse_slice("arm64e");
+        let magic = object.read_bytes_at_vmaddr(0x100000000, 4).unwrap();
+        assert_eq!(magic, vec![0xcf, 0xfa, 0xed, 0xfe]);
+        assert_eq!(object.read_bytes_at_vmaddr(0x1000, 2).unwrap(), vec![0, 0]);
+        assert!(object.read_bytes_at_vmaddr(0x200000000, 1).is_err());
+
+        let cstring = object
+            .sections()
+            .into_iter()
+            .find(|s| s.sectname.to_string() == "__cstring")
+            .unwrap();
+        let mut data = Vec::new();
+        cstring.read_data_to(&mut data).unwrap();
+        let expected = data.split(|b| *b == 0).next().unwrap();
+
+        let string = object.read_cstring_at_vmaddr(cstring.addr.0).unwrap();
+        assert_eq!(string.as_bytes(), expected);
+        let section = object.section_for_vmaddr(cstring.addr.0 + 1).unwrap();
+        assert_eq!(section.sectname.to_string(), "__cstring");
+    }
+}
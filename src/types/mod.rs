@@ -38,6 +38,65 @@ pub mod dylib;
 
 pub mod reloc;
 
+pub mod chained_fixups;
+pub use chained_fixups::*;
+
+pub mod bind_info;
+pub use bind_info::*;
+
+mod image;
+
+pub mod objc;
+
+pub mod swift;
+
+pub mod strings;
+
+pub mod dwarf;
+
+pub mod function_starts;
+
+pub mod exports;
+
+pub mod symbolicator;
+
+pub mod dsym;
+
+pub mod debug_map;
+
+pub mod data_in_code;
+
+pub mod indirect_symbols;
+
+pub mod disasm;
+
+pub mod imports;
+
+pub mod dependencies;
+
+pub mod tbd;
+
+pub mod link_check;
+
+pub mod abi;
+
+pub mod object_diff;
+
+pub mod size_report;
+
+pub mod writer;
+
+pub mod edit;
+
+pub mod lipo;
+
+pub mod strip;
+
+pub mod build_version;
+
+#[cfg(test)]
+pub(crate) mod test_utils;
+
 pub use super::fmt_ext;
 
 pub use super::auto_enum_fields;
@@ -55,7 +114,7 @@ impl ObjectType {
         let magic = reader.borrow_mut().ioread_with::<u32>(scroll::BE)?;
         let magic: Magic = magic.try_into()?;
         if magic.is_fat() {
-            let header = FatObject::parse(reader.clone())?;
+            let header = FatObject::parse(reader.clone(), magic)?;
             Ok(ObjectType::Fat(header))
         } else {
             let header = MachObject::parse(reader.clone(), 0)?;
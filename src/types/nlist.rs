@@ -137,6 +137,29 @@ impl Nlist {
             name,
         })
     }
+
+    /// Demangled name of the symbol (C++, Swift or Rust).
+    /// `None` if the symbol has no name or the name isn't mangled.
+    pub fn demangled_name(&self) -> Option<String> {
+        let name = self.name.as_ref()?.load_string().ok()?;
+        crate::demangle::demangle(&name)
+    }
+
+    /// `GET_LIBRARY_ORDINAL`. In two-level namespace images it's the number of the library,
+    /// that provides undefined symbol, or one of `*_ORDINAL` constants. See [`Nlist::n_desc`]
+    pub fn library_ordinal(&self) -> u8 {
+        ((self.n_desc >> 8) & 0xff) as u8
+    }
+
+    /// See [`N_WEAK_REF`]
+    pub fn is_weak_ref(&self) -> bool {
+        self.n_desc & N_WEAK_REF > 0
+    }
+
+    /// See [`N_WEAK_DEF`]
+    pub fn is_weak_def(&self) -> bool {
+        self.n_desc & N_WEAK_DEF > 0
+    }
 }
 
 pub mod constants {
@@ -151,6 +174,18 @@ pub mod constants {
     pub const N_PBUD: u8 = 0xc;
     pub const N_INDR: u8 = 0xa;
 
+    /// Mask of reference type bits of `n_desc`
+    pub const REFERENCE_TYPE: u16 = 0x7;
+    pub const REFERENCED_DYNAMICALLY: u16 = 0x10;
+    pub const N_WEAK_REF: u16 = 0x40;
+    pub const N_WEAK_DEF: u16 = 0x80;
+
+    /// Library ordinal of symbols defined in the image itself
+    pub const SELF_LIBRARY_ORDINAL: u8 = 0x0;
+    pub const MAX_LIBRARY_ORDINAL: u8 = 0xfd;
+    pub const DYNAMIC_LOOKUP_ORDINAL: u8 = 0xfe;
+    pub const EXECUTABLE_ORDINAL: u8 = 0xff;
+
     /// Symbolic debugger symbols.  The comments give the conventional use for
     ///
     ///.stabs "n_name", n_type, n_sect, n_desc, n_value
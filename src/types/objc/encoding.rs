@@ -0,0 +1,500 @@
+//! Objective-C type encodings, as produced by `@encode` and stored in method, ivar and property metadata.
+//! Described in "Type Encodings" section of Objective-C Runtime Programming Guide.
+
+use crate::result::{Error, Result};
+
+/// Decoded type encoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjcType {
+    Char,
+    Int,
+    Short,
+    Long,
+    LongLong,
+    Int128,
+    UChar,
+    UInt,
+    UShort,
+    ULong,
+    ULongLong,
+    UInt128,
+    Float,
+    Double,
+    LongDouble,
+    Bool,
+    Void,
+    /// `char *`
+    CString,
+    /// `id`, optionally with class name and protocols, like `NSObject<NSCopying> *`
+    Object {
+        class_name: Option<String>,
+        protocols: Vec<String>,
+    },
+    /// Block, with return and argument types if extended encoding is available
+    Block(Option<Vec<ObjcType>>),
+    Class,
+    Selector,
+    Array(u64, Box<ObjcType>),
+    Struct(String, Vec<ObjcField>),
+    Union(String, Vec<ObjcField>),
+    Bitfield(u32),
+    Pointer(Box<ObjcType>),
+    /// Function pointers and other types without encoding
+    Unknown,
+    /// Type with qualifiers like `const` or `out`
+    Qualified(Vec<ObjcQualifier>, Box<ObjcType>),
+}
+
+/// Field of structure or union. Names are available only for ivar encodings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjcField {
+    pub name: Option<String>,
+    pub field_type: ObjcType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjcQualifier {
+    Const,
+    In,
+    Inout,
+    Out,
+    Bycopy,
+    Byref,
+    Oneway,
+    Atomic,
+    Complex,
+}
+
+impl ObjcQualifier {
+    fn new(c: u8) -> Option<Self> {
+        match c {
+            b'r' => Some(Self::Const),
+            b'n' => Some(Self::In),
+            b'N' => Some(Self::Inout),
+            b'o' => Some(Self::Out),
+            b'O' => Some(Self::Bycopy),
+            b'R' => Some(Self::Byref),
+            b'V' => Some(Self::Oneway),
+            b'A' => Some(Self::Atomic),
+            b'j' => Some(Self::Complex),
+            _ => None,
+        }
+    }
+
+    fn keyword(&self) -> &'static str {
+        match self {
+            Self::Const => "const",
+            Self::In => "in",
+            Self::Inout => "inout",
+            Self::Out => "out",
+            Self::Bycopy => "bycopy",
+            Self::Byref => "byref",
+            Self::Oneway => "oneway",
+            Self::Atomic => "_Atomic",
+            Self::Complex => "_Complex",
+        }
+    }
+}
+
+impl ObjcType {
+    /// Parses single type, like ivar type
+    pub fn parse(encoding: &str) -> Result<ObjcType> {
+        let mut decoder = Decoder::new(encoding);
+        let result = decoder.parse_type(false)?;
+        match decoder.is_at_end() {
+            true => Ok(result),
+            false => Err(decoder.error()),
+        }
+    }
+
+    /// Parses method type encoding, like `v24@0:8@16`.
+    /// Returns return type followed by types of all arguments, including `self` and `_cmd`.
+    pub fn parse_method(encoding: &str) -> Result<Vec<ObjcType>> {
+        let mut decoder = Decoder::new(encoding);
+        let mut result = Vec::new();
+        while !decoder.is_at_end() {
+            result.push(decoder.parse_type(false)?);
+            decoder.skip_offset();
+        }
+        Ok(result)
+    }
+
+    /// C declaration of variable with this type, like `NSString *name` or `char name[16]`
+    pub fn declaration(&self, name: &str) -> String {
+        match self {
+            ObjcType::Array(count, element) => element.declaration(&format!("{name}[{count}]")),
+            ObjcType::Pointer(pointee) => match pointee.as_ref() {
+                ObjcType::Unknown => format!("void (*{name})(void)"),
+                ObjcType::Array(..) => pointee.declaration(&format!("(*{name})")),
+                _ => pointee.declaration(&format!("*{name}")),
+            },
+            ObjcType::Bitfield(width) => join("unsigned int", &format!("{name} : {width}")),
+            ObjcType::Block(Some(types)) if !types.is_empty() => {
+                // The first argument is the block itself
+                let arguments: Vec<String> =
+                    types.iter().skip(2).map(|t| t.declaration("")).collect();
+                let arguments = match arguments.is_empty() {
+                    true => "void".to_string(),
+                    false => arguments.join(", "),
+                };
+                types[0].declaration(&format!("(^{name})({arguments})"))
+            }
+            ObjcType::Object {
+                class_name: Some(class_name),
+                protocols,
+            } => format!("{class_name}{} *{name}", protocols_string(protocols)),
+            ObjcType::Object {
+                class_name: None,
+                protocols,
+            } => join(&format!("id{}", protocols_string(protocols)), name),
+            ObjcType::Qualified(qualifiers, inner) => {
+                let qualifiers: Vec<&str> = qualifiers.iter().map(|q| q.keyword()).collect();
+                format!("{} {}", qualifiers.join(" "), inner.declaration(name))
+            }
+            _ => join(&self.base_name(), name),
+        }
+    }
+
+    fn base_name(&self) -> String {
+        match self {
+            ObjcType::Char => "char".to_string(),
+            ObjcType::Int => "int".to_string(),
+            ObjcType::Short => "short".to_string(),
+            ObjcType::Long => "long".to_string(),
+            ObjcType::LongLong => "long long".to_string(),
+            ObjcType::Int128 => "__int128".to_string(),
+            ObjcType::UChar => "unsigned char".to_string(),
+            ObjcType::UInt => "unsigned int".to_string(),
+            ObjcType::UShort => "unsigned short".to_string(),
+            ObjcType::ULong => "unsigned long".to_string(),
+            ObjcType::ULongLong => "unsigned long long".to_string(),
+            ObjcType::UInt128 => "unsigned __int128".to_string(),
+            ObjcType::Float => "float".to_string(),
+            ObjcType::Double => "double".to_string(),
+            ObjcType::LongDouble => "long double".to_string(),
+            ObjcType::Bool => "_Bool".to_string(),
+            ObjcType::Void => "void".to_string(),
+            ObjcType::CString => "char *".to_string(),
+            ObjcType::Block(_) => "id".to_string(),
+            ObjcType::Class => "Class".to_string(),
+            ObjcType::Selector => "SEL".to_string(),
+            ObjcType::Struct(name, fields) => aggregate_name("struct", name, fields),
+            ObjcType::Union(name, fields) => aggregate_name("union", name, fields),
+            ObjcType::Unknown => "void".to_string(),
+            // Declarations of the rest depend on name
+            _ => self.declaration(""),
+        }
+    }
+
+    /// Names of classes referenced by the type, including ones in structure fields
+    pub fn class_names(&self) -> Vec<String> {
+        match self {
+            ObjcType::Object {
+                class_name: Some(class_name),
+                ..
+            } => vec![class_name.clone()],
+            ObjcType::Array(_, inner) | ObjcType::Pointer(inner) | ObjcType::Qualified(_, inner) => {
+                inner.class_names()
+            }
+            ObjcType::Block(Some(types)) => types.iter().flat_map(|t| t.class_names()).collect(),
+            ObjcType::Struct(_, fields) | ObjcType::Union(_, fields) => fields
+                .iter()
+                .flat_map(|f| f.field_type.class_names())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Names of protocols referenced by the type
+    pub fn protocol_names(&self) -> Vec<String> {
+        match self {
+            ObjcType::Object { protocols, .. } => protocols.clone(),
+            ObjcType::Array(_, inner) | ObjcType::Pointer(inner) | ObjcType::Qualified(_, inner) => {
+                inner.protocol_names()
+            }
+            ObjcType::Block(Some(types)) => types.iter().flat_map(|t| t.protocol_names()).collect(),
+            ObjcType::Struct(_, fields) | ObjcType::Union(_, fields) => fields
+                .iter()
+                .flat_map(|f| f.field_type.protocol_names())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn join(type_name: &str, name: &str) -> String {
+    match (name.is_empty(), type_name.ends_with('*')) {
+        (true, _) => type_name.to_string(),
+        (false, true) => format!("{type_name}{name}"),
+        (false, false) => format!("{type_name} {name}"),
+    }
+}
+
+fn protocols_string(protocols: &[String]) -> String {
+    match protocols.is_empty() {
+        true => "".to_string(),
+        false => format!("<{}>", protocols.join(", ")),
+    }
+}
+
+/// Named aggregates are referenced by name, anonymous ones are expanded
+fn aggregate_name(keyword: &str, name: &str, fields: &[ObjcField]) -> String {
+    if name != "?" && !name.is_empty() {
+        return format!("{keyword} {name}");
+    }
+
+    let fields: Vec<String> = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let name = match &field.name {
+                Some(name) => name.clone(),
+                None => format!("field{}", index + 1),
+            };
+            format!("{};", field.field_type.declaration(&name))
+        })
+        .collect();
+    format!("{keyword} {{ {} }}", fields.join(" "))
+}
+
+struct Decoder<'a> {
+    encoding: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(encoding: &'a str) -> Self {
+        Decoder {
+            encoding,
+            bytes: encoding.as_bytes(),
+            position: 0,
+        }
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Result<u8> {
+        let c = self.peek().ok_or_else(|| self.error())?;
+        self.position += 1;
+        Ok(c)
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<()> {
+        match self.next()? == expected {
+            true => Ok(()),
+            false => Err(self.error()),
+        }
+    }
+
+    fn error(&self) -> Error {
+        Error::Text(format!(
+            "Invalid type encoding \"{}\" at position {}",
+            self.encoding, self.position
+        ))
+    }
+
+    /// Reads characters until `end`, which is consumed but not included
+    fn read_until(&mut self, end: u8) -> Result<String> {
+        let start = self.position;
+        while self.next()? != end {}
+        Ok(self.encoding[start..self.position - 1].to_string())
+    }
+
+    fn read_number(&mut self) -> Result<u64> {
+        let start = self.position;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        self.encoding[start..self.position]
+            .parse()
+            .map_err(|_| self.error())
+    }
+
+    /// Skips stack offset after type in method encoding
+    fn skip_offset(&mut self) {
+        if matches!(self.peek(), Some(b'-') | Some(b'+')) {
+            self.position += 1;
+        }
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.position += 1;
+        }
+    }
+
+    /// `in_named_fields` tells if quoted string after `@` may be name of the next field
+    fn parse_type(&mut self, in_named_fields: bool) -> Result<ObjcType> {
+        let mut qualifiers = Vec::new();
+        while let Some(qualifier) = self.peek().and_then(ObjcQualifier::new) {
+            qualifiers.push(qualifier);
+            self.position += 1;
+        }
+
+        let result = match self.next()? {
+            b'c' => ObjcType::Char,
+            b'i' => ObjcType::Int,
+            b's' => ObjcType::Short,
+            b'l' => ObjcType::Long,
+            b'q' => ObjcType::LongLong,
+            b't' => ObjcType::Int128,
+            b'C' => ObjcType::UChar,
+            b'I' => ObjcType::UInt,
+            b'S' => ObjcType::UShort,
+            b'L' => ObjcType::ULong,
+            b'Q' => ObjcType::ULongLong,
+            b'T' => ObjcType::UInt128,
+            b'f' => ObjcType::Float,
+            b'd' => ObjcType::Double,
+            b'D' => ObjcType::LongDouble,
+            b'B' => ObjcType::Bool,
+            b'v' => ObjcType::Void,
+            b'*' => ObjcType::CString,
+            b'#' => ObjcType::Class,
+            b':' => ObjcType::Selector,
+            b'?' => ObjcType::Unknown,
+            b'@' => self.parse_object(in_named_fields)?,
+            b'^' => ObjcType::Pointer(Box::new(self.parse_type(false)?)),
+            b'b' => ObjcType::Bitfield(self.read_number()? as u32),
+            b'[' => {
+                let count = self.read_number()?;
+                let element = self.parse_type(false)?;
+                self.expect(b']')?;
+                ObjcType::Array(count, Box::new(element))
+            }
+            b'{' => {
+                let (name, fields) = self.parse_aggregate(b'}')?;
+                ObjcType::Struct(name, fields)
+            }
+            b'(' => {
+                let (name, fields) = self.parse_aggregate(b')')?;
+                ObjcType::Union(name, fields)
+            }
+            _ => {
+                self.position -= 1;
+                return Err(self.error());
+            }
+        };
+
+        match qualifiers.is_empty() {
+            true => Ok(result),
+            false => Ok(ObjcType::Qualified(qualifiers, Box::new(result))),
+        }
+    }
+
+    fn parse_object(&mut self, in_named_fields: bool) -> Result<ObjcType> {
+        match self.peek() {
+            Some(b'?') => {
+                self.position += 1;
+                if self.peek() != Some(b'<') {
+                    return Ok(ObjcType::Block(None));
+                }
+                self.position += 1;
+                let mut types = Vec::new();
+                while self.peek() != Some(b'>') {
+                    types.push(self.parse_type(false)?);
+                    self.skip_offset();
+                }
+                self.position += 1;
+                Ok(ObjcType::Block(Some(types)))
+            }
+            Some(b'"') => {
+                // In structures with field names `"name"` may belong to the next field
+                let start = self.position;
+                self.position += 1;
+                let quoted = self.read_until(b'"')?;
+                let is_class_name = !in_named_fields
+                    || matches!(self.peek(), None | Some(b'"') | Some(b'}') | Some(b')'));
+                if !is_class_name {
+                    self.position = start;
+                    return Ok(Self::object(None, Vec::new()));
+                }
+                Ok(Self::parse_class_name(&quoted))
+            }
+            _ => Ok(Self::object(None, Vec::new())),
+        }
+    }
+
+    fn object(class_name: Option<String>, protocols: Vec<String>) -> ObjcType {
+        ObjcType::Object {
+            class_name,
+            protocols,
+        }
+    }
+
+    /// Parses `NSObject<NSCopying><NSCoding>` or `<Protocol>`
+    fn parse_class_name(quoted: &str) -> ObjcType {
+        let mut parts = quoted.split('<');
+        let class_name = parts
+            .next()
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_string());
+        let protocols = parts.map(|p| p.trim_end_matches('>').to_string()).collect();
+        Self::object(class_name, protocols)
+    }
+
+    fn parse_aggregate(&mut self, end: u8) -> Result<(String, Vec<ObjcField>)> {
+        let start = self.position;
+        while !matches!(self.peek(), Some(b'=')) && self.peek() != Some(end) {
+            self.next()?;
+        }
+        let name = self.encoding[start..self.position].to_string();
+
+        let mut fields = Vec::new();
+        if self.next()? == b'=' {
+            while self.peek() != Some(end) {
+                let name = match self.peek() {
+                    Some(b'"') => {
+                        self.position += 1;
+                        Some(self.read_until(b'"')?)
+                    }
+                    _ => None,
+                };
+                let in_named_fields = name.is_some();
+                fields.push(ObjcField {
+                    name,
+                    field_type: self.parse_type(in_named_fields)?,
+                });
+            }
+            self.position += 1;
+        }
+
+        Ok((name, fields))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_type_declarations() {
+        let declaration = |encoding: &str| ObjcType::parse(encoding).unwrap().declaration("x");
+
+        assert_eq!(declaration("@\"NSString\""), "NSString *x");
+        assert_eq!(declaration("@\"<NSCopying>\""), "id<NSCopying> x");
+        assert_eq!(declaration("@\"NSObject<A><B>\""), "NSObject<A, B> *x");
+        assert_eq!(declaration("[16c]"), "char x[16]");
+        assert_eq!(declaration("^{CGPoint=dd}"), "struct CGPoint *x");
+        assert_eq!(declaration("r*"), "const char *x");
+        assert_eq!(declaration("b3"), "unsigned int x : 3");
+        assert_eq!(declaration("^?"), "void (*x)(void)");
+        assert_eq!(
+            declaration("{?=\"a\"@\"b\"i}"),
+            "struct { id a; int b; } x"
+        );
+        assert_eq!(
+            declaration("{?=\"a\"@\"NSArray\"\"b\"i}"),
+            "struct { NSArray *a; int b; } x"
+        );
+        assert_eq!(declaration("@?<v@?@\"NSError\">"), "void (^x)(NSError *)");
+
+        let method = ObjcType::parse_method("v24@0:8@?<v@?B>16").unwrap();
+        assert_eq!(method.len(), 4);
+        assert_eq!(method[3].declaration(""), "void (^)(_Bool)");
+    }
+}
@@ -0,0 +1,648 @@
+//! Objective-C runtime metadata (`objc2` ABI), stored in `__objc_*` sections.
+//! Layout of structures is described in `objc-runtime-new.h` of `objc4`.
+
+pub mod encoding;
+
+use super::image::{Image, Pointer};
+use super::primitives::Hu32;
+use super::MachObject;
+use crate::result::{Error, Result};
+
+pub const OBJC_SECT_CLASSLIST: &str = "__objc_classlist";
+pub const OBJC_SECT_CATLIST: &str = "__objc_catlist";
+pub const OBJC_SECT_PROTOLIST: &str = "__objc_protolist";
+pub const OBJC_SECT_SELREFS: &str = "__objc_selrefs";
+pub const OBJC_SECT_CLASSREFS: &str = "__objc_classrefs";
+pub const OBJC_SECT_SUPERREFS: &str = "__objc_superrefs";
+pub const OBJC_SECT_IMAGEINFO: &str = "__objc_imageinfo";
+
+/// `class_ro_t` flags
+pub const RO_META: u32 = 1 << 0;
+pub const RO_ROOT: u32 = 1 << 1;
+pub const RO_HAS_CXX_STRUCTORS: u32 = 1 << 2;
+pub const RO_HIDDEN: u32 = 1 << 4;
+pub const RO_EXCEPTION: u32 = 1 << 5;
+pub const RO_HAS_SWIFT_INITIALIZER: u32 = 1 << 6;
+pub const RO_IS_ARC: u32 = 1 << 7;
+pub const RO_HAS_CXX_DTOR_ONLY: u32 = 1 << 8;
+pub const RO_HAS_WEAK_WITHOUT_ARC: u32 = 1 << 9;
+
+/// `objc_image_info` flags
+pub const OBJC_IMAGE_IS_REPLACEMENT: u32 = 1 << 0;
+pub const OBJC_IMAGE_SUPPORTS_GC: u32 = 1 << 1;
+pub const OBJC_IMAGE_REQUIRES_GC: u32 = 1 << 2;
+pub const OBJC_IMAGE_OPTIMIZED_BY_DYLD: u32 = 1 << 3;
+pub const OBJC_IMAGE_IS_SIMULATED: u32 = 1 << 5;
+pub const OBJC_IMAGE_HAS_CATEGORY_CLASS_PROPERTIES: u32 = 1 << 6;
+
+/// Flags of `method_list_t::entsizeAndFlags`
+const METHOD_LIST_IS_SMALL: u32 = 0x8000_0000;
+const METHOD_LIST_USES_DIRECT_SELECTORS: u32 = 0x4000_0000;
+const METHOD_LIST_FLAGS_MASK: u32 = 0xffff_0003;
+
+/// Masks of `objc_class::bits`
+const FAST_IS_SWIFT_MASK: u64 = 0x3;
+const FAST_DATA_MASK_64: u64 = 0x0000_7fff_ffff_fff8;
+const FAST_DATA_MASK_32: u64 = 0xffff_fffc;
+
+/// Sanity limit for count of entries in lists
+const MAX_LIST_COUNT: u32 = 0x10_0000;
+
+const CLASS_SYMBOL_PREFIX: &str = "_OBJC_CLASS_$_";
+const METACLASS_SYMBOL_PREFIX: &str = "_OBJC_METACLASS_$_";
+const PROTOCOL_SYMBOL_PREFIX: &str = "__OBJC_PROTOCOL_$_";
+
+/// `objc_image_info`
+#[derive(Debug, Clone)]
+pub struct ObjcImageInfo {
+    pub version: u32,
+    pub flags: Hu32,
+}
+
+impl ObjcImageInfo {
+    /// Swift ABI version the image was compiled with, `0` for pure ObjC images
+    pub fn swift_abi_version(&self) -> u8 {
+        (self.flags.0 >> 8) as u8
+    }
+
+    pub fn is_simulated(&self) -> bool {
+        self.flags.0 & OBJC_IMAGE_IS_SIMULATED != 0
+    }
+
+    pub fn has_category_class_properties(&self) -> bool {
+        self.flags.0 & OBJC_IMAGE_HAS_CATEGORY_CLASS_PROPERTIES != 0
+    }
+}
+
+/// `method_t`, both pointer-based and relative (small) forms
+#[derive(Debug, Clone)]
+pub struct ObjcMethod {
+    pub name: String,
+    /// Type encoding, like `v16@0:8`
+    pub types: String,
+    /// Extended type encoding with class names of objects, available only for protocol methods
+    pub extended_types: Option<String>,
+    /// Address of implementation, `None` for protocol methods or unresolved
+    pub imp: Option<u64>,
+}
+
+/// `ivar_t`
+#[derive(Debug, Clone)]
+pub struct ObjcIvar {
+    pub name: String,
+    pub type_encoding: String,
+    /// Offset in instance, `None` if the offset variable is not available
+    pub offset: Option<u32>,
+    pub size: u32,
+    pub alignment: u32,
+}
+
+/// `property_t`
+#[derive(Debug, Clone)]
+pub struct ObjcProperty {
+    pub name: String,
+    /// Attributes string, like `T@"NSString",C,N,V_name`
+    pub attributes: String,
+}
+
+/// `objc_class` together with its `class_ro_t` and metaclass
+#[derive(Debug, Clone)]
+pub struct ObjcClass {
+    pub address: u64,
+    pub name: String,
+    /// Name of superclass, `None` for root classes
+    pub superclass: Option<String>,
+    /// `class_ro_t::flags`
+    pub flags: Hu32,
+    pub instance_start: u32,
+    pub instance_size: u32,
+    pub is_swift: bool,
+    pub protocols: Vec<String>,
+    pub ivars: Vec<ObjcIvar>,
+    pub properties: Vec<ObjcProperty>,
+    pub class_properties: Vec<ObjcProperty>,
+    pub instance_methods: Vec<ObjcMethod>,
+    pub class_methods: Vec<ObjcMethod>,
+}
+
+impl ObjcClass {
+    pub fn is_root(&self) -> bool {
+        self.flags.0 & RO_ROOT != 0
+    }
+}
+
+/// `category_t`
+#[derive(Debug, Clone)]
+pub struct ObjcCategory {
+    pub address: u64,
+    pub name: String,
+    /// Name of extended class
+    pub class_name: Option<String>,
+    pub protocols: Vec<String>,
+    pub properties: Vec<ObjcProperty>,
+    pub class_properties: Vec<ObjcProperty>,
+    pub instance_methods: Vec<ObjcMethod>,
+    pub class_methods: Vec<ObjcMethod>,
+}
+
+/// `protocol_t`
+#[derive(Debug, Clone)]
+pub struct ObjcProtocol {
+    pub address: u64,
+    pub name: String,
+    pub protocols: Vec<String>,
+    pub properties: Vec<ObjcProperty>,
+    pub class_properties: Vec<ObjcProperty>,
+    pub instance_methods: Vec<ObjcMethod>,
+    pub class_methods: Vec<ObjcMethod>,
+    pub optional_instance_methods: Vec<ObjcMethod>,
+    pub optional_class_methods: Vec<ObjcMethod>,
+}
+
+/// Entry of `__objc_selrefs`, `__objc_classrefs` or `__objc_superrefs`
+#[derive(Debug, Clone)]
+pub struct ObjcReference {
+    /// Address of the reference itself
+    pub address: u64,
+    /// Selector or class name, `None` if the reference can not be resolved
+    pub name: Option<String>,
+}
+
+/// All ObjC metadata of single [MachObject]
+#[derive(Debug, Default)]
+pub struct ObjcMetadata {
+    pub image_info: Option<ObjcImageInfo>,
+    pub classes: Vec<ObjcClass>,
+    pub categories: Vec<ObjcCategory>,
+    pub protocols: Vec<ObjcProtocol>,
+    pub selector_refs: Vec<ObjcReference>,
+    pub class_refs: Vec<ObjcReference>,
+    pub super_refs: Vec<ObjcReference>,
+}
+
+impl ObjcMetadata {
+    /// Parses metadata of `object`. Returns empty metadata if the object has no ObjC sections.
+    pub fn parse(object: &MachObject) -> Result<Self> {
+        let image = Image::build(object)?;
+        let parser = Parser { image: &image };
+
+        // The same section may be split between segments, like `__DATA` and `__DATA_CONST`
+        let sections = object.sections();
+        let list = |sectname: &str| -> Vec<(u64, u64)> {
+            sections
+                .iter()
+                .filter(|s| s.sectname.to_string() == sectname)
+                .map(|s| (s.addr.0, s.size.0))
+                .collect()
+        };
+
+        let image_info = match list(OBJC_SECT_IMAGEINFO).first() {
+            Some((addr, size)) if *size >= 8 => Some(ObjcImageInfo {
+                version: image.read_u32(*addr)?,
+                flags: Hu32(image.read_u32(addr + 4)?),
+            }),
+            _ => None,
+        };
+        let has_category_class_properties = image_info
+            .as_ref()
+            .map(|info| info.has_category_class_properties())
+            .unwrap_or(false);
+
+        let mut metadata = ObjcMetadata {
+            image_info,
+            ..Default::default()
+        };
+
+        for address in parser.list_pointers(&list(OBJC_SECT_CLASSLIST))? {
+            metadata.classes.push(parser.class(address)?);
+        }
+        for address in parser.list_pointers(&list(OBJC_SECT_CATLIST))? {
+            metadata
+                .categories
+                .push(parser.category(address, has_category_class_properties)?);
+        }
+        for address in parser.list_pointers(&list(OBJC_SECT_PROTOLIST))? {
+            metadata.protocols.push(parser.protocol(address)?);
+        }
+
+        metadata.selector_refs = parser.references(&list(OBJC_SECT_SELREFS), |p| parser.selector(p))?;
+        metadata.class_refs = parser.references(&list(OBJC_SECT_CLASSREFS), |p| parser.class_name(p))?;
+        metadata.super_refs = parser.references(&list(OBJC_SECT_SUPERREFS), |p| parser.class_name(p))?;
+
+        Ok(metadata)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.image_info.is_none()
+            && self.classes.is_empty()
+            && self.categories.is_empty()
+            && self.protocols.is_empty()
+    }
+}
+
+struct Parser<'a> {
+    image: &'a Image,
+}
+
+impl Parser<'_> {
+    fn ptr_size(&self) -> u64 {
+        self.image.pointer_size()
+    }
+
+    /// Address of `index`-th pointer-sized field of structure at `address`
+    fn field(&self, address: u64, index: u64) -> u64 {
+        address + index * self.ptr_size()
+    }
+
+    /// Targets of non-null pointers in list sections like `__objc_classlist`
+    fn list_pointers(&self, sections: &[(u64, u64)]) -> Result<Vec<u64>> {
+        let mut result = Vec::new();
+        for (addr, size) in sections {
+            for index in 0..size / self.ptr_size() {
+                let location = self.field(*addr, index);
+                match self.image.read_pointer(location)? {
+                    Pointer::Address(target) => result.push(target),
+                    Pointer::Null => (),
+                    Pointer::Symbol(name) => {
+                        return Err(Error::Text(format!(
+                            "Unexpected reference to external symbol {name} at {:#x}",
+                            location
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn references(
+        &self,
+        sections: &[(u64, u64)],
+        name: impl Fn(Pointer) -> Result<Option<String>>,
+    ) -> Result<Vec<ObjcReference>> {
+        let mut result = Vec::new();
+        for (addr, size) in sections {
+            for index in 0..size / self.ptr_size() {
+                let address = self.field(*addr, index);
+                let name = name(self.image.read_pointer(address)?)?;
+                result.push(ObjcReference { address, name });
+            }
+        }
+        Ok(result)
+    }
+
+    fn string(&self, pointer: Pointer) -> Result<Option<String>> {
+        match pointer {
+            Pointer::Address(address) => Ok(Some(self.image.read_cstring(address)?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn string_at(&self, address: u64) -> Result<String> {
+        Ok(self.string(self.image.read_pointer(address)?)?.unwrap_or_default())
+    }
+
+    fn selector(&self, pointer: Pointer) -> Result<Option<String>> {
+        self.string(pointer)
+    }
+
+    /// Name of class referenced by `pointer`, either defined in the object or imported
+    fn class_name(&self, pointer: Pointer) -> Result<Option<String>> {
+        match pointer {
+            Pointer::Null => Ok(None),
+            Pointer::Address(address) => {
+                let ro = self.class_ro(address)?;
+                Ok(Some(self.string_at(self.ro_field(ro, RoField::Name))?))
+            }
+            Pointer::Symbol(name) => {
+                let name = name
+                    .strip_prefix(CLASS_SYMBOL_PREFIX)
+                    .or_else(|| name.strip_prefix(METACLASS_SYMBOL_PREFIX))
+                    .unwrap_or(&name);
+                Ok(Some(name.to_string()))
+            }
+        }
+    }
+
+    fn protocol_name(&self, pointer: Pointer) -> Result<Option<String>> {
+        match pointer {
+            Pointer::Null => Ok(None),
+            Pointer::Address(address) => Ok(Some(self.string_at(self.field(address, 1))?)),
+            Pointer::Symbol(name) => {
+                let name = name.strip_prefix(PROTOCOL_SYMBOL_PREFIX).unwrap_or(&name);
+                Ok(Some(name.to_string()))
+            }
+        }
+    }
+}
+
+/// Indices of pointer fields of `class_ro_t` after `flags`, `instanceStart` and `instanceSize`
+#[derive(Clone, Copy)]
+enum RoField {
+    Name = 1,
+    BaseMethods = 2,
+    BaseProtocols = 3,
+    Ivars = 4,
+    BaseProperties = 6,
+}
+
+impl Parser<'_> {
+    /// `objc_class::bits`, which is address of `class_ro_t` with flags in low bits
+    fn class_bits(&self, address: u64) -> Result<u64> {
+        // isa, superclass, cache, vtable, bits
+        let bits = self.image.read_address(self.field(address, 4))?;
+        bits.ok_or_else(|| Error::Text(format!("Class at {:#x} has no read-only data", address)))
+    }
+
+    /// Address of `class_ro_t` of class at `address`
+    fn class_ro(&self, address: u64) -> Result<u64> {
+        let mask = match self.ptr_size() {
+            8 => FAST_DATA_MASK_64,
+            _ => FAST_DATA_MASK_32,
+        };
+        Ok(self.class_bits(address)? & mask)
+    }
+
+    fn ro_field(&self, ro: u64, field: RoField) -> u64 {
+        // On 64-bit there is `reserved` field after the three `uint32_t`
+        let header_size = match self.ptr_size() {
+            8 => 16,
+            _ => 12,
+        };
+        ro + header_size + field as u64 * self.ptr_size()
+    }
+
+    fn class(&self, address: u64) -> Result<ObjcClass> {
+        let bits = self.class_bits(address)?;
+        let ro = self.class_ro(address)?;
+
+        let superclass = self.class_name(self.image.read_pointer(self.field(address, 1))?)?;
+        let (class_methods, class_properties) = match self.image.read_pointer(address)? {
+            Pointer::Address(metaclass) => {
+                let meta_ro = self.class_ro(metaclass)?;
+                (
+                    self.methods(self.ro_field(meta_ro, RoField::BaseMethods))?,
+                    self.properties(self.ro_field(meta_ro, RoField::BaseProperties))?,
+                )
+            }
+            _ => (Vec::new(), Vec::new()),
+        };
+
+        Ok(ObjcClass {
+            address,
+            name: self.string_at(self.ro_field(ro, RoField::Name))?,
+            superclass,
+            flags: Hu32(self.image.read_u32(ro)?),
+            instance_start: self.image.read_u32(ro + 4)?,
+            instance_size: self.image.read_u32(ro + 8)?,
+            is_swift: bits & FAST_IS_SWIFT_MASK != 0,
+            protocols: self.protocol_names(self.ro_field(ro, RoField::BaseProtocols))?,
+            ivars: self.ivars(self.ro_field(ro, RoField::Ivars))?,
+            properties: self.properties(self.ro_field(ro, RoField::BaseProperties))?,
+            class_properties,
+            instance_methods: self.methods(self.ro_field(ro, RoField::BaseMethods))?,
+            class_methods,
+        })
+    }
+
+    fn category(&self, address: u64, has_class_properties: bool) -> Result<ObjcCategory> {
+        let class_properties = match has_class_properties {
+            true => self.properties(self.field(address, 6))?,
+            false => Vec::new(),
+        };
+
+        Ok(ObjcCategory {
+            address,
+            name: self.string_at(address)?,
+            class_name: self.class_name(self.image.read_pointer(self.field(address, 1))?)?,
+            instance_methods: self.methods(self.field(address, 2))?,
+            class_methods: self.methods(self.field(address, 3))?,
+            protocols: self.protocol_names(self.field(address, 4))?,
+            properties: self.properties(self.field(address, 5))?,
+            class_properties,
+        })
+    }
+
+    fn protocol(&self, address: u64) -> Result<ObjcProtocol> {
+        let mut instance_methods = self.methods(self.field(address, 3))?;
+        let mut class_methods = self.methods(self.field(address, 4))?;
+        let mut optional_instance_methods = self.methods(self.field(address, 5))?;
+        let mut optional_class_methods = self.methods(self.field(address, 6))?;
+
+        // `size` and `flags` follow `instanceProperties`, then optional fields
+        let size = self.image.read_u32(self.field(address, 8))? as u64;
+        let extended_offset = self.ptr_size() * 8 + 8;
+        let has_field = |index: u64| size >= extended_offset + (index + 1) * self.ptr_size();
+
+        if has_field(0) {
+            // Array of extended types is parallel to concatenation of all method lists
+            let extended_types = self.image.read_address(address + extended_offset)?;
+            if let Some(extended_types) = extended_types {
+                let methods = instance_methods
+                    .iter_mut()
+                    .chain(class_methods.iter_mut())
+                    .chain(optional_instance_methods.iter_mut())
+                    .chain(optional_class_methods.iter_mut());
+                for (index, method) in methods.enumerate() {
+                    let location = self.field(extended_types, index as u64);
+                    method.extended_types = self.string(self.image.read_pointer(location)?)?;
+                }
+            }
+        }
+        let class_properties = match has_field(2) {
+            true => self.properties(address + extended_offset + 2 * self.ptr_size())?,
+            false => Vec::new(),
+        };
+
+        Ok(ObjcProtocol {
+            address,
+            name: self.string_at(self.field(address, 1))?,
+            protocols: self.protocol_names(self.field(address, 2))?,
+            properties: self.properties(self.field(address, 7))?,
+            class_properties,
+            instance_methods,
+            class_methods,
+            optional_instance_methods,
+            optional_class_methods,
+        })
+    }
+}
+
+impl Parser<'_> {
+    /// Reads `entsize` and `count` of list, referenced by pointer at `location`
+    fn list_header(&self, location: u64) -> Result<Option<(u64, u32, u32)>> {
+        let list = match self.image.read_address(location)? {
+            Some(list) => list,
+            None => return Ok(None),
+        };
+        let entsize_and_flags = self.image.read_u32(list)?;
+        let count = self.image.read_u32(list + 4)?;
+        if count > MAX_LIST_COUNT {
+            return Err(Error::Text(format!(
+                "List at {:#x} has too many entries ({count})",
+                list
+            )));
+        }
+        Ok(Some((list, entsize_and_flags, count)))
+    }
+
+    /// `method_list_t`, referenced by pointer at `location`
+    fn methods(&self, location: u64) -> Result<Vec<ObjcMethod>> {
+        let (list, flags, count) = match self.list_header(location)? {
+            Some(header) => header,
+            None => return Ok(Vec::new()),
+        };
+        let entsize = (flags & !METHOD_LIST_FLAGS_MASK) as u64;
+        let is_small = flags & METHOD_LIST_IS_SMALL != 0;
+
+        let mut result = Vec::with_capacity(count as usize);
+        for index in 0..count as u64 {
+            let method = list + 8 + index * entsize;
+            let method = match is_small {
+                true => self.small_method(method, flags & METHOD_LIST_USES_DIRECT_SELECTORS != 0)?,
+                false => ObjcMethod {
+                    name: self.string_at(method)?,
+                    types: self.string_at(self.field(method, 1))?,
+                    extended_types: None,
+                    imp: self.image.read_address(self.field(method, 2))?,
+                },
+            };
+            result.push(method);
+        }
+        Ok(result)
+    }
+
+    /// Relative method with three `int32_t` offsets from fields themselves
+    fn small_method(&self, method: u64, uses_direct_selectors: bool) -> Result<ObjcMethod> {
+        let relative = |field: u64| -> Result<u64> {
+            let offset = self.image.read_i32(method + field * 4)?;
+            Ok((method + field * 4).wrapping_add(offset as i64 as u64))
+        };
+
+        // Selector reference, or selector itself in shared cache
+        let name = match uses_direct_selectors {
+            true => self.image.read_cstring(relative(0)?)?,
+            false => self.string_at(relative(0)?)?,
+        };
+        let imp = match self.image.read_i32(method + 8)? {
+            0 => None,
+            _ => Some(relative(2)?),
+        };
+
+        Ok(ObjcMethod {
+            name,
+            types: self.image.read_cstring(relative(1)?)?,
+            extended_types: None,
+            imp,
+        })
+    }
+
+    /// `ivar_list_t`, referenced by pointer at `location`
+    fn ivars(&self, location: u64) -> Result<Vec<ObjcIvar>> {
+        let (list, entsize, count) = match self.list_header(location)? {
+            Some(header) => header,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut result = Vec::with_capacity(count as usize);
+        for index in 0..count as u64 {
+            let ivar = list + 8 + index * entsize as u64;
+            // Only 32 bits of offset variable are meaningful
+            let offset = match self.image.read_address(ivar)? {
+                Some(offset) => Some(self.image.read_u32(offset)?),
+                None => None,
+            };
+            let alignment_raw = self.image.read_u32(self.field(ivar, 3))?;
+            let alignment = match alignment_raw {
+                u32::MAX => self.ptr_size() as u32,
+                _ => 1u32.checked_shl(alignment_raw).unwrap_or(0),
+            };
+
+            result.push(ObjcIvar {
+                name: self.string_at(self.field(ivar, 1))?,
+                type_encoding: self.string_at(self.field(ivar, 2))?,
+                offset,
+                size: self.image.read_u32(self.field(ivar, 3) + 4)?,
+                alignment,
+            });
+        }
+        Ok(result)
+    }
+
+    /// `property_list_t`, referenced by pointer at `location`
+    fn properties(&self, location: u64) -> Result<Vec<ObjcProperty>> {
+        let (list, entsize, count) = match self.list_header(location)? {
+            Some(header) => header,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut result = Vec::with_capacity(count as usize);
+        for index in 0..count as u64 {
+            let property = list + 8 + index * entsize as u64;
+            result.push(ObjcProperty {
+                name: self.string_at(property)?,
+                attributes: self.string_at(self.field(property, 1))?,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Names of protocols in `protocol_list_t`, referenced by pointer at `location`
+    fn protocol_names(&self, location: u64) -> Result<Vec<String>> {
+        let list = match self.image.read_address(location)? {
+            Some(list) => list,
+            None => return Ok(Vec::new()),
+        };
+        // Count is pointer-sized integer
+        let count = self.image.read_word(list)?;
+        if count > MAX_LIST_COUNT as u64 {
+            return Err(Error::Text(format!(
+                "Protocol list at {:#x} has too many entries ({count})",
+                list
+            )));
+        }
+
+        let mut result = Vec::with_capacity(count as usize);
+        for index in 0..count {
+            let pointer = self.image.read_pointer(self.field(list, index + 1))?;
+            if let Some(name) = self.protocol_name(pointer)? {
+                result.push(name);
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse_single;
+
+    #[test]
+    fn test_parse_chained_arm64e() {
+        let object = parse_single("testable/objc_arm64e");
+        let metadata = ObjcMetadata::parse(&object).unwrap();
+
+        let class = &metadata.classes[0];
+        assert_eq!(class.name, "Foo");
+        assert_eq!(class.superclass.as_deref(), Some("NSObject"));
+        assert_eq!(class.protocols, vec!["P"]);
+        assert_eq!(class.ivars[1].name, "_name");
+        assert_eq!(class.ivars[1].offset, Some(16));
+        assert_eq!(class.properties[0].attributes, "T@\"NSString\",C,N,V_name");
+        let methods: Vec<&str> = class.instance_methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(methods, vec!["req", "name", "setName:"]);
+        assert_eq!(class.class_methods[0].types, "i16@0:8");
+
+        // Relative method list
+        let category = &metadata.categories[0];
+        assert_eq!(category.class_name.as_deref(), Some("Foo"));
+        assert_eq!(category.instance_methods[0].name, "catMethod");
+        assert_eq!(category.instance_methods[0].imp, Some(0x1_0000_4014));
+
+        let protocol = &metadata.protocols[0];
+        assert_eq!(protocol.optional_class_methods[0].name, "opt");
+        assert_eq!(metadata.class_refs[0].name.as_deref(), Some("NSString"));
+        assert_eq!(metadata.super_refs[0].name.as_deref(), Some("Foo"));
+    }
+}
@@ -0,0 +1,583 @@
+//! Structural comparison of two Mach-O files. Slices of fat files are aligned by CPU, then
+//! header, load commands, segments with their sections, dependent libraries, rpaths,
+//! symbols and contents of sections are compared.
+//! Segment and dylib commands are compared by segment and install names rather than by position,
+//! so an inserted command doesn't shift all following ones.
+
+use super::abi::arch_name;
+use super::auto_enum_fields::*;
+use super::constants::SEG_LINKEDIT;
+use super::section_constants::*;
+use super::fmt_ext::load_command_to_string;
+use super::nlist::constants::stab::N_OSO;
+use super::LcVariant;
+use super::MachObject;
+use super::Section;
+use crate::result::Result;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Field of `LC_*_DYLIB` commands and value of `N_OSO` symbols
+const TIMESTAMP_FIELD: &str = "timestamp";
+
+/// Parts of files, that differ in every build even if nothing else changed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiffOptions {
+    /// Timestamps of dylib commands and modification times of object files in `N_OSO` stabs
+    pub ignore_timestamps: bool,
+    /// `LC_UUID`
+    pub ignore_uuid: bool,
+    /// `LC_CODE_SIGNATURE` and sizes of `__LINKEDIT` segment, that contains the signature
+    pub ignore_code_signature: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffScope {
+    Arch,
+    Header,
+    LoadCommand,
+    Segment,
+    Section,
+    Dylib,
+    Rpath,
+    Symbol,
+    SectionContent,
+}
+
+impl Display for DiffScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            DiffScope::Arch => "arch",
+            DiffScope::Header => "header",
+            DiffScope::LoadCommand => "load command",
+            DiffScope::Segment => "segment",
+            DiffScope::Section => "section",
+            DiffScope::Dylib => "dylib",
+            DiffScope::Rpath => "rpath",
+            DiffScope::Symbol => "symbol",
+            DiffScope::SectionContent => "section content",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    /// `None` if both files are single arch
+    pub arch: Option<String>,
+    pub scope: DiffScope,
+    /// Name of the item, like `__TEXT,__text` for section
+    pub name: String,
+    /// Changed field of the item, `None` if whole item is added or removed
+    pub field: Option<String>,
+    /// `None` if item is added
+    pub old: Option<String>,
+    /// `None` if item is removed
+    pub new: Option<String>,
+}
+
+impl Difference {
+    pub fn is_added(&self) -> bool {
+        self.old.is_none()
+    }
+
+    pub fn is_removed(&self) -> bool {
+        self.new.is_none()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ObjectDiff {
+    pub differences: Vec<Difference>,
+}
+
+impl ObjectDiff {
+    pub fn compare(old: &[MachObject], new: &[MachObject], options: &DiffOptions) -> Result<Self> {
+        let mut diff = ObjectDiff::default();
+
+        // Single arch files are compared even if their CPUs differ
+        if old.len() == 1 && new.len() == 1 {
+            SliceDiff::new(&mut diff, None, options).compare(&old[0], &new[0])?;
+            return Ok(diff);
+        }
+
+        for object in old {
+            let arch = arch_name(object);
+            match new.iter().find(|n| arch_name(n) == arch) {
+                Some(other) => {
+                    SliceDiff::new(&mut diff, Some(arch), options).compare(object, other)?
+                }
+                None => diff.push_item(None, DiffScope::Arch, &arch, Some(&arch), None),
+            }
+        }
+        for object in new {
+            let arch = arch_name(object);
+            if !old.iter().any(|o| arch_name(o) == arch) {
+                diff.push_item(None, DiffScope::Arch, &arch, None, Some(&arch));
+            }
+        }
+
+        Ok(diff)
+    }
+
+    fn push_item(
+        &mut self,
+        arch: Option<String>,
+        scope: DiffScope,
+        name: &str,
+        old: Option<&str>,
+        new: Option<&str>,
+    ) {
+        self.differences.push(Difference {
+            arch,
+            scope,
+            name: name.to_string(),
+            field: None,
+            old: old.map(|s| s.to_string()),
+            new: new.map(|s| s.to_string()),
+        });
+    }
+}
+
+/// Comparison of slices with the same architecture
+struct SliceDiff<'a> {
+    diff: &'a mut ObjectDiff,
+    arch: Option<String>,
+    options: &'a DiffOptions,
+}
+
+impl<'a> SliceDiff<'a> {
+    fn new(diff: &'a mut ObjectDiff, arch: Option<String>, options: &'a DiffOptions) -> Self {
+        SliceDiff {
+            diff,
+            arch,
+            options,
+        }
+    }
+
+    fn compare(&mut self, old: &MachObject, new: &MachObject) -> Result<()> {
+        self.compare_fields(
+            DiffScope::Header,
+            "",
+            &old.header().all_fields(),
+            &new.header().all_fields(),
+        );
+        self.compare_load_commands(old, new);
+        self.compare_segments(old, new);
+        self.compare_dylibs(old, new);
+        self.compare_rpaths(old, new);
+        self.compare_symbols(old, new);
+        self.compare_section_contents(old, new)
+    }
+
+    /// Commands, that aren't compared by name, are aligned by type and index among commands
+    /// of the same type
+    fn compare_load_commands(&mut self, old: &MachObject, new: &MachObject) {
+        let old = self.positional_commands(old);
+        let new = self.positional_commands(new);
+
+        for (name, fields) in &old {
+            match new.iter().find(|(n, _)| n == name) {
+                Some((_, new_fields)) => {
+                    self.compare_fields(DiffScope::LoadCommand, name, fields, new_fields)
+                }
+                None => self.push_item(DiffScope::LoadCommand, name, Some(name), None),
+            }
+        }
+        for (name, _) in &new {
+            if !old.iter().any(|(n, _)| n == name) {
+                self.push_item(DiffScope::LoadCommand, name, None, Some(name));
+            }
+        }
+    }
+
+    fn positional_commands(&self, object: &MachObject) -> Vec<(String, Vec<Field>)> {
+        let mut counts: HashMap<u32, usize> = HashMap::new();
+        let mut result = Vec::new();
+        for cmd in object.load_commands_iterator() {
+            let skip = match cmd.variant {
+                LcVariant::Segment32(_)
+                | LcVariant::Segment64(_)
+                | LcVariant::LoadDylib(_)
+                | LcVariant::LoadWeakDylib(_)
+                | LcVariant::ReexportDylib(_)
+                | LcVariant::LazyLoadDylib(_)
+                | LcVariant::LoadUpwardDylib(_)
+                | LcVariant::Rpath(_) => true,
+                LcVariant::Uuid(_) => self.options.ignore_uuid,
+                LcVariant::CodeSignature(_) => self.options.ignore_code_signature,
+                _ => false,
+            };
+            if skip {
+                continue;
+            }
+
+            let index = counts.entry(cmd.cmd).or_default();
+            let name = match index {
+                0 => load_command_to_string(cmd.cmd),
+                _ => format!("{}[{index}]", load_command_to_string(cmd.cmd)),
+            };
+            *index += 1;
+
+            let mut fields = vec![Field::new("cmdsize".to_string(), cmd.cmdsize.to_string())];
+            fields.append(&mut cmd.variant.all_fields());
+            result.push((name, fields));
+        }
+        result
+    }
+
+    fn compare_segments(&mut self, old: &MachObject, new: &MachObject) {
+        let old_segments = old.segments();
+        let new_segments = new.segments();
+
+        for segment in &old_segments {
+            let name = segment.segname.to_string();
+            let other = match new_segments.iter().find(|s| s.segname.to_string() == name) {
+                Some(other) => other,
+                None => {
+                    self.push_item(DiffScope::Segment, &name, Some(&name), None);
+                    continue;
+                }
+            };
+
+            let mut fields = segment.all_fields();
+            let mut other_fields = other.all_fields();
+            if self.options.ignore_code_signature && name == SEG_LINKEDIT {
+                let is_size = |field: &Field| field.name == "vmsize" || field.name == "filesize";
+                fields.retain(|field| !is_size(field));
+                other_fields.retain(|field| !is_size(field));
+            }
+            self.compare_fields(DiffScope::Segment, &name, &fields, &other_fields);
+        }
+        for segment in &new_segments {
+            let name = segment.segname.to_string();
+            if !old_segments.iter().any(|s| s.segname.to_string() == name) {
+                self.push_item(DiffScope::Segment, &name, None, Some(&name));
+            }
+        }
+
+        let old_sections = old.sections();
+        let new_sections = new.sections();
+        for section in &old_sections {
+            let name = section_name(section);
+            match new_sections.iter().find(|s| section_name(s) == name) {
+                Some(other) => self.compare_fields(
+                    DiffScope::Section,
+                    &name,
+                    &section.all_fields(),
+                    &other.all_fields(),
+                ),
+                None => self.push_item(DiffScope::Section, &name, Some(&name), None),
+            }
+        }
+        for section in &new_sections {
+            let name = section_name(section);
+            if !old_sections.iter().any(|s| section_name(s) == name) {
+                self.push_item(DiffScope::Section, &name, None, Some(&name));
+            }
+        }
+    }
+
+    fn compare_dylibs(&mut self, old: &MachObject, new: &MachObject) {
+        let old = self.dylibs(old);
+        let new = self.dylibs(new);
+
+        for (name, fields) in &old {
+            match new.iter().find(|(n, _)| n == name) {
+                Some((_, new_fields)) => {
+                    self.compare_fields(DiffScope::Dylib, name, fields, new_fields)
+                }
+                None => self.push_item(DiffScope::Dylib, name, Some(name), None),
+            }
+        }
+        for (name, _) in &new {
+            if !old.iter().any(|(n, _)| n == name) {
+                self.push_item(DiffScope::Dylib, name, None, Some(name));
+            }
+        }
+
+        // Ordinals of imported symbols refer libraries by position
+        let order = |dylibs: &[(String, Vec<Field>)]| {
+            dylibs
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<String>>()
+        };
+        let (old_order, new_order) = (order(&old), order(&new));
+        let mut old_common = old_order.iter().filter(|n| new_order.contains(n));
+        let mut new_common = new_order.iter().filter(|n| old_order.contains(n));
+        if !old_common.by_ref().eq(new_common.by_ref()) {
+            self.push_change(
+                DiffScope::Dylib,
+                "",
+                "order",
+                &old_order.join(", "),
+                &new_order.join(", "),
+            );
+        }
+    }
+
+    /// Install names of dependent libraries with command type, versions and timestamp
+    fn dylibs(&self, object: &MachObject) -> Vec<(String, Vec<Field>)> {
+        object
+            .load_commands_iterator()
+            .filter_map(|cmd| match cmd.variant {
+                LcVariant::LoadDylib(dylib)
+                | LcVariant::LoadWeakDylib(dylib)
+                | LcVariant::ReexportDylib(dylib)
+                | LcVariant::LazyLoadDylib(dylib)
+                | LcVariant::LoadUpwardDylib(dylib) => {
+                    let mut fields = vec![Field::new(
+                        "cmd".to_string(),
+                        load_command_to_string(cmd.cmd),
+                    )];
+                    fields.append(&mut dylib.all_fields());
+                    fields.retain(|field| field.name != "name");
+                    Some((dylib.name.to_string(), fields))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn compare_rpaths(&mut self, old: &MachObject, new: &MachObject) {
+        let rpaths = |object: &MachObject| -> Vec<String> {
+            object
+                .load_commands_iterator()
+                .filter_map(|cmd| match cmd.variant {
+                    LcVariant::Rpath(rpath) => Some(rpath.path.to_string()),
+                    _ => None,
+                })
+                .collect()
+        };
+        let (old, new) = (rpaths(old), rpaths(new));
+
+        for rpath in &old {
+            if !new.contains(rpath) {
+                self.push_item(DiffScope::Rpath, rpath, Some(rpath), None);
+            }
+        }
+        for rpath in &new {
+            if !old.contains(rpath) {
+                self.push_item(DiffScope::Rpath, rpath, None, Some(rpath));
+            }
+        }
+    }
+
+    /// Symbols are aligned by name and index among symbols with the same name,
+    /// so repeated stabs are compared in order
+    fn compare_symbols(&mut self, old: &MachObject, new: &MachObject) {
+        let old = self.symbols(old);
+        let new = self.symbols(new);
+        let old_index: HashMap<&String, &Vec<Field>> = old.iter().map(|(n, f)| (n, f)).collect();
+        let new_index: HashMap<&String, &Vec<Field>> = new.iter().map(|(n, f)| (n, f)).collect();
+
+        for (name, fields) in &old {
+            match new_index.get(name) {
+                Some(new_fields) => {
+                    self.compare_fields(DiffScope::Symbol, name, fields, new_fields)
+                }
+                None => self.push_item(DiffScope::Symbol, name, Some(name), None),
+            }
+        }
+        for (name, _) in &new {
+            if !old_index.contains_key(name) {
+                self.push_item(DiffScope::Symbol, name, None, Some(name));
+            }
+        }
+    }
+
+    fn symbols(&self, object: &MachObject) -> Vec<(String, Vec<Field>)> {
+        let symtab = match object.symtab() {
+            Some(symtab) => symtab,
+            None => return Vec::new(),
+        };
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut result = Vec::new();
+        for nlist in symtab.nlist_iterator() {
+            let name = match &nlist.name {
+                Some(name) => name.load_string().unwrap_or_default(),
+                None => String::new(),
+            };
+            let index = counts.entry(name.clone()).or_default();
+            let key = match index {
+                0 => name,
+                _ => format!("{name}[{index}]"),
+            };
+            *index += 1;
+
+            let mut fields = vec![
+                Field::new("n_type".to_string(), format!("{:#04x}", nlist.n_type.0)),
+                Field::new("n_sect".to_string(), nlist.n_sect.to_string()),
+                Field::new("n_desc".to_string(), format!("{:#06x}", nlist.n_desc)),
+            ];
+            let is_timestamp = nlist.n_type.0 == N_OSO;
+            if !(is_timestamp && self.options.ignore_timestamps) {
+                fields.push(Field::new(
+                    "n_value".to_string(),
+                    format!("{:?}", nlist.n_value),
+                ));
+            }
+            result.push((key, fields));
+        }
+        result
+    }
+
+    fn compare_section_contents(&mut self, old: &MachObject, new: &MachObject) -> Result<()> {
+        let new_sections = new.sections();
+        for section in old.sections() {
+            let name = section_name(&section);
+            let other = match new_sections.iter().find(|s| section_name(s) == name) {
+                Some(other) => other,
+                None => continue,
+            };
+            let (old_hash, new_hash) = match (content_hash(&section)?, content_hash(other)?) {
+                (Some(old_hash), Some(new_hash)) => (old_hash, new_hash),
+                _ => continue,
+            };
+            if old_hash != new_hash {
+                self.push_change(
+                    DiffScope::SectionContent,
+                    &name,
+                    "hash",
+                    &format!("{old_hash:016x}"),
+                    &format!("{new_hash:016x}"),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn compare_fields(&mut self, scope: DiffScope, name: &str, old: &[Field], new: &[Field]) {
+        let skip = |field: &Field| self.options.ignore_timestamps && field.name == TIMESTAMP_FIELD;
+        for field in old.iter().filter(|f| !skip(f)) {
+            match new.iter().find(|f| f.name == field.name) {
+                Some(other) if other.value != field.value => {
+                    self.push_change(scope, name, &field.name, &field.value, &other.value)
+                }
+                Some(_) => (),
+                None => self.push_change(scope, name, &field.name, &field.value, ""),
+            }
+        }
+        for field in new.iter().filter(|f| !skip(f)) {
+            if !old.iter().any(|f| f.name == field.name) {
+                self.push_change(scope, name, &field.name, "", &field.value);
+            }
+        }
+    }
+
+    fn push_item(&mut self, scope: DiffScope, name: &str, old: Option<&str>, new: Option<&str>) {
+        self.diff
+            .push_item(self.arch.clone(), scope, name, old, new);
+    }
+
+    fn push_change(&mut self, scope: DiffScope, name: &str, field: &str, old: &str, new: &str) {
+        self.diff.differences.push(Difference {
+            arch: self.arch.clone(),
+            scope,
+            name: name.to_string(),
+            field: Some(field.to_string()),
+            old: Some(old.to_string()),
+            new: Some(new.to_string()),
+        });
+    }
+}
+
+fn section_name(section: &Section) -> String {
+    format!("{},{}", section.segname, section.sectname)
+}
+
+/// FNV-1a hash of section data, `None` for sections without data in file
+fn content_hash(section: &Section) -> Result<Option<u64>> {
+    match section.section_type() {
+        S_ZEROFILL | S_GB_ZEROFILL | S_THREAD_LOCAL_ZEROFILL => return Ok(None),
+        _ => (),
+    }
+
+    let mut data = Vec::new();
+    section.read_data_to(&mut data)?;
+    let hash = data.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    });
+    Ok(Some(hash))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse;
+
+    #[test]
+    fn test_reproducible() {
+        let old = parse("testable/diff/liba.dylib").mach_objects();
+        let new = parse("testable/diff/liba_rebuilt.dylib").mach_objects();
+
+        let diff = ObjectDiff::compare(&old, &new, &DiffOptions::default()).unwrap();
+        let changed: Vec<(DiffScope, &str)> = diff
+            .differences
+            .iter()
+            .map(|d| (d.scope, d.field.as_deref().unwrap_or_default()))
+            .collect();
+        assert_eq!(
+            changed,
+            vec![
+                (DiffScope::LoadCommand, "timestamp"),
+                (DiffScope::LoadCommand, "uuid"),
+                (DiffScope::Dylib, "timestamp"),
+            ]
+        );
+
+        let options = DiffOptions {
+            ignore_timestamps: true,
+            ignore_uuid: true,
+            ignore_code_signature: true,
+        };
+        let diff = ObjectDiff::compare(&old, &new, &options).unwrap();
+        assert!(diff.differences.is_empty());
+    }
+
+    #[test]
+    fn test_changed() {
+        let old = parse("testable/diff/liba.dylib").mach_objects();
+        let new = parse("testable/diff/liba_changed.dylib").mach_objects();
+
+        let diff = ObjectDiff::compare(&old, &new, &DiffOptions::default()).unwrap();
+        let has = |scope: DiffScope, name: &str, field: Option<&str>| {
+            diff.differences
+                .iter()
+                .any(|d| d.scope == scope && d.name == name && d.field.as_deref() == field)
+        };
+        assert!(has(DiffScope::Header, "", Some("ncmds")));
+        assert!(has(DiffScope::Section, "__TEXT,__text", Some("size")));
+        assert!(has(DiffScope::Dylib, "/usr/lib/libz.1.dylib", None));
+        assert!(has(DiffScope::Rpath, "@loader_path", None));
+        assert!(has(DiffScope::Symbol, "_c", None));
+        assert!(has(
+            DiffScope::SectionContent,
+            "__TEXT,__text",
+            Some("hash")
+        ));
+        assert!(!has(DiffScope::LoadCommand, "LC_UUID", Some("uuid")));
+
+        let added = diff.differences.iter().find(|d| d.name == "_c").unwrap();
+        assert!(added.is_added());
+    }
+
+    #[test]
+    fn test_archs() {
+        let old = parse("testable/abi/libabi_old.dylib").mach_objects();
+        let new = parse("testable/abi/libabi_new.dylib").mach_objects();
+
+        let diff = ObjectDiff::compare(&old, &new, &DiffOptions::default()).unwrap();
+        let removed = &diff.differences.last().unwrap();
+        assert_eq!(removed.scope, DiffScope::Arch);
+        assert_eq!(removed.name, "x86_64");
+        assert!(removed.is_removed());
+        assert!(diff
+            .differences
+            .iter()
+            .all(|d| d.scope == DiffScope::Arch || d.arch.as_deref() == Some("arm64")));
+    }
+}
@@ -235,6 +235,14 @@ impl CPUType {
     pub fn is_64(&self) -> bool {
         (self.0 & CPU_ARCH_ABI64) == CPU_ARCH_ABI64
     }
+
+    /// Size of VM page, that segments are aligned to by linker
+    pub fn page_size(&self) -> u64 {
+        match *self {
+            CPU_TYPE_ARM | CPU_TYPE_ARM64 | CPU_TYPE_ARM64_32 => 0x4000,
+            _ => 0x1000,
+        }
+    }
 }
 
 impl BitOr<u32> for CPUType {
@@ -207,7 +207,7 @@ impl Display for Segname {
 }
 
 #[repr(transparent)]
-#[derive(IOread, SizeWith)]
+#[derive(Clone, Copy, PartialEq, Eq, IOread, SizeWith)]
 pub struct Version32(pub u32);
 
 impl Version32 {
@@ -0,0 +1,372 @@
+//! Applying relocations to section contents, as static linker does.
+//! Supported only `x86_64` and `arm64` relocation types.
+
+use super::*;
+use crate::filetype_constants::MH_OBJECT;
+use crate::result::{Error, Result};
+use crate::{MachObject, Section};
+
+use std::collections::HashMap;
+
+/// Produces relocated section contents as if object was linked at `base_address`.
+/// Every section is placed at `base_address + section.addr`.
+/// Symbols, defined in the object, get addresses according to their sections.
+/// Addresses of undefined symbols and GOT slots should be provided by caller.
+pub struct Relocator {
+    resolver: RelocationResolver,
+    cputype: CPUType,
+    base_address: u64,
+
+    /// Addresses of symbols defined in the object, by symbol index
+    defined: Vec<Option<u64>>,
+    symbols: HashMap<String, u64>,
+    got: HashMap<String, u64>,
+}
+
+impl Relocator {
+    pub fn build(object: &MachObject, base_address: u64) -> Result<Self> {
+        use crate::cpu_constants::*;
+
+        if object.header().filetype.0 != MH_OBJECT {
+            return Err(Error::Text(
+                "Applying relocations is supported only for object files".to_string(),
+            ));
+        }
+
+        let cputype = object.header().cputype;
+        if cputype != CPU_TYPE_X86_64 && cputype != CPU_TYPE_ARM64 {
+            return Err(Error::Text(format!(
+                "Applying relocations is not supported for CPU type {cputype}"
+            )));
+        }
+
+        let defined = match object.symtab() {
+            Some(symtab) => symtab
+                .nlist_iterator()
+                .map(|nlist| {
+                    match nlist.n_type.is_defined_in_n_sect() && !nlist.n_type.is_stab() {
+                        true => Some(base_address.wrapping_add(nlist.n_value.0)),
+                        false => None,
+                    }
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            resolver: RelocationResolver::build(object)?,
+            cputype,
+            base_address,
+            defined,
+            symbols: HashMap::new(),
+            got: HashMap::new(),
+        })
+    }
+
+    /// Sets address of symbol. Overrides address of symbol defined in the object
+    pub fn set_symbol_address(&mut self, name: &str, address: u64) {
+        self.symbols.insert(name.to_string(), address);
+    }
+
+    /// Sets address of GOT slot of symbol. Used by `*_GOT*` relocations
+    pub fn set_got_address(&mut self, name: &str, address: u64) {
+        self.got.insert(name.to_string(), address);
+    }
+
+    /// Address of section after relocation
+    pub fn relocated_address(&self, section: &Section) -> u64 {
+        self.base_address.wrapping_add(section.addr.0)
+    }
+}
+
+impl Relocator {
+    /// Returns contents of `section` with all relocations applied
+    pub fn apply(&self, section: &Section) -> Result<Vec<u8>> {
+        let mut data = section.read_data()?;
+
+        let section_address = self.relocated_address(section);
+        for reloc in self.resolver.resolve(section)? {
+            let fixup = section_address.wrapping_add(reloc.address as u64);
+            self.apply_relocation(&mut data, &reloc, fixup, section.endian)
+                .map_err(|e| {
+                    Error::Text(format!(
+                        "{},{} at {:#x} ({} {}): {e}",
+                        section.segname,
+                        section.sectname,
+                        reloc.address,
+                        reloc.r_type,
+                        reloc.target_string()
+                    ))
+                })?;
+        }
+
+        Ok(data)
+    }
+
+    fn apply_relocation(
+        &self,
+        data: &mut [u8],
+        reloc: &ResolvedRelocation,
+        fixup: u64,
+        endian: Endian,
+    ) -> Result<()> {
+        let offset = reloc.address as usize;
+        // Item would be patched relative to start of the target otherwise
+        let addend = reloc
+            .addend
+            .ok_or_else(|| Error::Text("addend can not be obtained".to_string()))?;
+
+        let value = || -> Result<i64> {
+            let target = self.address(&reloc.target)? as i64;
+            match &reloc.subtrahend {
+                Some(subtrahend) => {
+                    Ok(target - self.address(subtrahend)? as i64 + addend)
+                }
+                None => Ok(target + addend),
+            }
+        };
+        let got = || -> Result<i64> { Ok(self.got_address(&reloc.target)? as i64 + addend) };
+
+        match reloc.r_type {
+            RelocationType::X86_64(t) => {
+                use X86_64RelocationType::*;
+                // Distance from end of relocated item to end of instruction
+                let bias = match t {
+                    Signed1 => 1,
+                    Signed2 => 2,
+                    Signed4 => 4,
+                    _ => 0,
+                };
+                let next_pc = fixup as i64 + 4 + bias;
+                match t {
+                    Unsigned | Subtractor => write_item(data, offset, reloc.length, value()?, endian),
+                    Signed | Signed1 | Signed2 | Signed4 | Branch => {
+                        write_pcrel32(data, offset, value()? - next_pc, endian)
+                    }
+                    GotLoad | Got => write_pcrel32(data, offset, got()? - next_pc, endian),
+                    Tlv => Err(Error::Text("thread local variables are not supported".to_string())),
+                }
+            }
+            RelocationType::Arm64(t) => {
+                use Arm64RelocationType::*;
+                match t {
+                    Unsigned | Subtractor => write_item(data, offset, reloc.length, value()?, endian),
+                    Branch26 => patch_branch26(data, offset, value()? - fixup as i64, endian),
+                    Page21 => patch_page21(data, offset, value()?, fixup as i64, endian),
+                    Pageoff12 => patch_pageoff12(data, offset, value()?, endian),
+                    GotLoadPage21 => patch_page21(data, offset, got()?, fixup as i64, endian),
+                    GotLoadPageoff12 => patch_pageoff12(data, offset, got()?, endian),
+                    PointerToGot => match reloc.pcrel {
+                        true => write_pcrel32(data, offset, got()? - fixup as i64, endian),
+                        false => write_item(data, offset, reloc.length, got()?, endian),
+                    },
+                    TlvpLoadPage21 | TlvpLoadPageoff12 => {
+                        Err(Error::Text("thread local variables are not supported".to_string()))
+                    }
+                    Addend => Err(Error::Text("ADDEND without follow-up entry".to_string())),
+                }
+            }
+            other => Err(Error::Text(format!("unsupported relocation type {other} for {}", self.cputype))),
+        }
+    }
+
+    fn address(&self, target: &RelocationTarget) -> Result<u64> {
+        match target {
+            RelocationTarget::Symbol { index, name } => {
+                if let Some(address) = self.symbols.get(name) {
+                    return Ok(*address);
+                }
+                match self.defined.get(*index) {
+                    Some(Some(address)) => Ok(*address),
+                    _ => Err(Error::Text(format!("no address for symbol {target}"))),
+                }
+            }
+            RelocationTarget::Section { ordinal, .. } => {
+                match self.resolver.sections.get(ordinal - 1) {
+                    Some((_, _, addr, _)) => Ok(self.base_address.wrapping_add(*addr)),
+                    None => Err(Error::Text(format!("no section with ordinal {ordinal}"))),
+                }
+            }
+            RelocationTarget::Address { value, .. } => {
+                Ok(self.base_address.wrapping_add(*value as u64))
+            }
+            RelocationTarget::Absolute => Ok(0),
+        }
+    }
+
+    fn got_address(&self, target: &RelocationTarget) -> Result<u64> {
+        let name = match target {
+            RelocationTarget::Symbol { name, .. } => name,
+            _ => return Err(Error::Text(format!("GOT reference to non symbol {target}"))),
+        };
+
+        match self.got.get(name) {
+            Some(address) => Ok(*address),
+            None => Err(Error::Text(format!("no GOT slot address for symbol {name}"))),
+        }
+    }
+}
+
+fn write_item(data: &mut [u8], offset: usize, r_length: u8, value: i64, endian: Endian) -> Result<()> {
+    match r_length {
+        2 => {
+            if value < i32::MIN as i64 || value > u32::MAX as i64 {
+                return Err(Error::Text(format!("value {:#x} does not fit 32 bits", value)));
+            }
+            data.pwrite_with(value as u32, offset, endian)?;
+        }
+        3 => {
+            data.pwrite_with(value as u64, offset, endian)?;
+        }
+        _ => return Err(Error::Text(format!("unsupported length {r_length}"))),
+    };
+    Ok(())
+}
+
+fn write_pcrel32(data: &mut [u8], offset: usize, displacement: i64, endian: Endian) -> Result<()> {
+    if displacement < i32::MIN as i64 || displacement > i32::MAX as i64 {
+        return Err(Error::Text(format!("displacement {:#x} out of range", displacement)));
+    }
+    data.pwrite_with(displacement as i32, offset, endian)?;
+    Ok(())
+}
+
+fn read_instruction(data: &[u8], offset: usize, endian: Endian) -> Result<u32> {
+    Ok(data.pread_with::<u32>(offset, endian)?)
+}
+
+fn write_instruction(data: &mut [u8], offset: usize, instruction: u32, endian: Endian) -> Result<()> {
+    data.pwrite_with(instruction, offset, endian)?;
+    Ok(())
+}
+
+/// `B`/`BL` - 26-bit word displacement
+fn patch_branch26(data: &mut [u8], offset: usize, displacement: i64, endian: Endian) -> Result<()> {
+    const RANGE: i64 = 1 << 27;
+    if !(-RANGE..RANGE).contains(&displacement) || displacement & 0x3 != 0 {
+        return Err(Error::Text(format!("branch displacement {:#x} out of range", displacement)));
+    }
+
+    let instruction = read_instruction(data, offset, endian)?;
+    let imm26 = ((displacement >> 2) as u32) & 0x03ff_ffff;
+    write_instruction(data, offset, (instruction & 0xfc00_0000) | imm26, endian)
+}
+
+/// `ADRP` - 21-bit page displacement, split to `immlo` and `immhi`
+fn patch_page21(data: &mut [u8], offset: usize, target: i64, fixup: i64, endian: Endian) -> Result<()> {
+    const RANGE: i64 = 1 << 20;
+    let pages = (target >> 12) - (fixup >> 12);
+    if !(-RANGE..RANGE).contains(&pages) {
+        return Err(Error::Text(format!("page displacement {:#x} out of range", pages)));
+    }
+
+    let instruction = read_instruction(data, offset, endian)?;
+    let immlo = (pages as u32 & 0x3) << 29;
+    let immhi = ((pages >> 2) as u32 & 0x7_ffff) << 5;
+    write_instruction(data, offset, (instruction & 0x9f00_001f) | immlo | immhi, endian)
+}
+
+/// `ADD` or `LDR`/`STR` (unsigned immediate) - 12-bit page offset, scaled by access size
+fn patch_pageoff12(data: &mut [u8], offset: usize, target: i64, endian: Endian) -> Result<()> {
+    let instruction = read_instruction(data, offset, endian)?;
+    let page_offset = (target & 0xfff) as u32;
+
+    let scale = match instruction & 0x3b00_0000 == 0x3900_0000 {
+        // Load/store register (unsigned immediate)
+        true => {
+            let size = instruction >> 30;
+            let is_simd = instruction & 0x0400_0000 != 0;
+            let is_128 = is_simd && size == 0 && instruction & 0x0080_0000 != 0;
+            match is_128 {
+                true => 4,
+                false => size,
+            }
+        }
+        false => 0,
+    };
+
+    if page_offset & ((1 << scale) - 1) != 0 {
+        return Err(Error::Text(format!(
+            "page offset {:#x} is not aligned to access size {}",
+            page_offset,
+            1 << scale
+        )));
+    }
+
+    let imm12 = ((page_offset >> scale) & 0xfff) << 10;
+    write_instruction(data, offset, (instruction & 0xffc0_03ff) | imm12, endian)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{parse_single, read_single, with_temp_file};
+
+    #[test]
+    fn test_patch_arm64_instructions() {
+        let endian = Endian::Little;
+        // bl #0; adrp x0, #0; ldr x1, [x1]
+        let mut data = vec![0u8; 12];
+        data.pwrite_with(0x9400_0000u32, 0, endian).unwrap();
+        data.pwrite_with(0x9000_0000u32, 4, endian).unwrap();
+        data.pwrite_with(0xf940_0021u32, 8, endian).unwrap();
+
+        patch_branch26(&mut data, 0, -8, endian).unwrap();
+        patch_page21(&mut data, 4, 0x1_0000_5010, 0x1_0000_0004, endian).unwrap();
+        patch_pageoff12(&mut data, 8, 0x1_0000_5010, endian).unwrap();
+
+        // bl #-8
+        assert_eq!(data.pread_with::<u32>(0, endian).unwrap(), 0x97ff_fffe);
+        // adrp x0, #0x5000
+        assert_eq!(data.pread_with::<u32>(4, endian).unwrap(), 0xb000_0020);
+        // ldr x1, [x1, #0x10]
+        assert_eq!(data.pread_with::<u32>(8, endian).unwrap(), 0xf940_0821);
+
+        assert!(patch_pageoff12(&mut data, 8, 0x1_0000_5004, endian).is_err());
+        assert!(patch_branch26(&mut data, 0, 1 << 28, endian).is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let object = parse_single("testable/disasm_arm64.o");
+        let text = &object.sections()[0];
+        // Page of `l_.str` follows page of `adrp`
+        let mut relocator = Relocator::build(&object, 0x1_0000_0ff0).unwrap();
+        assert!(relocator.apply(text).is_err());
+
+        relocator.set_symbol_address("_puts", 0x1_0000_5000);
+        let data = relocator.apply(text).unwrap();
+        let endian = Endian::Little;
+        // adrp x0, #0x1000
+        assert_eq!(data.pread_with::<u32>(0x8, endian).unwrap(), 0xb000_0000);
+        // add x0, x0, #0x30
+        assert_eq!(data.pread_with::<u32>(0xc, endian).unwrap(), 0x9100_c000);
+        // bl #0x4000
+        assert_eq!(data.pread_with::<u32>(0x10, endian).unwrap(), 0x9400_1000);
+    }
+
+    #[test]
+    fn test_unknown_addend() {
+        // `PAGE21` of `l_.str` turned to one of `__cstring`, its addend is encoded in instructions
+        let path = "testable/disasm_arm64.o";
+        let offset =
+            parse_single(path).sections()[0].reloff as usize + 2 * BYTES_PER_RELOCATION_INFO;
+        let mut bytes = read_single(path).data().to_vec();
+        bytes
+            .pwrite_with(0x3500_0002u32, offset + 4, Endian::Little)
+            .unwrap();
+        let error = with_temp_file("schnauzer_apply_test.o", &bytes, |path| {
+            let object = parse_single(path);
+            let mut relocator = Relocator::build(&object, 0).unwrap();
+            relocator.set_symbol_address("_puts", 0x1000);
+            relocator.apply(&object.sections()[0]).err().unwrap()
+        });
+        assert_eq!(
+            error.to_string(),
+            "__TEXT,__text at 0x8 (PAGE21 __TEXT,__cstring): addend can not be obtained"
+        );
+
+        let object = parse_single("testable/abi/libabi_new.dylib");
+        assert!(Relocator::build(&object, 0).is_err());
+    }
+}
@@ -0,0 +1,647 @@
+//! References:
+//! <https://llvm.org/doxygen/namespacellvm_1_1MachO.html#ad23021ed657c8b0f302154585b0fd3bfa75544865ca16caaf6ebb768f93879546>
+//! <https://opensource.apple.com/source/xnu/xnu-4570.71.2/EXTERNAL_HEADERS/mach-o/reloc.h.auto.html>
+//! <https://opensource.apple.com/source/clang/clang-703.0.31/src/lib/Target/ARM/MCTargetDesc/ARMMachObjectWriter.cpp.auto.html>
+
+use scroll::*;
+
+use std::fmt::{Debug, Display};
+
+use self::constants::*;
+use super::primitives::*;
+use super::constants::BYTES_PER_RELOCATION_INFO;
+
+pub mod resolved;
+pub use resolved::*;
+
+pub mod apply;
+pub use apply::*;
+
+pub mod constants {
+    /// Absolute relocation type for Mach-O files
+    pub const R_ABS: u8 = 0;
+
+    /// mask to be applied to the r_address field
+    /// of a relocation_info structure to tell that
+    /// is is really a scattered_relocation_info
+    /// stucture
+    pub const R_SCATTERED: u32 = 0x80000000;
+
+    /// Absolute address
+    pub const X86_64_RELOC_UNSIGNED: u8 = 0;
+    /// Signed 32-bit displacement
+    pub const X86_64_RELOC_SIGNED: u8 = 1;
+    /// A CALL/JMP instruction with 32-bit displacement
+    pub const X86_64_RELOC_BRANCH: u8 = 2;
+    /// A MOVQ load of a GOT entry
+    pub const X86_64_RELOC_GOT_LOAD: u8 = 3;
+    /// Other GOT references
+    pub const X86_64_RELOC_GOT: u8 = 4;
+    /// Must be followed by a X86_64_RELOC_UNSIGNED relocation
+    pub const X86_64_RELOC_SUBTRACTOR: u8 = 5;
+    /// for signed 32-bit displacement with a -1 addend
+    pub const X86_64_RELOC_SIGNED_1: u8 = 6;
+    /// for signed 32-bit displacement with a -2 addend
+    pub const X86_64_RELOC_SIGNED_2: u8 = 7;
+    /// for signed 32-bit displacement with a -4 addend
+    pub const X86_64_RELOC_SIGNED_4: u8 = 8;
+    /// https://llvm.org/doxygen/namespacellvm_1_1MachO.html#ad23021ed657c8b0f302154585b0fd3bfa75544865ca16caaf6ebb768f93879546
+    pub const X86_64_RELOC_TLV: u8 = 9;
+
+    // x86
+    pub const GENERIC_RELOC_VANILLA: u8 = 0;
+    pub const GENERIC_RELOC_PAIR: u8 = 1;
+    pub const GENERIC_RELOC_SECTDIFF: u8 = 2;
+    pub const GENERIC_RELOC_PB_LA_PTR: u8 = 3;
+    pub const GENERIC_RELOC_LOCAL_SECTDIFF: u8 = 4;
+    pub const GENERIC_RELOC_TLV: u8 = 5;
+
+    // arm
+    pub const ARM_RELOC_VANILLA: u8 = GENERIC_RELOC_VANILLA;
+    pub const ARM_RELOC_PAIR: u8 = GENERIC_RELOC_PAIR;
+    pub const ARM_RELOC_SECTDIFF: u8 = GENERIC_RELOC_SECTDIFF;
+    pub const ARM_RELOC_LOCAL_SECTDIFF: u8 = 3;
+    pub const ARM_RELOC_PB_LA_PTR: u8 = 4;
+    pub const ARM_RELOC_BR24: u8 = 5;
+    pub const ARM_THUMB_RELOC_BR22: u8 = 6;
+
+    /// Obsolete
+    pub const ARM_THUMB_32BIT_BRANCH: u8 = 7;
+    pub const ARM_RELOC_HALF: u8 = 8;
+    pub const ARM_RELOC_HALF_SECTDIFF: u8 = 9;
+
+    /// For pointers.
+    pub const ARM64_RELOC_UNSIGNED: u8 = 0;
+    /// Must be followed by an ARM64_RELOC_UNSIGNED
+    pub const ARM64_RELOC_SUBTRACTOR: u8 = 1;
+    /// A B/BL instruction with 26-bit displacement.
+    pub const ARM64_RELOC_BRANCH26: u8 = 2;
+    /// PC-rel distance to page of target.
+    pub const ARM64_RELOC_PAGE21: u8 = 3;
+    /// Offset within page, scaled by r_length.
+    pub const ARM64_RELOC_PAGEOFF12: u8 = 4;
+    /// PC-rel distance to page of GOT slot.
+    pub const ARM64_RELOC_GOT_LOAD_PAGE21: u8 = 5;
+    /// Offset within page of GOT slot, scaled by r_length.
+    pub const ARM64_RELOC_GOT_LOAD_PAGEOFF12: u8 = 6;
+    /// For pointers to GOT slots.
+    pub const ARM64_RELOC_POINTER_TO_GOT: u8 = 7;
+    /// PC-rel distance to page of TLVP slot.
+    pub const ARM64_RELOC_TLVP_LOAD_PAGE21: u8 = 8;
+    /// Offset within page of TLVP slot, scaled by r_length.
+    pub const ARM64_RELOC_TLVP_LOAD_PAGEOFF12: u8 = 9;
+    /// Must be followed by ARM64_RELOC_PAGE21 or ARM64_RELOC_PAGEOFF12.
+    pub const ARM64_RELOC_ADDEND: u8 = 10;
+}
+
+/// Represents `relocation_info`
+/// Format of a relocation entry of a Mach-O file.  Modified from the 4.3BSD
+/// format.  The modifications from the original format were changing the value
+/// of the r_symbolnum field for "local" (r_extern == 0) relocation entries.
+/// This modification is required to support symbols in an arbitrary number of
+/// sections not just the three sections (text, data and bss) in a 4.3BSD file.
+/// Also the last 4 bits have had the r_type tag added to them.
+#[derive(IOread, SizeWith)]
+pub struct RelocationInfo {
+    /// offset in the section to what is being relocated
+    pub r_address: i32,
+    /// See functions.
+    /// Bits are always stored in little endian order,
+    /// entries of big endian files are converted while reading
+    pub r_bitfield: u32,
+}
+
+impl RelocationInfo {
+    /// Symbol index if r_extern == 1 or section ordinal if r_extern == 0
+    /// r_symbolnum:24
+    pub fn r_symbolnum(&self) -> usize {
+        (self.r_bitfield & 0x00ff_ffffu32) as usize
+    }
+
+    /// Was relocated pc relative already
+    // r_pcrel:1
+    pub fn r_pcrel(&self) -> u8 {
+        ((self.r_bitfield & 0x0100_0000u32) >> 24) as u8
+    }
+
+    /// 0=byte, 1=word, 2=long, 3=quad
+    /// r_length:2
+    pub fn r_length(&self) -> u8 {
+        ((self.r_bitfield & 0x0600_0000u32) >> 25) as u8
+    }
+
+    /// Does not include value of sym referenced
+    /// r_extern:1
+    pub fn r_extern(&self) -> u8 {
+        ((self.r_bitfield & 0x0800_0000) >> 27) as u8
+    }
+
+    /// If not 0, machine specific relocation type
+    /// r_type:4
+    pub fn r_type(&self) -> u8 {
+        ((self.r_bitfield & 0xf000_0000) >> 28) as u8
+    }
+
+    pub fn is_scattered(&self) -> bool {
+        self.r_address as u32 & R_SCATTERED == R_SCATTERED
+    }
+}
+
+/// Represents `scattered_relocation_info`
+/// Describes an item in the file - using a nonzero constant in its relocatable expression or
+/// two addresses in its relocatable expression - that needs to be updated if the addresses that it uses are changed.
+/// This information is needed to reconstruct the addresses that make up the relocatable expression's value in order
+/// to change the addresses independently of each other.
+/// Not used by `x86_64` and `arm64`.
+#[derive(IOread, SizeWith)]
+pub struct ScatteredRelocationInfo {
+    /// See functions.
+    /// Layout is the same for both endians:
+    /// r_scattered:1 (high bit), r_pcrel:1, r_length:2, r_type:4, r_address:24 (low bits)
+    pub r_bitfield: u32,
+    /// The address of the relocatable expression for the item in the file that needs
+    /// to be updated if the address is changed
+    pub r_value: i32,
+}
+
+impl ScatteredRelocationInfo {
+    /// offset in the section to what is being relocated
+    /// r_address:24
+    pub fn r_address(&self) -> u32 {
+        self.r_bitfield & 0x00ff_ffff
+    }
+
+    /// If not 0, machine specific relocation type
+    /// r_type:4
+    pub fn r_type(&self) -> u8 {
+        ((self.r_bitfield & 0x0f00_0000) >> 24) as u8
+    }
+
+    /// 0=byte, 1=word, 2=long, 3=quad
+    /// r_length:2
+    pub fn r_length(&self) -> u8 {
+        ((self.r_bitfield & 0x3000_0000) >> 28) as u8
+    }
+
+    /// Was relocated pc relative already
+    /// r_pcrel:1
+    pub fn r_pcrel(&self) -> u8 {
+        ((self.r_bitfield & 0x4000_0000) >> 30) as u8
+    }
+}
+
+/// Relocation entry, that may be either `relocation_info` or `scattered_relocation_info`.
+/// Form is determined by [R_SCATTERED] bit of the first word on architectures, that have scattered entries.
+pub enum Relocation {
+    Plain(RelocationInfo),
+    Scattered(ScatteredRelocationInfo),
+}
+
+impl Relocation {
+    /// Reads entry with given endian. We assume reader already stands on start of entry
+    pub(super) fn parse(
+        reader: &mut dyn std::io::Read,
+        endian: Endian,
+        cputype: CPUType,
+    ) -> crate::result::Result<Self> {
+        let mut bytes = [0u8; BYTES_PER_RELOCATION_INFO];
+        reader.read_exact(&mut bytes)?;
+        Self::from_bytes(&bytes, endian, cputype)
+    }
+
+    /// Entries of `x86_64` and `arm64` are always plain, [R_SCATTERED] bit is a part of `r_address` there
+    pub(super) fn from_bytes(
+        bytes: &[u8],
+        endian: Endian,
+        cputype: CPUType,
+    ) -> crate::result::Result<Self> {
+        use crate::cpu_constants::*;
+
+        let first: u32 = bytes.pread_with(0, endian)?;
+        let second: u32 = bytes.pread_with(4, endian)?;
+
+        let has_scattered = !matches!(
+            cputype,
+            CPU_TYPE_X86_64 | CPU_TYPE_ARM64 | CPU_TYPE_ARM64_32
+        );
+        if has_scattered && first & R_SCATTERED == R_SCATTERED {
+            return Ok(Self::Scattered(ScatteredRelocationInfo {
+                r_bitfield: first,
+                r_value: second as i32,
+            }));
+        }
+
+        let r_bitfield = match endian {
+            Endian::Little => second,
+            // r_symbolnum:24, r_pcrel:1, r_length:2, r_extern:1, r_type:4 - from high bit to low bit
+            Endian::Big => {
+                (second >> 8)
+                    | ((second >> 7) & 0x1) << 24
+                    | ((second >> 5) & 0x3) << 25
+                    | ((second >> 4) & 0x1) << 27
+                    | (second & 0xf) << 28
+            }
+        };
+
+        Ok(Self::Plain(RelocationInfo {
+            r_address: first as i32,
+            r_bitfield,
+        }))
+    }
+}
+
+impl Relocation {
+    pub fn is_scattered(&self) -> bool {
+        match self {
+            Relocation::Plain(_) => false,
+            Relocation::Scattered(_) => true,
+        }
+    }
+
+    /// Offset in the section to what is being relocated
+    pub fn r_address(&self) -> u32 {
+        match self {
+            Relocation::Plain(r) => r.r_address as u32,
+            Relocation::Scattered(r) => r.r_address(),
+        }
+    }
+
+    pub fn r_type(&self) -> u8 {
+        match self {
+            Relocation::Plain(r) => r.r_type(),
+            Relocation::Scattered(r) => r.r_type(),
+        }
+    }
+
+    pub fn r_length(&self) -> u8 {
+        match self {
+            Relocation::Plain(r) => r.r_length(),
+            Relocation::Scattered(r) => r.r_length(),
+        }
+    }
+
+    pub fn r_pcrel(&self) -> u8 {
+        match self {
+            Relocation::Plain(r) => r.r_pcrel(),
+            Relocation::Scattered(r) => r.r_pcrel(),
+        }
+    }
+
+    /// Returns named relocation type, according to `cputype`
+    pub fn relocation_type(&self, cputype: CPUType) -> RelocationType {
+        RelocationType::new(cputype, self.r_type())
+    }
+}
+
+impl Debug for Relocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Relocation::Plain(r) => f
+                .debug_struct("RelocationInfo")
+                .field("r_address", &r.r_address)
+                .field("r_symbolnum", &r.r_symbolnum())
+                .field("r_pcrel", &r.r_pcrel())
+                .field("r_length", &r.r_length())
+                .field("r_extern", &r.r_extern())
+                .field("r_type", &r.r_type())
+                .finish(),
+            Relocation::Scattered(r) => f
+                .debug_struct("ScatteredRelocationInfo")
+                .field("r_address", &r.r_address())
+                .field("r_pcrel", &r.r_pcrel())
+                .field("r_length", &r.r_length())
+                .field("r_type", &r.r_type())
+                .field("r_value", &r.r_value)
+                .finish(),
+        }
+    }
+}
+
+/// Machine specific relocation type (`r_type`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocationType {
+    /// `GENERIC_RELOC_*`, used by `i386`
+    Generic(GenericRelocationType),
+    /// `X86_64_RELOC_*`
+    X86_64(X86_64RelocationType),
+    /// `ARM_RELOC_*`
+    Arm(ArmRelocationType),
+    /// `ARM64_RELOC_*`
+    Arm64(Arm64RelocationType),
+    /// Architecture or value unknown for lib
+    Unknown(u8),
+}
+
+impl RelocationType {
+    pub fn new(cputype: CPUType, r_type: u8) -> Self {
+        use crate::cpu_constants::*;
+
+        let known = match cputype {
+            CPU_TYPE_X86 => GenericRelocationType::new(r_type).map(Self::Generic),
+            CPU_TYPE_X86_64 => X86_64RelocationType::new(r_type).map(Self::X86_64),
+            CPU_TYPE_ARM => ArmRelocationType::new(r_type).map(Self::Arm),
+            CPU_TYPE_ARM64 | CPU_TYPE_ARM64_32 => {
+                Arm64RelocationType::new(r_type).map(Self::Arm64)
+            }
+            _ => None,
+        };
+
+        known.unwrap_or(Self::Unknown(r_type))
+    }
+}
+
+impl Display for RelocationType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelocationType::Generic(t) => write!(f, "{t}"),
+            RelocationType::X86_64(t) => write!(f, "{t}"),
+            RelocationType::Arm(t) => write!(f, "{t}"),
+            RelocationType::Arm64(t) => write!(f, "{t}"),
+            RelocationType::Unknown(t) => write!(f, "{t}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenericRelocationType {
+    Vanilla,
+    Pair,
+    Sectdiff,
+    PbLaPtr,
+    LocalSectdiff,
+    Tlv,
+}
+
+impl GenericRelocationType {
+    pub fn new(r_type: u8) -> Option<Self> {
+        match r_type {
+            GENERIC_RELOC_VANILLA => Some(Self::Vanilla),
+            GENERIC_RELOC_PAIR => Some(Self::Pair),
+            GENERIC_RELOC_SECTDIFF => Some(Self::Sectdiff),
+            GENERIC_RELOC_PB_LA_PTR => Some(Self::PbLaPtr),
+            GENERIC_RELOC_LOCAL_SECTDIFF => Some(Self::LocalSectdiff),
+            GENERIC_RELOC_TLV => Some(Self::Tlv),
+            _ => None,
+        }
+    }
+}
+
+impl Display for GenericRelocationType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Vanilla => "VANILLA",
+            Self::Pair => "PAIR",
+            Self::Sectdiff => "SECTDIFF",
+            Self::PbLaPtr => "PB_LA_PTR",
+            Self::LocalSectdiff => "LOCAL_SECTDIFF",
+            Self::Tlv => "TLV",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum X86_64RelocationType {
+    Unsigned,
+    Signed,
+    Branch,
+    GotLoad,
+    Got,
+    Subtractor,
+    Signed1,
+    Signed2,
+    Signed4,
+    Tlv,
+}
+
+impl X86_64RelocationType {
+    pub fn new(r_type: u8) -> Option<Self> {
+        match r_type {
+            X86_64_RELOC_UNSIGNED => Some(Self::Unsigned),
+            X86_64_RELOC_SIGNED => Some(Self::Signed),
+            X86_64_RELOC_BRANCH => Some(Self::Branch),
+            X86_64_RELOC_GOT_LOAD => Some(Self::GotLoad),
+            X86_64_RELOC_GOT => Some(Self::Got),
+            X86_64_RELOC_SUBTRACTOR => Some(Self::Subtractor),
+            X86_64_RELOC_SIGNED_1 => Some(Self::Signed1),
+            X86_64_RELOC_SIGNED_2 => Some(Self::Signed2),
+            X86_64_RELOC_SIGNED_4 => Some(Self::Signed4),
+            X86_64_RELOC_TLV => Some(Self::Tlv),
+            _ => None,
+        }
+    }
+}
+
+impl Display for X86_64RelocationType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Unsigned => "UNSIGNED",
+            Self::Signed => "SIGNED",
+            Self::Branch => "BRANCH",
+            Self::GotLoad => "GOT_LOAD",
+            Self::Got => "GOT",
+            Self::Subtractor => "SUBTRACTOR",
+            Self::Signed1 => "SIGNED_1",
+            Self::Signed2 => "SIGNED_2",
+            Self::Signed4 => "SIGNED_4",
+            Self::Tlv => "TLV",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmRelocationType {
+    Vanilla,
+    Pair,
+    Sectdiff,
+    LocalSectdiff,
+    PbLaPtr,
+    Br24,
+    ThumbBr22,
+    Thumb32BitBranch,
+    Half,
+    HalfSectdiff,
+}
+
+impl ArmRelocationType {
+    pub fn new(r_type: u8) -> Option<Self> {
+        match r_type {
+            ARM_RELOC_VANILLA => Some(Self::Vanilla),
+            ARM_RELOC_PAIR => Some(Self::Pair),
+            ARM_RELOC_SECTDIFF => Some(Self::Sectdiff),
+            ARM_RELOC_LOCAL_SECTDIFF => Some(Self::LocalSectdiff),
+            ARM_RELOC_PB_LA_PTR => Some(Self::PbLaPtr),
+            ARM_RELOC_BR24 => Some(Self::Br24),
+            ARM_THUMB_RELOC_BR22 => Some(Self::ThumbBr22),
+            ARM_THUMB_32BIT_BRANCH => Some(Self::Thumb32BitBranch),
+            ARM_RELOC_HALF => Some(Self::Half),
+            ARM_RELOC_HALF_SECTDIFF => Some(Self::HalfSectdiff),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ArmRelocationType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Vanilla => "VANILLA",
+            Self::Pair => "PAIR",
+            Self::Sectdiff => "SECTDIFF",
+            Self::LocalSectdiff => "LOCAL_SECTDIFF",
+            Self::PbLaPtr => "PB_LA_PTR",
+            Self::Br24 => "BR24",
+            Self::ThumbBr22 => "THUMB_BR22",
+            Self::Thumb32BitBranch => "THUMB_32BIT_BRANCH",
+            Self::Half => "HALF",
+            Self::HalfSectdiff => "HALF_SECTDIFF",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arm64RelocationType {
+    Unsigned,
+    Subtractor,
+    Branch26,
+    Page21,
+    Pageoff12,
+    GotLoadPage21,
+    GotLoadPageoff12,
+    PointerToGot,
+    TlvpLoadPage21,
+    TlvpLoadPageoff12,
+    Addend,
+}
+
+impl Arm64RelocationType {
+    pub fn new(r_type: u8) -> Option<Self> {
+        match r_type {
+            ARM64_RELOC_UNSIGNED => Some(Self::Unsigned),
+            ARM64_RELOC_SUBTRACTOR => Some(Self::Subtractor),
+            ARM64_RELOC_BRANCH26 => Some(Self::Branch26),
+            ARM64_RELOC_PAGE21 => Some(Self::Page21),
+            ARM64_RELOC_PAGEOFF12 => Some(Self::Pageoff12),
+            ARM64_RELOC_GOT_LOAD_PAGE21 => Some(Self::GotLoadPage21),
+            ARM64_RELOC_GOT_LOAD_PAGEOFF12 => Some(Self::GotLoadPageoff12),
+            ARM64_RELOC_POINTER_TO_GOT => Some(Self::PointerToGot),
+            ARM64_RELOC_TLVP_LOAD_PAGE21 => Some(Self::TlvpLoadPage21),
+            ARM64_RELOC_TLVP_LOAD_PAGEOFF12 => Some(Self::TlvpLoadPageoff12),
+            ARM64_RELOC_ADDEND => Some(Self::Addend),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Arm64RelocationType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Unsigned => "UNSIGNED",
+            Self::Subtractor => "SUBTRACTOR",
+            Self::Branch26 => "BRANCH26",
+            Self::Page21 => "PAGE21",
+            Self::Pageoff12 => "PAGEOFF12",
+            Self::GotLoadPage21 => "GOT_LOAD_PAGE21",
+            Self::GotLoadPageoff12 => "GOT_LOAD_PAGEOFF12",
+            Self::PointerToGot => "POINTER_TO_GOT",
+            Self::TlvpLoadPage21 => "TLVP_LOAD_PAGE21",
+            Self::TlvpLoadPageoff12 => "TLVP_LOAD_PAGEOFF12",
+            Self::Addend => "ADDEND",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cpu_constants::*;
+
+    #[test]
+    fn test_plain_relocation_little_endian() {
+        // r_address: 0x18, r_symbolnum: 5, r_pcrel: 1, r_length: 2, r_extern: 1, r_type: BRANCH26
+        let bytes: &[u8] = &[0x18, 0, 0, 0, 0x05, 0, 0, 0x2d];
+        let reloc = Relocation::from_bytes(bytes, Endian::Little, CPU_TYPE_ARM64).unwrap();
+
+        let info = match &reloc {
+            Relocation::Plain(info) => info,
+            Relocation::Scattered(_) => panic!("Expected plain relocation, got {:?}", reloc),
+        };
+
+        assert_eq!(info.r_address, 0x18);
+        assert_eq!(info.r_symbolnum(), 5);
+        assert_eq!(info.r_pcrel(), 1);
+        assert_eq!(info.r_length(), 2);
+        assert_eq!(info.r_extern(), 1);
+        assert_eq!(info.r_type(), ARM64_RELOC_BRANCH26);
+        assert_eq!(
+            reloc.relocation_type(CPU_TYPE_ARM64),
+            RelocationType::Arm64(Arm64RelocationType::Branch26)
+        );
+    }
+
+    #[test]
+    fn test_plain_relocation_big_endian() {
+        // Same fields as in little endian test, but with big endian bit order
+        let bytes: &[u8] = &[0, 0, 0, 0x18, 0, 0, 0x05, 0xd2];
+        let reloc = Relocation::from_bytes(bytes, Endian::Big, CPU_TYPE_POWERPC).unwrap();
+
+        let info = match &reloc {
+            Relocation::Plain(info) => info,
+            Relocation::Scattered(_) => panic!("Expected plain relocation, got {:?}", reloc),
+        };
+
+        assert_eq!(info.r_address, 0x18);
+        assert_eq!(info.r_symbolnum(), 5);
+        assert_eq!(info.r_pcrel(), 1);
+        assert_eq!(info.r_length(), 2);
+        assert_eq!(info.r_extern(), 1);
+        assert_eq!(info.r_type(), 2);
+    }
+
+    #[test]
+    fn test_scattered_relocation() {
+        // r_scattered: 1, r_pcrel: 0, r_length: 2, r_type: SECTDIFF, r_address: 0x1234, r_value: 0x40
+        let le: &[u8] = &[0x34, 0x12, 0x00, 0xa2, 0x40, 0, 0, 0];
+        let be: &[u8] = &[0xa2, 0x00, 0x12, 0x34, 0, 0, 0, 0x40];
+
+        for (bytes, endian) in [(le, Endian::Little), (be, Endian::Big)] {
+            let reloc = Relocation::from_bytes(bytes, endian, CPU_TYPE_I386).unwrap();
+            let info = match &reloc {
+                Relocation::Scattered(info) => info,
+                Relocation::Plain(_) => panic!("Expected scattered relocation, got {:?}", reloc),
+            };
+
+            assert_eq!(info.r_address(), 0x1234);
+            assert_eq!(info.r_pcrel(), 0);
+            assert_eq!(info.r_length(), 2);
+            assert_eq!(info.r_type(), GENERIC_RELOC_SECTDIFF);
+            assert_eq!(info.r_value, 0x40);
+            assert_eq!(
+                reloc.relocation_type(CPU_TYPE_I386),
+                RelocationType::Generic(GenericRelocationType::Sectdiff)
+            );
+        }
+    }
+
+    #[test]
+    fn test_scattered_bit_of_plain_relocation() {
+        // r_address with high bit set, r_symbolnum: 1, r_length: 3, r_extern: 1, r_type: UNSIGNED
+        let bytes: &[u8] = &[0x08, 0, 0, 0x80, 0x01, 0, 0, 0x0e];
+        let reloc = Relocation::from_bytes(bytes, Endian::Little, CPU_TYPE_X86_64).unwrap();
+
+        let info = match &reloc {
+            Relocation::Plain(info) => info,
+            Relocation::Scattered(_) => panic!("Expected plain relocation, got {:?}", reloc),
+        };
+        assert_eq!(info.r_address as u32, 0x8000_0008);
+        assert_eq!(info.r_symbolnum(), 1);
+        assert_eq!(info.r_length(), 3);
+        assert_eq!(info.r_extern(), 1);
+        assert_eq!(info.r_type(), X86_64_RELOC_UNSIGNED);
+
+        let reloc = Relocation::from_bytes(bytes, Endian::Little, CPU_TYPE_I386).unwrap();
+        assert!(reloc.is_scattered());
+    }
+}
@@ -0,0 +1,643 @@
+//! Resolution of relocation entries to their targets, in the way `otool -rv` does.
+//! Paired entries (`*_SUBTRACTOR`, `*_PAIR` and `ARM64_RELOC_ADDEND`) are merged
+//! with their follow-up entries.
+
+use super::*;
+use crate::result::Result;
+use crate::{LcSymtab, MachObject, Section};
+
+use std::fmt::Display;
+
+/// Target of relocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelocationTarget {
+    /// `r_extern == 1`. Index and name of symbol in `LcSymtab`
+    Symbol { index: usize, name: String },
+    /// `r_extern == 0`. Section ordinal (starting at 1) with names
+    Section {
+        ordinal: usize,
+        segname: String,
+        sectname: String,
+    },
+    /// `r_value` of scattered relocation with names of section, that contains the address
+    Address {
+        value: u32,
+        section: Option<(String, String)>,
+    },
+    /// `R_ABS`, no relocation needed
+    Absolute,
+}
+
+impl Display for RelocationTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelocationTarget::Symbol { index, name } => match name.len() {
+                0 => write!(f, "symbol #{index}"),
+                _ => write!(f, "{name}"),
+            },
+            RelocationTarget::Section {
+                segname, sectname, ..
+            } => write!(f, "{segname},{sectname}"),
+            RelocationTarget::Address { value, section } => match section {
+                Some((segname, sectname)) => write!(f, "{:#x} ({segname},{sectname})", value),
+                None => write!(f, "{:#x}", value),
+            },
+            RelocationTarget::Absolute => write!(f, "absolute"),
+        }
+    }
+}
+
+/// Relocation with resolved target.
+/// Built from one or two (for pairs) raw relocation entries
+pub struct ResolvedRelocation {
+    /// Offset in the section to what is being relocated
+    pub address: u32,
+    /// Type of the first entry. For `ARM64_RELOC_ADDEND` pairs - type of the follow-up entry
+    pub r_type: RelocationType,
+    pub pcrel: bool,
+    /// 0=byte, 1=word, 2=long, 3=quad
+    pub length: u8,
+    pub target: RelocationTarget,
+    /// Subtracted target of `*_SUBTRACTOR` and `*_SECTDIFF` pairs
+    pub subtrahend: Option<RelocationTarget>,
+    /// Value added to `target`. `None` if it can not be obtained for the relocation type
+    pub addend: Option<i64>,
+    /// Raw entries the relocation built from
+    pub entries: Vec<Relocation>,
+}
+
+impl ResolvedRelocation {
+    /// Returns string like `_main - _ptr + 0x8`
+    pub fn target_string(&self) -> String {
+        let mut result = self.target.to_string();
+        if let Some(subtrahend) = &self.subtrahend {
+            result += &format!(" - {subtrahend}");
+        }
+        match self.addend {
+            Some(addend) if addend > 0 => result += &format!(" + {:#x}", addend),
+            Some(addend) if addend < 0 => result += &format!(" - {:#x}", -addend),
+            _ => (),
+        }
+        result
+    }
+}
+
+/// Resolves relocations of sections of single [MachObject].
+/// Symbols and sections are loaded once, while building.
+pub struct RelocationResolver {
+    cputype: CPUType,
+    symbols: Vec<String>,
+    /// `(segname, sectname, addr, size)` in order of section ordinals
+    pub(super) sections: Vec<(String, String, u64, u64)>,
+}
+
+impl RelocationResolver {
+    pub fn build(object: &MachObject) -> Result<Self> {
+        let symbols = match object.symtab() {
+            Some(symtab) => Self::load_symbol_names(&symtab)?,
+            None => Vec::new(),
+        };
+
+        let sections = object
+            .sections()
+            .iter()
+            .map(|s| {
+                (
+                    s.segname.to_string(),
+                    s.sectname.to_string(),
+                    s.addr.0,
+                    s.size.0,
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            cputype: object.header().cputype,
+            symbols,
+            sections,
+        })
+    }
+
+    fn load_symbol_names(symtab: &LcSymtab) -> Result<Vec<String>> {
+        let mut result = Vec::with_capacity(symtab.nsyms as usize);
+        for nlist in symtab.nlist_iterator() {
+            let name = match &nlist.name {
+                Some(name) => name.load_string()?,
+                None => "".to_string(),
+            };
+            result.push(name);
+        }
+        Ok(result)
+    }
+}
+
+impl RelocationResolver {
+    /// Resolves all relocation entries of `section`
+    pub fn resolve(&self, section: &Section) -> Result<Vec<ResolvedRelocation>> {
+        let data = section.read_data()?;
+
+        let mut entries = section.relocations_iterator().peekable();
+        let mut result = Vec::new();
+
+        while let Some(first) = entries.next() {
+            let resolved = match self.pair_kind(&first) {
+                PairKind::Single => self.resolve_single(section, &data, first),
+                PairKind::Subtractor => match entries.next() {
+                    Some(second) => self.resolve_subtractor(section, &data, first, second),
+                    None => self.resolve_single(section, &data, first),
+                },
+                PairKind::Addend => match entries.next() {
+                    Some(second) => self.resolve_addend(first, second),
+                    None => self.resolve_single(section, &data, first),
+                },
+                PairKind::Pair => match entries.peek() {
+                    Some(next) if self.is_pair(next) => {
+                        let second = entries.next().unwrap();
+                        self.resolve_sectdiff(section, &data, first, second)
+                    }
+                    _ => self.resolve_single(section, &data, first),
+                },
+            };
+            result.push(resolved);
+        }
+
+        Ok(result)
+    }
+
+    fn pair_kind(&self, reloc: &Relocation) -> PairKind {
+        match reloc.relocation_type(self.cputype) {
+            RelocationType::X86_64(X86_64RelocationType::Subtractor)
+            | RelocationType::Arm64(Arm64RelocationType::Subtractor) => PairKind::Subtractor,
+            RelocationType::Arm64(Arm64RelocationType::Addend) => PairKind::Addend,
+            RelocationType::Generic(GenericRelocationType::Sectdiff)
+            | RelocationType::Generic(GenericRelocationType::LocalSectdiff)
+            | RelocationType::Arm(ArmRelocationType::Sectdiff)
+            | RelocationType::Arm(ArmRelocationType::LocalSectdiff)
+            | RelocationType::Arm(ArmRelocationType::Half)
+            | RelocationType::Arm(ArmRelocationType::HalfSectdiff) => PairKind::Pair,
+            _ => PairKind::Single,
+        }
+    }
+
+    fn is_pair(&self, reloc: &Relocation) -> bool {
+        matches!(
+            reloc.relocation_type(self.cputype),
+            RelocationType::Generic(GenericRelocationType::Pair)
+                | RelocationType::Arm(ArmRelocationType::Pair)
+        )
+    }
+
+    fn resolve_single(&self, section: &Section, data: &[u8], reloc: Relocation) -> ResolvedRelocation {
+        let target = self.target(&reloc);
+        let addend = self.addend(section, data, &reloc, &target);
+        let r_type = reloc.relocation_type(self.cputype);
+        self.make(0, r_type, target, None, addend, vec![reloc])
+    }
+
+    /// `SUBTRACTOR` contains subtrahend, follow-up `UNSIGNED` contains minuend
+    fn resolve_subtractor(
+        &self,
+        section: &Section,
+        data: &[u8],
+        first: Relocation,
+        second: Relocation,
+    ) -> ResolvedRelocation {
+        let subtrahend = self.target(&first);
+        let target = self.target(&second);
+
+        let addend = match (&subtrahend, &target) {
+            (RelocationTarget::Symbol { .. }, RelocationTarget::Symbol { .. }) => {
+                self.content(section, data, &second)
+            }
+            _ => None,
+        };
+
+        let r_type = first.relocation_type(self.cputype);
+        self.make(
+            0,
+            r_type,
+            target,
+            Some(subtrahend),
+            addend,
+            vec![first, second],
+        )
+    }
+
+    /// `r_symbolnum` of `ARM64_RELOC_ADDEND` contains 24-bit signed addend for the follow-up entry
+    fn resolve_addend(&self, first: Relocation, second: Relocation) -> ResolvedRelocation {
+        let addend = match &first {
+            Relocation::Plain(info) => Some(sign_extend(info.r_symbolnum() as u64, 24)),
+            Relocation::Scattered(_) => None,
+        };
+        let target = self.target(&second);
+
+        let r_type = second.relocation_type(self.cputype);
+        self.make(
+            1,
+            r_type,
+            target,
+            None,
+            addend,
+            vec![first, second],
+        )
+    }
+
+    /// `r_value` of first entry contains minuend address, `r_value` of `PAIR` - subtrahend address
+    fn resolve_sectdiff(
+        &self,
+        section: &Section,
+        data: &[u8],
+        first: Relocation,
+        second: Relocation,
+    ) -> ResolvedRelocation {
+        let r_type = first.relocation_type(self.cputype);
+        let target = self.target(&first);
+
+        let is_sectdiff = matches!(
+            r_type,
+            RelocationType::Generic(GenericRelocationType::Sectdiff)
+                | RelocationType::Generic(GenericRelocationType::LocalSectdiff)
+                | RelocationType::Arm(ArmRelocationType::Sectdiff)
+                | RelocationType::Arm(ArmRelocationType::LocalSectdiff)
+        );
+
+        let (subtrahend, addend) = match (&first, &second, is_sectdiff) {
+            (Relocation::Scattered(a), Relocation::Scattered(b), true) => {
+                let subtrahend = self.target(&second);
+                let addend = self
+                    .content(section, data, &first)
+                    .map(|c| c - (a.r_value as i64 - b.r_value as i64));
+                (Some(subtrahend), addend)
+            }
+            _ => (None, None),
+        };
+
+        self.make(0, r_type, target, subtrahend, addend, vec![first, second])
+    }
+
+    /// `primary` - index of entry in `entries`, that describes relocated item
+    fn make(
+        &self,
+        primary: usize,
+        r_type: RelocationType,
+        target: RelocationTarget,
+        subtrahend: Option<RelocationTarget>,
+        addend: Option<i64>,
+        entries: Vec<Relocation>,
+    ) -> ResolvedRelocation {
+        let reloc = &entries[primary];
+        ResolvedRelocation {
+            address: reloc.r_address(),
+            r_type,
+            pcrel: reloc.r_pcrel() == 1,
+            length: reloc.r_length(),
+            target,
+            subtrahend,
+            addend,
+            entries,
+        }
+    }
+}
+
+impl RelocationResolver {
+    fn target(&self, reloc: &Relocation) -> RelocationTarget {
+        match reloc {
+            Relocation::Plain(info) if info.r_extern() == 1 => {
+                let index = info.r_symbolnum();
+                RelocationTarget::Symbol {
+                    index,
+                    name: self.symbols.get(index).cloned().unwrap_or_default(),
+                }
+            }
+            Relocation::Plain(info) => {
+                let ordinal = info.r_symbolnum();
+                if ordinal == R_ABS as usize {
+                    return RelocationTarget::Absolute;
+                }
+                match self.sections.get(ordinal - 1) {
+                    Some((segname, sectname, _, _)) => RelocationTarget::Section {
+                        ordinal,
+                        segname: segname.clone(),
+                        sectname: sectname.clone(),
+                    },
+                    None => RelocationTarget::Section {
+                        ordinal,
+                        segname: "".to_string(),
+                        sectname: "".to_string(),
+                    },
+                }
+            }
+            Relocation::Scattered(info) => RelocationTarget::Address {
+                value: info.r_value as u32,
+                section: self
+                    .section_containing(info.r_value as u32 as u64)
+                    .map(|(seg, sect, _, _)| (seg.clone(), sect.clone())),
+            },
+        }
+    }
+
+    fn section_containing(&self, addr: u64) -> Option<&(String, String, u64, u64)> {
+        self.sections
+            .iter()
+            .find(|(_, _, start, size)| addr >= *start && addr < start + size)
+    }
+
+    fn section_addr(&self, target: &RelocationTarget) -> Option<u64> {
+        match target {
+            RelocationTarget::Section { ordinal, .. } => {
+                self.sections.get(ordinal - 1).map(|s| s.2)
+            }
+            _ => None,
+        }
+    }
+
+    /// Sign extended value of relocated item, stored in section data
+    fn content(&self, section: &Section, data: &[u8], reloc: &Relocation) -> Option<i64> {
+        read_signed(data, reloc.r_address() as usize, reloc.r_length(), section.endian)
+    }
+
+    fn addend(
+        &self,
+        section: &Section,
+        data: &[u8],
+        reloc: &Relocation,
+        target: &RelocationTarget,
+    ) -> Option<i64> {
+        let content = self.content(section, data, reloc)?;
+        let fixup_addr = section.addr.0 as i64 + reloc.r_address() as i64;
+        let item_size = 1i64 << reloc.r_length();
+
+        // Converts absolute address stored in item to offset from target section
+        let from_address = |address: i64| match target {
+            RelocationTarget::Section { .. } => {
+                self.section_addr(target).map(|s| address - s as i64)
+            }
+            RelocationTarget::Symbol { .. } => Some(address),
+            _ => None,
+        };
+
+        match reloc.relocation_type(self.cputype) {
+            RelocationType::X86_64(t) => {
+                let bias = match t {
+                    X86_64RelocationType::Signed1 => 1,
+                    X86_64RelocationType::Signed2 => 2,
+                    X86_64RelocationType::Signed4 => 4,
+                    _ => 0,
+                };
+                match (t, target) {
+                    (X86_64RelocationType::Unsigned, _) => from_address(content),
+                    (_, RelocationTarget::Symbol { .. }) => Some(content + bias),
+                    _ => from_address(fixup_addr + item_size + bias + content),
+                }
+            }
+            RelocationType::Arm64(t) => match (t, target) {
+                (Arm64RelocationType::Unsigned, _) => from_address(content),
+                (_, RelocationTarget::Symbol { .. }) => Some(0),
+                _ => None,
+            },
+            RelocationType::Generic(GenericRelocationType::Vanilla)
+            | RelocationType::Arm(ArmRelocationType::Vanilla) => {
+                let value = match reloc.r_pcrel() {
+                    1 => content + fixup_addr + item_size,
+                    _ => content,
+                };
+                match (reloc, target) {
+                    (Relocation::Scattered(info), _) => Some(value - info.r_value as i64),
+                    _ => from_address(value),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+enum PairKind {
+    Single,
+    /// `*_SUBTRACTOR` followed by `*_UNSIGNED`
+    Subtractor,
+    /// `ARM64_RELOC_ADDEND` followed by any other
+    Addend,
+    /// Any type followed by `*_PAIR`
+    Pair,
+}
+
+fn sign_extend(value: u64, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
+fn read_signed(data: &[u8], offset: usize, r_length: u8, endian: Endian) -> Option<i64> {
+    let value = match r_length {
+        0 => data.pread_with::<i8>(offset, endian).ok()? as i64,
+        1 => data.pread_with::<i16>(offset, endian).ok()? as i64,
+        2 => data.pread_with::<i32>(offset, endian).ok()? as i64,
+        _ => data.pread_with::<i64>(offset, endian).ok()?,
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cpu_constants::CPU_TYPE_I386;
+    use crate::test_utils::{parse_single, read_single, with_temp_file};
+
+    fn resolve(object: &MachObject, sectname: &str) -> Vec<ResolvedRelocation> {
+        let section = object
+            .sections()
+            .into_iter()
+            .find(|section| section.sectname.to_string() == sectname)
+            .unwrap();
+        RelocationResolver::build(object)
+            .unwrap()
+            .resolve(&section)
+            .unwrap()
+    }
+
+    fn symbol(index: usize, name: &str) -> RelocationTarget {
+        RelocationTarget::Symbol {
+            index,
+            name: name.to_string(),
+        }
+    }
+
+    /// `i386` object with `_b - _a + 2` (`SECTDIFF` and `PAIR`) and `_a + 3` (scattered `VANILLA`)
+    /// in `__data`, where `_a` and `_b` are at 0x2 and 0x4 of `__text`
+    fn scattered_i386() -> Vec<u8> {
+        fn name(name: &str) -> [u32; 4] {
+            let mut bytes = [0u8; 16];
+            bytes[..name.len()].copy_from_slice(name.as_bytes());
+            let word = |idx: usize| bytes.pread_with(idx * 4, Endian::Little).unwrap();
+            [word(0), word(1), word(2), word(3)]
+        }
+
+        let sizeofcmds = 56 + 2 * 68;
+        let data_offset = 28 + sizeofcmds;
+        let mut words = Vec::new();
+        // mach_header
+        words.extend([0xfeedface, CPU_TYPE_I386.0, 3, 1, 1, sizeofcmds, 0]);
+        // LC_SEGMENT without name
+        words.extend([1, sizeofcmds, 0, 0, 0, 0]);
+        words.extend([0, 0x10, data_offset, 0x10, 7, 7, 2, 0]);
+        words.extend(name("__text"));
+        words.extend(name("__TEXT"));
+        words.extend([0, 8, data_offset, 0, 0, 0, 0x8000_0400, 0, 0]);
+        words.extend(name("__data"));
+        words.extend(name("__DATA"));
+        words.extend([8, 8, data_offset + 8, 0, data_offset + 0x10, 3, 0, 0, 0]);
+        // __text and __data
+        words.extend([0, 0, 4, 5]);
+        // SECTDIFF, PAIR and VANILLA
+        words.extend([0xa200_0000, 4, 0xa100_0000, 2, 0xa000_0004, 2]);
+
+        words.into_iter().flat_map(u32::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn test_symbols_and_sections() {
+        let object = parse_single("testable/dwarf_arm64.o");
+
+        let text = resolve(&object, "__text");
+        assert_eq!(text.len(), 1);
+        assert_eq!(text[0].address, 0x18);
+        assert_eq!(
+            text[0].r_type,
+            RelocationType::Arm64(Arm64RelocationType::Branch26)
+        );
+        assert!(text[0].pcrel);
+        assert!(matches!(&text[0].target, RelocationTarget::Symbol { name, .. } if name == "_add"));
+        assert_eq!(text[0].addend, Some(0));
+
+        let debug_info = resolve(&object, "__debug_info");
+        let targets: Vec<(u32, String, Option<i64>)> = debug_info
+            .iter()
+            .map(|reloc| (reloc.address, reloc.target.to_string(), reloc.addend))
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                (0x48, "__TEXT,__text".to_string(), Some(0xc)),
+                (0x2f, "__TEXT,__text".to_string(), Some(0)),
+                (0x22, "__TEXT,__text".to_string(), Some(0)),
+            ]
+        );
+        assert!(matches!(
+            debug_info[0].target,
+            RelocationTarget::Section { ordinal: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_subtractor() {
+        let object = parse_single("testable/dwarf_arm64.o");
+        let eh_frame = resolve(&object, "__eh_frame");
+        assert_eq!(eh_frame.len(), 2);
+
+        let reloc = &eh_frame[0];
+        assert_eq!(reloc.entries.len(), 2);
+        assert_eq!(reloc.address, 0x38);
+        assert_eq!(
+            reloc.r_type,
+            RelocationType::Arm64(Arm64RelocationType::Subtractor)
+        );
+        assert!(matches!(&reloc.target, RelocationTarget::Symbol { name, .. } if name == "_main"));
+        assert!(
+            matches!(&reloc.subtrahend, Some(RelocationTarget::Symbol { name, .. }) if name == "ltmp2")
+        );
+        assert_eq!(reloc.addend, Some(-0x38));
+        assert_eq!(reloc.target_string(), "_main - ltmp2 - 0x38");
+    }
+
+    #[test]
+    fn test_addend() {
+        // `PAGEOFF12` of `l_.str` turned to `ADDEND` of 8 for the following `PAGE21`
+        let path = "testable/disasm_arm64.o";
+        let reloff = parse_single(path).sections()[0].reloff as usize;
+        let mut bytes = read_single(path).data().to_vec();
+        bytes
+            .pwrite_with(
+                0xa400_0008u32,
+                reloff + BYTES_PER_RELOCATION_INFO + 4,
+                Endian::Little,
+            )
+            .unwrap();
+        let text = with_temp_file("schnauzer_resolved_test.o", &bytes, |path| {
+            resolve(&parse_single(path), "__text")
+        });
+
+        assert_eq!(text.len(), 2);
+        assert_eq!(text[0].target, symbol(5, "_puts"));
+        let reloc = &text[1];
+        assert_eq!(reloc.entries.len(), 2);
+        assert_eq!(reloc.address, 0x8);
+        assert_eq!(
+            reloc.r_type,
+            RelocationType::Arm64(Arm64RelocationType::Page21)
+        );
+        assert_eq!(reloc.target, symbol(1, "l_.str"));
+        assert_eq!(reloc.addend, Some(8));
+    }
+
+    #[test]
+    fn test_scattered_pair() {
+        let data = with_temp_file("schnauzer_resolved_i386.o", &scattered_i386(), |path| {
+            resolve(&parse_single(path), "__data")
+        });
+        assert_eq!(data.len(), 2);
+
+        let sectdiff = &data[0];
+        assert_eq!(sectdiff.entries.len(), 2);
+        assert!(sectdiff.entries.iter().all(|entry| entry.is_scattered()));
+        assert_eq!(
+            sectdiff.r_type,
+            RelocationType::Generic(GenericRelocationType::Sectdiff)
+        );
+        let text = Some(("__TEXT".to_string(), "__text".to_string()));
+        assert_eq!(
+            sectdiff.target,
+            RelocationTarget::Address {
+                value: 4,
+                section: text.clone(),
+            }
+        );
+        assert_eq!(
+            sectdiff.subtrahend,
+            Some(RelocationTarget::Address {
+                value: 2,
+                section: text.clone(),
+            })
+        );
+        assert_eq!(sectdiff.addend, Some(2));
+
+        let vanilla = &data[1];
+        assert_eq!(vanilla.entries.len(), 1);
+        assert_eq!(vanilla.address, 4);
+        assert_eq!(
+            vanilla.target,
+            RelocationTarget::Address {
+                value: 2,
+                section: text,
+            }
+        );
+        assert_eq!(vanilla.addend, Some(3));
+    }
+
+    #[test]
+    fn test_section_out_of_file() {
+        let mut bytes = scattered_i386();
+        // size of __data
+        bytes[188..192].copy_from_slice(&0xffff_fff0u32.to_le_bytes());
+
+        let result = with_temp_file("schnauzer_resolved_huge.o", &bytes, |path| {
+            let object = parse_single(path);
+            let section = object
+                .sections()
+                .into_iter()
+                .find(|section| section.sectname.to_string() == "__data")
+                .unwrap();
+            assert_eq!(section.size.0, 0xffff_fff0);
+            RelocationResolver::build(&object).unwrap().resolve(&section)
+        });
+        assert!(result.is_err());
+    }
+}
@@ -2,8 +2,10 @@ use super::primitives::*;
 
 use super::auto_enum_fields::*;
 use super::reloc::*;
+use super::constants::*;
 use super::RcReader;
 use super::Result;
+use crate::result::Error;
 use schnauzer_derive::AutoEnumFields;
 use scroll::ctx::SizeWith;
 use scroll::Endian;
@@ -35,12 +37,14 @@ pub struct Section {
 
     reader: RcReader,
     pub endian: Endian,
+    cputype: CPUType,
 }
 
 impl Section {
     pub(super) fn parse(
         reader: RcReader,
         ctx: X64Context,
+        cputype: CPUType,
         object_file_offset: u64,
     ) -> Result<Self> {
         let endian = ctx.endian().clone();
@@ -78,11 +82,37 @@ impl Section {
             reserved3,
             reader,
             endian,
+            cputype,
         })
     }
 }
 
 impl Section {
+    /// Type of the section, one of `S_*` in [section_constants]
+    pub fn section_type(&self) -> u32 {
+        self.flags.0 & section_constants::SECTION_TYPE
+    }
+
+    /// Contents of the section. Sizes of malformed sections are checked
+    /// against the file before allocating
+    pub fn read_data(&self) -> Result<Vec<u8>> {
+        let start = self.object_file_offset + self.offset as u64;
+        let file_size = self.reader.borrow_mut().seek(SeekFrom::End(0))?;
+        match start.checked_add(self.size.0) {
+            Some(end) if end <= file_size => (),
+            _ => {
+                return Err(Error::Text(format!(
+                    "Section {},{} of size {:#x} is out of file",
+                    self.segname, self.sectname, self.size.0
+                )))
+            }
+        }
+
+        let mut data = Vec::with_capacity(self.size.0 as usize);
+        self.read_data_to(&mut data)?;
+        Ok(data)
+    }
+
     pub fn read_data_to(&self, out: &mut dyn Write) -> Result<()> {
         use std::cmp::min;
         const BUFFER_SIZE: usize = 4096;
@@ -132,12 +162,14 @@ impl SizeWith<X64Context> for Section {
 }
 
 impl Section {
+    /// Entries are read according to `cputype` of the object, as only some architectures have scattered ones
     pub fn relocations_iterator(&self) -> RelocationIterator {
         RelocationIterator::new(
             self.reader.clone(),
             self.nreloc,
             self.object_file_offset + self.reloff as u64,
             self.endian,
+            self.cputype,
         )
     }
 }
@@ -148,24 +180,32 @@ pub struct RelocationIterator {
     count: u32,
     base_offset: u64,
     endian: Endian,
+    cputype: CPUType,
 
     current: u32,
 }
 
 impl RelocationIterator {
-    fn new(reader: RcReader, count: u32, base_offset: u64, endian: Endian) -> Self {
+    fn new(
+        reader: RcReader,
+        count: u32,
+        base_offset: u64,
+        endian: Endian,
+        cputype: CPUType,
+    ) -> Self {
         RelocationIterator {
             reader: reader,
             count: count,
             base_offset: base_offset,
             endian: endian,
+            cputype,
             current: 0,
         }
     }
 }
 
 impl Iterator for RelocationIterator {
-    type Item = RelocationInfo;
+    type Item = Relocation;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.current >= self.count {
@@ -173,7 +213,7 @@ impl Iterator for RelocationIterator {
         }
 
         let offset =
-            self.base_offset + RelocationInfo::size_with(&self.endian) as u64 * self.current as u64;
+            self.base_offset + BYTES_PER_RELOCATION_INFO as u64 * self.current as u64;
         self.current += 1;
 
         let mut reader_mut = self.reader.borrow_mut();
@@ -181,9 +221,85 @@ impl Iterator for RelocationIterator {
             return None;
         }
 
-        match reader_mut.ioread_with::<RelocationInfo>(self.endian) {
-            Ok(info) => Some(info),
+        match Relocation::parse(&mut *reader_mut, self.endian, self.cputype) {
+            Ok(reloc) => Some(reloc),
             Err(_) => None,
         }
     }
 }
+
+/// Constants for the `flags` field of the section, declared in `/usr/include/mach-o/loader.h`
+pub mod section_constants {
+    /// 256 section types
+    pub const SECTION_TYPE: u32 = 0x000000ff;
+    /// 24 section attributes
+    pub const SECTION_ATTRIBUTES: u32 = 0xffffff00;
+
+    /// regular section
+    pub const S_REGULAR: u32 = 0x0;
+    /// zero fill on demand section
+    pub const S_ZEROFILL: u32 = 0x1;
+    /// section with only literal C strings
+    pub const S_CSTRING_LITERALS: u32 = 0x2;
+    /// section with only 4 byte literals
+    pub const S_4BYTE_LITERALS: u32 = 0x3;
+    /// section with only 8 byte literals
+    pub const S_8BYTE_LITERALS: u32 = 0x4;
+    /// section with only pointers to literals
+    pub const S_LITERAL_POINTERS: u32 = 0x5;
+    /// section with only non-lazy symbol pointers
+    pub const S_NON_LAZY_SYMBOL_POINTERS: u32 = 0x6;
+    /// section with only lazy symbol pointers
+    pub const S_LAZY_SYMBOL_POINTERS: u32 = 0x7;
+    /// section with only symbol stubs, byte size of stub in the reserved2 field
+    pub const S_SYMBOL_STUBS: u32 = 0x8;
+    /// section with only function pointers for initialization
+    pub const S_MOD_INIT_FUNC_POINTERS: u32 = 0x9;
+    /// section with only function pointers for termination
+    pub const S_MOD_TERM_FUNC_POINTERS: u32 = 0xa;
+    /// section contains symbols that are to be coalesced
+    pub const S_COALESCED: u32 = 0xb;
+    /// zero fill on demand section (that can be larger than 4 gigabytes)
+    pub const S_GB_ZEROFILL: u32 = 0xc;
+    /// section with only pairs of function pointers for interposing
+    pub const S_INTERPOSING: u32 = 0xd;
+    /// section with only 16 byte literals
+    pub const S_16BYTE_LITERALS: u32 = 0xe;
+    /// section contains DTrace Object Format
+    pub const S_DTRACE_DOF: u32 = 0xf;
+    /// section with only lazy symbol pointers to lazy loaded dylibs
+    pub const S_LAZY_DYLIB_SYMBOL_POINTERS: u32 = 0x10;
+    /// template of initial values for TLVs
+    pub const S_THREAD_LOCAL_REGULAR: u32 = 0x11;
+    /// template of initial values for TLVs
+    pub const S_THREAD_LOCAL_ZEROFILL: u32 = 0x12;
+    /// TLV descriptors
+    pub const S_THREAD_LOCAL_VARIABLES: u32 = 0x13;
+    /// pointers to TLV descriptors
+    pub const S_THREAD_LOCAL_VARIABLE_POINTERS: u32 = 0x14;
+    /// functions to call to initialize TLV values
+    pub const S_THREAD_LOCAL_INIT_FUNCTION_POINTERS: u32 = 0x15;
+    /// 32-bit offsets to initializers
+    pub const S_INIT_FUNC_OFFSETS: u32 = 0x16;
+
+    /// section contains only true machine instructions
+    pub const S_ATTR_PURE_INSTRUCTIONS: u32 = 0x80000000;
+    /// section contains coalesced symbols that are not to be in a ranlib table of contents
+    pub const S_ATTR_NO_TOC: u32 = 0x40000000;
+    /// ok to strip static symbols in this section in files with the MH_DYLDLINK flag
+    pub const S_ATTR_STRIP_STATIC_SYMS: u32 = 0x20000000;
+    /// no dead stripping
+    pub const S_ATTR_NO_DEAD_STRIP: u32 = 0x10000000;
+    /// blocks are live if they reference live blocks
+    pub const S_ATTR_LIVE_SUPPORT: u32 = 0x08000000;
+    /// Used with i386 code stubs written on by dyld
+    pub const S_ATTR_SELF_MODIFYING_CODE: u32 = 0x04000000;
+    /// a debug section
+    pub const S_ATTR_DEBUG: u32 = 0x02000000;
+    /// section contains some machine instructions
+    pub const S_ATTR_SOME_INSTRUCTIONS: u32 = 0x00000400;
+    /// section has external relocation entries
+    pub const S_ATTR_EXT_RELOC: u32 = 0x00000200;
+    /// section has local relocation entries
+    pub const S_ATTR_LOC_RELOC: u32 = 0x00000100;
+}
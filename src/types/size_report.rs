@@ -0,0 +1,331 @@
+//! Attribution of file and VM bytes to segments, sections, symbols and, if the binary
+//! has STABS debug map, to object files it was linked from.
+//!
+//! Symbol spans from its address to the next symbol or function start, but not beyond the end of
+//! its section. Bytes of sections, that aren't covered by symbols, are attributed to
+//! the section itself, like `[__TEXT,__text]`, so sizes of symbols sum up to sizes of sections.
+
+use super::debug_map::DebugMap;
+use super::function_starts::FunctionStarts;
+use super::section_constants::*;
+use super::symbolicator::symtab_symbols;
+use super::MachObject;
+use super::Section;
+use crate::result::Result;
+
+use std::collections::HashMap;
+
+/// Name of the item, that bytes of debug map objects without symbols are attributed to
+pub const NO_OBJECT_NAME: &str = "[no object]";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeItem {
+    pub name: String,
+    pub file_size: u64,
+    pub vm_size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeSort {
+    /// Largest first
+    FileSize,
+    /// Largest first
+    VmSize,
+    Name,
+}
+
+#[derive(Debug, Default)]
+pub struct SizeReport {
+    /// Size of the image in file, up to the end of its last segment
+    pub file_size: u64,
+    pub vm_size: u64,
+    pub segments: Vec<SizeItem>,
+    /// Named as `__TEXT,__text`
+    pub sections: Vec<SizeItem>,
+    pub symbols: Vec<SizeItem>,
+    /// Empty if there is no debug map
+    pub objects: Vec<SizeItem>,
+}
+
+impl SizeReport {
+    pub fn parse(object: &MachObject) -> Result<Self> {
+        let mut report = SizeReport::default();
+
+        for segment in object.segments() {
+            report.file_size = report.file_size.max(segment.fileoff.0 + segment.filesize.0);
+            report.vm_size += segment.vmsize.0;
+            report.segments.push(SizeItem {
+                name: segment.segname.to_string(),
+                file_size: segment.filesize.0,
+                vm_size: segment.vmsize.0,
+            });
+        }
+
+        let sections = object.sections();
+        for section in &sections {
+            report.sections.push(SizeItem {
+                name: section_name(section),
+                file_size: section_file_size(section),
+                vm_size: section.size.0,
+            });
+        }
+
+        let spans = symbol_spans(object, &sections)?;
+        report.symbols = merge_by_name(spans.iter().map(|span| span.item()));
+
+        let map = DebugMap::parse(object)?;
+        if !map.is_empty() {
+            let mut object_paths = HashMap::new();
+            for map_object in &map.objects {
+                for symbol in &map_object.symbols {
+                    if let Some(address) = symbol.address {
+                        object_paths
+                            .entry(address)
+                            .or_insert(map_object.path.clone());
+                    }
+                }
+            }
+            let items = spans.iter().map(|span| {
+                let name = match span.is_symbol {
+                    true => object_paths.get(&span.address).cloned(),
+                    false => None,
+                };
+                SizeItem {
+                    name: name.unwrap_or(NO_OBJECT_NAME.to_string()),
+                    ..span.item()
+                }
+            });
+            report.objects = merge_by_name(items);
+        }
+
+        Ok(report)
+    }
+}
+
+/// Sorts `items`, names break ties of sizes
+pub fn sort_items(items: &mut [SizeItem], sort: SizeSort) {
+    items.sort_by(|a, b| {
+        let order = match sort {
+            SizeSort::FileSize => b.file_size.cmp(&a.file_size),
+            SizeSort::VmSize => b.vm_size.cmp(&a.vm_size),
+            SizeSort::Name => std::cmp::Ordering::Equal,
+        };
+        order.then(a.name.cmp(&b.name))
+    });
+}
+
+/// Change of item size relative to baseline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeDelta {
+    pub name: String,
+    /// `None` if item is added
+    pub old: Option<SizeItem>,
+    /// `None` if item is removed
+    pub new: Option<SizeItem>,
+}
+
+impl SizeDelta {
+    pub fn file_delta(&self) -> i64 {
+        let size = |item: &Option<SizeItem>| item.as_ref().map_or(0, |i| i.file_size as i64);
+        size(&self.new) - size(&self.old)
+    }
+
+    pub fn vm_delta(&self) -> i64 {
+        let size = |item: &Option<SizeItem>| item.as_ref().map_or(0, |i| i.vm_size as i64);
+        size(&self.new) - size(&self.old)
+    }
+}
+
+/// Changed items of `new` relative to `old`, in order of `sort` applied to absolute deltas
+pub fn diff_items(old: &[SizeItem], new: &[SizeItem], sort: SizeSort) -> Vec<SizeDelta> {
+    let old_index: HashMap<&str, &SizeItem> = old.iter().map(|i| (i.name.as_str(), i)).collect();
+    let new_index: HashMap<&str, &SizeItem> = new.iter().map(|i| (i.name.as_str(), i)).collect();
+
+    let mut names: Vec<&str> = old.iter().chain(new).map(|i| i.name.as_str()).collect();
+    names.sort();
+    names.dedup();
+
+    let mut result: Vec<SizeDelta> = names
+        .into_iter()
+        .map(|name| SizeDelta {
+            name: name.to_string(),
+            old: old_index.get(name).map(|i| (*i).clone()),
+            new: new_index.get(name).map(|i| (*i).clone()),
+        })
+        .filter(|delta| {
+            delta.old.is_none()
+                || delta.new.is_none()
+                || delta.file_delta() != 0
+                || delta.vm_delta() != 0
+        })
+        .collect();
+
+    result.sort_by(|a, b| {
+        let order = match sort {
+            SizeSort::FileSize => b.file_delta().abs().cmp(&a.file_delta().abs()),
+            SizeSort::VmSize => b.vm_delta().abs().cmp(&a.vm_delta().abs()),
+            SizeSort::Name => std::cmp::Ordering::Equal,
+        };
+        order.then(a.name.cmp(&b.name))
+    });
+    result
+}
+
+/// Range of section, attributed either to symbol or to the section itself
+struct Span {
+    name: String,
+    address: u64,
+    size: u64,
+    is_symbol: bool,
+    is_zerofill: bool,
+}
+
+impl Span {
+    fn item(&self) -> SizeItem {
+        SizeItem {
+            name: self.name.clone(),
+            file_size: match self.is_zerofill {
+                true => 0,
+                false => self.size,
+            },
+            vm_size: self.size,
+        }
+    }
+}
+
+fn symbol_spans(object: &MachObject, sections: &[Section]) -> Result<Vec<Span>> {
+    let mut symbols = symtab_symbols(object);
+    symbols.sort_by(|a, b| a.address.cmp(&b.address).then(a.name.cmp(&b.name)));
+    // Aliases take no space
+    symbols.dedup_by(|a, b| a.address == b.address);
+
+    let mut boundaries: Vec<u64> = symbols.iter().map(|symbol| symbol.address).collect();
+    if let Some(starts) = FunctionStarts::parse(object)? {
+        boundaries.extend(starts.addresses);
+    }
+    boundaries.sort();
+    boundaries.dedup();
+
+    let mut spans = Vec::new();
+    for section in sections.iter().filter(|s| s.flags.0 & S_ATTR_DEBUG == 0) {
+        let start = section.addr.0;
+        let end = start + section.size.0;
+        let is_zerofill = section_file_size(section) == 0;
+
+        let mut covered = 0;
+        let first = symbols.partition_point(|symbol| symbol.address < start);
+        for symbol in symbols[first..].iter().take_while(|s| s.address < end) {
+            let next = boundaries.partition_point(|address| *address <= symbol.address);
+            let symbol_end = boundaries.get(next).map_or(end, |next| end.min(*next));
+            let size = symbol_end - symbol.address;
+            covered += size;
+            spans.push(Span {
+                name: symbol.name.clone(),
+                address: symbol.address,
+                size,
+                is_symbol: true,
+                is_zerofill,
+            });
+        }
+
+        if covered < section.size.0 {
+            spans.push(Span {
+                name: format!("[{}]", section_name(section)),
+                address: start,
+                size: section.size.0 - covered,
+                is_symbol: false,
+                is_zerofill,
+            });
+        }
+    }
+    Ok(spans)
+}
+
+fn merge_by_name(items: impl Iterator<Item = SizeItem>) -> Vec<SizeItem> {
+    let mut result: Vec<SizeItem> = Vec::new();
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    for item in items {
+        match indices.get(&item.name) {
+            Some(index) => {
+                result[*index].file_size += item.file_size;
+                result[*index].vm_size += item.vm_size;
+            }
+            None => {
+                indices.insert(item.name.clone(), result.len());
+                result.push(item);
+            }
+        }
+    }
+    result
+}
+
+fn section_name(section: &Section) -> String {
+    format!("{},{}", section.segname, section.sectname)
+}
+
+fn section_file_size(section: &Section) -> u64 {
+    match section.section_type() {
+        S_ZEROFILL | S_GB_ZEROFILL | S_THREAD_LOCAL_ZEROFILL => 0,
+        _ => section.size.0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse_single;
+
+    fn item(name: &str, file_size: u64, vm_size: u64) -> SizeItem {
+        SizeItem {
+            name: name.to_string(),
+            file_size,
+            vm_size,
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let mut report = SizeReport::parse(&parse_single("testable/stabs_arm64")).unwrap();
+        assert_eq!(report.file_size, 32768 + 496);
+
+        sort_items(&mut report.symbols, SizeSort::FileSize);
+        assert_eq!(
+            report.symbols,
+            vec![
+                item("_helper", 0x20, 0x20),
+                item("_main", 0x10, 0x10),
+                // `_counter` is static, it has only stab
+                item("[__DATA,__data]", 4, 4),
+                item("_global", 4, 4),
+            ]
+        );
+
+        sort_items(&mut report.objects, SizeSort::Name);
+        assert_eq!(
+            report.objects,
+            vec![
+                item("/tmp/build/main.o", 0x14, 0x14),
+                item("/tmp/build/util.o", 0x20, 0x20),
+                item(NO_OBJECT_NAME, 4, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = vec![item("_a", 8, 8), item("_b", 4, 4), item("_c", 4, 4)];
+        let new = vec![item("_a", 8, 8), item("_b", 12, 12), item("_d", 2, 2)];
+        let deltas: Vec<(String, i64)> = diff_items(&old, &new, SizeSort::FileSize)
+            .into_iter()
+            .map(|delta| (delta.name.clone(), delta.file_delta()))
+            .collect();
+        assert_eq!(
+            deltas,
+            vec![
+                ("_b".to_string(), 8),
+                ("_c".to_string(), -4),
+                ("_d".to_string(), 2),
+            ]
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! String literals of [MachObject]: C strings of `S_CSTRING_LITERALS` sections,
+//! UTF-16 strings of `__TEXT,__ustring` and constant `CFString` objects of `__cfstring`.
+
+use super::image::Image;
+use super::section_constants::S_CSTRING_LITERALS;
+use super::{MachObject, Section};
+use crate::result::Result;
+
+use scroll::{Endian, Pread};
+
+use std::fmt::{Display, Formatter};
+
+pub const USTRING_SECTNAME: &str = "__ustring";
+pub const CFSTRING_SECTNAME: &str = "__cfstring";
+
+/// `__kCFIsUnicode` bit of `CFString` info, backing string is UTF-16
+const CFSTRING_IS_UNICODE: u32 = 0x10;
+/// Sanity limit for length of `CFString`
+const MAX_CFSTRING_LENGTH: u64 = 0x100_0000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringLiteralKind {
+    CString,
+    /// UTF-16 string
+    UString,
+    CFString,
+}
+
+impl Display for StringLiteralKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CString => write!(f, "cstring"),
+            Self::UString => write!(f, "ustring"),
+            Self::CFString => write!(f, "cfstring"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StringLiteral {
+    /// Address of the string, or of `CFString` object
+    pub addr: u64,
+    pub segname: String,
+    pub sectname: String,
+    pub kind: StringLiteralKind,
+    pub value: String,
+}
+
+/// All string literals of single [MachObject]
+#[derive(Debug, Default)]
+pub struct StringLiterals {
+    pub strings: Vec<StringLiteral>,
+}
+
+impl StringLiterals {
+    /// Reads C strings and UTF-16 strings through [Section::read_data_to],
+    /// and follows pointers of `CFString` objects to their backing strings
+    pub fn parse(object: &MachObject) -> Result<Self> {
+        let endian = object.header().magic.endian();
+        let mut result = StringLiterals::default();
+        let mut cfstring_sections = Vec::new();
+
+        for section in object.sections() {
+            let sectname = section.sectname.to_string();
+            if section.section_type() == S_CSTRING_LITERALS {
+                let data = section.read_data()?;
+                result.push_strings(&section, split_cstrings(&data), StringLiteralKind::CString);
+            } else if sectname == USTRING_SECTNAME {
+                let data = section.read_data()?;
+                result.push_strings(
+                    &section,
+                    split_ustrings(&data, endian),
+                    StringLiteralKind::UString,
+                );
+            } else if sectname == CFSTRING_SECTNAME {
+                cfstring_sections.push(section);
+            }
+        }
+
+        // Pointers of `CFString` objects need fixups
+        if !cfstring_sections.is_empty() {
+            let image = Image::build(object)?;
+            for section in &cfstring_sections {
+                result.push_cfstrings(section, &image)?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn push_strings(
+        &mut self,
+        section: &Section,
+        strings: Vec<(u64, String)>,
+        kind: StringLiteralKind,
+    ) {
+        let segname = section.segname.to_string();
+        let sectname = section.sectname.to_string();
+        self.strings
+            .extend(strings.into_iter().map(|(offset, value)| StringLiteral {
+                addr: section.addr.0 + offset,
+                segname: segname.clone(),
+                sectname: sectname.clone(),
+                kind,
+                value,
+            }));
+    }
+
+    /// `__NSConstantString`: `isa`, `info`, pointer to string and length, each of them pointer sized
+    fn push_cfstrings(&mut self, section: &Section, image: &Image) -> Result<()> {
+        let pointer_size = image.pointer_size();
+        let entry_size = pointer_size * 4;
+
+        for index in 0..section.size.0 / entry_size {
+            let addr = section.addr.0 + index * entry_size;
+            let info = image.read_u32(addr + pointer_size)?;
+            let length = image.read_word(addr + pointer_size * 3)?;
+            let target = match image.read_address(addr + pointer_size * 2)? {
+                Some(target) if length <= MAX_CFSTRING_LENGTH => target,
+                _ => continue,
+            };
+
+            let value = match info & CFSTRING_IS_UNICODE {
+                0 => {
+                    String::from_utf8_lossy(&image.read_bytes(target, length as usize)?).to_string()
+                }
+                _ => {
+                    let bytes = image.read_bytes(target, length as usize * 2)?;
+                    decode_utf16(&bytes, section.endian)
+                }
+            };
+
+            self.strings.push(StringLiteral {
+                addr,
+                segname: section.segname.to_string(),
+                sectname: section.sectname.to_string(),
+                kind: StringLiteralKind::CFString,
+                value,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Non-empty zero terminated strings with their offsets
+fn split_cstrings(data: &[u8]) -> Vec<(u64, String)> {
+    let mut result = Vec::new();
+    let mut offset = 0;
+    for chunk in data.split(|b| *b == 0) {
+        if !chunk.is_empty() {
+            result.push((offset as u64, String::from_utf8_lossy(chunk).to_string()));
+        }
+        offset += chunk.len() + 1;
+    }
+    result
+}
+
+/// Non-empty UTF-16 strings terminated with zero code unit, with their offsets
+fn split_ustrings(data: &[u8], endian: Endian) -> Vec<(u64, String)> {
+    let units = utf16_units(data, endian);
+    let mut result = Vec::new();
+    let mut offset = 0;
+    for chunk in units.split(|u| *u == 0) {
+        if !chunk.is_empty() {
+            result.push((offset as u64 * 2, String::from_utf16_lossy(chunk)));
+        }
+        offset += chunk.len() + 1;
+    }
+    result
+}
+
+fn decode_utf16(bytes: &[u8], endian: Endian) -> String {
+    String::from_utf16_lossy(&utf16_units(bytes, endian))
+}
+
+fn utf16_units(bytes: &[u8], endian: Endian) -> Vec<u16> {
+    (0..bytes.len() / 2)
+        .filter_map(|index| bytes.pread_with::<u16>(index * 2, endian).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_cstrings() {
+        let strings = split_cstrings(b"\0https://example.com\0\0key\0tail");
+        assert_eq!(
+            strings,
+            vec![
+                (1, "https://example.com".to_string()),
+                (22, "key".to_string()),
+                (26, "tail".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_ustrings() {
+        let data = [0x41, 0x00, 0x42, 0x04, 0x00, 0x00, 0x43, 0x00];
+        let strings = split_ustrings(&data, scroll::LE);
+        assert_eq!(
+            strings,
+            vec![(0, "A\u{442}".to_string()), (6, "C".to_string())]
+        );
+    }
+}
@@ -0,0 +1,581 @@
+//! Removal of STABS and local symbols, like `strip -S -x` does.
+//!
+//! Symbol and string tables are rebuilt, indices of `LC_DYSYMTAB` ranges, indirect symbols
+//! and external relocations are remapped, then contents of `__LINKEDIT` are packed again
+//! in the same order with the new tables, so the segment shrinks. Any code signature is removed,
+//! as it covers the old `__LINKEDIT`.
+
+use super::constants::{
+    BYTES_PER_NLIST32, BYTES_PER_NLIST64, BYTES_PER_RELOCATION_INFO, SEG_LINKEDIT,
+};
+use super::filetype_constants::MH_OBJECT;
+use super::indirect_symbols::{INDIRECT_SYMBOL_ABS, INDIRECT_SYMBOL_LOCAL};
+use super::nlist::constants::*;
+use super::reloc::Relocation;
+use super::writer::MachImage;
+use super::{
+    LC_ATOM_INFO, LC_CODE_SIGNATURE, LC_DATA_IN_CODE, LC_DYLD_CHAINED_FIXUPS, LC_DYLD_EXPORTS_TRIE,
+    LC_DYLD_INFO, LC_DYLD_INFO_ONLY, LC_DYLIB_CODE_SIGN_DRS, LC_DYSYMTAB, LC_FUNCTION_STARTS,
+    LC_LINKER_OPTIMIZATION_HINT, LC_SEGMENT_SPLIT_INFO, LC_SYMTAB, LC_TWOLEVEL_HINTS,
+};
+use crate::result::{Error, Result};
+
+use scroll::{Endian, Pread, Pwrite};
+use std::collections::{HashMap, HashSet};
+
+/// `r_symbolnum` in the second word of relocation entry, its low bits in little endian images
+/// and high bits in big endian ones
+const R_SYMBOLNUM_MASK: u32 = 0xffffff;
+
+/// `ilocalsym`, `nlocalsym`, `iextdefsym`, `nextdefsym`, `iundefsym` and `nundefsym`
+/// fields of `LC_DYSYMTAB`
+const DYSYMTAB_RANGES: [(usize, usize); 3] = [(8, 12), (16, 20), (24, 28)];
+const DYSYMTAB_NTOC: usize = 36;
+const DYSYMTAB_NMODTAB: usize = 44;
+const DYSYMTAB_NEXTREFSYMS: usize = 52;
+const DYSYMTAB_INDIRECTSYMOFF: usize = 56;
+const DYSYMTAB_EXTRELOFF: usize = 64;
+const DYSYMTAB_LOCRELOFF: usize = 72;
+
+/// Offset and size fields of commands with `linkedit_data_command` layout
+const LINKEDIT_DATA_COMMANDS: [u32; 9] = [
+    LC_CODE_SIGNATURE,
+    LC_SEGMENT_SPLIT_INFO,
+    LC_FUNCTION_STARTS,
+    LC_DATA_IN_CODE,
+    LC_DYLIB_CODE_SIGN_DRS,
+    LC_LINKER_OPTIMIZATION_HINT,
+    LC_DYLD_EXPORTS_TRIE,
+    LC_DYLD_CHAINED_FIXUPS,
+    LC_ATOM_INFO,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StripOptions {
+    /// `N_STAB` debug entries
+    pub stabs: bool,
+    /// Symbols without `N_EXT`, but not ones referenced by indirect symbols or dynamically
+    pub locals: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StripSummary {
+    pub removed_symbols: usize,
+    pub removed_signature: bool,
+    /// File size of `__LINKEDIT` before and after stripping
+    pub old_linkedit_size: u64,
+    pub new_linkedit_size: u64,
+}
+
+/// Symbol table entry as it's stored in file
+struct RawNlist {
+    n_strx: u32,
+    n_type: u8,
+    n_sect: u8,
+    n_desc: u16,
+    n_value: u64,
+}
+
+/// Table of `__LINKEDIT`, referenced by offset field of load command
+struct Piece {
+    command: usize,
+    offset_field: usize,
+    offset: u32,
+    size: usize,
+    /// New contents, the old ones are copied if `None`
+    bytes: Option<Vec<u8>>,
+}
+
+impl MachImage {
+    pub fn strip(&mut self, options: &StripOptions) -> Result<StripSummary> {
+        if self.header.filetype.0 == MH_OBJECT {
+            return Err(Error::Text(
+                "Object files can't be stripped, their relocations reference symbols".to_string(),
+            ));
+        }
+        let symtab = match self.load_commands.iter().position(|c| c.cmd == LC_SYMTAB) {
+            Some(index) => index,
+            None => return Err(Error::Text("Image has no LC_SYMTAB".to_string())),
+        };
+        let linkedit = match self.segment_index(SEG_LINKEDIT) {
+            Some(index) => index,
+            None => return Err(Error::Text("Image has no __LINKEDIT segment".to_string())),
+        };
+        let dysymtab = self.load_commands.iter().position(|c| c.cmd == LC_DYSYMTAB);
+        let endian = self.endian();
+        if let Some(dysymtab) = dysymtab {
+            let command = &self.load_commands[dysymtab];
+            for field in [DYSYMTAB_NTOC, DYSYMTAB_NMODTAB, DYSYMTAB_NEXTREFSYMS] {
+                if command.field(field, endian)? > 0 {
+                    return Err(Error::Text(
+                        "Table of contents, module and reference tables aren't supported"
+                            .to_string(),
+                    ));
+                }
+            }
+        }
+
+        let old_linkedit_size = self.segment_sizes(linkedit)?.2;
+        let removed_signature = self.remove_code_signature()?;
+
+        let mut pieces = self.linkedit_pieces(symtab, dysymtab)?;
+        let symbols = self.read_symbols(symtab)?;
+        let indirect = self.read_u32_table(dysymtab, DYSYMTAB_INDIRECTSYMOFF)?;
+
+        let referenced: HashSet<u32> = indirect
+            .iter()
+            .filter(|index| *index & (INDIRECT_SYMBOL_LOCAL | INDIRECT_SYMBOL_ABS) == 0)
+            .copied()
+            .collect();
+        let keep: Vec<bool> = symbols
+            .iter()
+            .enumerate()
+            .map(|(index, symbol)| {
+                if symbol.n_type & N_STAB != 0 {
+                    !options.stabs
+                } else if symbol.n_type & N_EXT == 0 {
+                    !options.locals
+                        || referenced.contains(&(index as u32))
+                        || symbol.n_desc & REFERENCED_DYNAMICALLY != 0
+                } else {
+                    true
+                }
+            })
+            .collect();
+        let mut new_indices = Vec::with_capacity(symbols.len());
+        let mut count = 0u32;
+        for keep in &keep {
+            new_indices.push(count);
+            count += *keep as u32;
+        }
+        let remap = |index: u32| -> Result<u32> {
+            match new_indices.get(index as usize) {
+                Some(new) => Ok(*new),
+                None => Err(Error::Text(format!("Symbol index {index} is out of table"))),
+            }
+        };
+
+        let (symbol_bytes, string_bytes) = self.symbol_tables(symtab, &symbols, &keep)?;
+        let command = &mut self.load_commands[symtab];
+        command.set_field(12, count, endian)?;
+        command.set_field(20, string_bytes.len() as u32, endian)?;
+        pieces[0].bytes = Some(symbol_bytes);
+        pieces[1].bytes = Some(string_bytes);
+
+        if let Some(dysymtab) = dysymtab {
+            for (index_field, count_field) in DYSYMTAB_RANGES {
+                let command = &self.load_commands[dysymtab];
+                let start = command.field(index_field, endian)? as usize;
+                let end = start + command.field(count_field, endian)? as usize;
+                let kept = keep.get(start..end).unwrap_or_default();
+                let new_start = new_indices.get(start).copied().unwrap_or(count);
+                let new_count = kept.iter().filter(|keep| **keep).count() as u32;
+                let command = &mut self.load_commands[dysymtab];
+                command.set_field(index_field, new_start, endian)?;
+                command.set_field(count_field, new_count, endian)?;
+            }
+
+            let mut bytes = Vec::new();
+            for entry in &indirect {
+                let entry = match referenced.contains(entry) {
+                    true => remap(*entry)?,
+                    false => *entry,
+                };
+                bytes.extend(to_bytes(entry, endian)?);
+            }
+            set_piece_bytes(&mut pieces, dysymtab, DYSYMTAB_INDIRECTSYMOFF, bytes);
+
+            let relocations = self.read_u32_table(Some(dysymtab), DYSYMTAB_EXTRELOFF)?;
+            let mut bytes = Vec::new();
+            for entry in relocations.chunks(2) {
+                let mut raw = [0u8; BYTES_PER_RELOCATION_INFO];
+                raw.pwrite_with(entry[0], 0, endian)?;
+                raw.pwrite_with(entry[1], 4, endian)?;
+                if let Relocation::Plain(info) =
+                    Relocation::from_bytes(&raw, endian, self.header.cputype)?
+                {
+                    if info.r_extern() == 1 {
+                        let symbol = remap(info.r_symbolnum() as u32)?;
+                        let word = match endian {
+                            Endian::Little => (entry[1] & !R_SYMBOLNUM_MASK) | symbol,
+                            Endian::Big => (entry[1] & !(R_SYMBOLNUM_MASK << 8)) | symbol << 8,
+                        };
+                        raw.pwrite_with(word, 4, endian)?;
+                    }
+                }
+                bytes.extend(raw);
+            }
+            set_piece_bytes(&mut pieces, dysymtab, DYSYMTAB_EXTRELOFF, bytes);
+        }
+
+        let new_linkedit_size = self.pack_linkedit(linkedit, pieces)?;
+        Ok(StripSummary {
+            removed_symbols: keep.iter().filter(|keep| !**keep).count(),
+            removed_signature,
+            old_linkedit_size,
+            new_linkedit_size,
+        })
+    }
+
+    fn nlist_size(&self) -> usize {
+        match self.is_64() {
+            true => BYTES_PER_NLIST64,
+            false => BYTES_PER_NLIST32,
+        }
+    }
+
+    /// All tables of `__LINKEDIT`, symbol and string tables are the first two
+    fn linkedit_pieces(&self, symtab: usize, dysymtab: Option<usize>) -> Result<Vec<Piece>> {
+        let endian = self.endian();
+        let symtab_command = &self.load_commands[symtab];
+        let nsyms = symtab_command.field(12, endian)? as usize;
+        let mut fields = vec![
+            (symtab, 8, nsyms * self.nlist_size()),
+            (symtab, 16, symtab_command.field(20, endian)? as usize),
+        ];
+
+        if let Some(dysymtab) = dysymtab {
+            let command = &self.load_commands[dysymtab];
+            let tables = [
+                (DYSYMTAB_INDIRECTSYMOFF, 4),
+                (DYSYMTAB_EXTRELOFF, BYTES_PER_RELOCATION_INFO),
+                (DYSYMTAB_LOCRELOFF, BYTES_PER_RELOCATION_INFO),
+            ];
+            for (offset_field, entry_size) in tables {
+                let count = command.field(offset_field + 4, endian)? as usize;
+                fields.push((dysymtab, offset_field, count * entry_size));
+            }
+        }
+        for (index, command) in self.load_commands.iter().enumerate() {
+            if LINKEDIT_DATA_COMMANDS.contains(&command.cmd) {
+                fields.push((index, 8, command.field(12, endian)? as usize));
+            } else if command.cmd == LC_DYLD_INFO || command.cmd == LC_DYLD_INFO_ONLY {
+                // Rebase, bind, weak bind, lazy bind and export info
+                for offset_field in [8, 16, 24, 32, 40] {
+                    let size = command.field(offset_field + 4, endian)? as usize;
+                    fields.push((index, offset_field, size));
+                }
+            } else if command.cmd == LC_TWOLEVEL_HINTS {
+                fields.push((index, 8, command.field(12, endian)? as usize * 4));
+            }
+        }
+
+        let mut pieces = Vec::new();
+        for (command, offset_field, size) in fields {
+            pieces.push(Piece {
+                command,
+                offset_field,
+                offset: self.load_commands[command].field(offset_field, endian)?,
+                size,
+                bytes: None,
+            });
+        }
+        Ok(pieces)
+    }
+
+    fn read_symbols(&self, symtab: usize) -> Result<Vec<RawNlist>> {
+        let endian = self.endian();
+        let command = &self.load_commands[symtab];
+        let symoff = command.field(8, endian)? as usize;
+        let nsyms = command.field(12, endian)? as usize;
+
+        let data = self.data();
+        let mut symbols = Vec::with_capacity(nsyms);
+        for index in 0..nsyms {
+            let offset = symoff + index * self.nlist_size();
+            symbols.push(RawNlist {
+                n_strx: data.pread_with(offset, endian)?,
+                n_type: data.pread_with(offset + 4, endian)?,
+                n_sect: data.pread_with(offset + 5, endian)?,
+                n_desc: data.pread_with(offset + 6, endian)?,
+                n_value: match self.is_64() {
+                    true => data.pread_with(offset + 8, endian)?,
+                    false => data.pread_with::<u32>(offset + 8, endian)? as u64,
+                },
+            });
+        }
+        Ok(symbols)
+    }
+
+    /// Table of 32 bit words, referenced by offset field of `LC_DYSYMTAB` followed by
+    /// number of entries. Relocations are read as pairs of words
+    fn read_u32_table(&self, dysymtab: Option<usize>, offset_field: usize) -> Result<Vec<u32>> {
+        let dysymtab = match dysymtab {
+            Some(dysymtab) => dysymtab,
+            None => return Ok(Vec::new()),
+        };
+        let endian = self.endian();
+        let command = &self.load_commands[dysymtab];
+        let offset = command.field(offset_field, endian)? as usize;
+        let mut count = command.field(offset_field + 4, endian)? as usize;
+        if offset_field == DYSYMTAB_EXTRELOFF {
+            count *= BYTES_PER_RELOCATION_INFO / 4;
+        }
+
+        let mut words = Vec::with_capacity(count);
+        for index in 0..count {
+            words.push(self.data().pread_with(offset + index * 4, endian)?);
+        }
+        Ok(words)
+    }
+
+    /// Kept symbols and their names, the string table starts with `" \0"`
+    /// and is padded to pointer size like `ld` does it
+    fn symbol_tables(
+        &self,
+        symtab: usize,
+        symbols: &[RawNlist],
+        keep: &[bool],
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        let endian = self.endian();
+        let command = &self.load_commands[symtab];
+        let stroff = command.field(16, endian)? as usize;
+        let strsize = command.field(20, endian)? as usize;
+        let strings = match self.data().get(stroff..stroff + strsize) {
+            Some(strings) => strings,
+            None => return Err(Error::Text("String table is out of file".to_string())),
+        };
+
+        let mut string_bytes = b" \0".to_vec();
+        let mut string_indices: HashMap<&[u8], u32> = HashMap::new();
+        let mut add_string = |strx: u32| -> u32 {
+            let string = strings.get(strx as usize..).unwrap_or_default();
+            let end = string.iter().position(|b| *b == 0).unwrap_or(string.len());
+            let string = &string[..end];
+            if strx == 0 || string.is_empty() {
+                return 0;
+            }
+            *string_indices.entry(string).or_insert_with(|| {
+                let index = string_bytes.len() as u32;
+                string_bytes.extend_from_slice(string);
+                string_bytes.push(0);
+                index
+            })
+        };
+
+        let mut symbol_bytes = vec![0u8; keep.iter().filter(|k| **k).count() * self.nlist_size()];
+        let mut offset = 0;
+        for symbol in symbols
+            .iter()
+            .zip(keep)
+            .filter(|(_, keep)| **keep)
+            .map(|(s, _)| s)
+        {
+            let mut value = symbol.n_value;
+            // Value of indirect symbol is the name of the other one
+            if symbol.n_type & N_STAB == 0 && symbol.n_type & N_TYPE == N_INDR {
+                value = add_string(value as u32) as u64;
+            }
+            symbol_bytes.pwrite_with(add_string(symbol.n_strx), offset, endian)?;
+            symbol_bytes.pwrite_with(symbol.n_type, offset + 4, endian)?;
+            symbol_bytes.pwrite_with(symbol.n_sect, offset + 5, endian)?;
+            symbol_bytes.pwrite_with(symbol.n_desc, offset + 6, endian)?;
+            match self.is_64() {
+                true => symbol_bytes.pwrite_with(value, offset + 8, endian)?,
+                false => symbol_bytes.pwrite_with(value as u32, offset + 8, endian)?,
+            };
+            offset += self.nlist_size();
+        }
+
+        let alignment = self.nlist_size() - 8;
+        string_bytes.resize(string_bytes.len().next_multiple_of(alignment), 0);
+        Ok((symbol_bytes, string_bytes))
+    }
+
+    /// Writes `pieces` one after another in the order of their old offsets and updates
+    /// offsets in load commands. Returns the new file size of `__LINKEDIT`
+    fn pack_linkedit(&mut self, linkedit: usize, mut pieces: Vec<Piece>) -> Result<u64> {
+        let (vmsize, fileoff, filesize) = self.segment_sizes(linkedit)?;
+        let start = fileoff as usize;
+        let end = start + filesize as usize;
+        let alignment = self.nlist_size() - 8;
+
+        pieces.retain(|piece| match &piece.bytes {
+            Some(bytes) => piece.size > 0 || !bytes.is_empty(),
+            None => piece.size > 0,
+        });
+        pieces.sort_by_key(|piece| (piece.offset, std::cmp::Reverse(piece.size)));
+
+        let mut contents: Vec<u8> = Vec::new();
+        // Old range and new offset of written pieces
+        let mut written: Vec<(usize, usize, usize)> = Vec::new();
+        let mut offsets = Vec::new();
+        for piece in &pieces {
+            let old_start = piece.offset as usize;
+            let old_end = old_start + piece.size;
+            if old_start < start || old_end > end {
+                return Err(Error::Text(format!(
+                    "Table at {old_start:#x} is out of __LINKEDIT segment"
+                )));
+            }
+
+            // Tables, that share bytes, like export info of `LC_DYLD_INFO` and exports trie
+            let shared = written
+                .iter()
+                .find(|(s, e, _)| piece.bytes.is_none() && *s <= old_start && old_end <= *e);
+            let new_offset = match shared {
+                Some((s, _, new)) => new + (old_start - s),
+                None => {
+                    contents.resize(contents.len().next_multiple_of(alignment), 0);
+                    let new_offset = start + contents.len();
+                    match &piece.bytes {
+                        Some(bytes) => contents.extend_from_slice(bytes),
+                        None => contents.extend_from_slice(&self.data()[old_start..old_end]),
+                    }
+                    written.push((old_start, old_end, new_offset));
+                    new_offset
+                }
+            };
+            offsets.push(new_offset);
+        }
+
+        let endian = self.endian();
+        for (piece, offset) in pieces.iter().zip(offsets) {
+            self.load_commands[piece.command].set_field(
+                piece.offset_field,
+                offset as u32,
+                endian,
+            )?;
+        }
+
+        let new_filesize = contents.len() as u64;
+        let page_size = self.header.cputype.page_size();
+        let new_vmsize = vmsize.min(new_filesize.next_multiple_of(page_size));
+        self.set_segment_sizes(linkedit, new_vmsize, new_filesize)?;
+
+        let data = self.data_mut();
+        data.truncate(start);
+        data.extend(contents);
+        Ok(new_filesize)
+    }
+}
+
+fn set_piece_bytes(pieces: &mut [Piece], command: usize, offset_field: usize, bytes: Vec<u8>) {
+    if let Some(piece) = pieces
+        .iter_mut()
+        .find(|piece| piece.command == command && piece.offset_field == offset_field)
+    {
+        piece.bytes = Some(bytes);
+    }
+}
+
+fn to_bytes(value: u32, endian: Endian) -> Result<[u8; 4]> {
+    let mut bytes = [0u8; 4];
+    bytes.pwrite_with(value, 0, endian)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debug_map::DebugMap;
+    use crate::indirect_symbols::indirect_symbols;
+    use crate::test_utils::{read, reparse};
+    use crate::{LcVariant, MachObject};
+
+    const ALL: StripOptions = StripOptions {
+        stabs: true,
+        locals: true,
+    };
+
+    fn symbol_names(object: &MachObject) -> Vec<String> {
+        object
+            .symtab()
+            .unwrap()
+            .nlist_iterator()
+            .map(|symbol| match symbol.name {
+                Some(name) => name.to_string(),
+                None => String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stabs() {
+        let mut file = read("testable/stabs_arm64");
+        let summary = file.images_mut()[0].strip(&ALL).unwrap();
+        assert_eq!(summary.removed_symbols, 18);
+        assert!(!summary.removed_signature);
+        assert_eq!(summary.old_linkedit_size, 496);
+        assert!(summary.new_linkedit_size < 496);
+
+        let object = &reparse(&file, "schnauzer_strip_stabs")[0];
+        assert_eq!(
+            symbol_names(object),
+            vec!["__mh_execute_header", "_global", "_helper", "_main"]
+        );
+        assert!(DebugMap::parse(object).unwrap().is_empty());
+        let linkedit = object
+            .segments()
+            .into_iter()
+            .find(|segment| segment.segname.to_string() == SEG_LINKEDIT)
+            .unwrap();
+        assert_eq!(linkedit.filesize.0, summary.new_linkedit_size);
+    }
+
+    #[test]
+    fn test_locals() {
+        let options = StripOptions {
+            stabs: true,
+            locals: false,
+        };
+        let mut file = read("testable/strip/liblocal.dylib");
+        assert_eq!(
+            file.images_mut()[0]
+                .strip(&options)
+                .unwrap()
+                .removed_symbols,
+            0
+        );
+
+        let options = StripOptions {
+            stabs: false,
+            locals: true,
+        };
+        let summary = file.images_mut()[0].strip(&options).unwrap();
+        assert_eq!(summary.removed_symbols, 3);
+
+        let object = &reparse(&file, "schnauzer_strip_locals")[0];
+        assert_eq!(symbol_names(object), vec!["_a", "_b", "_printf"]);
+        let dysymtab = object
+            .load_commands_iterator()
+            .find_map(|cmd| match cmd.variant {
+                LcVariant::Dysimtab(c) => Some(c),
+                _ => None,
+            })
+            .unwrap();
+        let ranges = [
+            (dysymtab.ilocalsym, dysymtab.nlocalsym),
+            (dysymtab.iextdefsym, dysymtab.nextdefsym),
+            (dysymtab.iundefsym, dysymtab.nundefsym),
+        ];
+        assert_eq!(ranges, [(0, 0), (0, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn test_indirect_symbols() {
+        let original = read("testable/cat");
+        let mut file = original.clone();
+        for image in file.images_mut() {
+            let summary = image.strip(&ALL).unwrap();
+            // `radr://5614542` marker of `N_OPT` stab
+            assert_eq!(summary.removed_symbols, 1);
+            assert!(summary.removed_signature);
+            assert!(!image.has_code_signature());
+        }
+
+        let old_objects = reparse(&original, "schnauzer_strip_original");
+        let new_objects = reparse(&file, "schnauzer_strip_indirect");
+        for (old, new) in old_objects.iter().zip(&new_objects) {
+            let names = |object: &MachObject| -> Vec<(u64, Option<String>)> {
+                indirect_symbols(object)
+                    .unwrap()
+                    .into_iter()
+                    .map(|symbol| (symbol.address, symbol.name))
+                    .collect()
+            };
+            assert!(!names(old).is_empty());
+            assert_eq!(names(new), names(old));
+        }
+    }
+}
@@ -0,0 +1,814 @@
+//! Swift reflection metadata, stored in `__swift5_*` sections.
+//! Layout of records is described in `include/swift/ABI/Metadata.h` and `include/swift/RemoteInspection/Records.h` of Swift.
+//! All references between records are relative pointers, that is `int32_t` offsets from the pointer itself.
+
+use super::image::{Image, Pointer};
+use super::primitives::Hu32;
+use super::MachObject;
+use crate::demangle::swift::{demangle_context_descriptor, demangle_type};
+use crate::result::{Error, Result};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+pub const SWIFT_SECT_TYPES: &str = "__swift5_types";
+pub const SWIFT_SECT_PROTO: &str = "__swift5_proto";
+pub const SWIFT_SECT_PROTOS: &str = "__swift5_protos";
+pub const SWIFT_SECT_FIELDMD: &str = "__swift5_fieldmd";
+pub const SWIFT_SECT_ASSOCTY: &str = "__swift5_assocty";
+pub const SWIFT_SECT_REFLSTR: &str = "__swift5_reflstr";
+pub const SWIFT_SECT_TYPEREF: &str = "__swift5_typeref";
+
+/// `ContextDescriptorFlags`
+pub const CONTEXT_KIND_MASK: u32 = 0x1f;
+pub const CONTEXT_IS_GENERIC: u32 = 1 << 7;
+pub const CONTEXT_IS_UNIQUE: u32 = 1 << 6;
+
+/// `ContextDescriptorKind`
+pub const CONTEXT_KIND_MODULE: u8 = 0;
+pub const CONTEXT_KIND_EXTENSION: u8 = 1;
+pub const CONTEXT_KIND_ANONYMOUS: u8 = 2;
+pub const CONTEXT_KIND_PROTOCOL: u8 = 3;
+pub const CONTEXT_KIND_OPAQUE_TYPE: u8 = 4;
+pub const CONTEXT_KIND_CLASS: u8 = 16;
+pub const CONTEXT_KIND_STRUCT: u8 = 17;
+pub const CONTEXT_KIND_ENUM: u8 = 18;
+
+/// Symbolic reference in mangled name to context descriptor
+pub const SYMBOLIC_REFERENCE_DIRECT_CONTEXT: u8 = 0x01;
+
+/// `TypeReferenceKind`
+pub const TYPE_REFERENCE_DIRECT_TYPE_DESCRIPTOR: u8 = 0;
+pub const TYPE_REFERENCE_INDIRECT_TYPE_DESCRIPTOR: u8 = 1;
+pub const TYPE_REFERENCE_DIRECT_OBJC_CLASS_NAME: u8 = 2;
+pub const TYPE_REFERENCE_INDIRECT_OBJC_CLASS: u8 = 3;
+
+/// `ConformanceFlags`
+pub const CONFORMANCE_TYPE_REFERENCE_KIND_MASK: u32 = 0x7 << 3;
+pub const CONFORMANCE_TYPE_REFERENCE_KIND_SHIFT: u32 = 3;
+pub const CONFORMANCE_IS_RETROACTIVE: u32 = 1 << 6;
+pub const CONFORMANCE_IS_SYNTHESIZED_NON_UNIQUE: u32 = 1 << 7;
+pub const CONFORMANCE_NUM_CONDITIONAL_REQUIREMENTS_MASK: u32 = 0xff << 8;
+pub const CONFORMANCE_NUM_CONDITIONAL_REQUIREMENTS_SHIFT: u32 = 8;
+pub const CONFORMANCE_HAS_RESILIENT_WITNESSES: u32 = 1 << 16;
+pub const CONFORMANCE_HAS_GENERIC_WITNESS_TABLE: u32 = 1 << 17;
+
+/// `FieldRecordFlags`
+pub const FIELD_IS_INDIRECT_CASE: u32 = 0x1;
+pub const FIELD_IS_VAR: u32 = 0x2;
+
+/// `GenericRequirementKind`
+pub const GENERIC_REQUIREMENT_PROTOCOL: u8 = 0;
+pub const GENERIC_REQUIREMENT_SAME_TYPE: u8 = 1;
+pub const GENERIC_REQUIREMENT_BASE_CLASS: u8 = 2;
+pub const GENERIC_REQUIREMENT_SAME_CONFORMANCE: u8 = 3;
+pub const GENERIC_REQUIREMENT_LAYOUT: u8 = 0x1f;
+
+/// `GenericParamKind`
+pub const GENERIC_PARAM_KIND_TYPE: u8 = 0;
+const GENERIC_PARAM_KIND_MASK: u8 = 0x3f;
+const GENERIC_PARAM_HAS_KEY_ARGUMENT: u8 = 0x80;
+
+/// Sizes of descriptors before generic context, by kind
+const CLASS_DESCRIPTOR_SIZE: u64 = 44;
+const STRUCT_DESCRIPTOR_SIZE: u64 = 28;
+const ENUM_DESCRIPTOR_SIZE: u64 = 28;
+/// `InstantiationCache` and `DefaultInstantiationPattern` of `TypeGenericContextDescriptorHeader`,
+/// which precede `GenericContextDescriptorHeader`
+const TYPE_GENERIC_CONTEXT_PREFIX_SIZE: u64 = 8;
+const GENERIC_CONTEXT_HEADER_SIZE: u64 = 8;
+const GENERIC_REQUIREMENT_SIZE: u64 = 12;
+
+/// Sanity limits for count of entries in lists and nesting of contexts
+const MAX_LIST_COUNT: u32 = 0x10_0000;
+const MAX_CONTEXT_DEPTH: usize = 64;
+
+/// Kind of context descriptor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwiftContextKind {
+    Module,
+    Extension,
+    Anonymous,
+    Protocol,
+    OpaqueType,
+    Class,
+    Struct,
+    Enum,
+    Other(u8),
+}
+
+impl SwiftContextKind {
+    pub fn from_flags(flags: u32) -> Self {
+        match (flags & CONTEXT_KIND_MASK) as u8 {
+            CONTEXT_KIND_MODULE => Self::Module,
+            CONTEXT_KIND_EXTENSION => Self::Extension,
+            CONTEXT_KIND_ANONYMOUS => Self::Anonymous,
+            CONTEXT_KIND_PROTOCOL => Self::Protocol,
+            CONTEXT_KIND_OPAQUE_TYPE => Self::OpaqueType,
+            CONTEXT_KIND_CLASS => Self::Class,
+            CONTEXT_KIND_STRUCT => Self::Struct,
+            CONTEXT_KIND_ENUM => Self::Enum,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl Display for SwiftContextKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Module => write!(f, "module"),
+            Self::Extension => write!(f, "extension"),
+            Self::Anonymous => write!(f, "anonymous"),
+            Self::Protocol => write!(f, "protocol"),
+            Self::OpaqueType => write!(f, "opaque type"),
+            Self::Class => write!(f, "class"),
+            Self::Struct => write!(f, "struct"),
+            Self::Enum => write!(f, "enum"),
+            Self::Other(kind) => write!(f, "kind {kind}"),
+        }
+    }
+}
+
+/// Part of mangled name. Mangled names in metadata may embed symbolic references to descriptors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwiftMangledNamePart {
+    Text(String),
+    /// Reference to context descriptor, resolved to full name of the context
+    /// or to symbol name if the descriptor is defined outside of the object
+    Context(String),
+    /// Reference of other kind, like to accessor function
+    Unresolved {
+        kind: u8,
+        address: u64,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SwiftMangledName {
+    pub parts: Vec<SwiftMangledNamePart>,
+}
+
+impl SwiftMangledName {
+    /// Name without symbolic references, which can be demangled with `$s` prefix
+    pub fn as_text(&self) -> Option<&str> {
+        match self.parts.as_slice() {
+            [SwiftMangledNamePart::Text(text)] => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Demangled type, like `[Swift.Int]` for `SaySiG`.
+    /// `None` if the name contains unresolved references or can't be demangled.
+    pub fn demangled(&self) -> Option<String> {
+        let mut mangled = Vec::new();
+        let mut references = Vec::new();
+        for part in &self.parts {
+            match part {
+                SwiftMangledNamePart::Text(text) => mangled.extend_from_slice(text.as_bytes()),
+                SwiftMangledNamePart::Context(name) => {
+                    mangled.push(SYMBOLIC_REFERENCE_DIRECT_CONTEXT);
+                    mangled.extend_from_slice(&(references.len() as u32).to_le_bytes());
+                    // Contexts outside of the object are referenced by descriptor symbols
+                    references.push(demangle_context_descriptor(name).unwrap_or(name.clone()));
+                }
+                SwiftMangledNamePart::Unresolved { .. } => return None,
+            }
+        }
+        demangle_type(&mangled, &references)
+    }
+}
+
+/// Symbolic references are printed as resolved names in braces
+impl Display for SwiftMangledName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let [SwiftMangledNamePart::Context(name)] = self.parts.as_slice() {
+            return write!(f, "{name}");
+        }
+        for part in &self.parts {
+            match part {
+                SwiftMangledNamePart::Text(text) => write!(f, "{text}")?,
+                SwiftMangledNamePart::Context(name) => write!(f, "{{{name}}}")?,
+                SwiftMangledNamePart::Unresolved { kind, address } => {
+                    write!(f, "{{{kind:#04x}:{address:#x}}}")?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `FieldRecord`, stored property of class or struct, or case of enum
+#[derive(Debug, Clone)]
+pub struct SwiftField {
+    pub name: String,
+    /// `None` for enum cases without payload
+    pub mangled_type: Option<SwiftMangledName>,
+    pub flags: Hu32,
+}
+
+impl SwiftField {
+    pub fn is_var(&self) -> bool {
+        self.flags.0 & FIELD_IS_VAR != 0
+    }
+
+    pub fn is_indirect_case(&self) -> bool {
+        self.flags.0 & FIELD_IS_INDIRECT_CASE != 0
+    }
+}
+
+/// `GenericParamDescriptor`
+#[derive(Debug, Clone, Copy)]
+pub struct SwiftGenericParam {
+    pub kind: u8,
+    pub has_key_argument: bool,
+}
+
+/// `GenericRequirementDescriptor`
+#[derive(Debug, Clone)]
+pub struct SwiftGenericRequirement {
+    pub kind: u8,
+    pub param: SwiftMangledName,
+    /// Protocol name, type name or layout kind, depending on `kind`
+    pub target: String,
+}
+
+/// Nominal type descriptor: `ClassDescriptor`, `StructDescriptor` or `EnumDescriptor`
+#[derive(Debug, Clone)]
+pub struct SwiftType {
+    pub address: u64,
+    pub kind: SwiftContextKind,
+    pub flags: Hu32,
+    pub name: String,
+    /// Full name of parent context, like module name
+    pub parent: Option<String>,
+    /// Mangled name of superclass type, only for classes
+    pub superclass: Option<SwiftMangledName>,
+    /// Stored properties or enum cases
+    pub fields: Vec<SwiftField>,
+    pub generic_params: Vec<SwiftGenericParam>,
+    pub generic_requirements: Vec<SwiftGenericRequirement>,
+}
+
+impl SwiftType {
+    pub fn full_name(&self) -> String {
+        full_name(&self.parent, &self.name)
+    }
+
+    pub fn is_generic(&self) -> bool {
+        self.flags.0 & CONTEXT_IS_GENERIC != 0
+    }
+}
+
+/// `ProtocolDescriptor`
+#[derive(Debug, Clone)]
+pub struct SwiftProtocol {
+    pub address: u64,
+    pub name: String,
+    pub parent: Option<String>,
+    pub num_requirements_in_signature: u32,
+    pub num_requirements: u32,
+    pub associated_type_names: Vec<String>,
+}
+
+impl SwiftProtocol {
+    pub fn full_name(&self) -> String {
+        full_name(&self.parent, &self.name)
+    }
+}
+
+/// `ProtocolConformanceDescriptor`
+#[derive(Debug, Clone)]
+pub struct SwiftConformance {
+    pub address: u64,
+    /// Full name of the protocol, or symbol name if it's defined outside of the object
+    pub protocol: String,
+    /// Full name of conforming type, or name of ObjC class
+    pub type_name: String,
+    pub flags: Hu32,
+}
+
+impl SwiftConformance {
+    pub fn type_reference_kind(&self) -> u8 {
+        ((self.flags.0 & CONFORMANCE_TYPE_REFERENCE_KIND_MASK)
+            >> CONFORMANCE_TYPE_REFERENCE_KIND_SHIFT) as u8
+    }
+
+    pub fn is_retroactive(&self) -> bool {
+        self.flags.0 & CONFORMANCE_IS_RETROACTIVE != 0
+    }
+
+    pub fn num_conditional_requirements(&self) -> u32 {
+        (self.flags.0 & CONFORMANCE_NUM_CONDITIONAL_REQUIREMENTS_MASK)
+            >> CONFORMANCE_NUM_CONDITIONAL_REQUIREMENTS_SHIFT
+    }
+}
+
+/// `AssociatedTypeRecord`
+#[derive(Debug, Clone)]
+pub struct SwiftAssociatedType {
+    pub name: String,
+    pub substituted_type: SwiftMangledName,
+}
+
+/// `AssociatedTypeDescriptor`, associated types of single conformance
+#[derive(Debug, Clone)]
+pub struct SwiftAssociatedTypes {
+    pub address: u64,
+    pub conforming_type: SwiftMangledName,
+    pub protocol: SwiftMangledName,
+    pub types: Vec<SwiftAssociatedType>,
+}
+
+/// All Swift reflection metadata of single [MachObject]
+#[derive(Debug, Default)]
+pub struct SwiftMetadata {
+    pub types: Vec<SwiftType>,
+    pub protocols: Vec<SwiftProtocol>,
+    pub conformances: Vec<SwiftConformance>,
+    pub associated_types: Vec<SwiftAssociatedTypes>,
+}
+
+impl SwiftMetadata {
+    /// Parses metadata of `object`. Returns empty metadata if the object has no Swift sections.
+    pub fn parse(object: &MachObject) -> Result<Self> {
+        let image = Image::build(object)?;
+        let parser = Parser {
+            image: &image,
+            context_names: RefCell::new(HashMap::new()),
+        };
+
+        let sections = object.sections();
+        let list = |sectname: &str| -> Vec<(u64, u64)> {
+            sections
+                .iter()
+                .filter(|s| s.sectname.to_string() == sectname)
+                .map(|s| (s.addr.0, s.size.0))
+                .collect()
+        };
+
+        let mut metadata = SwiftMetadata::default();
+        for entry in parser.relative_entries(&list(SWIFT_SECT_TYPES)) {
+            // Type records use the lowest bits for `TypeReferenceKind`
+            let offset = image.read_i32(entry)?;
+            let kind = (offset & 0x3) as u8;
+            let target = entry.wrapping_add((offset & !0x3) as i64 as u64);
+            let address = match kind {
+                TYPE_REFERENCE_DIRECT_TYPE_DESCRIPTOR => target,
+                TYPE_REFERENCE_INDIRECT_TYPE_DESCRIPTOR => match image.read_address(target)? {
+                    Some(address) => address,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            metadata.types.push(parser.nominal_type(address)?);
+        }
+        for entry in parser.relative_entries(&list(SWIFT_SECT_PROTOS)) {
+            if let Pointer::Address(address) = image.read_relative_indirectable(entry)? {
+                metadata.protocols.push(parser.protocol(address)?);
+            }
+        }
+        for entry in parser.relative_entries(&list(SWIFT_SECT_PROTO)) {
+            if let Some(address) = image.read_relative(entry)? {
+                metadata.conformances.push(parser.conformance(address)?);
+            }
+        }
+        for (address, size) in list(SWIFT_SECT_ASSOCTY) {
+            let mut current = address;
+            while current + 16 <= address + size {
+                let (record, next) = parser.associated_types(current)?;
+                metadata.associated_types.push(record);
+                current = next;
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+            && self.protocols.is_empty()
+            && self.conformances.is_empty()
+            && self.associated_types.is_empty()
+    }
+}
+
+fn full_name(parent: &Option<String>, name: &str) -> String {
+    match parent {
+        Some(parent) => format!("{parent}.{name}"),
+        None => name.to_string(),
+    }
+}
+
+struct Parser<'a> {
+    image: &'a Image,
+    /// Full names of context descriptors by address
+    context_names: RefCell<HashMap<u64, String>>,
+}
+
+impl Parser<'_> {
+    /// Addresses of 32-bit entries of sections
+    fn relative_entries(&self, sections: &[(u64, u64)]) -> Vec<u64> {
+        sections
+            .iter()
+            .flat_map(|(address, size)| (0..size / 4).map(move |index| address + index * 4))
+            .collect()
+    }
+
+    fn string(&self, address: u64) -> Result<String> {
+        match self.image.read_relative(address)? {
+            Some(target) => self.image.read_cstring(target),
+            None => Ok(String::new()),
+        }
+    }
+
+    fn count(&self, address: u64) -> Result<u32> {
+        let count = self.image.read_u32(address)?;
+        match count > MAX_LIST_COUNT {
+            true => Err(Error::Text(format!(
+                "Too many entries ({count}) at {:#x}",
+                address
+            ))),
+            false => Ok(count),
+        }
+    }
+
+    /// Reads mangled name referenced by relative pointer at `address`
+    fn mangled_name_at(&self, address: u64) -> Result<Option<SwiftMangledName>> {
+        match self.image.read_relative(address)? {
+            Some(target) => self.mangled_name(target).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads mangled name at `address`, resolving embedded symbolic references.
+    /// Bytes `0x01...0x17` are followed by relative reference, `0x18...0x1f` by absolute pointer.
+    fn mangled_name(&self, address: u64) -> Result<SwiftMangledName> {
+        let mut name = SwiftMangledName::default();
+        let mut text = Vec::new();
+        let mut current = address;
+
+        loop {
+            let byte = self.image.read_bytes(current, 1)?[0];
+            let reference = match byte {
+                0 => break,
+                0x01..=0x17 => {
+                    let target = self.image.read_relative(current + 1)?.unwrap_or(0);
+                    current += 5;
+                    match byte {
+                        SYMBOLIC_REFERENCE_DIRECT_CONTEXT => SwiftMangledNamePart::Context(self.context_name(target)?),
+                        0x02 => match self.image.read_pointer(target)? {
+                            Pointer::Address(target) => {
+                                SwiftMangledNamePart::Context(self.context_name(target)?)
+                            }
+                            Pointer::Symbol(symbol) => SwiftMangledNamePart::Context(symbol),
+                            Pointer::Null => SwiftMangledNamePart::Unresolved {
+                                kind: byte,
+                                address: target,
+                            },
+                        },
+                        _ => SwiftMangledNamePart::Unresolved {
+                            kind: byte,
+                            address: target,
+                        },
+                    }
+                }
+                0x18..=0x1f => {
+                    let target = self.image.read_word(current + 1)?;
+                    current += 1 + self.image.pointer_size();
+                    SwiftMangledNamePart::Unresolved {
+                        kind: byte,
+                        address: target,
+                    }
+                }
+                _ => {
+                    text.push(byte);
+                    current += 1;
+                    continue;
+                }
+            };
+
+            if !text.is_empty() {
+                name.parts.push(SwiftMangledNamePart::Text(
+                    String::from_utf8_lossy(&text).to_string(),
+                ));
+                text.clear();
+            }
+            name.parts.push(reference);
+        }
+
+        if !text.is_empty() {
+            name.parts.push(SwiftMangledNamePart::Text(
+                String::from_utf8_lossy(&text).to_string(),
+            ));
+        }
+        Ok(name)
+    }
+
+    /// Full name of context descriptor, like `Module.Outer.Inner`
+    fn context_name(&self, address: u64) -> Result<String> {
+        self.context_name_with_depth(address, 0)
+    }
+
+    fn context_name_with_depth(&self, address: u64, depth: usize) -> Result<String> {
+        if let Some(name) = self.context_names.borrow().get(&address) {
+            return Ok(name.clone());
+        }
+        if depth > MAX_CONTEXT_DEPTH {
+            return Err(Error::Text(format!(
+                "Context at {:#x} is nested too deep",
+                address
+            )));
+        }
+
+        let flags = self.image.read_u32(address)?;
+        let name = match SwiftContextKind::from_flags(flags) {
+            // Extension takes the name of extended type
+            SwiftContextKind::Extension => match self.mangled_name_at(address + 8)? {
+                Some(extended) => return Ok(extended.to_string()),
+                None => "(extension)".to_string(),
+            },
+            SwiftContextKind::Anonymous => "(anonymous)".to_string(),
+            _ => self.string(address + 8)?,
+        };
+
+        let result = match self.parent_name(address, depth)? {
+            Some(parent) => format!("{parent}.{name}"),
+            None => name,
+        };
+        self.context_names
+            .borrow_mut()
+            .insert(address, result.clone());
+        Ok(result)
+    }
+
+    fn parent_name(&self, address: u64, depth: usize) -> Result<Option<String>> {
+        match self.image.read_relative_indirectable(address + 4)? {
+            Pointer::Null => Ok(None),
+            Pointer::Address(parent) => self.context_name_with_depth(parent, depth + 1).map(Some),
+            Pointer::Symbol(symbol) => Ok(Some(symbol)),
+        }
+    }
+}
+
+impl Parser<'_> {
+    fn nominal_type(&self, address: u64) -> Result<SwiftType> {
+        let flags = self.image.read_u32(address)?;
+        let kind = SwiftContextKind::from_flags(flags);
+
+        let (superclass, descriptor_size) = match kind {
+            SwiftContextKind::Class => (self.mangled_name_at(address + 20)?, CLASS_DESCRIPTOR_SIZE),
+            SwiftContextKind::Struct => (None, STRUCT_DESCRIPTOR_SIZE),
+            SwiftContextKind::Enum => (None, ENUM_DESCRIPTOR_SIZE),
+            other => {
+                return Err(Error::Text(format!(
+                    "Unexpected {other} descriptor at {:#x} in type records",
+                    address
+                )))
+            }
+        };
+
+        let mut result = SwiftType {
+            address,
+            kind,
+            flags: Hu32(flags),
+            name: self.string(address + 8)?,
+            parent: self.parent_name(address, 0)?,
+            superclass,
+            fields: match self.image.read_relative(address + 16)? {
+                Some(fields) => self.fields(fields)?,
+                None => Vec::new(),
+            },
+            generic_params: Vec::new(),
+            generic_requirements: Vec::new(),
+        };
+
+        if flags & CONTEXT_IS_GENERIC != 0 {
+            let header = address + descriptor_size + TYPE_GENERIC_CONTEXT_PREFIX_SIZE;
+            self.generic_context(header, &mut result)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Reads `GenericContextDescriptorHeader` at `address`, followed by params and requirements
+    fn generic_context(&self, address: u64, result: &mut SwiftType) -> Result<()> {
+        let num_params = self.image.read_u16(address)? as u64;
+        let num_requirements = self.image.read_u16(address + 2)? as u64;
+
+        let params = self
+            .image
+            .read_bytes(address + GENERIC_CONTEXT_HEADER_SIZE, num_params as usize)?;
+        result.generic_params = params
+            .iter()
+            .map(|param| SwiftGenericParam {
+                kind: param & GENERIC_PARAM_KIND_MASK,
+                has_key_argument: param & GENERIC_PARAM_HAS_KEY_ARGUMENT != 0,
+            })
+            .collect();
+
+        // Requirements are aligned to 4 bytes
+        let requirements = address + GENERIC_CONTEXT_HEADER_SIZE + num_params.div_ceil(4) * 4;
+        for index in 0..num_requirements {
+            let requirement = requirements + index * GENERIC_REQUIREMENT_SIZE;
+            let kind = (self.image.read_u32(requirement)? & 0x1f) as u8;
+            let param = self.mangled_name_at(requirement + 4)?.unwrap_or_default();
+            let target = match kind {
+                GENERIC_REQUIREMENT_PROTOCOL => self.protocol_reference(requirement + 8)?,
+                GENERIC_REQUIREMENT_LAYOUT => match self.image.read_u32(requirement + 8)? {
+                    0 => "AnyObject".to_string(),
+                    other => format!("layout {other}"),
+                },
+                _ => self
+                    .mangled_name_at(requirement + 8)?
+                    .map(|name| name.to_string())
+                    .unwrap_or_default(),
+            };
+            result.generic_requirements.push(SwiftGenericRequirement {
+                kind,
+                param,
+                target,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Reads `RelativeProtocolDescriptorPointer`, the second lowest bit tells if it's ObjC protocol
+    fn protocol_reference(&self, address: u64) -> Result<String> {
+        let offset = self.image.read_i32(address)?;
+        let is_objc = offset & 0x2 != 0;
+        let target = address.wrapping_add((offset & !0x3) as i64 as u64);
+        let pointer = match offset & 0x1 {
+            0 => Pointer::Address(target),
+            _ => self.image.read_pointer(target)?,
+        };
+
+        match (pointer, is_objc) {
+            (Pointer::Address(protocol), true) => match self
+                .image
+                .read_address(protocol + self.image.pointer_size())?
+            {
+                Some(name) => self.image.read_cstring(name),
+                None => Ok(format!("{:#x}", protocol)),
+            },
+            (Pointer::Address(protocol), false) => self.context_name(protocol),
+            (Pointer::Symbol(symbol), _) => Ok(symbol),
+            (Pointer::Null, _) => Ok(String::new()),
+        }
+    }
+
+    /// Reads `FieldDescriptor` at `address`
+    fn fields(&self, address: u64) -> Result<Vec<SwiftField>> {
+        let record_size = self.image.read_u16(address + 10)? as u64;
+        let count = self.count(address + 12)?;
+
+        let mut result = Vec::with_capacity(count as usize);
+        for index in 0..count as u64 {
+            let record = address + 16 + index * record_size;
+            result.push(SwiftField {
+                flags: Hu32(self.image.read_u32(record)?),
+                mangled_type: self.mangled_name_at(record + 4)?,
+                name: self.string(record + 8)?,
+            });
+        }
+        Ok(result)
+    }
+
+    fn protocol(&self, address: u64) -> Result<SwiftProtocol> {
+        let associated_type_names = self.string(address + 20)?;
+        Ok(SwiftProtocol {
+            address,
+            name: self.string(address + 8)?,
+            parent: self.parent_name(address, 0)?,
+            num_requirements_in_signature: self.count(address + 12)?,
+            num_requirements: self.count(address + 16)?,
+            associated_type_names: associated_type_names
+                .split(' ')
+                .filter(|name| !name.is_empty())
+                .map(|name| name.to_string())
+                .collect(),
+        })
+    }
+
+    fn conformance(&self, address: u64) -> Result<SwiftConformance> {
+        let flags = self.image.read_u32(address + 12)?;
+        let protocol = match self.image.read_relative_indirectable(address)? {
+            Pointer::Address(protocol) => self.context_name(protocol)?,
+            Pointer::Symbol(symbol) => symbol,
+            Pointer::Null => String::new(),
+        };
+
+        let kind = ((flags & CONFORMANCE_TYPE_REFERENCE_KIND_MASK)
+            >> CONFORMANCE_TYPE_REFERENCE_KIND_SHIFT) as u8;
+        let target = self.image.read_relative(address + 4)?;
+        let type_name = match (kind, target) {
+            (_, None) => String::new(),
+            (TYPE_REFERENCE_DIRECT_TYPE_DESCRIPTOR, Some(target)) => self.context_name(target)?,
+            (TYPE_REFERENCE_INDIRECT_TYPE_DESCRIPTOR, Some(target)) => {
+                match self.image.read_pointer(target)? {
+                    Pointer::Address(descriptor) => self.context_name(descriptor)?,
+                    Pointer::Symbol(symbol) => symbol,
+                    Pointer::Null => String::new(),
+                }
+            }
+            (TYPE_REFERENCE_DIRECT_OBJC_CLASS_NAME, Some(target)) => {
+                self.image.read_cstring(target)?
+            }
+            (TYPE_REFERENCE_INDIRECT_OBJC_CLASS, Some(target)) => {
+                match self.image.read_pointer(target)? {
+                    Pointer::Symbol(symbol) => symbol,
+                    Pointer::Address(class) => format!("{:#x}", class),
+                    Pointer::Null => String::new(),
+                }
+            }
+            (other, _) => format!("(type reference kind {other})"),
+        };
+
+        Ok(SwiftConformance {
+            address,
+            protocol,
+            type_name,
+            flags: Hu32(flags),
+        })
+    }
+
+    /// Reads `AssociatedTypeDescriptor` at `address`, returns it with address of the next one
+    fn associated_types(&self, address: u64) -> Result<(SwiftAssociatedTypes, u64)> {
+        let count = self.count(address + 8)?;
+        let record_size = self.image.read_u32(address + 12)? as u64;
+
+        let mut types = Vec::with_capacity(count as usize);
+        for index in 0..count as u64 {
+            let record = address + 16 + index * record_size;
+            types.push(SwiftAssociatedType {
+                name: self.string(record)?,
+                substituted_type: self.mangled_name_at(record + 4)?.unwrap_or_default(),
+            });
+        }
+
+        let result = SwiftAssociatedTypes {
+            address,
+            conforming_type: self.mangled_name_at(address)?.unwrap_or_default(),
+            protocol: self.mangled_name_at(address + 4)?.unwrap_or_default(),
+            types,
+        };
+        Ok((result, address + 16 + count as u64 * record_size))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse_single;
+
+    #[test]
+    fn test_parse_chained_arm64() {
+        let object = parse_single("testable/swift_arm64");
+        let metadata = SwiftMetadata::parse(&object).unwrap();
+
+        let names: Vec<String> = metadata.types.iter().map(|t| t.full_name()).collect();
+        assert_eq!(
+            names,
+            vec!["Main.Base", "Main.Box", "Main.Point", "Main.Tree"]
+        );
+
+        // Generic class with superclass referenced symbolically
+        let class = &metadata.types[1];
+        assert_eq!(class.kind, SwiftContextKind::Class);
+        assert_eq!(class.superclass.as_ref().unwrap().to_string(), "Main.Base");
+        assert_eq!(class.generic_params.len(), 1);
+        assert_eq!(class.generic_requirements[0].target, "Main.Shape");
+        assert!(class.fields[0].is_var());
+        assert_eq!(
+            class.fields[1].mangled_type.as_ref().unwrap().as_text(),
+            Some("Si")
+        );
+
+        let cases = &metadata.types[3].fields;
+        assert!(cases[1].is_indirect_case());
+        assert!(cases[2].mangled_type.is_none());
+
+        assert_eq!(metadata.protocols[0].associated_type_names, vec!["Unit"]);
+
+        // Protocol outside of the image is bound through GOT slot
+        let conformances: Vec<(&str, &str)> = metadata
+            .conformances
+            .iter()
+            .map(|c| (c.type_name.as_str(), c.protocol.as_str()))
+            .collect();
+        assert_eq!(
+            conformances,
+            vec![
+                ("Main.Point", "Main.Shape"),
+                ("Main.Point", "_$sSQMp"),
+                ("NSObject", "Main.Shape")
+            ]
+        );
+        assert!(metadata.conformances[2].is_retroactive());
+
+        let associated_types = &metadata.associated_types[0];
+        assert_eq!(associated_types.conforming_type.to_string(), "Main.Point");
+        assert_eq!(associated_types.types[0].name, "Unit");
+    }
+}
@@ -0,0 +1,251 @@
+//! Mapping of runtime addresses to symbols and source lines, as `atos(1)` does.
+//! Symbols are collected from `LC_SYMTAB`, the export trie and `LC_FUNCTION_STARTS`,
+//! source lines are looked up in DWARF of dSYM companion (or of the object itself).
+
+use super::constants::SEG_TEXT;
+use super::dwarf::{DwarfInfo, DwarfLocation};
+use super::exports::{Exports, EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE};
+use super::function_starts::FunctionStarts;
+use super::section_constants::S_ATTR_DEBUG;
+use super::MachObject;
+use crate::result::Result;
+
+/// Name and address of a symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolAddress {
+    pub address: u64,
+    pub name: String,
+}
+
+/// Result of symbolication of single address
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Symbolication {
+    /// Address as given, in address space of the process
+    pub address: u64,
+    /// Address in the image as it's linked, without ASLR slide
+    pub image_address: u64,
+    /// Name of the symbol containing the address. `None` for unnamed function starts.
+    pub symbol: Option<String>,
+    /// Address of the symbol (or of the function start) in the image
+    pub symbol_address: Option<u64>,
+    /// Source location, if DWARF is available
+    pub location: Option<DwarfLocation>,
+}
+
+impl Symbolication {
+    /// Offset of the address from start of the symbol
+    pub fn offset(&self) -> Option<u64> {
+        self.symbol_address
+            .map(|symbol_address| self.image_address - symbol_address)
+    }
+}
+
+pub struct Symbolicator {
+    text_vmaddr: u64,
+    /// Ranges of non-debug sections, the only places where symbols are looked up
+    section_ranges: Vec<std::ops::Range<u64>>,
+    /// Sorted by address
+    symbols: Vec<SymbolAddress>,
+    function_starts: Vec<u64>,
+    dwarf: Option<DwarfInfo>,
+}
+
+impl Symbolicator {
+    /// Collects symbols of `object`. If `dsym` is given, its symbols and DWARF are used as well.
+    pub fn build(object: &MachObject, dsym: Option<&MachObject>) -> Result<Self> {
+        let text_vmaddr = object
+            .segments()
+            .iter()
+            .find(|seg| seg.segname.to_string() == SEG_TEXT)
+            .map(|seg| seg.vmaddr.0)
+            .unwrap_or_default();
+
+        let section_ranges = object
+            .sections()
+            .iter()
+            .filter(|sect| sect.flags.0 & S_ATTR_DEBUG == 0)
+            .map(|sect| sect.addr.0..sect.addr.0 + sect.size.0)
+            .collect();
+
+        let mut symbols = symtab_symbols(object);
+        if let Some(dsym) = dsym {
+            symbols.append(&mut symtab_symbols(dsym));
+        }
+        if let Some(exports) = Exports::parse(object)? {
+            let exported = exports
+                .entries
+                .into_iter()
+                .filter(|e| !e.is_reexport() && e.kind() != EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE)
+                .map(|e| SymbolAddress {
+                    address: text_vmaddr + e.address,
+                    name: e.name,
+                });
+            symbols.extend(exported);
+        }
+        symbols.sort_by(|a, b| a.address.cmp(&b.address).then(a.name.cmp(&b.name)));
+        symbols.dedup_by(|a, b| a.address == b.address);
+
+        let function_starts = match FunctionStarts::parse(object)? {
+            Some(starts) => starts.addresses,
+            None => Vec::new(),
+        };
+
+        let dwarf = match dsym {
+            Some(dsym) => Some(DwarfInfo::parse(dsym)?),
+            None => Some(DwarfInfo::parse(object)?),
+        };
+        let dwarf = dwarf.filter(|dwarf| !dwarf.is_empty());
+
+        Ok(Symbolicator {
+            text_vmaddr,
+            section_ranges,
+            symbols,
+            function_starts,
+            dwarf,
+        })
+    }
+
+    /// `vmaddr` of `__TEXT` segment, the address the image is linked at
+    pub fn text_vmaddr(&self) -> u64 {
+        self.text_vmaddr
+    }
+
+    pub fn symbols(&self) -> &[SymbolAddress] {
+        &self.symbols
+    }
+
+    pub fn has_dwarf(&self) -> bool {
+        self.dwarf.is_some()
+    }
+
+    /// Symbolicates `address`. If `load_address` (runtime address of `__TEXT`) is given,
+    /// the address is slid back by the difference between it and `__TEXT.vmaddr`.
+    pub fn symbolicate(&self, address: u64, load_address: Option<u64>) -> Result<Symbolication> {
+        let slide = match load_address {
+            Some(load_address) => load_address.wrapping_sub(self.text_vmaddr),
+            None => 0,
+        };
+        let image_address = address.wrapping_sub(slide);
+
+        let mut result = Symbolication {
+            address,
+            image_address,
+            ..Default::default()
+        };
+        if !self
+            .section_ranges
+            .iter()
+            .any(|range| range.contains(&image_address))
+        {
+            return Ok(result);
+        }
+
+        let symbol = match self.symbols.partition_point(|s| s.address <= image_address) {
+            0 => None,
+            index => Some(&self.symbols[index - 1]),
+        };
+        let function_start = match self
+            .function_starts
+            .partition_point(|a| *a <= image_address)
+        {
+            0 => None,
+            index => Some(self.function_starts[index - 1]),
+        };
+
+        match (symbol, function_start) {
+            // Function starts without symbols are stripped functions
+            (Some(symbol), Some(start)) if start > symbol.address => {
+                result.symbol_address = Some(start)
+            }
+            (None, Some(start)) => result.symbol_address = Some(start),
+            (Some(symbol), _) => {
+                result.symbol = Some(symbol.name.clone());
+                result.symbol_address = Some(symbol.address);
+            }
+            (None, None) => (),
+        }
+
+        if let Some(dwarf) = &self.dwarf {
+            result.location = dwarf.lookup(image_address)?;
+        }
+
+        // Only DWARF knows names of stripped functions
+        if result.symbol.is_none() {
+            if let Some(location) = &result.location {
+                result.symbol = location.linkage_name.clone().or(location.function.clone());
+                result.symbol_address = location.function_address.or(result.symbol_address);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Defined non-debug symbols
+pub(super) fn symtab_symbols(object: &MachObject) -> Vec<SymbolAddress> {
+    let symtab = match object.symtab() {
+        Some(symtab) => symtab,
+        None => return Vec::new(),
+    };
+
+    symtab
+        .nlist_iterator()
+        .filter(|nlist| !nlist.n_type.is_stab() && nlist.n_type.is_defined_in_n_sect())
+        .filter_map(|nlist| {
+            let name = nlist.name.as_ref()?.load_string().ok()?;
+            Some(SymbolAddress {
+                address: nlist.n_value.0,
+                name,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::parse;
+    use crate::ObjectType;
+
+    fn parse_object(path: &str, arch: &str) -> MachObject {
+        let object = parse(path);
+        match object {
+            ObjectType::MachO(object) => object,
+            ObjectType::Fat(_) => object.mach_object_with_arch(arch).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_stripped() {
+        let object = parse_object("testable/cat", "x86_64");
+        let symbolicator = Symbolicator::build(&object, None).unwrap();
+        let starts = FunctionStarts::parse(&object).unwrap().unwrap().addresses;
+
+        let load_address = 0x1_0420_0000;
+        let slide = load_address - symbolicator.text_vmaddr();
+        let result = symbolicator
+            .symbolicate(starts[1] + slide + 4, Some(load_address))
+            .unwrap();
+        assert_eq!(result.image_address, starts[1] + 4);
+        assert_eq!(result.symbol, None);
+        assert_eq!(result.symbol_address, Some(starts[1]));
+        assert_eq!(result.offset(), Some(4));
+
+        let result = symbolicator.symbolicate(0x10, None).unwrap();
+        assert_eq!(result.symbol_address, None);
+    }
+
+    #[test]
+    fn test_dwarf() {
+        let object = parse_object("testable/dwarf_arm64.o", "arm64");
+        let symbolicator = Symbolicator::build(&object, None).unwrap();
+        assert!(symbolicator.has_dwarf());
+
+        let result = symbolicator.symbolicate(0x10, None).unwrap();
+        assert_eq!(result.symbol.as_deref(), Some("_main"));
+        assert_eq!(result.offset(), Some(4));
+        let location = result.location.unwrap();
+        assert_eq!(location.function.as_deref(), Some("main"));
+        assert_eq!(location.line, Some(9));
+    }
+}
@@ -0,0 +1,278 @@
+//! Exported symbols of text-based stubs (`.tbd`), that SDKs contain instead of libraries.
+//! Only symbol lists of `exports` and `reexports` sections are read, of entries targeting
+//! the importing architecture. Stubs of umbrella libraries contain documents of their
+//! re-exported libraries, so symbols of all documents are merged.
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Top level sections of stub with symbols the library provides
+const EXPORT_SECTIONS: [&str; 3] = ["exports", "reexports", "re-exports"];
+/// Lists of entry of export section with architectures it applies to,
+/// `targets` like `arm64-macos` since version 4 and `archs` like `arm64` before it
+const TARGET_LISTS: [&str; 2] = ["targets", "archs"];
+
+/// Lists of exported symbols and prefixes of symbol names they contain
+const SYMBOL_LISTS: [(&str, &[&str]); 6] = [
+    ("symbols", &[""]),
+    ("weak-symbols", &[""]),
+    ("weak-def-symbols", &[""]),
+    ("thread-local-symbols", &[""]),
+    ("objc-classes", &["_OBJC_CLASS_$_", "_OBJC_METACLASS_$_"]),
+    ("objc-eh-types", &["_OBJC_EHTYPE_$_"]),
+];
+const OBJC_IVARS_LIST: &str = "objc-ivars";
+const OBJC_IVAR_PREFIX: &str = "_OBJC_IVAR_$_";
+
+/// Version of documents, that are tagged with `!tapi-tbd` and set `tbd-version`
+const TAPI_TBD_VERSION: u32 = 4;
+
+/// Top level export section of document
+struct ExportSection {
+    /// Version of stub format, before 4 names of Objective-C lists have leading underscore
+    version: u32,
+    text: String,
+}
+
+fn list_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"([a-z-]+)\s*:\s*\[([^\]]*)\]").unwrap())
+}
+
+/// Symbols exported by stub with contents `text` for architecture `arch`, like `arm64`.
+/// Entries of other architectures are skipped
+pub fn exported_symbols(text: &str, arch: &str) -> HashSet<String> {
+    let mut result = HashSet::new();
+    for section in export_sections(text) {
+        for entry in section_entries(&section.text) {
+            let lists: Vec<(&str, Vec<&str>)> = list_pattern()
+                .captures_iter(entry)
+                .map(|captures| {
+                    let key = captures.get(1).unwrap().as_str();
+                    let names = captures
+                        .get(2)
+                        .unwrap()
+                        .as_str()
+                        .split(',')
+                        .map(|name| name.trim().trim_matches(|c| c == '\'' || c == '"'))
+                        .filter(|name| !name.is_empty())
+                        .collect();
+                    (key, names)
+                })
+                .collect();
+            let other_arch = lists.iter().any(|(key, targets)| {
+                TARGET_LISTS.contains(key)
+                    && !targets
+                        .iter()
+                        .any(|target| target.split('-').next() == Some(arch))
+            });
+            if other_arch {
+                continue;
+            }
+
+            for (key, names) in lists {
+                let (prefixes, is_objc): (&[&str], bool) =
+                    match SYMBOL_LISTS.iter().find(|(list, _)| *list == key) {
+                        Some((_, prefixes)) => (prefixes, key.starts_with("objc-")),
+                        None if key == OBJC_IVARS_LIST => (&[OBJC_IVAR_PREFIX], true),
+                        None => continue,
+                    };
+                for name in names {
+                    let name = match is_objc && section.version < TAPI_TBD_VERSION {
+                        true => name.strip_prefix('_').unwrap_or(name),
+                        false => name,
+                    };
+                    for prefix in prefixes {
+                        result.insert(format!("{prefix}{name}"));
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Top level export sections of all documents
+fn export_sections(text: &str) -> Vec<ExportSection> {
+    let mut result = Vec::new();
+    let mut current: Option<ExportSection> = None;
+    let mut version = 1;
+    for line in text.lines() {
+        let is_top_level = line.starts_with(|c: char| c.is_ascii_alphabetic());
+        let is_document_start = line.starts_with("---");
+        if is_top_level || is_document_start || line.starts_with("...") {
+            if let Some(section) = current.take() {
+                result.push(section);
+            }
+            if is_document_start {
+                version = document_version(line);
+            }
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) if is_top_level => (key.trim(), value),
+                _ => continue,
+            };
+            if key == "tbd-version" {
+                version = value.trim().parse().unwrap_or(version);
+            } else if EXPORT_SECTIONS.contains(&key) {
+                current = Some(ExportSection {
+                    version,
+                    text: value.to_string(),
+                });
+            }
+            continue;
+        }
+        if let Some(section) = current.as_mut() {
+            section.text.push('\n');
+            section.text.push_str(line);
+        }
+    }
+    if let Some(section) = current {
+        result.push(section);
+    }
+    result
+}
+
+/// Version by tag of document start, `--- !tapi-tbd-v3` or untagged `---` of version 1
+fn document_version(line: &str) -> u32 {
+    let tag = line.trim_start_matches('-').trim();
+    match tag.strip_prefix("!tapi-tbd-v") {
+        Some(version) => version.parse().unwrap_or(1),
+        None if tag == "!tapi-tbd" => TAPI_TBD_VERSION,
+        None => 1,
+    }
+}
+
+/// Entries of export section, each starts with `- ` of block sequence
+fn section_entries(text: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim_start().starts_with("- ") && offset > start {
+            result.push(&text[start..offset]);
+            start = offset;
+        }
+        offset += line.len();
+    }
+    result.push(&text[start..]);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_exported_symbols() {
+        let text = "--- !tapi-tbd
+tbd-version:     4
+targets:         [ arm64-macos ]
+install-name:    '/usr/lib/libSystem.B.dylib'
+exports:
+  - targets:         [ arm64-macos ]
+    symbols:         [ _printf, '_puts',
+                       _malloc ]
+    objc-classes:    [ NSObject ]
+undefineds:
+  - targets:         [ arm64-macos ]
+    symbols:         [ _undefined ]
+--- !tapi-tbd
+tbd-version:     4
+install-name:    '/usr/lib/system/libsystem_c.dylib'
+reexports:
+  - targets:         [ arm64-macos ]
+    weak-symbols:    [ _strlen ]
+...
+";
+        let symbols = exported_symbols(text, "arm64");
+        let mut expected = vec![
+            "_printf",
+            "_puts",
+            "_malloc",
+            "_OBJC_CLASS_$_NSObject",
+            "_OBJC_METACLASS_$_NSObject",
+            "_strlen",
+        ];
+        let mut symbols: Vec<&str> = symbols.iter().map(|s| s.as_str()).collect();
+        symbols.sort();
+        expected.sort();
+        assert_eq!(symbols, expected);
+    }
+
+    fn sorted(symbols: HashSet<String>) -> Vec<String> {
+        let mut symbols: Vec<String> = symbols.into_iter().collect();
+        symbols.sort();
+        symbols
+    }
+
+    #[test]
+    fn test_targets() {
+        let text = "--- !tapi-tbd
+tbd-version:     4
+targets:         [ x86_64-macos, arm64-macos, arm64e-macos ]
+install-name:    '/usr/lib/libfoo.dylib'
+exports:
+  - targets:         [ x86_64-macos, arm64e-macos ]
+    symbols:         [ _intel ]
+  - targets:         [ arm64-macos,
+                       arm64e-macos ]
+    symbols:         [ _arm ]
+    objc-classes:    [ Foo ]
+  - targets:         [ x86_64-macos, arm64-macos, arm64e-macos ]
+    symbols:         [ _common ]
+...
+";
+        assert_eq!(
+            sorted(exported_symbols(text, "arm64")),
+            [
+                "_OBJC_CLASS_$_Foo",
+                "_OBJC_METACLASS_$_Foo",
+                "_arm",
+                "_common"
+            ]
+        );
+        assert_eq!(
+            sorted(exported_symbols(text, "x86_64")),
+            ["_common", "_intel"]
+        );
+    }
+
+    #[test]
+    fn test_old_versions() {
+        let text = "--- !tapi-tbd-v3
+archs:           [ x86_64, arm64 ]
+install-name:    '/usr/lib/libfoo.dylib'
+exports:
+  - archs:           [ x86_64 ]
+    symbols:         [ _intel ]
+  - archs:           [ x86_64, arm64 ]
+    objc-classes:    [ _Foo ]
+    objc-ivars:      [ _Foo._bar ]
+...
+";
+        assert_eq!(
+            sorted(exported_symbols(text, "arm64")),
+            [
+                "_OBJC_CLASS_$_Foo",
+                "_OBJC_IVAR_$_Foo._bar",
+                "_OBJC_METACLASS_$_Foo"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed() {
+        let text = "--- !tapi-tbd
+tbd-version:     4
+exports
+  - targets:         [ arm64-macos ]
+    symbols:         [ _skipped ]
+reexports:
+  - targets:         [ arm64-macos ]
+    symbols:         [ _printf ]
+garbage
+";
+        assert_eq!(sorted(exported_symbols(text, "arm64")), ["_printf"]);
+    }
+}
@@ -0,0 +1,50 @@
+//! Fixtures of unit tests, paths are relative to the crate root
+
+use super::writer::{FileImage, MachImage};
+use super::{MachObject, ObjectType};
+use crate::Parser;
+use std::path::Path;
+
+pub(crate) fn parse(path: impl AsRef<Path>) -> ObjectType {
+    Parser::build(path.as_ref()).unwrap().parse().unwrap()
+}
+
+/// Object of single arch file
+pub(crate) fn parse_single(path: impl AsRef<Path>) -> MachObject {
+    match parse(path) {
+        ObjectType::MachO(object) => object,
+        ObjectType::Fat(_) => panic!("Expected single arch object"),
+    }
+}
+
+/// Editable images of file
+pub(crate) fn read(path: impl AsRef<Path>) -> FileImage {
+    FileImage::read(&parse(path)).unwrap()
+}
+
+/// Editable image of single arch file
+pub(crate) fn read_single(path: impl AsRef<Path>) -> MachImage {
+    match read(path) {
+        FileImage::Thin(image) => image,
+        FileImage::Fat(..) => panic!("Expected single arch object"),
+    }
+}
+
+/// Calls `handle` with temporary file `name`, that contains `bytes`
+pub(crate) fn with_temp_file<T>(name: &str, bytes: &[u8], handle: impl FnOnce(&Path) -> T) -> T {
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, bytes).unwrap();
+    let result = handle(&path);
+    std::fs::remove_file(&path).unwrap();
+    result
+}
+
+/// Objects of `file` written to temporary file `name` and parsed back
+pub(crate) fn reparse(file: &FileImage, name: &str) -> Vec<MachObject> {
+    with_temp_file(name, &file.to_bytes().unwrap(), |path| parse(path)).mach_objects()
+}
+
+/// Images of `file` written to temporary file `name` and read back
+pub(crate) fn reread(file: &FileImage, name: &str) -> Vec<MachImage> {
+    with_temp_file(name, &file.to_bytes().unwrap(), |path| read(path)).into_images()
+}
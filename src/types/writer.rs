@@ -0,0 +1,454 @@
+//! Serialization of parsed files back to bytes, the base of editing tools.
+//!
+//! Image keeps all bytes of the slice as they are in file. Header and load commands are
+//! written over them, so anything the writer doesn't know about is preserved and
+//! an unmodified file is written byte to byte as it was read.
+
+use super::constants::*;
+use super::section_constants::*;
+use super::MachHeader;
+use super::Magic;
+use super::MachObject;
+use super::ObjectType;
+use super::{CPUSubtype, CPUType};
+use super::{LC_SEGMENT, LC_SEGMENT_64};
+use crate::result::{Error, Result};
+
+use scroll::{Endian, Pread, Pwrite};
+use std::path::Path;
+
+/// Load command as it's stored in file, including `cmd` and `cmdsize`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawLoadCommand {
+    pub cmd: u32,
+    pub bytes: Vec<u8>,
+}
+
+impl RawLoadCommand {
+    pub fn cmdsize(&self) -> u32 {
+        self.bytes.len() as u32
+    }
+
+    /// 32 bit field at `offset` from the start of command
+    pub fn field(&self, offset: usize, endian: Endian) -> Result<u32> {
+        Ok(self.bytes.pread_with(offset, endian)?)
+    }
+
+    pub fn set_field(&mut self, offset: usize, value: u32, endian: Endian) -> Result<()> {
+        self.bytes.pwrite_with(value, offset, endian)?;
+        Ok(())
+    }
+}
+
+/// Editable single arch image
+#[derive(Debug, Clone)]
+pub struct MachImage {
+    /// `ncmds` and `sizeofcmds` are calculated from `load_commands` on write
+    pub header: MachHeader,
+    pub load_commands: Vec<RawLoadCommand>,
+
+    endian: Endian,
+    /// The whole slice as it was read
+    data: Vec<u8>,
+    /// End of load commands as they were read
+    commands_end: usize,
+    /// Start of the first data after load commands
+    commands_limit: usize,
+}
+
+impl MachImage {
+    /// `data` is the whole slice of `object`
+    fn parse(object: &MachObject, data: Vec<u8>) -> Result<Self> {
+        let header = object.header().clone();
+        let endian = header.magic.endian();
+
+        let mut load_commands = Vec::new();
+        let mut offset = header_size(&header);
+        for _ in 0..header.ncmds {
+            let cmd: u32 = data.pread_with(offset, endian)?;
+            let cmdsize: u32 = data.pread_with(offset + 4, endian)?;
+            let end = offset + cmdsize as usize;
+            if (cmdsize as usize) < BYTES_PER_LOAD_COMMAND || end > data.len() {
+                return Err(Error::Text(format!(
+                    "Load command at {offset:#x} has invalid size {cmdsize}"
+                )));
+            }
+            load_commands.push(RawLoadCommand {
+                cmd,
+                bytes: data[offset..end].to_vec(),
+            });
+            offset = end;
+        }
+
+        let commands_limit = commands_limit(object, data.len()).max(offset);
+        Ok(MachImage {
+            header,
+            load_commands,
+            endian,
+            data,
+            commands_end: offset,
+            commands_limit,
+        })
+    }
+
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    pub fn is_64(&self) -> bool {
+        self.header.magic.is_64()
+    }
+
+    /// The whole slice as it was read, header and load commands in it aren't updated
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Contents after load commands, that may be changed in place
+    pub fn data_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.data
+    }
+
+    pub fn sizeofcmds(&self) -> usize {
+        self.load_commands.iter().map(|cmd| cmd.bytes.len()).sum()
+    }
+
+    /// Bytes, that load commands may grow by before reaching data of the first section
+    pub fn header_padding(&self) -> usize {
+        let end = header_size(&self.header) + self.sizeofcmds();
+        self.commands_limit.saturating_sub(end)
+    }
+
+    /// Index of `LC_SEGMENT` or `LC_SEGMENT_64` command of segment `name`
+    pub fn segment_index(&self, name: &str) -> Option<usize> {
+        self.load_commands.iter().position(|command| {
+            let is_segment = command.cmd == LC_SEGMENT || command.cmd == LC_SEGMENT_64;
+            let segname = command.bytes.get(8..24).unwrap_or_default();
+            let end = segname.iter().position(|b| *b == 0).unwrap_or(segname.len());
+            is_segment && &segname[..end] == name.as_bytes()
+        })
+    }
+
+    /// `vmsize`, `fileoff` and `filesize` of segment command at `index`
+    pub fn segment_sizes(&self, index: usize) -> Result<(u64, u64, u64)> {
+        let bytes = &self.load_commands[index].bytes;
+        let endian = self.endian;
+        match self.is_64() {
+            true => Ok((
+                bytes.pread_with(32, endian)?,
+                bytes.pread_with(40, endian)?,
+                bytes.pread_with(48, endian)?,
+            )),
+            false => Ok((
+                bytes.pread_with::<u32>(28, endian)? as u64,
+                bytes.pread_with::<u32>(32, endian)? as u64,
+                bytes.pread_with::<u32>(36, endian)? as u64,
+            )),
+        }
+    }
+
+    pub fn set_segment_sizes(&mut self, index: usize, vmsize: u64, filesize: u64) -> Result<()> {
+        let endian = self.endian;
+        let is_64 = self.is_64();
+        let bytes = &mut self.load_commands[index].bytes;
+        match is_64 {
+            true => {
+                bytes.pwrite_with(vmsize, 32, endian)?;
+                bytes.pwrite_with(filesize, 48, endian)?;
+            }
+            false => {
+                bytes.pwrite_with(vmsize as u32, 28, endian)?;
+                bytes.pwrite_with(filesize as u32, 36, endian)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let header_size = header_size(&self.header);
+        let commands_end = header_size + self.sizeofcmds();
+        if commands_end > self.commands_limit {
+            return Err(Error::Text(format!(
+                "Load commands need {} bytes, but only {} bytes are available before data",
+                commands_end - header_size,
+                self.commands_limit - header_size
+            )));
+        }
+
+        let mut out = self.data.clone();
+        let header = &self.header;
+        let fields = [
+            header.cputype.0,
+            header.cpusubtype.0,
+            header.filetype.0,
+            self.load_commands.len() as u32,
+            self.sizeofcmds() as u32,
+            header.flags.0,
+        ];
+        for (idx, value) in fields.into_iter().enumerate() {
+            out.pwrite_with(value, BYTES_PER_MAGIC + 4 * idx, self.endian)?;
+        }
+        if self.is_64() {
+            out.pwrite_with(header.reserved.0, BYTES_PER_MACH_HEADER32, self.endian)?;
+        }
+
+        let mut offset = header_size;
+        for command in &self.load_commands {
+            out[offset..offset + command.bytes.len()].copy_from_slice(&command.bytes);
+            offset += command.bytes.len();
+        }
+        // Space left by removed or shrunk commands
+        let old_end = self.commands_end.max(offset);
+        out[offset..old_end].fill(0);
+
+        Ok(out)
+    }
+}
+
+/// Slice of fat file
+#[derive(Debug, Clone)]
+pub struct FatSlice {
+    pub cputype: CPUType,
+    pub cpusubtype: CPUSubtype,
+    /// Power of 2
+    pub align: u32,
+    pub image: MachImage,
+    /// Offset the slice was read at, kept if the slice still fits there
+    offset: Option<u32>,
+}
+
+impl FatSlice {
+    /// Slice of `image` to put into fat file. `align` is power of 2
+    pub fn new(image: MachImage, align: u32) -> Self {
+        FatSlice {
+            cputype: image.header.cputype,
+            cpusubtype: image.header.cpusubtype,
+            align,
+            image,
+            offset: None,
+        }
+    }
+}
+
+/// Parts of fat file besides slices, kept so the file is written as it was read
+#[derive(Debug, Clone)]
+pub struct FatLayout {
+    /// Byte order of fat header, big endian unless magic is reversed
+    pub endian: Endian,
+    /// Data after the last slice
+    pub trailing: Vec<u8>,
+}
+
+impl Default for FatLayout {
+    fn default() -> Self {
+        FatLayout {
+            endian: scroll::BE,
+            trailing: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum FileImage {
+    Thin(MachImage),
+    Fat(Vec<FatSlice>, FatLayout),
+}
+
+impl FileImage {
+    pub fn read(object: &ObjectType) -> Result<Self> {
+        match object {
+            ObjectType::MachO(object) => {
+                let data = object.read_to_end()?;
+                Ok(FileImage::Thin(MachImage::parse(object, data)?))
+            }
+            ObjectType::Fat(fat) => {
+                let mut slices = Vec::new();
+                let mut end = (BYTES_PER_FAT_HEADER + BYTES_PER_FAT_ARCH * fat.nfat_arch as usize) as u64;
+                for arch in fat.arch_iterator() {
+                    end = end.max(arch.offset as u64 + arch.size as u64);
+                    let object = arch.object()?;
+                    let data = object.read_bytes(0, arch.size as usize)?;
+                    slices.push(FatSlice {
+                        cputype: arch.cputype,
+                        cpusubtype: arch.cpusubtype,
+                        align: arch.align,
+                        image: MachImage::parse(&object, data)?,
+                        offset: Some(arch.offset),
+                    });
+                }
+                let layout = FatLayout {
+                    endian: fat.endian,
+                    trailing: fat.read_from(end)?,
+                };
+                Ok(FileImage::Fat(slices, layout))
+            }
+        }
+    }
+
+    /// Images of all architectures
+    pub fn images_mut(&mut self) -> Vec<&mut MachImage> {
+        match self {
+            FileImage::Thin(image) => vec![image],
+            FileImage::Fat(slices, _) => {
+                slices.iter_mut().map(|slice| &mut slice.image).collect()
+            }
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let (slices, layout) = match self {
+            FileImage::Thin(image) => return image.to_bytes(),
+            FileImage::Fat(slices, layout) => (slices, layout),
+        };
+
+        let mut archs = Vec::new();
+        let mut contents = Vec::new();
+        let mut end = (BYTES_PER_FAT_HEADER + BYTES_PER_FAT_ARCH * slices.len()) as u64;
+        for slice in slices {
+            let bytes = slice.image.to_bytes()?;
+            let alignment = 1u64 << slice.align;
+            let mut offset = (end + alignment - 1) & !(alignment - 1);
+            if let Some(original) = slice.offset {
+                offset = offset.max(original as u64);
+            }
+            if offset + bytes.len() as u64 > u32::MAX as u64 {
+                return Err(Error::Text("Fat file exceeds 4 GB".to_string()));
+            }
+            archs.push([
+                slice.cputype.0,
+                slice.cpusubtype.0,
+                offset as u32,
+                bytes.len() as u32,
+                slice.align,
+            ]);
+            end = offset + bytes.len() as u64;
+            contents.push((offset as usize, bytes));
+        }
+
+        let mut out = vec![0u8; end as usize];
+        let endian = layout.endian;
+        out.pwrite_with(Magic::Fat.raw_value(), 0, endian)?;
+        out.pwrite_with(slices.len() as u32, BYTES_PER_MAGIC, endian)?;
+        for (idx, arch) in archs.iter().enumerate() {
+            let base = BYTES_PER_FAT_HEADER + BYTES_PER_FAT_ARCH * idx;
+            for (field_idx, value) in arch.iter().enumerate() {
+                out.pwrite_with(*value, base + 4 * field_idx, endian)?;
+            }
+        }
+        for (offset, bytes) in contents {
+            out[offset..offset + bytes.len()].copy_from_slice(&bytes);
+        }
+        out.extend_from_slice(&layout.trailing);
+        Ok(out)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+}
+
+fn header_size(header: &MachHeader) -> usize {
+    match header.magic.is_64() {
+        true => BYTES_PER_MACH_HEADER64,
+        false => BYTES_PER_MACH_HEADER32,
+    }
+}
+
+/// Offset, that load commands can't extend beyond: data of the first segment or section
+/// after the header, or tables of `LC_SYMTAB`
+fn commands_limit(object: &MachObject, size: usize) -> usize {
+    let mut limit = size;
+    for segment in object.segments() {
+        if segment.fileoff.0 > 0 && segment.filesize.0 > 0 {
+            limit = limit.min(segment.fileoff.0 as usize);
+        }
+        for section in segment.sections_iterator() {
+            let is_zerofill = matches!(
+                section.section_type(),
+                S_ZEROFILL | S_GB_ZEROFILL | S_THREAD_LOCAL_ZEROFILL
+            );
+            if !is_zerofill && section.offset > 0 && section.size.0 > 0 {
+                limit = limit.min(section.offset as usize);
+            }
+        }
+    }
+    if let Some(symtab) = object.symtab() {
+        for offset in [symtab.symoff, symtab.stroff] {
+            if offset > 0 {
+                limit = limit.min(offset as usize);
+            }
+        }
+    }
+    limit
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{read, reparse, with_temp_file};
+    use crate::LC_UUID;
+
+    #[test]
+    fn test_round_trip() {
+        let paths = [
+            "testable/cat",
+            "testable/objc_arm64e",
+            "testable/stabs_arm64",
+            "testable/dwarf_arm64.o",
+            "testable/abi/libabi_old.dylib",
+            "testable/sysroot/Applications/App/app",
+        ];
+        for path in paths {
+            let bytes = read(path).to_bytes().unwrap();
+            assert!(bytes == std::fs::read(path).unwrap(), "{path} differs");
+        }
+    }
+
+    #[test]
+    fn test_round_trip_fat_layout() {
+        // Little endian fat header of cat with data after the last slice
+        let mut bytes = std::fs::read("testable/cat").unwrap();
+        let nfat_arch = u32::from_be_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        for field in bytes[..BYTES_PER_FAT_HEADER + nfat_arch * BYTES_PER_FAT_ARCH].chunks_mut(4) {
+            field.reverse();
+        }
+        bytes.extend_from_slice(b"trailing data");
+
+        let file = with_temp_file("schnauzer_writer_fat_layout", &bytes, |path| read(path));
+        match &file {
+            FileImage::Fat(slices, layout) => {
+                assert_eq!(slices.len(), 2);
+                assert_eq!(layout.endian, Endian::Little);
+                assert_eq!(layout.trailing, b"trailing data");
+            }
+            FileImage::Thin(_) => panic!("Expected fat file"),
+        }
+        assert!(file.to_bytes().unwrap() == bytes);
+    }
+
+    #[test]
+    fn test_load_commands() {
+        let mut file = read("testable/diff/liba.dylib");
+        let image = &mut file.images_mut()[0];
+        let padding = image.header_padding();
+        let index = image
+            .load_commands
+            .iter()
+            .position(|cmd| cmd.cmd == LC_UUID)
+            .unwrap();
+        let removed = image.load_commands.remove(index);
+        assert_eq!(image.header_padding(), padding + removed.bytes.len());
+
+        let object = reparse(&file, "schnauzer_writer_test.dylib").remove(0);
+        assert_eq!(object.header().ncmds, 6);
+        assert!(object.uuid().is_none());
+
+        let image = &mut file.images_mut()[0];
+        image.load_commands.push(RawLoadCommand {
+            cmd: removed.cmd,
+            bytes: vec![0; image.header_padding() + 8],
+        });
+        assert!(image.to_bytes().is_err());
+    }
+}
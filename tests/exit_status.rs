@@ -0,0 +1,52 @@
+//! Commands, that check something, fail with non-zero exit status, so they can be used in scripts
+
+use std::process::Command;
+
+fn status(args: &[&str]) -> i32 {
+    let output = Command::new(env!("CARGO_BIN_EXE_schnauzer"))
+        .args(args)
+        .output()
+        .unwrap();
+    output.status.code().unwrap()
+}
+
+#[test]
+fn test_abi_diff() {
+    let old = "testable/abi/libabi_old.dylib";
+    let new = "testable/abi/libabi_new.dylib";
+    assert_eq!(status(&["abi-diff", old, new]), 1);
+    assert_eq!(status(&["abi-diff", old, old]), 0);
+}
+
+#[test]
+fn test_check_links() {
+    let sysroot = "testable/sysroot";
+    let app = "testable/sysroot/Applications/App/app";
+    let lib = "testable/sysroot/Applications/App/lib/libfoo.dylib";
+    assert_eq!(status(&["check-links", app, "--sysroot", sysroot]), 1);
+    assert_eq!(status(&["check-links", lib, "--sysroot", sysroot]), 0);
+}
+
+#[test]
+fn test_diff() {
+    let lib = "testable/diff/liba.dylib";
+    assert_eq!(status(&["diff", lib, "testable/diff/liba_changed.dylib"]), 1);
+    assert_eq!(status(&["diff", lib, lib]), 0);
+}
+
+#[test]
+fn test_dsym_match() {
+    let root = std::env::temp_dir().join("schnauzer_dsym_match_status");
+    let _ = std::fs::remove_dir_all(&root);
+    // The binary itself stands for its DWARF file, the other one has different UUIDs
+    for (dir, dwarf) in [("good", "testable/cat"), ("bad", "testable/objc_arm64e")] {
+        let path = root.join(dir).join("cat.dSYM/Contents/Resources/DWARF");
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::copy(dwarf, path.join("cat")).unwrap();
+    }
+
+    let dir = |name: &str| root.join(name).to_string_lossy().to_string();
+    assert_eq!(status(&["dsym-match", "testable/cat", &dir("good")]), 0);
+    assert_eq!(status(&["dsym-match", "testable/cat", &dir("bad")]), 1);
+    std::fs::remove_dir_all(&root).unwrap();
+}